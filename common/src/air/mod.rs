@@ -23,6 +23,9 @@ pub use transition::{EvaluationFrame, TransitionConstraintDegree, TransitionCons
 mod divisor;
 pub use divisor::ConstraintDivisor;
 
+mod product;
+pub use product::{LinkConstraints, NoLinks, ProductAir, ProductInputs};
+
 #[cfg(test)]
 mod tests;
 
@@ -86,6 +89,16 @@ pub trait Air: Send + Sync {
         Vec::new()
     }
 
+    /// Returns values for all preprocessed columns of the computation. The number of returned
+    /// columns must be equal to the number of preprocessed columns declared in the context of
+    /// this computation, and each column must contain exactly `trace_length` values. In contrast
+    /// to periodic columns, preprocessed columns are committed to during setup, and their values
+    /// are passed to the evaluate_transition() method as the last registers of the evaluation
+    /// frame. The default implementation of this method returns an empty vector.
+    fn get_preprocessed_columns(&self) -> Vec<Vec<Self::BaseElement>> {
+        Vec::new()
+    }
+
     /// Returns polynomial for all periodic columns. These polynomials are interpolated from
     /// the values returned from the get_periodic_column_values() method.
     fn get_periodic_column_polys(&self) -> Vec<Vec<Self::BaseElement>> {
@@ -0,0 +1,319 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{Air, Assertion, EvaluationFrame, TraceInfo, TransitionConstraintDegree};
+use crate::{ComputationContext, ProofOptions};
+use math::field::FieldElement;
+
+#[cfg(test)]
+mod tests;
+
+// LINK CONSTRAINTS
+// ================================================================================================
+
+/// Describes constraints which link two computations combined by a `ProductAir`.
+///
+/// Link constraints are evaluated against the evaluation frame of the combined computation: the
+/// registers of the first computation are followed by the registers of the second computation,
+/// and, if any of the computations has preprocessed columns, these follow all trace registers
+/// (again, in the same order). Link constraints can also define their own periodic columns and
+/// assertions; registers in the assertions refer to the registers of the combined trace.
+pub trait LinkConstraints<A, B>: Send + Sync
+where
+    A: Air,
+    B: Air<BaseElement = A::BaseElement>,
+{
+    type PublicInputs;
+
+    // REQUIRED METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Should create a new instance of link constraints for the provided instances of the
+    /// combined computations.
+    fn new(first: &A, second: &B, pub_inputs: Self::PublicInputs) -> Self;
+
+    /// Should return degrees of all link constraints; the number of returned degrees defines
+    /// the number of link constraints.
+    fn get_degrees(&self) -> Vec<TransitionConstraintDegree>;
+
+    /// Should evaluate link constraints over the specified evaluation frame of the combined
+    /// computation. The evaluations should be saved into the `result` slice.
+    fn evaluate<E: FieldElement + From<A::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    );
+
+    // PROVIDED METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns values for periodic columns used by link constraints; these are passed to the
+    /// evaluate() method after periodic values of both computations. The default implementation
+    /// returns an empty vector.
+    fn get_periodic_column_values(&self) -> Vec<Vec<A::BaseElement>> {
+        Vec::new()
+    }
+
+    /// Returns assertions against the combined execution trace in addition to the assertions
+    /// of both computations. The default implementation returns an empty vector.
+    fn get_assertions(&self) -> Vec<Assertion<A::BaseElement>> {
+        Vec::new()
+    }
+}
+
+/// Link constraints for computations which do not share any values.
+pub struct NoLinks;
+
+impl<A, B> LinkConstraints<A, B> for NoLinks
+where
+    A: Air,
+    B: Air<BaseElement = A::BaseElement>,
+{
+    type PublicInputs = ();
+
+    fn new(_first: &A, _second: &B, _pub_inputs: ()) -> Self {
+        NoLinks
+    }
+
+    fn get_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        Vec::new()
+    }
+
+    fn evaluate<E: FieldElement + From<A::BaseElement>>(
+        &self,
+        _frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        _result: &mut [E],
+    ) {
+    }
+}
+
+// PRODUCT INPUTS
+// ================================================================================================
+
+/// Public inputs of a `ProductAir`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductInputs<P1, P2, L> {
+    /// Public inputs of the first computation.
+    pub first: P1,
+    /// Public inputs of the second computation.
+    pub second: P2,
+    /// Public inputs of the link constraints.
+    pub links: L,
+}
+
+impl<P1, P2> ProductInputs<P1, P2, ()> {
+    /// Returns public inputs for a product of computations which do not share any values.
+    pub fn new(first: P1, second: P2) -> Self {
+        ProductInputs {
+            first,
+            second,
+            links: (),
+        }
+    }
+}
+
+// PRODUCT AIR
+// ================================================================================================
+
+/// AIR for two computations proven together in a single proof.
+///
+/// The execution trace of the combined computation consists of the registers of the first
+/// computation followed by the registers of the second computation; both traces must have the
+/// same length. Transition constraints, periodic columns, preprocessed columns, and assertions
+/// of the combined computation are those of the first computation, followed by those of the
+/// second computation, followed by those of link constraints `L`. Assertions of the second
+/// computation are shifted to refer to its registers in the combined trace.
+///
+/// Link constraints are transition constraints, and thus, they are not enforced against the
+/// last step of the trace.
+///
+/// Both computations are instantiated with the trace info and proof options of the combined
+/// computation, and thus, the blowup factor must be sufficient for either of them. Evaluation
+/// frames of the combined computation are copied into frames of the individual computations
+/// every time transition constraints are evaluated.
+pub struct ProductAir<A, B, L = NoLinks>
+where
+    A: Air,
+    B: Air<BaseElement = A::BaseElement>,
+    L: LinkConstraints<A, B>,
+{
+    context: ComputationContext,
+    first: A,
+    second: B,
+    links: L,
+    first_num_periodic_columns: usize,
+    second_num_periodic_columns: usize,
+}
+
+impl<A, B, L> ProductAir<A, B, L>
+where
+    A: Air,
+    B: Air<BaseElement = A::BaseElement>,
+    L: LinkConstraints<A, B>,
+{
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the instance of the first computation.
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Returns the instance of the second computation.
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+
+    /// Returns the instance of link constraints.
+    pub fn links(&self) -> &L {
+        &self.links
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Copies registers of the combined `frame` which belong to each of the computations into
+    /// separate frames.
+    fn split_frame<E: FieldElement>(
+        &self,
+        frame: &EvaluationFrame<E>,
+    ) -> (EvaluationFrame<E>, EvaluationFrame<E>) {
+        let first_context = self.first.context();
+        let first_width = first_context.trace_width();
+        let first_preprocessed = first_context.num_preprocessed_columns();
+        let trace_width = self.context.trace_width();
+
+        let split = |row: &[E]| {
+            let mut first = row[..first_width].to_vec();
+            first.extend_from_slice(&row[trace_width..trace_width + first_preprocessed]);
+            let mut second = row[first_width..trace_width].to_vec();
+            second.extend_from_slice(&row[trace_width + first_preprocessed..]);
+            (first, second)
+        };
+
+        let (first_current, second_current) = split(&frame.current);
+        let (first_next, second_next) = split(&frame.next);
+        (
+            EvaluationFrame {
+                current: first_current,
+                next: first_next,
+            },
+            EvaluationFrame {
+                current: second_current,
+                next: second_next,
+            },
+        )
+    }
+}
+
+impl<A, B, L> Air for ProductAir<A, B, L>
+where
+    A: Air,
+    B: Air<BaseElement = A::BaseElement>,
+    L: LinkConstraints<A, B>,
+{
+    type BaseElement = A::BaseElement;
+    type PublicInputs = ProductInputs<A::PublicInputs, B::PublicInputs, L::PublicInputs>;
+
+    fn new(trace_info: TraceInfo, pub_inputs: Self::PublicInputs, options: ProofOptions) -> Self {
+        let second_trace_info = TraceInfo {
+            length: trace_info.length,
+            meta: trace_info.meta.clone(),
+        };
+        let first = A::new(trace_info, pub_inputs.first, options.clone());
+        let second = B::new(second_trace_info, pub_inputs.second, options.clone());
+        let links = L::new(&first, &second, pub_inputs.links);
+
+        let first_context = first.context();
+        let second_context = second.context();
+        let mut degrees = first_context.transition_constraint_degrees().to_vec();
+        degrees.extend_from_slice(second_context.transition_constraint_degrees());
+        degrees.extend(links.get_degrees());
+
+        let mut context = ComputationContext::new(
+            first_context.trace_width() + second_context.trace_width(),
+            first_context.trace_length(),
+            degrees,
+            options,
+        )
+        .with_preprocessed_columns(
+            first_context.num_preprocessed_columns() + second_context.num_preprocessed_columns(),
+        );
+
+        // the combined trace is committed to as a single segment, unless any of the computations
+        // splits its trace into several segments
+        if first_context.num_trace_segments() > 1 || second_context.num_trace_segments() > 1 {
+            let mut segment_widths = first_context.trace_segment_widths().to_vec();
+            segment_widths.extend_from_slice(second_context.trace_segment_widths());
+            context = context.with_trace_segments(segment_widths);
+        }
+
+        ProductAir {
+            context,
+            first_num_periodic_columns: first.get_periodic_column_values().len(),
+            second_num_periodic_columns: second.get_periodic_column_values().len(),
+            first,
+            second,
+            links,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let (first_frame, second_frame) = self.split_frame(frame);
+        let (first_periodic, periodic_values) =
+            periodic_values.split_at(self.first_num_periodic_columns);
+        let (second_periodic, link_periodic) =
+            periodic_values.split_at(self.second_num_periodic_columns);
+        let (first_result, result) = result.split_at_mut(self.first.num_transition_constraints());
+        let (second_result, link_result) =
+            result.split_at_mut(self.second.num_transition_constraints());
+
+        self.first
+            .evaluate_transition(&first_frame, first_periodic, first_result);
+        self.second
+            .evaluate_transition(&second_frame, second_periodic, second_result);
+        self.links.evaluate(frame, link_periodic, link_result);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        let register_offset = self.first.trace_width();
+        let mut result = self.first.get_assertions();
+        result.extend(
+            self.second
+                .get_assertions()
+                .into_iter()
+                .map(|mut assertion| {
+                    assertion.register += register_offset;
+                    assertion
+                }),
+        );
+        result.extend(self.links.get_assertions());
+        result
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        let mut result = self.first.get_periodic_column_values();
+        result.extend(self.second.get_periodic_column_values());
+        result.extend(self.links.get_periodic_column_values());
+        result
+    }
+
+    fn get_preprocessed_columns(&self) -> Vec<Vec<Self::BaseElement>> {
+        let mut result = self.first.get_preprocessed_columns();
+        result.extend(self.second.get_preprocessed_columns());
+        result
+    }
+}
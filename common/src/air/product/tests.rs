@@ -0,0 +1,243 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    Air, Assertion, ComputationContext, EvaluationFrame, LinkConstraints, ProductAir,
+    ProductInputs, ProofOptions, TraceInfo, TransitionConstraintDegree,
+};
+use crate::{FieldExtension, HashFunction};
+use math::field::{f128::BaseElement, FieldElement};
+
+// PRODUCT AIR
+// ================================================================================================
+
+#[test]
+fn product_air_context() {
+    let air = build_air::<_, NoLinksAir>(16, ());
+    let context = air.context();
+    assert_eq!(3, context.trace_width());
+    assert_eq!(16, context.trace_length());
+    assert_eq!(1, context.num_preprocessed_columns());
+    assert_eq!(4, context.frame_width());
+    assert_eq!(&[3], context.trace_segment_widths());
+
+    let degrees = context
+        .transition_constraint_degrees()
+        .iter()
+        .map(|degree| degree.get_evaluation_degree(16))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![23, 30], degrees);
+
+    let air = build_air::<_, LinkedAir>(16, BaseElement::new(7));
+    let degrees = air
+        .context()
+        .transition_constraint_degrees()
+        .iter()
+        .map(|degree| degree.get_evaluation_degree(16))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![23, 30, 15], degrees);
+}
+
+#[test]
+fn product_air_assertions() {
+    let air = build_air::<_, LinkedAir>(16, BaseElement::new(7));
+    let expected = vec![
+        Assertion::single(0, 0, BaseElement::new(3)),
+        Assertion::single(2, 0, BaseElement::new(5)),
+        Assertion::single(1, 15, BaseElement::new(7)),
+    ];
+    assert_eq!(expected, air.get_assertions());
+}
+
+#[test]
+fn product_air_periodic_columns() {
+    let air = build_air::<_, LinkedAir>(16, BaseElement::new(7));
+    let expected = vec![
+        vec![BaseElement::ONE, BaseElement::ZERO],
+        vec![BaseElement::new(2); 4],
+        vec![BaseElement::new(3); 8],
+    ];
+    assert_eq!(expected, air.get_periodic_column_values());
+    assert_eq!(
+        vec![vec![BaseElement::new(11); 16]],
+        air.get_preprocessed_columns()
+    );
+}
+
+#[test]
+fn product_air_evaluate_transition() {
+    let air = build_air::<_, LinkedAir>(16, BaseElement::new(7));
+
+    // the frame of the combined computation contains registers of the first computation,
+    // registers of the second computation, and then the preprocessed column of the first
+    // computation
+    let frame = EvaluationFrame {
+        current: vec![1u128, 2, 3, 4]
+            .into_iter()
+            .map(BaseElement::new)
+            .collect(),
+        next: vec![5u128, 6, 7, 8]
+            .into_iter()
+            .map(BaseElement::new)
+            .collect(),
+    };
+    let periodic_values = vec![
+        BaseElement::new(9),
+        BaseElement::new(10),
+        BaseElement::new(11),
+    ];
+    let mut result = vec![BaseElement::ZERO; 3];
+    air.evaluate_transition(&frame, &periodic_values, &mut result);
+
+    let expected = vec![
+        // first: next[0] - current[0] - current[1] * periodic[0] = 5 - 1 - 4 * 9
+        BaseElement::new(5) - BaseElement::new(1) - BaseElement::new(36),
+        // second: next[1] - current[0]^2 * periodic[0] = 7 - 2 * 2 * 10
+        BaseElement::new(7) - BaseElement::new(40),
+        // link: next[1] - current[0] - periodic[0] = 6 - 1 - 11
+        BaseElement::new(6) - BaseElement::new(1) - BaseElement::new(11),
+    ];
+    assert_eq!(expected, result);
+}
+
+// MOCK COMPUTATIONS
+// ================================================================================================
+
+type NoLinksAir = ProductAir<FirstAir, SecondAir>;
+type LinkedAir = ProductAir<FirstAir, SecondAir, MockLinks>;
+
+/// A computation with one register and one preprocessed column.
+struct FirstAir {
+    context: ComputationContext,
+}
+
+impl Air for FirstAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = ();
+
+    fn new(trace_info: TraceInfo, _pub_inputs: (), options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::with_cycles(1, vec![2])];
+        let context = ComputationContext::new(1, trace_info.length, degrees, options)
+            .with_preprocessed_columns(1);
+        FirstAir { context }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        result[0] = frame.next[0] - frame.current[0] - frame.current[1] * periodic_values[0];
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        vec![Assertion::single(0, 0, BaseElement::new(3))]
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        vec![vec![BaseElement::ONE, BaseElement::ZERO]]
+    }
+
+    fn get_preprocessed_columns(&self) -> Vec<Vec<Self::BaseElement>> {
+        vec![vec![BaseElement::new(11); self.trace_length()]]
+    }
+}
+
+/// A computation with two registers.
+struct SecondAir {
+    context: ComputationContext,
+}
+
+impl Air for SecondAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, _pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(2)];
+        let context = ComputationContext::new(2, trace_info.length, degrees, options);
+        SecondAir { context }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        result[0] = frame.next[1] - frame.current[0].square() * periodic_values[0];
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        vec![Assertion::single(1, 0, BaseElement::new(5))]
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        vec![vec![BaseElement::new(2); 4]]
+    }
+}
+
+/// Links the first register of the first computation to the first register of the second
+/// computation at the next step.
+struct MockLinks {
+    final_value: BaseElement,
+}
+
+impl LinkConstraints<FirstAir, SecondAir> for MockLinks {
+    type PublicInputs = BaseElement;
+
+    fn new(_first: &FirstAir, _second: &SecondAir, final_value: BaseElement) -> Self {
+        MockLinks { final_value }
+    }
+
+    fn get_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        vec![TransitionConstraintDegree::new(1)]
+    }
+
+    fn evaluate<E: FieldElement + From<BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        result[0] = frame.next[1] - frame.current[0] - periodic_values[0];
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<BaseElement>> {
+        vec![vec![BaseElement::new(3); 8]]
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<BaseElement>> {
+        vec![Assertion::single(1, 15, self.final_value)]
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_air<L, AIR>(trace_length: usize, links: L) -> AIR
+where
+    AIR: Air<PublicInputs = ProductInputs<(), BaseElement, L>>,
+{
+    let trace_info = TraceInfo {
+        length: trace_length,
+        meta: Vec::new(),
+    };
+    let pub_inputs = ProductInputs {
+        first: (),
+        second: BaseElement::ONE,
+        links,
+    };
+    let options = ProofOptions::new(32, 8, 0, HashFunction::Blake3_256, FieldExtension::None);
+    AIR::new(trace_info, pub_inputs, options)
+}
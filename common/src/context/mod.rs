@@ -13,6 +13,8 @@ use math::{field::StarkField, utils::log2};
 pub struct ComputationContext {
     options: ProofOptions,
     trace_width: usize,
+    trace_segment_widths: Vec<usize>,
+    num_preprocessed_columns: usize,
     trace_length: usize,
     transition_constraint_degrees: Vec<TransitionConstraintDegree>,
     ce_blowup_factor: usize,
@@ -75,12 +77,41 @@ impl ComputationContext {
         ComputationContext {
             options,
             trace_width,
+            trace_segment_widths: vec![trace_width],
+            num_preprocessed_columns: 0,
             trace_length,
             transition_constraint_degrees,
             ce_blowup_factor,
         }
     }
 
+    /// Splits registers of the execution trace into consecutive segments of the specified
+    /// widths; the prover commits to each segment separately. By default, the entire trace
+    /// forms a single segment.
+    ///
+    /// Panics if any of the widths is zero, or if the widths do not add up to the trace width.
+    pub fn with_trace_segments(mut self, segment_widths: Vec<usize>) -> Self {
+        assert!(
+            segment_widths.iter().all(|&width| width > 0),
+            "trace segment widths must be greater than 0"
+        );
+        assert_eq!(
+            self.trace_width,
+            segment_widths.iter().sum::<usize>(),
+            "trace segment widths must add up to trace width"
+        );
+        self.trace_segment_widths = segment_widths;
+        self
+    }
+
+    /// Declares the number of preprocessed columns of the computation. Values of these columns
+    /// are fixed by the computation itself (rather than by the witness), and are committed to
+    /// once during setup; in evaluation frames they follow the registers of the execution trace.
+    pub fn with_preprocessed_columns(mut self, num_columns: usize) -> Self {
+        self.num_preprocessed_columns = num_columns;
+        self
+    }
+
     // TRACE INFO
     // --------------------------------------------------------------------------------------------
 
@@ -88,6 +119,35 @@ impl ComputationContext {
         self.trace_width
     }
 
+    /// Returns widths of the trace segments committed to separately by the prover.
+    pub fn trace_segment_widths(&self) -> &[usize] {
+        &self.trace_segment_widths
+    }
+
+    pub fn num_trace_segments(&self) -> usize {
+        self.trace_segment_widths.len()
+    }
+
+    pub fn num_preprocessed_columns(&self) -> usize {
+        self.num_preprocessed_columns
+    }
+
+    /// Returns the number of registers in an evaluation frame; this includes registers of the
+    /// execution trace as well as preprocessed columns.
+    pub fn frame_width(&self) -> usize {
+        self.trace_width + self.num_preprocessed_columns
+    }
+
+    /// Returns widths of all segments committed to during proof generation; when the computation
+    /// has preprocessed columns, these form the last segment.
+    pub fn committed_segment_widths(&self) -> Vec<usize> {
+        let mut result = self.trace_segment_widths.clone();
+        if self.num_preprocessed_columns > 0 {
+            result.push(self.num_preprocessed_columns);
+        }
+        result
+    }
+
     pub fn trace_length(&self) -> usize {
         self.trace_length
     }
@@ -0,0 +1,84 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::Assertion;
+use math::field::StarkField;
+
+// SEGMENT BOUNDARY
+// ================================================================================================
+
+/// Public boundary values of a single segment of a computation split into segments.
+///
+/// A long computation can be proven as a sequence of fixed-length segments, each with its own
+/// proof. The proof of every segment exposes the states of the computation at the first and at
+/// the last step of the segment; consecutive segments are linked by requiring the initial state
+/// of a segment to be equal to the final state of the preceding segment. Thus, the last row of
+/// each segment trace is repeated as the first row of the next segment trace.
+///
+/// An AIR describing a segment should use `SegmentBoundary` as its public inputs, and should
+/// include assertions returned from `get_assertions()` into the assertions of the AIR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentBoundary<B: StarkField> {
+    initial_state: Vec<B>,
+    final_state: Vec<B>,
+}
+
+impl<B: StarkField> SegmentBoundary<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns boundary values for a segment which starts in `initial_state` and ends in
+    /// `final_state`.
+    pub fn new(initial_state: Vec<B>, final_state: Vec<B>) -> Self {
+        assert!(
+            !initial_state.is_empty(),
+            "segment state must consist of at least one register"
+        );
+        assert!(
+            initial_state.len() == final_state.len(),
+            "initial and final states of a segment must have the same width"
+        );
+        SegmentBoundary {
+            initial_state,
+            final_state,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the state of the computation at the first step of the segment.
+    pub fn initial_state(&self) -> &[B] {
+        &self.initial_state
+    }
+
+    /// Returns the state of the computation at the last step of the segment.
+    pub fn final_state(&self) -> &[B] {
+        &self.final_state
+    }
+
+    /// Returns true if the segment described by `next` starts in the state in which this
+    /// segment ends.
+    pub fn links_to(&self, next: &SegmentBoundary<B>) -> bool {
+        self.final_state == next.initial_state
+    }
+
+    // ASSERTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns assertions binding every register in the first and the last rows of a segment
+    /// trace of the specified length to the initial and the final states of the segment.
+    pub fn get_assertions(&self, trace_length: usize) -> Vec<Assertion<B>> {
+        let last_step = trace_length - 1;
+        let mut result = Vec::with_capacity(self.initial_state.len() * 2);
+        for (register, &value) in self.initial_state.iter().enumerate() {
+            result.push(Assertion::single(register, 0, value));
+        }
+        for (register, &value) in self.final_state.iter().enumerate() {
+            result.push(Assertion::single(register, last_step, value));
+        }
+        result
+    }
+}
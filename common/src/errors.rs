@@ -3,7 +3,9 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crypto::DigestSerializationError;
 use displaydoc::Display;
+use math::errors::SerializationError;
 use thiserror::Error;
 
 /// Represents an error thrown by the prover during an execution of the protocol
@@ -13,6 +15,26 @@ pub enum ProverError {
     UnsatisfiedTransitionConstraintError(usize),
     /// The constraint polynomial's components do not all have the same degree, expected {0} but found {1}
     MismatchedConstraintPolynomialDegree(usize, usize),
+    /// The selected hash function cannot be used with the base field of the computation
+    UnsupportedHashFunction,
+    /// The proving key was generated for a different instance of the computation
+    IncompatibleProvingKey,
+    /// Expected an execution trace of {0} steps, but received a trace of {1} steps
+    InconsistentTraceLength(usize, usize),
+    /// Expected an extended execution trace of {0} registers, but received a trace of {1} registers
+    InconsistentTraceWidth(usize, usize),
+    /// Expected an extended execution trace of {0} steps, but received a trace of {1} steps
+    InconsistentExtendedTraceLength(usize, usize),
+    /// Expected {0} trace polynomials, but received {1}
+    InconsistentTracePolyCount(usize, usize),
+    /// The field used by the prover is not the field extension specified in the proof options
+    InconsistentFieldExtension,
+    /// The hash function used by the prover is not the hash function specified in the proof options
+    InconsistentHashFunction,
+    /// Periodic columns of the computation differ from the ones the prover was instantiated for
+    InconsistentPeriodicColumns,
+    /// The provided Merkle trees do not commit to the extended execution trace
+    InconsistentTraceCommitment,
 }
 
 /// Represents an error thrown by the verifier during an execution of the protocol
@@ -28,12 +50,38 @@ pub enum VerifierError {
     ConstraintQueryDoesNotMatchCommitment,
     /// Constraint query deserialization failed
     ConstraintQueryDeserializationFailed,
+    /// Proof commitments deserialization failed
+    CommitmentDeserializationFailed,
     /// Query seed proof-of-work verification failed
     QuerySeedProofOfWorkVerificationFailed,
     /// Out-of-domain frame deserialization failed
     OodFrameDeserializationFailed,
     /// Computation context deserialization failed
     ComputationContextDeserializationFailed,
+    /// The hash function specified in the proof cannot be used with the base field of the computation
+    UnsupportedHashFunction,
+    /// Transcripts can be read only from proofs generated without field extension
+    UnsupportedFieldExtension,
+    /// Transcripts can be read only from proofs with a single trace segment and without preprocessed columns
+    UnsupportedTraceLayout,
+    /// FRI folding factor {0} is not supported
+    UnsupportedFoldingFactor(usize),
+    /// The proof does not contain any queries
+    NoQueries,
+    /// The computation has preprocessed columns, but no verifying key was provided
+    MissingVerifyingKey,
+    /// The verifying key was generated for a different instance of the computation
+    IncompatibleVerifyingKey,
+    /// The computation was not split into any segments
+    NoSegments,
+    /// Initial state of the first segment does not match the initial state of the computation
+    InitialStateMismatch,
+    /// Final state of the last segment does not match the final state of the computation
+    FinalStateMismatch,
+    /// Initial state of segment {0} does not match the final state of the preceding segment
+    SegmentsNotLinked(usize),
+    /// Verification of segment {0} failed: {1}
+    SegmentVerificationFailed(usize, Box<VerifierError>),
 }
 
 /// Represents an error thrown during evaluation
@@ -48,3 +96,14 @@ pub enum AssertionError {
     /// expected trace length to be exactly {0}, but was {1}
     TraceLengthNotExact(usize, usize),
 }
+
+/// Represents an error thrown during deserialization of STARK proof components
+#[derive(Debug, Display, Error, PartialEq)]
+pub enum ProofSerializationError {
+    /// failed to deserialize commitments: {0}
+    InvalidCommitments(DigestSerializationError),
+    /// failed to deserialize Merkle authentication paths: {0}
+    InvalidMerklePaths(DigestSerializationError),
+    /// failed to deserialize query values: {0}
+    InvalidQueryValues(SerializationError),
+}
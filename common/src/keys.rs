@@ -0,0 +1,63 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{ComputationContext, HashFunction};
+use crypto::{
+    hash::{digests_as_bytes, read_digests_into_vec},
+    DigestSerializationError, Hasher,
+};
+use serde::{Deserialize, Serialize};
+
+// VERIFYING KEY
+// ================================================================================================
+
+/// Commitment to the preprocessed columns of a computation, together with the parameters the
+/// commitment was computed for.
+///
+/// The key is generated once during setup, and can then be used to verify any number of proofs
+/// for the same computation. The cap of the Merkle tree built from the low-degree extension of
+/// the preprocessed columns is stored in serialized form so that the key does not depend on the
+/// digest type of the hash function; use `parse_cap()` to convert it into digests.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct VerifyingKey {
+    pub trace_length: usize,
+    pub lde_blowup_factor: usize,
+    pub hash_fn: HashFunction,
+    pub merkle_cap_height: usize,
+    pub num_columns: usize,
+    pub preprocessed_cap: Vec<u8>,
+}
+
+impl VerifyingKey {
+    /// Returns a new verifying key for a computation described by the specified `context`; the
+    /// `preprocessed_cap` is the cap of the Merkle tree committing to the preprocessed columns.
+    pub fn new<H: Hasher>(context: &ComputationContext, preprocessed_cap: &[H::Digest]) -> Self {
+        VerifyingKey {
+            trace_length: context.trace_length(),
+            lde_blowup_factor: context.lde_blowup_factor(),
+            hash_fn: context.options().hash_fn(),
+            merkle_cap_height: context.options().merkle_cap_height(),
+            num_columns: context.num_preprocessed_columns(),
+            preprocessed_cap: digests_as_bytes(preprocessed_cap),
+        }
+    }
+
+    /// Returns true if this key can be used with the computation described by the specified
+    /// `context`; that is, if the key was generated for the same trace length, number of
+    /// preprocessed columns, blowup factor, hash function, and Merkle tree cap height.
+    pub fn is_compatible_with(&self, context: &ComputationContext) -> bool {
+        self.trace_length == context.trace_length()
+            && self.lde_blowup_factor == context.lde_blowup_factor()
+            && self.hash_fn as u8 == context.options().hash_fn() as u8
+            && self.merkle_cap_height == context.options().merkle_cap_height()
+            && self.num_columns == context.num_preprocessed_columns()
+    }
+
+    /// Parses the serialized commitment into a Merkle tree cap using the digest type of the hash
+    /// function specified by the `H` generic parameter.
+    pub fn parse_cap<H: Hasher>(&self) -> Result<Vec<H::Digest>, DigestSerializationError> {
+        read_digests_into_vec(&self.preprocessed_cap)
+    }
+}
@@ -10,14 +10,21 @@ pub mod utils;
 mod context;
 pub use context::ComputationContext;
 
+mod keys;
+pub use keys::VerifyingKey;
+
 mod options;
 pub use options::{FieldExtension, HashFunction, ProofOptions};
 
 mod air;
 pub use air::{
     Air, Assertion, BoundaryConstraint, BoundaryConstraintGroup, ConstraintDivisor,
-    EvaluationFrame, TraceInfo, TransitionConstraintDegree, TransitionConstraintGroup,
+    EvaluationFrame, LinkConstraints, NoLinks, ProductAir, ProductInputs, TraceInfo,
+    TransitionConstraintDegree, TransitionConstraintGroup,
 };
 
 mod random;
 pub use random::{CompositionCoefficients, PublicCoin};
+
+mod continuation;
+pub use continuation::SegmentBoundary;
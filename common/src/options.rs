@@ -4,34 +4,53 @@
 // LICENSE file in the root directory of this source tree.
 
 use fri::FriOptions;
-use math::field::StarkField;
+use math::field::{f128, f62, StarkField};
 use serde::{Deserialize, Serialize};
 
 // TYPES AND INTERFACES
 // ================================================================================================
 
 #[repr(u8)]
-#[derive(Copy, Clone, Serialize, Deserialize)]
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FieldExtension {
     None = 1,
     Quadratic = 2,
 }
 
 #[repr(u8)]
-#[derive(Copy, Clone, Serialize, Deserialize)]
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HashFunction {
     Blake3_256 = 1,
     Sha3_256 = 2,
+    /// Rescue Prime hash function over the 62-bit field; can be used only for computations
+    /// defined over this field.
+    Rp62_248 = 3,
+    /// Rescue Prime hash function over the 128-bit field; can be used only for computations
+    /// defined over this field.
+    Rp128_256 = 4,
+    /// Poseidon hash function over the 62-bit field; can be used only for computations defined
+    /// over this field.
+    Poseidon62_248 = 5,
+    /// Poseidon hash function over the 128-bit field; can be used only for computations defined
+    /// over this field.
+    Poseidon128_256 = 6,
+    /// BLAKE3 hash function with output truncated to 192 bits; reduces proof size at the expense
+    /// of limiting proof security to 96 bits.
+    Blake3_192 = 7,
+    /// BLAKE3 hash function with output truncated to 160 bits; reduces proof size at the expense
+    /// of limiting proof security to 80 bits.
+    Blake3_160 = 8,
 }
 
 // TODO: validate field values on de-serialization
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProofOptions {
     num_queries: u8,
     blowup_factor: u8, // stored as power of 2
     grinding_factor: u8,
     hash_fn: HashFunction,
     field_extension: FieldExtension,
+    merkle_cap_height: u8,
 }
 
 // PROOF OPTIONS IMPLEMENTATION
@@ -44,7 +63,8 @@ impl ProofOptions {
     /// * num_queries must be an integer between 1 and 128;
     /// * blowup_factor must be an integer which is a power of two between 4 and 256;
     /// * grinding_factor must be an integer between 0 and 32;
-    /// * hash_fn must be blake3 or sha3 functions from crypto crate;
+    /// * hash_fn must be one of the hash functions from crypto crate; algebraic hash functions
+    ///   (e.g. Rescue Prime) must be defined over the base field of the computation;
     pub fn new(
         num_queries: usize,
         blowup_factor: usize,
@@ -76,9 +96,27 @@ impl ProofOptions {
             grinding_factor: grinding_factor as u8,
             hash_fn,
             field_extension,
+            merkle_cap_height: 0,
         }
     }
 
+    /// Returns these options with Merkle tree commitments made to caps of the specified height
+    /// rather than to tree roots; cap_height must be an integer between 0 and 16.
+    ///
+    /// A cap of height k consists of the 2^k nodes located k levels below the root of a tree.
+    /// Committing to a cap makes every Merkle authentication path in the proof k nodes shorter
+    /// at the cost of including the 2^k cap nodes into the proof; this reduces proof size when
+    /// the number of queries is large relative to 2^k. For trees of depth smaller than or equal
+    /// to the cap height, the cap is made of the nodes right above the leaves.
+    pub fn with_merkle_cap_height(mut self, cap_height: usize) -> ProofOptions {
+        assert!(
+            cap_height <= 16,
+            "merkle_cap_height cannot be greater than 16"
+        );
+        self.merkle_cap_height = cap_height as u8;
+        self
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -121,6 +159,12 @@ impl ProofOptions {
         self.field_extension
     }
 
+    /// Returns the height of Merkle tree caps used to commit to the execution trace, constraint
+    /// evaluations, and FRI layers. Height 0 means that trees are committed to via their roots.
+    pub fn merkle_cap_height(&self) -> usize {
+        self.merkle_cap_height as usize
+    }
+
     /// Returns the offset by which the low-degree extension domain is shifted in relation to the
     /// trace domain. Currently, this is hard-coded to the generator of the underlying base field.
     pub fn domain_offset<B: StarkField>(&self) -> B {
@@ -130,6 +174,38 @@ impl ProofOptions {
     /// Returns options for FRI protocol instantiated with parameters from this proof options.
     pub fn to_fri_options<B: StarkField>(&self) -> FriOptions<B> {
         FriOptions::new(self.blowup_factor(), self.domain_offset())
+            .with_merkle_cap_height(self.merkle_cap_height())
+    }
+}
+
+// HASH FUNCTION IMPLEMENTATION
+// ================================================================================================
+
+impl HashFunction {
+    /// Returns `true` if this hash function can be used to generate proofs for computations
+    /// defined over field `B`. Byte-oriented hash functions can be used with any field, while
+    /// algebraic hash functions can be used only with the field they are defined over.
+    pub fn supports_field<B: StarkField>(&self) -> bool {
+        match self {
+            Self::Blake3_256 | Self::Blake3_192 | Self::Blake3_160 | Self::Sha3_256 => true,
+            Self::Rp62_248 | Self::Poseidon62_248 => {
+                B::get_modulus_le_bytes() == f62::BaseElement::get_modulus_le_bytes()
+            }
+            Self::Rp128_256 | Self::Poseidon128_256 => {
+                B::get_modulus_le_bytes() == f128::BaseElement::get_modulus_le_bytes()
+            }
+        }
+    }
+
+    /// Returns the number of bits of collision resistance provided by this hash function; this is
+    /// half the size of the hash function's output.
+    pub fn collision_resistance(&self) -> u32 {
+        match self {
+            Self::Blake3_256 | Self::Sha3_256 | Self::Rp128_256 | Self::Poseidon128_256 => 128,
+            Self::Rp62_248 | Self::Poseidon62_248 => 124,
+            Self::Blake3_192 => 96,
+            Self::Blake3_160 => 80,
+        }
     }
 }
 
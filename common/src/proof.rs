@@ -3,12 +3,21 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{FieldExtension, ProofOptions};
-use crypto::{BatchMerkleProof, Hasher};
+use crate::{errors::ProofSerializationError, FieldExtension, ProofOptions};
+use crypto::{
+    hash::{digests_as_bytes, read_digests_into_vec},
+    BatchMerkleProof, Digest, Hasher,
+};
 use fri::FriProof;
-use math::{field::FieldElement, utils::log2};
+use math::{
+    field::FieldElement,
+    utils::{log2, read_elements_into_vec},
+};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "concurrent")]
+use rayon::prelude::*;
+
 // CONSTANTS
 // ================================================================================================
 
@@ -21,9 +30,9 @@ const GRINDING_CONTRIBUTION_FLOOR: u32 = 80;
 #[derive(Clone, Serialize, Deserialize)]
 pub struct StarkProof {
     pub context: Context,
-    pub commitments: Commitments,
-    pub trace_queries: Queries,
-    pub constraint_queries: Queries,
+    pub commitments: SerializedCommitments,
+    pub trace_queries: Vec<SerializedQueries>,
+    pub constraint_queries: SerializedQueries,
     pub ood_frame: OodEvaluationFrame,
     pub fri_proof: FriProof,
     pub pow_nonce: u64,
@@ -38,16 +47,37 @@ pub struct Context {
     pub options: ProofOptions,
 }
 
+/// Commitments made by the prover during the commit phase of the protocol. Each commitment is
+/// a cap of the corresponding Merkle tree (a cap of height 0 contains only the root of the tree);
+/// there is one trace commitment for every trace segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commitments<D: Digest> {
+    pub trace_caps: Vec<Vec<D>>,
+    pub constraint_cap: Vec<D>,
+    pub fri_caps: Vec<Vec<D>>,
+}
+
+/// Commitments in serialized form; the proof stores commitments in this form so that it does
+/// not depend on the digest type of the hash function used to generate it.
 #[derive(Clone, Serialize, Deserialize)]
-pub struct Commitments {
-    pub trace_root: [u8; 32],
-    pub constraint_root: [u8; 32],
-    pub fri_roots: Vec<[u8; 32]>,
+pub struct SerializedCommitments {
+    pub trace_caps: Vec<Vec<u8>>,
+    pub constraint_cap: Vec<u8>,
+    pub fri_caps: Vec<Vec<u8>>,
+}
+
+/// Decommitments to the queried values: nodes of a batch Merkle proof for the queried leaves,
+/// and the values hashed into these leaves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Queries<D: Digest> {
+    pub paths: Vec<Vec<D>>,
+    pub values: Vec<Vec<u8>>,
 }
 
+/// Decommitments in serialized form; Merkle authentication path nodes are stored as bytes.
 #[derive(Clone, Serialize, Deserialize)]
-pub struct Queries {
-    pub paths: Vec<Vec<[u8; 32]>>,
+pub struct SerializedQueries {
+    pub paths: Vec<Vec<u8>>,
     pub values: Vec<Vec<u8>>,
 }
 
@@ -91,8 +121,7 @@ impl StarkProof {
         }
 
         // Provided by the collision resistance (CR) of the hash function we use
-        // TODO: make this dynamic based on the hash function used
-        let cr_security = 128;
+        let cr_security = options.hash_fn().collision_resistance();
 
         // determine number of bits in the field modulus
         let field_modulus_bits = get_num_modulus_bits(&self.context.field_modulus_bytes);
@@ -109,13 +138,64 @@ impl StarkProof {
     }
 }
 
+// COMMITMENTS IMPLEMENTATION
+// ================================================================================================
+
+impl<D: Digest> Commitments<D> {
+    /// Returns a set of commitments consisting of the provided Merkle tree caps.
+    pub fn new(trace_caps: Vec<Vec<D>>, constraint_cap: Vec<D>, fri_caps: Vec<Vec<D>>) -> Self {
+        Commitments {
+            trace_caps,
+            constraint_cap,
+            fri_caps,
+        }
+    }
+
+    /// Serializes these commitments into a sequence of bytes for each of the caps.
+    pub fn serialize(&self) -> SerializedCommitments {
+        SerializedCommitments {
+            trace_caps: self
+                .trace_caps
+                .iter()
+                .map(|cap| digests_as_bytes(cap))
+                .collect(),
+            constraint_cap: digests_as_bytes(&self.constraint_cap),
+            fri_caps: self
+                .fri_caps
+                .iter()
+                .map(|cap| digests_as_bytes(cap))
+                .collect(),
+        }
+    }
+
+    /// Deserializes commitments from the provided `source`; returns an error if any of the caps
+    /// could not be deserialized into digests of type `D`.
+    pub fn deserialize(source: &SerializedCommitments) -> Result<Self, ProofSerializationError> {
+        Ok(Commitments {
+            trace_caps: parse_caps(&source.trace_caps)?,
+            constraint_cap: read_digests_into_vec(&source.constraint_cap)
+                .map_err(ProofSerializationError::InvalidCommitments)?,
+            fri_caps: parse_caps(&source.fri_caps)?,
+        })
+    }
+}
+
 // QUERY PROOFS IMPLEMENTATION
 // ================================================================================================
 
-impl Queries {
+impl<D: Digest> Queries<D> {
     /// Returns a set of queries constructed from a batch Merkle proof and corresponding values.
-    pub fn new<E: FieldElement>(merkle_proof: BatchMerkleProof, values: Vec<Vec<E>>) -> Self {
-        // TODO: add debug check that values actually hash into the leaf nodes of the batch proof
+    pub fn new<H: Hasher<Digest = D>, E: FieldElement>(
+        merkle_proof: BatchMerkleProof<H>,
+        values: Vec<Vec<E>>,
+    ) -> Self {
+        debug_assert!(
+            values
+                .iter()
+                .zip(merkle_proof.values.iter())
+                .all(|(v, leaf)| H::hash_elements(v) == *leaf),
+            "query values do not hash into the leaf nodes of the batch proof"
+        );
         Queries {
             paths: merkle_proof.nodes,
             values: values
@@ -125,28 +205,78 @@ impl Queries {
         }
     }
 
-    /// Convert a set of queries into a batch Merkle proof and corresponding values.
-    /// TODO: return values as a vector of field elements
-    pub fn into_batch<H: Hasher>(self, num_leaves: usize) -> (BatchMerkleProof, Vec<Vec<u8>>) {
-        let hash_fn = H::hash_fn();
-        let mut hashed_values = vec![[0u8; 32]; self.values.len()];
-        for (trace_state, state_hash) in self.values.iter().zip(hashed_values.iter_mut()) {
-            hash_fn(trace_state, state_hash);
+    /// Serializes these queries; Merkle authentication path nodes are serialized into a
+    /// sequence of bytes for each layer of the batch proof.
+    pub fn serialize(&self) -> SerializedQueries {
+        SerializedQueries {
+            paths: self
+                .paths
+                .iter()
+                .map(|nodes| digests_as_bytes(nodes))
+                .collect(),
+            values: self.values.clone(),
         }
+    }
+
+    /// Deserializes queries from the provided `source`; returns an error if Merkle authentication
+    /// path nodes could not be deserialized into digests of type `D`.
+    pub fn deserialize(source: &SerializedQueries) -> Result<Self, ProofSerializationError> {
+        let paths = source
+            .paths
+            .iter()
+            .map(|bytes| read_digests_into_vec(bytes))
+            .collect::<Result<_, _>>()
+            .map_err(ProofSerializationError::InvalidMerklePaths)?;
+        Ok(Queries {
+            paths,
+            values: source.values.clone(),
+        })
+    }
+
+    /// Convert a set of queries into a batch Merkle proof and corresponding values. The values
+    /// are hashed into leaves of the batch proof using the hash function specified by the `H`
+    /// generic parameter; when `concurrent` feature is enabled, the values are hashed in
+    /// multiple threads.
+    #[allow(clippy::type_complexity)]
+    pub fn into_batch<H: Hasher<Digest = D>, E: FieldElement>(
+        self,
+        num_leaves: usize,
+    ) -> Result<(BatchMerkleProof<H>, Vec<Vec<E>>), ProofSerializationError> {
+        let mut values = Vec::with_capacity(self.values.len());
+        for value_bytes in self.values.iter() {
+            let elements = read_elements_into_vec::<E>(value_bytes)
+                .map_err(ProofSerializationError::InvalidQueryValues)?;
+            values.push(elements);
+        }
+
+        #[cfg(not(feature = "concurrent"))]
+        let hashed_values = values.iter().map(|v| H::hash_elements(v)).collect();
+
+        #[cfg(feature = "concurrent")]
+        let hashed_values = values.par_iter().map(|v| H::hash_elements(v)).collect();
 
         let merkle_proof = BatchMerkleProof {
-            nodes: self.paths,
             values: hashed_values,
+            nodes: self.paths,
             depth: log2(num_leaves) as u8,
         };
 
-        (merkle_proof, self.values)
+        Ok((merkle_proof, values))
     }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Deserializes each of the provided byte vectors into a Merkle tree cap.
+fn parse_caps<D: Digest>(caps: &[Vec<u8>]) -> Result<Vec<Vec<D>>, ProofSerializationError> {
+    caps.iter()
+        .map(|cap_bytes| {
+            read_digests_into_vec(cap_bytes).map_err(ProofSerializationError::InvalidCommitments)
+        })
+        .collect()
+}
+
 /// Returns number of bits in the provided modulus; the modulus is assumed to be encoded in
 /// little-endian byte order
 fn get_num_modulus_bits(modulus_bytes: &[u8]) -> u32 {
@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::ComputationContext;
-use crypto::{Hasher, RandomElementGenerator};
+use crypto::{Digest, Hasher, RandomElementGenerator};
 use math::field::FieldElement;
 use std::{convert::TryInto, mem::size_of};
 
@@ -22,27 +22,28 @@ const COMPOSITION_COEFF_OFFSET: u64 = 1024;
 // ================================================================================================
 
 pub trait PublicCoin: fri::PublicCoin {
-    type Hasher: Hasher;
-
     // ABSTRACT METHODS
     // --------------------------------------------------------------------------------------------
 
     fn context(&self) -> &ComputationContext;
-    fn constraint_seed(&self) -> [u8; 32];
-    fn composition_seed(&self) -> [u8; 32];
-    fn query_seed(&self) -> [u8; 32];
+    fn constraint_seed(&self) -> <Self::Hasher as Hasher>::Digest;
+    fn composition_seed(&self) -> <Self::Hasher as Hasher>::Digest;
+    fn query_seed(&self) -> <Self::Hasher as Hasher>::Digest;
 
     // PRNG BUILDERS
     // --------------------------------------------------------------------------------------------
 
     /// Returns a PRNG for transition constraint coefficients.
     fn get_transition_coefficient_prng(&self) -> Self::RandomElementGenerator {
-        Self::RandomElementGenerator::new(self.constraint_seed(), TRANSITION_COEFF_OFFSET)
+        Self::RandomElementGenerator::new(
+            self.constraint_seed().as_bytes(),
+            TRANSITION_COEFF_OFFSET,
+        )
     }
 
     /// Returns a PRNG for boundary constraint coefficients.
     fn get_boundary_coefficient_prng(&self) -> Self::RandomElementGenerator {
-        Self::RandomElementGenerator::new(self.constraint_seed(), BOUNDARY_COEFF_OFFSET)
+        Self::RandomElementGenerator::new(self.constraint_seed().as_bytes(), BOUNDARY_COEFF_OFFSET)
     }
 
     // DRAW METHODS
@@ -50,33 +51,38 @@ pub trait PublicCoin: fri::PublicCoin {
 
     /// Draws a point from the entire field using PRNG seeded with composition seed.
     fn draw_deep_point<E: FieldElement>(&self) -> E {
-        let mut generator =
-            Self::RandomElementGenerator::new(self.composition_seed(), DEEP_POINT_OFFSET);
+        let mut generator = Self::RandomElementGenerator::new(
+            self.composition_seed().as_bytes(),
+            DEEP_POINT_OFFSET,
+        );
         generator.draw()
     }
 
     /// Draws coefficients for building composition polynomial using PRNG seeded with
     /// composition seed.
     fn draw_composition_coefficients<E: FieldElement>(&self) -> CompositionCoefficients<E> {
-        let generator =
-            Self::RandomElementGenerator::new(self.composition_seed(), COMPOSITION_COEFF_OFFSET);
-        CompositionCoefficients::new(generator, self.context().trace_width())
+        let generator = Self::RandomElementGenerator::new(
+            self.composition_seed().as_bytes(),
+            COMPOSITION_COEFF_OFFSET,
+        );
+        CompositionCoefficients::new(generator, self.context().frame_width())
     }
 
     /// Draws a set of unique query positions using PRNG seeded with query seed. The positions
     /// are selected from the range [0, lde_domain_size).
     fn draw_query_positions(&self) -> Vec<usize> {
-        let hash_fn = Self::Hasher::hash_fn();
         let num_queries = self.context().options().num_queries();
 
         // determine how many bits are needed to represent valid indexes in the domain
         let value_mask = self.context().lde_domain_size() - 1;
-        let value_offset = 32 - size_of::<usize>();
+        // values are read from the end of the digest; truncated digests are padded with zeros
+        // when serialized, and thus the padding must be skipped
+        let digest_size = <<Self::Hasher as Hasher>::Digest as Digest>::SIZE;
+        let value_offset = digest_size - size_of::<usize>();
 
         // initialize the seed for PRNG
         let mut seed = [0u8; 64];
-        seed[..32].copy_from_slice(&self.query_seed());
-        let mut value_bytes = [0u8; 32];
+        seed[..32].copy_from_slice(&self.query_seed().as_bytes());
 
         // draw values from PRNG until we get as many unique values as specified by
         // num_queries, but skipping values which are a multiple of blowup factor
@@ -84,11 +90,11 @@ pub trait PublicCoin: fri::PublicCoin {
         for i in 0usize..1000 {
             // update the seed with the new counter and hash the result
             seed[56..].copy_from_slice(&i.to_le_bytes());
-            hash_fn(&seed, &mut value_bytes);
+            let value_bytes = Self::Hasher::hash(&seed).as_bytes();
 
             // read the required number of bits from the hashed value
             let value =
-                usize::from_le_bytes(value_bytes[value_offset..].try_into().unwrap()) & value_mask;
+                usize::from_le_bytes(value_bytes[value_offset..digest_size].try_into().unwrap()) & value_mask;
 
             if result.contains(&value) {
                 continue;
@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use core::mem;
-use crypto::Hasher;
+use crypto::{Digest, Hasher};
 use math::field::FieldElement;
 
 // CONSTRAINT COMMITMENTS
@@ -38,3 +38,19 @@ pub fn map_trace_to_constraint_positions(
     }
     result
 }
+
+// TRACE COMMITMENTS
+// ================================================================================================
+
+/// Combines roots of trace segment trees into a single digest; when the trace consists of a
+/// single segment, the root of the segment tree is returned as is.
+pub fn merge_trace_roots<H: Hasher>(trace_roots: &[H::Digest]) -> H::Digest {
+    if trace_roots.len() == 1 {
+        return trace_roots[0];
+    }
+    let mut root_bytes: Vec<u8> = Vec::with_capacity(trace_roots.len() * 32);
+    for root in trace_roots.iter() {
+        root_bytes.extend_from_slice(&root.as_bytes());
+    }
+    H::hash(&root_bytes)
+}
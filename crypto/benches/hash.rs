@@ -4,7 +4,11 @@
 // LICENSE file in the root directory of this source tree.
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use winter_crypto::hash;
+use math::field::{f128, f62};
+use winter_crypto::{
+    hash::{Blake3_256, Poseidon128_256, Poseidon62_248, Rp128_256, Rp62_248, Sha3_256},
+    Hasher,
+};
 
 pub fn blake3(c: &mut Criterion) {
     let v: [u8; 64] = [
@@ -12,9 +16,8 @@ pub fn blake3(c: &mut Criterion) {
         26, 27, 28, 29, 30, 31, 32, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
         19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
     ];
-    let mut r = [0u8; 32];
     c.bench_function("hash_blake3", |bench| {
-        bench.iter(|| hash::blake3(black_box(&v), black_box(&mut r)))
+        bench.iter(|| Blake3_256::hash(black_box(&v)))
     });
 }
 
@@ -24,11 +27,58 @@ pub fn sha3(c: &mut Criterion) {
         26, 27, 28, 29, 30, 31, 32, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
         19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
     ];
-    let mut r = [0u8; 32];
     c.bench_function("hash_sha3", |bench| {
-        bench.iter(|| hash::sha3(black_box(&v), black_box(&mut r)))
+        bench.iter(|| Sha3_256::hash(black_box(&v)))
     });
 }
 
-criterion_group!(hash_group, blake3, sha3);
+pub fn rp62_248(c: &mut Criterion) {
+    let v = [
+        Rp62_248::hash_elements(&[f62::BaseElement::new(1)]),
+        Rp62_248::hash_elements(&[f62::BaseElement::new(2)]),
+    ];
+    c.bench_function("hash_rp62_248 (merge)", |bench| {
+        bench.iter(|| Rp62_248::merge(black_box(&v)))
+    });
+}
+
+pub fn rp128_256(c: &mut Criterion) {
+    let v = [
+        Rp128_256::hash_elements(&[f128::BaseElement::new(1)]),
+        Rp128_256::hash_elements(&[f128::BaseElement::new(2)]),
+    ];
+    c.bench_function("hash_rp128_256 (merge)", |bench| {
+        bench.iter(|| Rp128_256::merge(black_box(&v)))
+    });
+}
+
+pub fn poseidon62_248(c: &mut Criterion) {
+    let v = [
+        Poseidon62_248::hash_elements(&[f62::BaseElement::new(1)]),
+        Poseidon62_248::hash_elements(&[f62::BaseElement::new(2)]),
+    ];
+    c.bench_function("hash_poseidon62_248 (merge)", |bench| {
+        bench.iter(|| Poseidon62_248::merge(black_box(&v)))
+    });
+}
+
+pub fn poseidon128_256(c: &mut Criterion) {
+    let v = [
+        Poseidon128_256::hash_elements(&[f128::BaseElement::new(1)]),
+        Poseidon128_256::hash_elements(&[f128::BaseElement::new(2)]),
+    ];
+    c.bench_function("hash_poseidon128_256 (merge)", |bench| {
+        bench.iter(|| Poseidon128_256::merge(black_box(&v)))
+    });
+}
+
+criterion_group!(
+    hash_group,
+    blake3,
+    sha3,
+    rp62_248,
+    rp128_256,
+    poseidon62_248,
+    poseidon128_256
+);
 criterion_main!(hash_group);
@@ -4,9 +4,13 @@
 // LICENSE file in the root directory of this source tree.
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use math::field::f128::BaseElement;
 use rand::{rngs::ThreadRng, thread_rng, RngCore};
 use utils::uninit_vector;
-use winter_crypto::{hash, merkle, merkle2};
+use winter_crypto::{
+    hash::{Blake3_256, Rp128_256},
+    merkle, Hasher,
+};
 
 pub fn merkle_tree_construction(c: &mut Criterion) {
     let mut merkle_group = c.benchmark_group("merkle tree construction");
@@ -26,16 +30,55 @@ pub fn merkle_tree_construction(c: &mut Criterion) {
             res
         };
         merkle_group.bench_with_input(BenchmarkId::new("sequential", size), &data, |b, i| {
-            b.iter(|| merkle::build_merkle_nodes(&i, crate::hash::blake3))
+            b.iter(|| merkle::build_merkle_nodes::<Blake3_256>(&i))
         });
         merkle_group.bench_with_input(BenchmarkId::new("concurrent", size), &data, |b, i| {
-            b.iter(|| merkle::concurrent::build_merkle_nodes(&i, crate::hash::blake3))
+            b.iter(|| merkle::concurrent::build_merkle_nodes::<Blake3_256>(&i))
         });
-        merkle_group.bench_with_input(BenchmarkId::new("new", size), &data, |b, i| {
-            b.iter(|| merkle2::build_merkle_nodes::<hash::Blake3_256>(&i))
+    }
+}
+
+pub fn merkle_tree_arity(c: &mut Criterion) {
+    let mut merkle_group = c.benchmark_group("merkle tree arity");
+    merkle_group.sample_size(10);
+
+    // the number of leaves is a power of 2, 4, and 8
+    static BATCH_SIZES: [usize; 2] = [4096, 262144];
+
+    for &size in BATCH_SIZES.iter() {
+        let blake3_leaves = (0..size as u64)
+            .map(|i| Blake3_256::hash(&i.to_le_bytes()))
+            .collect::<Vec<_>>();
+        let rescue_leaves = (0..size as u128)
+            .map(|i| Rp128_256::hash_elements(&[BaseElement::new(i)]))
+            .collect::<Vec<_>>();
+
+        merkle_group.bench_with_input(
+            BenchmarkId::new("blake3/2", size),
+            &blake3_leaves,
+            |b, i| b.iter(|| merkle::build_merkle_nodes_n::<Blake3_256, 2>(i)),
+        );
+        merkle_group.bench_with_input(
+            BenchmarkId::new("blake3/4", size),
+            &blake3_leaves,
+            |b, i| b.iter(|| merkle::build_merkle_nodes_n::<Blake3_256, 4>(i)),
+        );
+        merkle_group.bench_with_input(
+            BenchmarkId::new("blake3/8", size),
+            &blake3_leaves,
+            |b, i| b.iter(|| merkle::build_merkle_nodes_n::<Blake3_256, 8>(i)),
+        );
+        merkle_group.bench_with_input(BenchmarkId::new("rp128/2", size), &rescue_leaves, |b, i| {
+            b.iter(|| merkle::build_merkle_nodes_n::<Rp128_256, 2>(i))
+        });
+        merkle_group.bench_with_input(BenchmarkId::new("rp128/4", size), &rescue_leaves, |b, i| {
+            b.iter(|| merkle::build_merkle_nodes_n::<Rp128_256, 4>(i))
+        });
+        merkle_group.bench_with_input(BenchmarkId::new("rp128/8", size), &rescue_leaves, |b, i| {
+            b.iter(|| merkle::build_merkle_nodes_n::<Rp128_256, 8>(i))
         });
     }
 }
 
-criterion_group!(merkle_group, merkle_tree_construction,);
+criterion_group!(merkle_group, merkle_tree_construction, merkle_tree_arity);
 criterion_main!(merkle_group);
@@ -0,0 +1,36 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum DigestSerializationError {
+    #[error("number of bytes ({0}) does not divide into whole number of digests")]
+    NotEnoughBytesForWholeDigests(usize),
+
+    #[error("failed to read digest from bytes at position {0}")]
+    FailedToReadDigest(usize),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MmrProofSerializationError {
+    #[error("number of bytes ({0}) is not enough to read proof header")]
+    NotEnoughBytesForHeader(usize),
+
+    #[error("proof size does not fit into usize")]
+    SizeTooLarge,
+
+    #[error("leaf index {0} is out of bounds for size {1}")]
+    InvalidLeafIndex(usize, usize),
+
+    #[error("old size {0} is greater than new size {1}")]
+    InvalidSizes(usize, usize),
+
+    #[error("expected {0} nodes in the proof, but found {1}")]
+    WrongNumberOfNodes(usize, usize),
+
+    #[error(transparent)]
+    DigestSerializationError(#[from] DigestSerializationError),
+}
@@ -3,25 +3,86 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::HashFunction;
-use core::fmt::Debug;
+use crate::errors::DigestSerializationError;
+use core::{fmt::Debug, marker::PhantomData};
 use math::field::FieldElement;
-use sha3::Digest;
+use sha3::Digest as _;
 use utils::AsBytes;
 
+mod rescue;
+pub use rescue::{rp128_256, rp62_248, Rp128_256, Rp62_248};
+
+pub mod poseidon;
+pub use poseidon::{Poseidon128_256, Poseidon62_248};
+
 // HASHER TRAIT
 // ================================================================================================
 
 pub trait Hasher {
-    type Digest: Debug + Copy + AsRef<[u8]> + Default + Eq + PartialEq;
+    type Digest: Digest;
+
+    /// Returns a hash of the provided sequence of bytes.
+    fn hash(bytes: &[u8]) -> Self::Digest;
 
+    /// Returns a hash of two digests. This method is intended for use in construction of
+    /// Merkle trees.
     fn merge(values: &[Self::Digest; 2]) -> Self::Digest;
 
+    /// Returns a hash of the provided digests. This method is intended for use in construction
+    /// of Merkle trees with arity greater than two; for two digests, the result is the same as
+    /// the result of merge(). The number of digests is not included into the hash, and thus,
+    /// all invocations within a single tree should merge the same number of digests.
+    fn merge_n(values: &[Self::Digest]) -> Self::Digest;
+
+    /// Returns a hash of the provided field elements.
     fn hash_elements<E: FieldElement>(elements: &[E]) -> Self::Digest;
+}
 
-    fn hash_fn() -> HashFunction;
+// DIGEST TRAIT
+// ================================================================================================
+
+pub trait Digest: Debug + Default + Copy + Clone + Eq + PartialEq + Send + Sync {
+    /// Number of bytes needed to serialize this digest.
+    const SIZE: usize;
+
+    /// Returns this digest serialized into an array of bytes. The first SIZE bytes of the array
+    /// contain the serialized digest; if SIZE is smaller than 32, the remaining bytes are zeros.
+    fn as_bytes(&self) -> [u8; 32];
+
+    /// Returns a digest deserialized from the provided bytes, or None if the bytes do not
+    /// encode a valid digest. The length of `bytes` must be exactly SIZE.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
 }
 
+/// Implements Digest trait for byte arrays of the specified lengths; byte arrays are used as
+/// digests of all byte-oriented hash functions.
+macro_rules! impl_byte_digest {
+    ($($size:expr),*) => {
+        $(
+            impl Digest for [u8; $size] {
+                const SIZE: usize = $size;
+
+                fn as_bytes(&self) -> [u8; 32] {
+                    let mut result = [0; 32];
+                    result[..$size].copy_from_slice(self);
+                    result
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                    if bytes.len() != Self::SIZE {
+                        return None;
+                    }
+                    let mut result = [0; $size];
+                    result.copy_from_slice(bytes);
+                    Some(result)
+                }
+            }
+        )*
+    };
+}
+
+impl_byte_digest!(20, 24, 32);
+
 // BLAKE3
 // ================================================================================================
 
@@ -31,29 +92,81 @@ pub struct Blake3_256();
 impl Hasher for Blake3_256 {
     type Digest = [u8; 32];
 
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        blake3::hash(bytes).into()
+    }
+
     fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
         blake3::hash(values.as_bytes()).into()
     }
 
+    fn merge_n(values: &[Self::Digest]) -> Self::Digest {
+        blake3::hash(values.as_bytes()).into()
+    }
+
     fn hash_elements<E: FieldElement>(elements: &[E]) -> Self::Digest {
         let bytes = E::elements_as_bytes(elements);
-        blake3::hash(&bytes).into()
+        blake3::hash(bytes).into()
+    }
+}
+
+/// BLAKE3 hash function with output truncated to 192 bits. Proofs generated with this hash
+/// function cannot provide more than 96 bits of security.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Blake3_192();
+
+impl Hasher for Blake3_192 {
+    type Digest = [u8; 24];
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        truncate(blake3::hash(bytes))
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        truncate(blake3::hash(values.as_bytes()))
+    }
+
+    fn merge_n(values: &[Self::Digest]) -> Self::Digest {
+        truncate(blake3::hash(values.as_bytes()))
+    }
+
+    fn hash_elements<E: FieldElement>(elements: &[E]) -> Self::Digest {
+        let bytes = E::elements_as_bytes(elements);
+        truncate(blake3::hash(bytes))
+    }
+}
+
+/// BLAKE3 hash function with output truncated to 160 bits. Proofs generated with this hash
+/// function cannot provide more than 80 bits of security.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Blake3_160();
+
+impl Hasher for Blake3_160 {
+    type Digest = [u8; 20];
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        truncate(blake3::hash(bytes))
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        truncate(blake3::hash(values.as_bytes()))
     }
 
-    fn hash_fn() -> HashFunction {
-        blake3
+    fn merge_n(values: &[Self::Digest]) -> Self::Digest {
+        truncate(blake3::hash(values.as_bytes()))
+    }
+
+    fn hash_elements<E: FieldElement>(elements: &[E]) -> Self::Digest {
+        let bytes = E::elements_as_bytes(elements);
+        truncate(blake3::hash(bytes))
     }
 }
 
-/// Wrapper around blake3 hash function
-pub fn blake3(values: &[u8], result: &mut [u8]) {
-    debug_assert!(
-        result.len() == 32,
-        "expected result to be exactly 32 bytes but received {}",
-        result.len()
-    );
-    let hash = blake3::hash(&values);
-    result.copy_from_slice(hash.as_bytes());
+/// Returns the first N bytes of the provided BLAKE3 hash.
+fn truncate<const N: usize>(hash: blake3::Hash) -> [u8; N] {
+    let mut result = [0; N];
+    result.copy_from_slice(&hash.as_bytes()[..N]);
+    result
 }
 
 // SHA3
@@ -64,29 +177,111 @@ pub struct Sha3_256();
 impl Hasher for Sha3_256 {
     type Digest = [u8; 32];
 
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        sha3::Sha3_256::digest(bytes).into()
+    }
+
     fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
         sha3::Sha3_256::digest(values.as_bytes()).into()
     }
 
+    fn merge_n(values: &[Self::Digest]) -> Self::Digest {
+        sha3::Sha3_256::digest(values.as_bytes()).into()
+    }
+
     fn hash_elements<E: FieldElement>(elements: &[E]) -> Self::Digest {
         let bytes = E::elements_as_bytes(elements);
         sha3::Sha3_256::digest(bytes).into()
     }
+}
+
+// DOMAIN SEPARATION
+// ================================================================================================
+
+/// Domain tag prepended to leaf data by [DomainSeparated] hasher.
+pub const LEAF_DOMAIN_TAG: u8 = 0;
+
+/// Domain tag prepended to child nodes by [DomainSeparated] hasher.
+pub const NODE_DOMAIN_TAG: u8 = 1;
+
+/// Hasher which separates hashes of leaf data from hashes of internal Merkle tree nodes.
+///
+/// For byte-oriented hash functions, [Hasher::merge()] is the same as hashing the 64-byte
+/// concatenation of the two digests. Thus, in a Merkle tree built with such a function, a
+/// 64-byte leaf is indistinguishable from an internal node. To prevent this, the hash of leaf
+/// data is computed as `H(0x00 || data)` while the hash of an internal node is computed as
+/// `H(0x01 || left || right)`, as described in RFC 6962.
+///
+/// Since all Merkle tree operations (including tree construction, concurrent tree construction,
+/// and verification of single and batch Merkle proofs) compute internal nodes via
+/// [Hasher::merge()], domain-separated trees are obtained by instantiating them with this
+/// hasher (e.g. `MerkleTree<DomainSeparated<Blake3_256>>`). Leaves of such trees must be
+/// computed using [DomainSeparated::hash()] or [DomainSeparated::hash_elements()].
+///
+/// This hasher has the following limitations:
+/// * Data and nodes are always hashed as byte strings prefixed with the domain tag; thus, when
+///   an algebraic hash function (e.g. Rescue Prime or Poseidon) is wrapped, field elements are
+///   serialized into bytes rather than absorbed as elements, and the result is a byte-oriented
+///   hash function which does not preserve the arithmetic structure of the wrapped one.
+/// * The hasher cannot be selected via `HashFunction` in proof options, and thus, it cannot be
+///   used for commitments in STARK proofs; it is meant for standalone Merkle trees.
+pub struct DomainSeparated<H: Hasher>(PhantomData<H>);
+
+impl<H: Hasher> Hasher for DomainSeparated<H> {
+    type Digest = H::Digest;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        let mut data = Vec::with_capacity(bytes.len() + 1);
+        data.push(LEAF_DOMAIN_TAG);
+        data.extend_from_slice(bytes);
+        H::hash(&data)
+    }
 
-    fn hash_fn() -> HashFunction {
-        sha3
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        let mut data = Vec::with_capacity(2 * <H::Digest as Digest>::SIZE + 1);
+        data.push(NODE_DOMAIN_TAG);
+        data.extend_from_slice(&digests_as_bytes(values));
+        H::hash(&data)
+    }
+
+    fn merge_n(values: &[Self::Digest]) -> Self::Digest {
+        let mut data = Vec::with_capacity(values.len() * <H::Digest as Digest>::SIZE + 1);
+        data.push(NODE_DOMAIN_TAG);
+        data.extend_from_slice(&digests_as_bytes(values));
+        H::hash(&data)
+    }
+
+    fn hash_elements<E: FieldElement>(elements: &[E]) -> Self::Digest {
+        Self::hash(E::elements_as_bytes(elements))
     }
 }
 
-/// Wrapper around sha3 hash function
-pub fn sha3(values: &[u8], result: &mut [u8]) {
-    debug_assert!(
-        result.len() == 32,
-        "expected result to be exactly 32 bytes but received {}",
-        result.len()
-    );
-    let mut hasher = sha3::Sha3_256::new();
-    hasher.update(&values);
-    let hash = hasher.finalize();
-    result.copy_from_slice(hash.as_ref());
+// DIGEST SERIALIZATION
+// ================================================================================================
+
+/// Serializes all digests in the provided slice into a single vector of bytes.
+pub fn digests_as_bytes<D: Digest>(digests: &[D]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(digests.len() * D::SIZE);
+    for digest in digests {
+        result.extend_from_slice(&digest.as_bytes()[..D::SIZE]);
+    }
+    result
+}
+
+/// Deserializes a vector of digests from the provided bytes. Returns an error if the bytes do
+/// not divide into a whole number of digests, or if any of the digests is invalid.
+pub fn read_digests_into_vec<D: Digest>(source: &[u8]) -> Result<Vec<D>, DigestSerializationError> {
+    if !source.len().is_multiple_of(D::SIZE) {
+        return Err(DigestSerializationError::NotEnoughBytesForWholeDigests(
+            source.len(),
+        ));
+    }
+
+    let mut result = Vec::with_capacity(source.len() / D::SIZE);
+    for (i, digest_bytes) in source.chunks(D::SIZE).enumerate() {
+        let digest = D::from_bytes(digest_bytes)
+            .ok_or(DigestSerializationError::FailedToReadDigest(i * D::SIZE))?;
+        result.push(digest);
+    }
+    Ok(result)
 }
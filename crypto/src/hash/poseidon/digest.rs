@@ -0,0 +1,70 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::hash::Digest;
+use math::field::StarkField;
+
+// ELEMENT DIGEST
+// ================================================================================================
+
+/// Digest of a Poseidon hash function; consists of `N` elements of field `B`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ElementDigest<B: StarkField, const N: usize>([B; N]);
+
+impl<B: StarkField, const N: usize> ElementDigest<B, N> {
+    pub fn new(value: [B; N]) -> Self {
+        Self(value)
+    }
+
+    pub fn as_elements(&self) -> &[B] {
+        &self.0
+    }
+
+    /// Returns a digest built from the first N elements of the provided sponge state.
+    pub(super) fn from_state(state: &[B]) -> Self {
+        let mut result = [B::ZERO; N];
+        result.copy_from_slice(&state[..N]);
+        Self(result)
+    }
+}
+
+impl<B: StarkField, const N: usize> Default for ElementDigest<B, N> {
+    fn default() -> Self {
+        Self([B::ZERO; N])
+    }
+}
+
+impl<B: StarkField, const N: usize> Digest for ElementDigest<B, N> {
+    const SIZE: usize = N * B::ELEMENT_BYTES;
+
+    fn as_bytes(&self) -> [u8; 32] {
+        assert!(
+            Self::SIZE <= 32,
+            "digest cannot be serialized into 32 bytes"
+        );
+        let mut result = [0; 32];
+        for (chunk, element) in result.chunks_mut(B::ELEMENT_BYTES).zip(&self.0) {
+            chunk.copy_from_slice(&element.to_canonical_bytes());
+        }
+        result
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::SIZE {
+            return None;
+        }
+        let mut result = [B::ZERO; N];
+        for (element, chunk) in result.iter_mut().zip(bytes.chunks(B::ELEMENT_BYTES)) {
+            *element = B::try_from(chunk).ok()?;
+        }
+        Some(Self(result))
+    }
+}
+
+impl<B: StarkField, const N: usize> From<[B; N]> for ElementDigest<B, N> {
+    fn from(value: [B; N]) -> Self {
+        Self(value)
+    }
+}
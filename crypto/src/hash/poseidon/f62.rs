@@ -0,0 +1,791 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::PoseidonParameters;
+use math::field::f62::BaseElement;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Sponge state is set to 12 field elements; 8 elements are reserved for rate and 4
+/// elements are reserved for capacity.
+const STATE_WIDTH: usize = 12;
+const RATE_WIDTH: usize = 8;
+
+/// The number of rounds was computed using the script from the Poseidon reference implementation
+/// for 128-bit security level, S-Box x^3, and includes the security margin recommended by the
+/// authors (two more full rounds and 7.5% more partial rounds).
+const NUM_FULL_ROUNDS: usize = 8;
+const NUM_PARTIAL_ROUNDS: usize = 41;
+const NUM_ROUNDS: usize = NUM_FULL_ROUNDS + NUM_PARTIAL_ROUNDS;
+
+// PARAMETERS
+// ================================================================================================
+
+/// Parameters of Poseidon permutation over the 62-bit field.
+pub struct Poseidon62Parameters;
+
+impl PoseidonParameters for Poseidon62Parameters {
+    type BaseField = BaseElement;
+
+    const STATE_WIDTH: usize = STATE_WIDTH;
+    const RATE_WIDTH: usize = RATE_WIDTH;
+    const ALPHA: u32 = 3;
+    const NUM_FULL_ROUNDS: usize = NUM_FULL_ROUNDS;
+    const NUM_PARTIAL_ROUNDS: usize = NUM_PARTIAL_ROUNDS;
+
+    fn round_constants() -> &'static [BaseElement] {
+        &ARK
+    }
+
+    fn mds() -> &'static [BaseElement] {
+        &MDS
+    }
+}
+
+// ROUND CONSTANTS
+// ================================================================================================
+// generated using generate_constants() from the grain module; the test for this module makes
+// sure that the values below match the output of the generator.
+
+/// Round constants for all rounds of the permutation, arranged round by round.
+const ARK: [BaseElement; STATE_WIDTH * NUM_ROUNDS] = [
+    BaseElement::new(216820512640598552),
+    BaseElement::new(2241240026723100927),
+    BaseElement::new(1211035622153972142),
+    BaseElement::new(4199846088540492839),
+    BaseElement::new(1991691809195515247),
+    BaseElement::new(4097410792422816960),
+    BaseElement::new(4137159926184568439),
+    BaseElement::new(3922631941324133789),
+    BaseElement::new(998486067071206355),
+    BaseElement::new(1572354697157144662),
+    BaseElement::new(1362429057080894793),
+    BaseElement::new(2090822334619367353),
+    BaseElement::new(759276745644300708),
+    BaseElement::new(2142469674344658545),
+    BaseElement::new(3967069609859097874),
+    BaseElement::new(1118734175748608934),
+    BaseElement::new(1136487295147373144),
+    BaseElement::new(3471779373560593162),
+    BaseElement::new(339912115558373957),
+    BaseElement::new(2781460048395041160),
+    BaseElement::new(4289646642183961879),
+    BaseElement::new(3733271281268599880),
+    BaseElement::new(1078685343774315153),
+    BaseElement::new(579635201239826717),
+    BaseElement::new(369772078947090636),
+    BaseElement::new(1284101319085318667),
+    BaseElement::new(1690955807196822669),
+    BaseElement::new(901448562034940019),
+    BaseElement::new(824124828143828226),
+    BaseElement::new(2867957037207471634),
+    BaseElement::new(285530774077979974),
+    BaseElement::new(1699650960629865257),
+    BaseElement::new(1723752927457525453),
+    BaseElement::new(1784240029632919911),
+    BaseElement::new(3724144635263778394),
+    BaseElement::new(949419090792460733),
+    BaseElement::new(2087484482639615334),
+    BaseElement::new(198328720845841202),
+    BaseElement::new(2188185904580288777),
+    BaseElement::new(58425521165502993),
+    BaseElement::new(1329639779788030676),
+    BaseElement::new(1314017476096420391),
+    BaseElement::new(2496136626214013894),
+    BaseElement::new(4119963522911414797),
+    BaseElement::new(4229270118796706393),
+    BaseElement::new(3446802500559447515),
+    BaseElement::new(699419065306151394),
+    BaseElement::new(2069556846713567887),
+    BaseElement::new(2170882576246664310),
+    BaseElement::new(589820238150185104),
+    BaseElement::new(415271102753228867),
+    BaseElement::new(1676754981978377777),
+    BaseElement::new(2261445289807903996),
+    BaseElement::new(1869198467136544097),
+    BaseElement::new(3986689045151291838),
+    BaseElement::new(1454156807813037723),
+    BaseElement::new(2785266583632890023),
+    BaseElement::new(3553541804930647942),
+    BaseElement::new(2081455664379544134),
+    BaseElement::new(4310520410992020726),
+    BaseElement::new(713955166430912729),
+    BaseElement::new(2515956704371482514),
+    BaseElement::new(1511796088193819817),
+    BaseElement::new(3456303137193970638),
+    BaseElement::new(1665911991432308820),
+    BaseElement::new(786824710638111314),
+    BaseElement::new(184161588985384566),
+    BaseElement::new(2602762805379327416),
+    BaseElement::new(283237799975428405),
+    BaseElement::new(3369981035028911367),
+    BaseElement::new(4303745546760801223),
+    BaseElement::new(3351908659995899640),
+    BaseElement::new(3607672272683396951),
+    BaseElement::new(3991244109762436105),
+    BaseElement::new(3364091596183185174),
+    BaseElement::new(1227669388656588252),
+    BaseElement::new(3825934419076457),
+    BaseElement::new(1275870704178834844),
+    BaseElement::new(3734614691721523753),
+    BaseElement::new(2354726782787368865),
+    BaseElement::new(2494302124736516700),
+    BaseElement::new(104708447494368968),
+    BaseElement::new(193427307533180643),
+    BaseElement::new(3456577890147932611),
+    BaseElement::new(4420903024013824381),
+    BaseElement::new(2864401030669109737),
+    BaseElement::new(2296560642966169761),
+    BaseElement::new(1609453979422466062),
+    BaseElement::new(3721136373313801345),
+    BaseElement::new(2014553148230352857),
+    BaseElement::new(841779409402689826),
+    BaseElement::new(353231372865310022),
+    BaseElement::new(3911090727174683188),
+    BaseElement::new(1736823272467758642),
+    BaseElement::new(2720090964354126855),
+    BaseElement::new(1030252512059242449),
+    BaseElement::new(3426579750271678893),
+    BaseElement::new(2549299737826045519),
+    BaseElement::new(3928722046962047433),
+    BaseElement::new(2184382030910323532),
+    BaseElement::new(2136201951162998189),
+    BaseElement::new(3171649146422952849),
+    BaseElement::new(1932056228038863626),
+    BaseElement::new(303585674372765808),
+    BaseElement::new(4557489328531390531),
+    BaseElement::new(3335606109524353979),
+    BaseElement::new(1898170787838800468),
+    BaseElement::new(3586185645112366329),
+    BaseElement::new(2047113590153995021),
+    BaseElement::new(4345693361982822430),
+    BaseElement::new(1561030816317218186),
+    BaseElement::new(742243501734166799),
+    BaseElement::new(3822365176224904084),
+    BaseElement::new(498354023764395006),
+    BaseElement::new(1827885135624043948),
+    BaseElement::new(1049132471794756874),
+    BaseElement::new(1654731630566159557),
+    BaseElement::new(3135165443263275673),
+    BaseElement::new(3161092617851506516),
+    BaseElement::new(104809020949810093),
+    BaseElement::new(1426810666672134968),
+    BaseElement::new(2512924211737767238),
+    BaseElement::new(4526942944748726053),
+    BaseElement::new(3443917870621574961),
+    BaseElement::new(3729579583298691031),
+    BaseElement::new(3236583641400594226),
+    BaseElement::new(3952663351716203829),
+    BaseElement::new(1417115654565512840),
+    BaseElement::new(4519769473661412635),
+    BaseElement::new(3635042612739212017),
+    BaseElement::new(4076715082016183789),
+    BaseElement::new(4533552894106359727),
+    BaseElement::new(3613807594487077253),
+    BaseElement::new(1179713468071507677),
+    BaseElement::new(3811500488571805762),
+    BaseElement::new(2380102515731060243),
+    BaseElement::new(1683029897010822484),
+    BaseElement::new(4424560393869495372),
+    BaseElement::new(2974382622698167721),
+    BaseElement::new(4340608575841398069),
+    BaseElement::new(532454574426847299),
+    BaseElement::new(2023483543238692128),
+    BaseElement::new(581285939513585288),
+    BaseElement::new(2082757627740607167),
+    BaseElement::new(2825136290986768632),
+    BaseElement::new(801305556817314471),
+    BaseElement::new(2063423052871538578),
+    BaseElement::new(2095819948862281507),
+    BaseElement::new(3329294375589976067),
+    BaseElement::new(390055969653940849),
+    BaseElement::new(2767841854771055230),
+    BaseElement::new(864923582866174871),
+    BaseElement::new(944651419280850122),
+    BaseElement::new(1401805504556224164),
+    BaseElement::new(3328261168820882691),
+    BaseElement::new(2019625533292170929),
+    BaseElement::new(3689763107413472731),
+    BaseElement::new(512573945055304479),
+    BaseElement::new(2656628466341290295),
+    BaseElement::new(1560186211700744589),
+    BaseElement::new(4408667595966728689),
+    BaseElement::new(900971458454548383),
+    BaseElement::new(4380530169270201596),
+    BaseElement::new(3415115311380894926),
+    BaseElement::new(503807616253065865),
+    BaseElement::new(3736876757227138012),
+    BaseElement::new(3742605016340498303),
+    BaseElement::new(2957362545517379881),
+    BaseElement::new(2849152004179458391),
+    BaseElement::new(3505120108108147752),
+    BaseElement::new(3189303512975987493),
+    BaseElement::new(1511079154210345104),
+    BaseElement::new(3165779565793648753),
+    BaseElement::new(747556948249480030),
+    BaseElement::new(4160066388387503006),
+    BaseElement::new(3617032954976979924),
+    BaseElement::new(791676868197683360),
+    BaseElement::new(3248818145972447958),
+    BaseElement::new(4318084043535296771),
+    BaseElement::new(226588896157351499),
+    BaseElement::new(3114126313874732483),
+    BaseElement::new(2687905572582484176),
+    BaseElement::new(4240887199394324370),
+    BaseElement::new(3603614637752045969),
+    BaseElement::new(130610682822520683),
+    BaseElement::new(3655645896977580639),
+    BaseElement::new(321125381968251091),
+    BaseElement::new(1997695316642744071),
+    BaseElement::new(1705008182672809106),
+    BaseElement::new(3575830089870597375),
+    BaseElement::new(2650764359516750882),
+    BaseElement::new(2485571730221189816),
+    BaseElement::new(1445946248120326850),
+    BaseElement::new(4542419943714809854),
+    BaseElement::new(578831687107730264),
+    BaseElement::new(1478189828424979360),
+    BaseElement::new(710191151671712705),
+    BaseElement::new(3587033661735457704),
+    BaseElement::new(1511397485925582479),
+    BaseElement::new(240287584727098653),
+    BaseElement::new(3284868450815112202),
+    BaseElement::new(2564868559714300701),
+    BaseElement::new(3135906854360438074),
+    BaseElement::new(38154307600684517),
+    BaseElement::new(750175562653759186),
+    BaseElement::new(3278047534268779883),
+    BaseElement::new(3092660924226084823),
+    BaseElement::new(1568356697751599038),
+    BaseElement::new(4336337927640758463),
+    BaseElement::new(1134771796873415654),
+    BaseElement::new(722327692294636487),
+    BaseElement::new(2436599588487908828),
+    BaseElement::new(2247252473692274962),
+    BaseElement::new(2419593467602424885),
+    BaseElement::new(917330686953461512),
+    BaseElement::new(968638210240142847),
+    BaseElement::new(998343776206406090),
+    BaseElement::new(1743881422104014775),
+    BaseElement::new(4283764785291821625),
+    BaseElement::new(2226248032398122620),
+    BaseElement::new(4083501265571390334),
+    BaseElement::new(3717803803121819894),
+    BaseElement::new(4187748727989763757),
+    BaseElement::new(1809430894955646385),
+    BaseElement::new(1737008797923170503),
+    BaseElement::new(3870044517186647750),
+    BaseElement::new(3595026030867255848),
+    BaseElement::new(576652666372635852),
+    BaseElement::new(2644461423144500512),
+    BaseElement::new(2248639337308108157),
+    BaseElement::new(3103102039019841393),
+    BaseElement::new(1596879158153996877),
+    BaseElement::new(255608684607650224),
+    BaseElement::new(4496378191379420352),
+    BaseElement::new(1494040099576102895),
+    BaseElement::new(3983373518834378070),
+    BaseElement::new(4563458316447098834),
+    BaseElement::new(2403667616506041013),
+    BaseElement::new(2602737517315382723),
+    BaseElement::new(3656179633518802582),
+    BaseElement::new(1042771209531416734),
+    BaseElement::new(1001292728909273293),
+    BaseElement::new(1535075918132991210),
+    BaseElement::new(3010728861365350061),
+    BaseElement::new(3436623610979194578),
+    BaseElement::new(3973916699856835707),
+    BaseElement::new(2493482491680972090),
+    BaseElement::new(4079593878054603835),
+    BaseElement::new(4050701671604107001),
+    BaseElement::new(1977124458378625602),
+    BaseElement::new(1609543946170195093),
+    BaseElement::new(134719808524460919),
+    BaseElement::new(42778426759805422),
+    BaseElement::new(2304238555792598044),
+    BaseElement::new(3772621628038159375),
+    BaseElement::new(2071480109343043471),
+    BaseElement::new(2320534525059196078),
+    BaseElement::new(847040069364879),
+    BaseElement::new(2678115396003152013),
+    BaseElement::new(3467700554058552174),
+    BaseElement::new(3008528455720005939),
+    BaseElement::new(226668740878682506),
+    BaseElement::new(668080440166792790),
+    BaseElement::new(2780483195282221097),
+    BaseElement::new(3898456629070623770),
+    BaseElement::new(3602320682338983325),
+    BaseElement::new(1231058301105376568),
+    BaseElement::new(3893129989425114468),
+    BaseElement::new(4093175013124675727),
+    BaseElement::new(616496965895623862),
+    BaseElement::new(4276978053673930446),
+    BaseElement::new(4534849902844511642),
+    BaseElement::new(767246326593616504),
+    BaseElement::new(1893019298750835285),
+    BaseElement::new(90127218863116106),
+    BaseElement::new(1731231013989056975),
+    BaseElement::new(4176370877564081398),
+    BaseElement::new(3754032846642231399),
+    BaseElement::new(3411654234420396846),
+    BaseElement::new(2798532873832346874),
+    BaseElement::new(4390646413414678752),
+    BaseElement::new(2635866180012670154),
+    BaseElement::new(3112861240075170966),
+    BaseElement::new(3877221278556388765),
+    BaseElement::new(636505003448065560),
+    BaseElement::new(388410702044185462),
+    BaseElement::new(2913379119087117422),
+    BaseElement::new(1959815715473283199),
+    BaseElement::new(2590397191125476851),
+    BaseElement::new(903383151842688395),
+    BaseElement::new(2784557189664193341),
+    BaseElement::new(4093234877157506906),
+    BaseElement::new(3822636579587859773),
+    BaseElement::new(2301965926796798860),
+    BaseElement::new(3490527625071917172),
+    BaseElement::new(2366136859762950747),
+    BaseElement::new(3555176232222098885),
+    BaseElement::new(1293818523650483442),
+    BaseElement::new(914526709361410903),
+    BaseElement::new(3341235220598917279),
+    BaseElement::new(4012977370489157944),
+    BaseElement::new(1820081786297257976),
+    BaseElement::new(3294615216733740234),
+    BaseElement::new(3596005518587341882),
+    BaseElement::new(663886785234810843),
+    BaseElement::new(2399811405197123901),
+    BaseElement::new(3395159722172698756),
+    BaseElement::new(4156019284138816154),
+    BaseElement::new(3245377344968344048),
+    BaseElement::new(1244124679425893484),
+    BaseElement::new(3123649842895692436),
+    BaseElement::new(4112850796004705447),
+    BaseElement::new(1688825397051352857),
+    BaseElement::new(2343099594158181138),
+    BaseElement::new(380210915981778058),
+    BaseElement::new(1696964333849000402),
+    BaseElement::new(579269732365085698),
+    BaseElement::new(1709988882482642083),
+    BaseElement::new(2626757247462767193),
+    BaseElement::new(255863632482058603),
+    BaseElement::new(2251727163396431893),
+    BaseElement::new(2359470152383828804),
+    BaseElement::new(4152970444429347602),
+    BaseElement::new(3522518333141982996),
+    BaseElement::new(3878325650953937338),
+    BaseElement::new(2315862785904277232),
+    BaseElement::new(2544460068525064660),
+    BaseElement::new(1048686266072238184),
+    BaseElement::new(3537247369016038860),
+    BaseElement::new(3655522468560821775),
+    BaseElement::new(2211508065791824352),
+    BaseElement::new(2340694166448627519),
+    BaseElement::new(3143774732719472071),
+    BaseElement::new(2273224517115514150),
+    BaseElement::new(1386431994031916419),
+    BaseElement::new(4082103385630256469),
+    BaseElement::new(3778824626734903419),
+    BaseElement::new(2129598225583937880),
+    BaseElement::new(71852212571513605),
+    BaseElement::new(4263804114869615150),
+    BaseElement::new(1545510705742849272),
+    BaseElement::new(1455553639537757969),
+    BaseElement::new(1593186664037468917),
+    BaseElement::new(1978841336209986069),
+    BaseElement::new(2900521814872737626),
+    BaseElement::new(108711141649973252),
+    BaseElement::new(1151541491707663517),
+    BaseElement::new(4321080833016742894),
+    BaseElement::new(985954695909857782),
+    BaseElement::new(1488936338849157651),
+    BaseElement::new(530625697008859835),
+    BaseElement::new(3539744075507043392),
+    BaseElement::new(3603666202937758178),
+    BaseElement::new(1264945598171103964),
+    BaseElement::new(4038709223324050214),
+    BaseElement::new(1805155404889488215),
+    BaseElement::new(2621914314323822310),
+    BaseElement::new(2063670705717078340),
+    BaseElement::new(2401298966624806400),
+    BaseElement::new(1572110988078277100),
+    BaseElement::new(3042550439130768184),
+    BaseElement::new(2880932022817556334),
+    BaseElement::new(3104255359498168581),
+    BaseElement::new(491175718185172464),
+    BaseElement::new(1645411647028057844),
+    BaseElement::new(2772927614066724838),
+    BaseElement::new(4439389932844034787),
+    BaseElement::new(3613689434123313785),
+    BaseElement::new(2120842803650506405),
+    BaseElement::new(3475298996642705476),
+    BaseElement::new(3382961931659656484),
+    BaseElement::new(1406728475982415982),
+    BaseElement::new(3958211264191612144),
+    BaseElement::new(1313485232685267586),
+    BaseElement::new(881108546287745460),
+    BaseElement::new(2800515091463277649),
+    BaseElement::new(1555564587234608958),
+    BaseElement::new(796329262270948883),
+    BaseElement::new(498323793074063154),
+    BaseElement::new(716290211554126408),
+    BaseElement::new(439963486044877655),
+    BaseElement::new(3686021775030560666),
+    BaseElement::new(2892003534284060231),
+    BaseElement::new(2894187991956566969),
+    BaseElement::new(2266529622697220346),
+    BaseElement::new(4598362786528883981),
+    BaseElement::new(1626479697977465919),
+    BaseElement::new(20449512867817315),
+    BaseElement::new(3320888055911991819),
+    BaseElement::new(1029839624748738277),
+    BaseElement::new(4202112886350930646),
+    BaseElement::new(1750078668073269249),
+    BaseElement::new(1315476517933167586),
+    BaseElement::new(3439875169477333666),
+    BaseElement::new(2470858639623577548),
+    BaseElement::new(1120244932017168975),
+    BaseElement::new(1243846736411491474),
+    BaseElement::new(2530969941949510932),
+    BaseElement::new(3632207244948620278),
+    BaseElement::new(4474578130847709948),
+    BaseElement::new(2987823604917590500),
+    BaseElement::new(2176925835969155001),
+    BaseElement::new(1677994697532751623),
+    BaseElement::new(423821250050371341),
+    BaseElement::new(524203217778104250),
+    BaseElement::new(1825607080527568661),
+    BaseElement::new(1202811425953329449),
+    BaseElement::new(800979327178300546),
+    BaseElement::new(32550345607420699),
+    BaseElement::new(3880000515606738395),
+    BaseElement::new(329679358221510498),
+    BaseElement::new(156481671661281894),
+    BaseElement::new(4055697568563967923),
+    BaseElement::new(2142272343168537235),
+    BaseElement::new(1989047594397971428),
+    BaseElement::new(1366308636536513775),
+    BaseElement::new(4302899999477076331),
+    BaseElement::new(1771688206405272696),
+    BaseElement::new(1412710943557734689),
+    BaseElement::new(4224927289657971387),
+    BaseElement::new(4229638718826275083),
+    BaseElement::new(1408492026453556103),
+    BaseElement::new(1820390361668437490),
+    BaseElement::new(699690959190697304),
+    BaseElement::new(825787405272724219),
+    BaseElement::new(871893887867367089),
+    BaseElement::new(3646877318113020659),
+    BaseElement::new(615796124774396593),
+    BaseElement::new(600367698310965669),
+    BaseElement::new(4434842519035014547),
+    BaseElement::new(2462736631758859436),
+    BaseElement::new(605086612520416495),
+    BaseElement::new(4277761265020518741),
+    BaseElement::new(4578838186526214864),
+    BaseElement::new(4073942533845981808),
+    BaseElement::new(899214466860927994),
+    BaseElement::new(2002264574412472263),
+    BaseElement::new(3849287665385233103),
+    BaseElement::new(2339601868176664732),
+    BaseElement::new(3242549318081179076),
+    BaseElement::new(120512122787312541),
+    BaseElement::new(3650776525198326017),
+    BaseElement::new(3655390055870620161),
+    BaseElement::new(2142837713556634245),
+    BaseElement::new(4204926592470505920),
+    BaseElement::new(2731860001729075760),
+    BaseElement::new(11296643111269489),
+    BaseElement::new(787137296258618164),
+    BaseElement::new(695217248895819835),
+    BaseElement::new(632523656807831827),
+    BaseElement::new(1355927716048120485),
+    BaseElement::new(1647490227082463288),
+    BaseElement::new(1943347441760944584),
+    BaseElement::new(2830468863398691588),
+    BaseElement::new(3415442756679921049),
+    BaseElement::new(3748384890309087391),
+    BaseElement::new(3611637592512257101),
+    BaseElement::new(588359598953329171),
+    BaseElement::new(1007293166723192097),
+    BaseElement::new(786820755263991771),
+    BaseElement::new(2534826894407688149),
+    BaseElement::new(3454818022916558114),
+    BaseElement::new(2792299488632977545),
+    BaseElement::new(308276760128333915),
+    BaseElement::new(2696860107202544437),
+    BaseElement::new(1455652688011003871),
+    BaseElement::new(2627555221579774979),
+    BaseElement::new(1085053522042359357),
+    BaseElement::new(3168526029483309066),
+    BaseElement::new(3906370084129083919),
+    BaseElement::new(405170135823491059),
+    BaseElement::new(1774818264451378389),
+    BaseElement::new(1991237010591458909),
+    BaseElement::new(2292850548744792401),
+    BaseElement::new(4324205483298179766),
+    BaseElement::new(930994482349253289),
+    BaseElement::new(4223217596312615813),
+    BaseElement::new(1790844292037286738),
+    BaseElement::new(3541132791239342085),
+    BaseElement::new(2508117906050947544),
+    BaseElement::new(196524504843421678),
+    BaseElement::new(4032351217672000988),
+    BaseElement::new(508435670511138951),
+    BaseElement::new(1951371913742710171),
+    BaseElement::new(1416059832938142012),
+    BaseElement::new(1403726506775139321),
+    BaseElement::new(1008103516491514306),
+    BaseElement::new(3259917446886297698),
+    BaseElement::new(3451723811231914513),
+    BaseElement::new(191314766773136646),
+    BaseElement::new(4307291858802698128),
+    BaseElement::new(512844925129835133),
+    BaseElement::new(4338471170129317852),
+    BaseElement::new(2295323430881117279),
+    BaseElement::new(816379026951192867),
+    BaseElement::new(535571461685102663),
+    BaseElement::new(3470249818098746146),
+    BaseElement::new(2384588190542047725),
+    BaseElement::new(1510904312621418907),
+    BaseElement::new(333485108850630514),
+    BaseElement::new(502824238930768236),
+    BaseElement::new(2180074610373146518),
+    BaseElement::new(1339830497220371463),
+    BaseElement::new(1853718456864632259),
+    BaseElement::new(293771822187760886),
+    BaseElement::new(3479395780500690652),
+    BaseElement::new(2217427632816514084),
+    BaseElement::new(4560500316725453133),
+    BaseElement::new(26885061003140546),
+    BaseElement::new(815898539779257586),
+    BaseElement::new(97213999772472964),
+    BaseElement::new(1401945456233539702),
+    BaseElement::new(2953768574571044543),
+    BaseElement::new(4151733413835159851),
+    BaseElement::new(1564968299786986083),
+    BaseElement::new(2264657406783371810),
+    BaseElement::new(2701812310183693733),
+    BaseElement::new(3586859857127709813),
+    BaseElement::new(3020544892858445995),
+    BaseElement::new(4429182227629457055),
+    BaseElement::new(4265193631926624948),
+    BaseElement::new(2616690203854052319),
+    BaseElement::new(3616971608301104223),
+    BaseElement::new(2534233069281708976),
+    BaseElement::new(1807349766919448450),
+    BaseElement::new(2583072763192117816),
+    BaseElement::new(642086198156581204),
+    BaseElement::new(4539223468132824258),
+    BaseElement::new(2090441536266017092),
+    BaseElement::new(3941239102927368110),
+    BaseElement::new(4445568437427989038),
+    BaseElement::new(721443487420522189),
+    BaseElement::new(1164413256892736013),
+    BaseElement::new(3003416744002608275),
+    BaseElement::new(3000044456113381162),
+    BaseElement::new(2866125297820771154),
+    BaseElement::new(3667621674757688608),
+    BaseElement::new(3656406650726327635),
+    BaseElement::new(1039757203721104037),
+    BaseElement::new(3836752356453427421),
+    BaseElement::new(2831927303749040560),
+    BaseElement::new(3546101703594211469),
+    BaseElement::new(3500345492700192583),
+    BaseElement::new(2381939877349013600),
+    BaseElement::new(4155339014170612849),
+    BaseElement::new(4347979935696928311),
+    BaseElement::new(4166145564710276485),
+    BaseElement::new(1723799521897874364),
+    BaseElement::new(4094307392348130307),
+    BaseElement::new(3010152980446609451),
+    BaseElement::new(47589504610064271),
+    BaseElement::new(1257366750340821436),
+    BaseElement::new(1617632777859700595),
+    BaseElement::new(1560615789001250723),
+    BaseElement::new(4003540793023387849),
+    BaseElement::new(3808606292783641627),
+    BaseElement::new(521282696439588655),
+    BaseElement::new(2200391569298280189),
+    BaseElement::new(1737847926177606942),
+    BaseElement::new(2696031901889164306),
+    BaseElement::new(2988418525412698136),
+    BaseElement::new(2957605497272192281),
+    BaseElement::new(45610590925023332),
+    BaseElement::new(310956900580647545),
+    BaseElement::new(3932740367920226782),
+    BaseElement::new(3328830144410133563),
+    BaseElement::new(2665996378935689731),
+    BaseElement::new(1529940828758498148),
+    BaseElement::new(663920398415246358),
+    BaseElement::new(709034705563520729),
+    BaseElement::new(2852638427642438764),
+    BaseElement::new(635935587891928301),
+    BaseElement::new(2208348130186904687),
+    BaseElement::new(1697344663470457889),
+    BaseElement::new(3939451966733101899),
+    BaseElement::new(887522000624092252),
+    BaseElement::new(2041016052322123250),
+    BaseElement::new(804305591153257916),
+    BaseElement::new(1621146210963600100),
+    BaseElement::new(4392698246495243472),
+    BaseElement::new(1605686485939483947),
+    BaseElement::new(2402395589439341201),
+    BaseElement::new(2114882828155804438),
+    BaseElement::new(3294368166330952318),
+    BaseElement::new(3851265870924511090),
+    BaseElement::new(2567942432970744250),
+    BaseElement::new(103742787551702423),
+    BaseElement::new(1518164981471657620),
+];
+
+/// MDS matrix in row-major form.
+const MDS: [BaseElement; STATE_WIDTH * STATE_WIDTH] = [
+    BaseElement::new(2541193528094914855),
+    BaseElement::new(7301056760878618),
+    BaseElement::new(2614576524559242784),
+    BaseElement::new(4136841513500517553),
+    BaseElement::new(1171648629376138898),
+    BaseElement::new(2184516121618121825),
+    BaseElement::new(618645439555828142),
+    BaseElement::new(4230627531236885221),
+    BaseElement::new(1650937838154767182),
+    BaseElement::new(2783807720384315273),
+    BaseElement::new(2076415174765699388),
+    BaseElement::new(3075109175657395375),
+    BaseElement::new(4144252692088689707),
+    BaseElement::new(2855726927620369495),
+    BaseElement::new(4214766612916016593),
+    BaseElement::new(4206355109427218485),
+    BaseElement::new(4550926300922816380),
+    BaseElement::new(3492488913279355563),
+    BaseElement::new(2088584203229605666),
+    BaseElement::new(483712689843252503),
+    BaseElement::new(1250466905878959391),
+    BaseElement::new(243205437556163598),
+    BaseElement::new(3045556737408320629),
+    BaseElement::new(2037676429598692308),
+    BaseElement::new(2082651007186997683),
+    BaseElement::new(758335240315633327),
+    BaseElement::new(470711426976492230),
+    BaseElement::new(4312189193111983969),
+    BaseElement::new(3984554901543312487),
+    BaseElement::new(3929356698327100794),
+    BaseElement::new(1021003662029167721),
+    BaseElement::new(4179233047828015962),
+    BaseElement::new(2658956926783767219),
+    BaseElement::new(2325796032688364397),
+    BaseElement::new(1002314439535994170),
+    BaseElement::new(1856839197247671984),
+    BaseElement::new(3589633260198579520),
+    BaseElement::new(1430911222960570229),
+    BaseElement::new(4413671419841444511),
+    BaseElement::new(3071489726823074046),
+    BaseElement::new(968833296907798150),
+    BaseElement::new(2174885439634941520),
+    BaseElement::new(2179200177969384953),
+    BaseElement::new(3999364201139960817),
+    BaseElement::new(2784103298838552792),
+    BaseElement::new(4537275180599678324),
+    BaseElement::new(747228852312235451),
+    BaseElement::new(1378671068971819301),
+    BaseElement::new(2657433639677415309),
+    BaseElement::new(3064968574052193785),
+    BaseElement::new(3013279014049270224),
+    BaseElement::new(3952979712490783413),
+    BaseElement::new(3117179275986716048),
+    BaseElement::new(519544896992649864),
+    BaseElement::new(651975826000811407),
+    BaseElement::new(4278822068763455482),
+    BaseElement::new(4608532795355786116),
+    BaseElement::new(2729912012312690222),
+    BaseElement::new(1565160766124378052),
+    BaseElement::new(4449574551125345455),
+    BaseElement::new(1810510342142136818),
+    BaseElement::new(2667120484205717672),
+    BaseElement::new(4106440639677296167),
+    BaseElement::new(3736566287897627244),
+    BaseElement::new(2152833519258247358),
+    BaseElement::new(3741034377327909924),
+    BaseElement::new(4605489875798520035),
+    BaseElement::new(801513154803952194),
+    BaseElement::new(1745145196386284920),
+    BaseElement::new(4531241705647431480),
+    BaseElement::new(4411521712293968175),
+    BaseElement::new(1495520985399267476),
+    BaseElement::new(1822924827963316200),
+    BaseElement::new(1060269272910698860),
+    BaseElement::new(3923114689171911555),
+    BaseElement::new(899416972480896703),
+    BaseElement::new(1473174201737172489),
+    BaseElement::new(104557308021848620),
+    BaseElement::new(614167541104110018),
+    BaseElement::new(904552491655676502),
+    BaseElement::new(907403814430591832),
+    BaseElement::new(1973753901349203774),
+    BaseElement::new(2383218246422716240),
+    BaseElement::new(2480358496318604774),
+    BaseElement::new(2456627313284142802),
+    BaseElement::new(2194725901607240363),
+    BaseElement::new(4329193030783697381),
+    BaseElement::new(3364131439385644898),
+    BaseElement::new(808393694539457304),
+    BaseElement::new(1637145442743454743),
+    BaseElement::new(496609176643052958),
+    BaseElement::new(2128825896376183337),
+    BaseElement::new(3509532410068836507),
+    BaseElement::new(4273484857988564681),
+    BaseElement::new(409432956301073373),
+    BaseElement::new(4215486086870547113),
+    BaseElement::new(952566393193731334),
+    BaseElement::new(3967497667648256259),
+    BaseElement::new(3239796677978136055),
+    BaseElement::new(4096018187847973908),
+    BaseElement::new(4491147248329791880),
+    BaseElement::new(242553271193304762),
+    BaseElement::new(4531090987082795030),
+    BaseElement::new(4519964785820645866),
+    BaseElement::new(714630135760137108),
+    BaseElement::new(3825653191868254152),
+    BaseElement::new(4303613034318394584),
+    BaseElement::new(4556502686850640358),
+    BaseElement::new(4495389631574267144),
+    BaseElement::new(2323684559137203698),
+    BaseElement::new(2700086750467670806),
+    BaseElement::new(4530141593761586994),
+    BaseElement::new(3707481668245493461),
+    BaseElement::new(979445754593254665),
+    BaseElement::new(101963963572325436),
+    BaseElement::new(953963280792823563),
+    BaseElement::new(3812032411009155922),
+    BaseElement::new(3418447190767883156),
+    BaseElement::new(3150954007853117780),
+    BaseElement::new(4513000882313382724),
+    BaseElement::new(3476800762052718022),
+    BaseElement::new(2554506205681091129),
+    BaseElement::new(33629294859962690),
+    BaseElement::new(792583646458069983),
+    BaseElement::new(1350629139388493175),
+    BaseElement::new(2362435988592447358),
+    BaseElement::new(2390038606674231204),
+    BaseElement::new(3961146542189422870),
+    BaseElement::new(2979855679886554954),
+    BaseElement::new(3668377094757479670),
+    BaseElement::new(1722699584519263668),
+    BaseElement::new(52727064799302377),
+    BaseElement::new(185539090521572783),
+    BaseElement::new(4325430805222771598),
+    BaseElement::new(120909899438556492),
+    BaseElement::new(1609581731766384084),
+    BaseElement::new(667420975794181525),
+    BaseElement::new(924958773822643035),
+    BaseElement::new(3072926592019352579),
+    BaseElement::new(1179348475787831667),
+    BaseElement::new(3989376092024043801),
+    BaseElement::new(4165863443558254001),
+    BaseElement::new(1408210352812678670),
+    BaseElement::new(820016102306491950),
+];
@@ -0,0 +1,216 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use math::field::{FieldElement, StarkField};
+
+// GRAIN LFSR
+// ================================================================================================
+
+/// Self-shrinking Grain LFSR used to derive Poseidon parameters.
+///
+/// This follows the reference procedure from `generate_parameters_grain.sage` accompanying
+/// https://eprint.iacr.org/2019/458.pdf: the 80-bit state is initialized from the description
+/// of the instance (field type, S-Box type, field size, state width and the number of rounds),
+/// the first 160 output bits are discarded, and afterwards bits are produced in pairs where the
+/// second bit of a pair is output only if the first bit is 1.
+pub struct GrainLfsr {
+    state: u128,
+}
+
+impl GrainLfsr {
+    /// Returns a new LFSR initialized for a Poseidon instance over a prime field with modulus of
+    /// `field_bits` bits, S-Box of the form x^alpha, and the specified state width and number of
+    /// rounds.
+    pub fn new(
+        field_bits: usize,
+        state_width: usize,
+        num_full_rounds: usize,
+        num_partial_rounds: usize,
+    ) -> Self {
+        // the fields are written into the state starting with the most significant bit of the
+        // first field; the first bit of the sequence is stored in the least significant bit of
+        // the state.
+        let fields = [
+            (1, 2),                           // field type: prime field
+            (0, 4),                           // S-Box type: x^alpha
+            (field_bits as u128, 12),         // field size
+            (state_width as u128, 12),        // state width
+            (num_full_rounds as u128, 10),    // number of full rounds
+            (num_partial_rounds as u128, 10), // number of partial rounds
+            ((1u128 << 30) - 1, 30),          // padding
+        ];
+
+        let mut state = 0u128;
+        let mut position = 0;
+        for &(value, num_bits) in fields.iter() {
+            for i in (0..num_bits).rev() {
+                state |= ((value >> i) & 1) << position;
+                position += 1;
+            }
+        }
+        debug_assert_eq!(80, position);
+
+        let mut lfsr = GrainLfsr { state };
+        for _ in 0..160 {
+            lfsr.update();
+        }
+        lfsr
+    }
+
+    /// Returns the next `num_bits` bits of the output sequence interpreted as a big-endian
+    /// integer; the integer is returned in little-endian byte order.
+    pub fn next_bits(&mut self, num_bits: usize) -> Vec<u8> {
+        let mut result = vec![0u8; num_bits.div_ceil(8)];
+        for i in (0..num_bits).rev() {
+            if self.next_bit() {
+                result[i / 8] |= 1 << (i % 8);
+            }
+        }
+        result
+    }
+
+    /// Returns the next field element sampled by rejection: `field_bits` bits are drawn from the
+    /// output sequence until they encode an integer smaller than the field modulus.
+    pub fn next_element<B: StarkField>(&mut self) -> B {
+        let num_bits = get_modulus_bits::<B>();
+        loop {
+            if let Some(element) = bytes_to_element(self.next_bits(num_bits)) {
+                return element;
+            }
+        }
+    }
+
+    /// Returns the next field element obtained by reducing `field_bits` bits drawn from the
+    /// output sequence modulo the field modulus.
+    pub fn next_reduced_element<B: StarkField>(&mut self) -> B {
+        let num_bits = get_modulus_bits::<B>();
+        let bytes = self.next_bits(num_bits);
+        match bytes_to_element(bytes.clone()) {
+            Some(element) => element,
+            None => {
+                // the value is smaller than 2^field_bits, and thus, smaller than twice the
+                // modulus; so, a single subtraction is sufficient to reduce it.
+                let modulus = B::get_modulus_le_bytes();
+                bytes_to_element(sub_le_bytes(&bytes, &modulus))
+                    .expect("failed to reduce value modulo field modulus")
+            }
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Shifts the state by one bit and returns the new bit.
+    fn update(&mut self) -> bool {
+        let s = self.state;
+        let bit = (s >> 62) ^ (s >> 51) ^ (s >> 38) ^ (s >> 23) ^ (s >> 13) ^ s;
+        let bit = bit & 1;
+        self.state = (s >> 1) | (bit << 79);
+        bit == 1
+    }
+
+    /// Returns the next bit of the self-shrinking output sequence.
+    fn next_bit(&mut self) -> bool {
+        while !self.update() {
+            self.update();
+        }
+        self.update()
+    }
+}
+
+// CONSTANT GENERATION
+// ================================================================================================
+
+/// Generates round constants and MDS matrix for a Poseidon instance over field `B`.
+///
+/// Round constants are returned as a single vector arranged round by round; the MDS matrix is
+/// returned in row-major form. The MDS matrix is a Cauchy matrix sampled from the same Grain
+/// LFSR as the round constants, exactly as in the reference script; however, the additional
+/// checks against infinitely long invariant subspace trails performed by the reference script
+/// are not repeated here.
+pub fn generate_constants<B: StarkField>(
+    state_width: usize,
+    num_full_rounds: usize,
+    num_partial_rounds: usize,
+) -> (Vec<B>, Vec<B>) {
+    let mut lfsr = GrainLfsr::new(
+        get_modulus_bits::<B>(),
+        state_width,
+        num_full_rounds,
+        num_partial_rounds,
+    );
+
+    let num_constants = (num_full_rounds + num_partial_rounds) * state_width;
+    let round_constants = (0..num_constants)
+        .map(|_| lfsr.next_element())
+        .collect::<Vec<B>>();
+
+    let mds = loop {
+        // sample 2 * state_width distinct elements
+        let mut values = Vec::with_capacity(2 * state_width);
+        while values.len() < 2 * state_width {
+            values = (0..2 * state_width)
+                .map(|_| lfsr.next_reduced_element())
+                .collect::<Vec<B>>();
+            if !all_distinct(&values) {
+                values.clear();
+            }
+        }
+
+        // build a Cauchy matrix from the sampled elements; if any of the sums is zero, start over
+        let (xs, ys) = values.split_at(state_width);
+        if xs.iter().any(|&x| ys.iter().any(|&y| x + y == B::ZERO)) {
+            continue;
+        }
+        break xs
+            .iter()
+            .flat_map(|&x| ys.iter().map(move |&y| (x + y).inv()))
+            .collect::<Vec<B>>();
+    };
+
+    (round_constants, mds)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of bits in the modulus of field `B`.
+fn get_modulus_bits<B: StarkField>() -> usize {
+    let modulus = B::get_modulus_le_bytes();
+    let last = modulus
+        .iter()
+        .rposition(|&byte| byte != 0)
+        .expect("field modulus cannot be zero");
+    last * 8 + (8 - modulus[last].leading_zeros() as usize)
+}
+
+/// Converts little-endian bytes into a field element; returns None if the encoded value is not
+/// smaller than the field modulus.
+fn bytes_to_element<B: StarkField>(mut bytes: Vec<u8>) -> Option<B> {
+    bytes.resize(B::ELEMENT_BYTES, 0);
+    B::try_from(&bytes[..]).ok()
+}
+
+/// Returns a - b for little-endian integers a and b; a must not be smaller than b.
+fn sub_le_bytes(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i16;
+    for (i, &byte) in a.iter().enumerate() {
+        let mut diff = byte as i16 - *b.get(i).unwrap_or(&0) as i16 - borrow;
+        borrow = (diff < 0) as i16;
+        if diff < 0 {
+            diff += 256;
+        }
+        result.push(diff as u8);
+    }
+    result
+}
+
+fn all_distinct<B: FieldElement>(values: &[B]) -> bool {
+    values
+        .iter()
+        .enumerate()
+        .all(|(i, a)| values[i + 1..].iter().all(|b| a != b))
+}
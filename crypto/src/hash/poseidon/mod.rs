@@ -0,0 +1,211 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Poseidon hash functions.
+//!
+//! The hash function is parameterized by a [PoseidonParameters] implementation which defines the
+//! field, the width and the rate of the sponge, the S-Box power, and the number of full and
+//! partial rounds. Round constants and the MDS matrix are derived using the Grain LFSR procedure
+//! from https://eprint.iacr.org/2019/458.pdf (see [generate_constants()]); this makes it possible
+//! to instantiate Poseidon over any STARK field.
+
+use crate::hash::Hasher;
+use core::{convert::TryFrom, marker::PhantomData};
+use math::field::{FieldElement, StarkField};
+
+mod digest;
+pub use digest::ElementDigest;
+
+mod grain;
+pub use grain::{generate_constants, GrainLfsr};
+
+mod f62;
+pub use f62::Poseidon62Parameters;
+
+mod f128;
+pub use f128::Poseidon128Parameters;
+
+#[cfg(test)]
+mod tests;
+
+// POSEIDON PARAMETERS
+// ================================================================================================
+
+/// Defines an instance of Poseidon permutation.
+///
+/// The sponge state consists of STATE_WIDTH elements; the first RATE_WIDTH elements are the rate
+/// portion of the state, and the remaining elements are the capacity portion of the state.
+pub trait PoseidonParameters {
+    /// Field over which the permutation is defined.
+    type BaseField: StarkField + 'static;
+
+    /// Number of field elements in the sponge state.
+    const STATE_WIDTH: usize;
+
+    /// Number of field elements in the rate portion of the sponge state.
+    const RATE_WIDTH: usize;
+
+    /// S-Box power; must be relatively prime to the field modulus minus one.
+    const ALPHA: u32;
+
+    /// Total number of full rounds; half of the full rounds are applied before the partial rounds
+    /// and the other half after.
+    const NUM_FULL_ROUNDS: usize;
+
+    /// Number of partial rounds; in a partial round, the S-Box is applied only to the first
+    /// element of the state.
+    const NUM_PARTIAL_ROUNDS: usize;
+
+    /// Returns round constants for all rounds arranged round by round; that is, the first
+    /// STATE_WIDTH constants are added to the state in the first round etc.
+    fn round_constants() -> &'static [Self::BaseField];
+
+    /// Returns the MDS matrix in row-major form.
+    fn mds() -> &'static [Self::BaseField];
+}
+
+// POSEIDON HASHER
+// ================================================================================================
+
+/// Poseidon hash function defined by parameters `P` which outputs digests of `N` field elements.
+///
+/// Both digests in [Hasher::merge()] must fit into the rate portion of the state, and thus, `N`
+/// must be at most half of `P::RATE_WIDTH`.
+pub struct Poseidon<P: PoseidonParameters, const N: usize>(PhantomData<P>);
+
+/// Poseidon hash function over the 62-bit field with 248-bit output.
+pub type Poseidon62_248 = Poseidon<Poseidon62Parameters, 4>;
+
+/// Poseidon hash function over the 128-bit field with 256-bit output.
+pub type Poseidon128_256 = Poseidon<Poseidon128Parameters, 2>;
+
+impl<P: PoseidonParameters, const N: usize> Hasher for Poseidon<P, N> {
+    type Digest = ElementDigest<P::BaseField, N>;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        // split the bytes into chunks which are guaranteed to encode valid field elements; that
+        // is, every chunk must be at least one bit shorter than the modulus.
+        let chunk_size = (P::BaseField::MODULUS_BITS as usize - 1) / 8;
+        let elements = bytes
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let mut buf = vec![0u8; P::BaseField::ELEMENT_BYTES];
+                buf[..chunk.len()].copy_from_slice(chunk);
+                P::BaseField::try_from(&buf[..])
+                    .unwrap_or_else(|_| panic!("failed to convert bytes into a field element"))
+            })
+            .collect::<Vec<_>>();
+        hash_base_elements::<P, N>(&elements, bytes.len())
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        debug_assert!(2 * N <= P::RATE_WIDTH, "digests do not fit into the rate");
+        // the two digests are placed into the rate portion of the state, and the capacity portion
+        // is left at zeros.
+        let mut state = vec![P::BaseField::ZERO; P::STATE_WIDTH];
+        state[..N].copy_from_slice(values[0].as_elements());
+        state[N..(2 * N)].copy_from_slice(values[1].as_elements());
+        apply_permutation::<P>(&mut state);
+        ElementDigest::from_state(&state)
+    }
+
+    fn merge_n(values: &[Self::Digest]) -> Self::Digest {
+        // the digests are absorbed into the rate portion of the state with the capacity portion
+        // left at zeros; thus, for two digests, the result is the same as for merge().
+        let elements = values
+            .iter()
+            .flat_map(|digest| digest.as_elements().iter().copied())
+            .collect::<Vec<_>>();
+        hash_base_elements::<P, N>(&elements, 0)
+    }
+
+    fn hash_elements<E: FieldElement>(elements: &[E]) -> Self::Digest {
+        // elements are reinterpreted in place below, and thus, this must be checked in release
+        // builds as well
+        assert_eq!(
+            E::Base::get_modulus_le_bytes(),
+            P::BaseField::get_modulus_le_bytes(),
+            "elements must be defined over the field of the hash function"
+        );
+        // elements in the base field and in its extensions are laid out in memory as sequences of
+        // base field elements; so, we can hash them without any conversions.
+        let elements = unsafe { P::BaseField::bytes_as_elements(E::elements_as_bytes(elements)) }
+            .expect("failed to interpret elements as elements of the hash function field");
+        hash_base_elements::<P, N>(elements, elements.len())
+    }
+}
+
+// POSEIDON PERMUTATION
+// ================================================================================================
+
+/// Applies Poseidon permutation defined by parameters `P` to the provided state.
+pub fn apply_permutation<P: PoseidonParameters>(state: &mut [P::BaseField]) {
+    debug_assert_eq!(P::STATE_WIDTH, state.len(), "invalid state width");
+    let round_constants = P::round_constants();
+    let mds = P::mds();
+    let alpha = P::ALPHA.into();
+
+    let num_rounds = P::NUM_FULL_ROUNDS + P::NUM_PARTIAL_ROUNDS;
+    let partial_rounds = (P::NUM_FULL_ROUNDS / 2)..(P::NUM_FULL_ROUNDS / 2 + P::NUM_PARTIAL_ROUNDS);
+
+    let mut temp = vec![P::BaseField::ZERO; P::STATE_WIDTH];
+    for round in 0..num_rounds {
+        // add round constants
+        let ark = &round_constants[(round * P::STATE_WIDTH)..((round + 1) * P::STATE_WIDTH)];
+        for (element, &constant) in state.iter_mut().zip(ark) {
+            *element += constant;
+        }
+
+        // apply S-Box to the entire state in full rounds, and only to the first element in
+        // partial rounds
+        if partial_rounds.contains(&round) {
+            state[0] = state[0].exp(alpha);
+        } else {
+            for element in state.iter_mut() {
+                *element = element.exp(alpha);
+            }
+        }
+
+        // multiply the state by the MDS matrix
+        for (result, row) in temp.iter_mut().zip(mds.chunks(P::STATE_WIDTH)) {
+            *result = row
+                .iter()
+                .zip(state.iter())
+                .fold(P::BaseField::ZERO, |acc, (&m, &s)| acc + m * s);
+        }
+        state.copy_from_slice(&temp);
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Absorbs the provided elements into a sponge and returns the resulting digest. The first
+/// capacity element is initialized to `input_len` to separate inputs of different lengths.
+fn hash_base_elements<P: PoseidonParameters, const N: usize>(
+    elements: &[P::BaseField],
+    input_len: usize,
+) -> ElementDigest<P::BaseField, N> {
+    let mut state = vec![P::BaseField::ZERO; P::STATE_WIDTH];
+    state[P::RATE_WIDTH] = P::BaseField::from(input_len as u64);
+
+    let mut i = 0;
+    for &element in elements.iter() {
+        state[i] += element;
+        i += 1;
+        if i == P::RATE_WIDTH {
+            apply_permutation::<P>(&mut state);
+            i = 0;
+        }
+    }
+
+    // absorb the last partially filled block; the remainder of the rate is implicitly padded
+    // with zeros.
+    if i > 0 {
+        apply_permutation::<P>(&mut state);
+    }
+
+    ElementDigest::from_state(&state)
+}
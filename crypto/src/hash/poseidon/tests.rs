@@ -0,0 +1,300 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    apply_permutation, generate_constants, ElementDigest, GrainLfsr, Hasher, Poseidon128Parameters,
+    Poseidon128_256, Poseidon62Parameters, Poseidon62_248, PoseidonParameters,
+};
+use crate::{hash::Digest, DefaultRandomElementGenerator, RandomElementGenerator};
+use math::field::{f128, f62, FieldElement, QuadExtension};
+use std::sync::OnceLock;
+
+mod bn254;
+
+// GRAIN LFSR
+// ================================================================================================
+
+#[test]
+fn grain_lfsr() {
+    // the first round constant of the reference Poseidon instance over the BN254 scalar field
+    // with state width 3, 8 full rounds and 57 partial rounds; the modulus of this field is 254
+    // bits long, and the constant is 0x0ee9a592ba9a9518d05986d656f40c2114c4993c11bb29938d21d47304cd8e6e
+    let expected: [u8; 32] = [
+        110, 142, 205, 4, 115, 212, 33, 141, 147, 41, 187, 17, 60, 153, 196, 20, 33, 12, 244, 86,
+        214, 134, 89, 208, 24, 149, 154, 186, 146, 165, 233, 14,
+    ];
+    let mut lfsr = GrainLfsr::new(254, 3, 8, 57);
+    assert_eq!(expected.to_vec(), lfsr.next_bits(254));
+}
+
+// REFERENCE INSTANCE
+// ================================================================================================
+
+/// Parameters of the reference Poseidon instance `poseidonperm_x5_254_3`: BN254 scalar field,
+/// state width 3, S-Box x^5, 8 full rounds and 57 partial rounds.
+struct PoseidonBn254Parameters();
+
+impl PoseidonBn254Parameters {
+    fn constants() -> &'static (Vec<bn254::BaseElement>, Vec<bn254::BaseElement>) {
+        static CONSTANTS: OnceLock<(Vec<bn254::BaseElement>, Vec<bn254::BaseElement>)> =
+            OnceLock::new();
+        CONSTANTS.get_or_init(|| generate_constants::<bn254::BaseElement>(3, 8, 57))
+    }
+}
+
+impl PoseidonParameters for PoseidonBn254Parameters {
+    type BaseField = bn254::BaseElement;
+
+    const STATE_WIDTH: usize = 3;
+    const RATE_WIDTH: usize = 2;
+    const ALPHA: u32 = 5;
+    const NUM_FULL_ROUNDS: usize = 8;
+    const NUM_PARTIAL_ROUNDS: usize = 57;
+
+    fn round_constants() -> &'static [bn254::BaseElement] {
+        &Self::constants().0
+    }
+
+    fn mds() -> &'static [bn254::BaseElement] {
+        &Self::constants().1
+    }
+}
+
+#[test]
+fn permutation_bn254_reference() {
+    // the first round constant and the test vector are taken from the reference implementation
+    // accompanying https://eprint.iacr.org/2019/458.pdf for the poseidonperm_x5_254_3 instance
+    let ark = PoseidonBn254Parameters::round_constants();
+    assert_eq!(
+        bn254::BaseElement::from_hex(
+            "0x0ee9a592ba9a9518d05986d656f40c2114c4993c11bb29938d21d47304cd8e6e"
+        ),
+        ark[0]
+    );
+
+    let mut state = (0..3u64).map(bn254::BaseElement::from).collect::<Vec<_>>();
+    apply_permutation::<PoseidonBn254Parameters>(&mut state);
+
+    let expected = [
+        "0x115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a",
+        "0x0fca49b798923ab0239de1c9e7a4a9a2210312b6a2f616d18b5a87f9b628ae29",
+        "0x0e7ae82e40091e63cbd4f16a6d16310b3729d4b6e138fcf54110e2867045a30c",
+    ];
+    let expected = expected
+        .iter()
+        .map(|hex| bn254::BaseElement::from_hex(hex))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, state);
+}
+
+#[test]
+fn constants_f62() {
+    let (ark, mds) = generate_constants::<f62::BaseElement>(
+        Poseidon62Parameters::STATE_WIDTH,
+        Poseidon62Parameters::NUM_FULL_ROUNDS,
+        Poseidon62Parameters::NUM_PARTIAL_ROUNDS,
+    );
+    assert_eq!(ark, Poseidon62Parameters::round_constants());
+    assert_eq!(mds, Poseidon62Parameters::mds());
+}
+
+#[test]
+fn constants_f128() {
+    let (ark, mds) = generate_constants::<f128::BaseElement>(
+        Poseidon128Parameters::STATE_WIDTH,
+        Poseidon128Parameters::NUM_FULL_ROUNDS,
+        Poseidon128Parameters::NUM_PARTIAL_ROUNDS,
+    );
+    assert_eq!(ark, Poseidon128Parameters::round_constants());
+    assert_eq!(mds, Poseidon128Parameters::mds());
+}
+
+// POSEIDON OVER F62
+// ================================================================================================
+
+#[test]
+fn permutation_f62() {
+    let mut state = (0..12u64).map(f62::BaseElement::new).collect::<Vec<_>>();
+    apply_permutation::<Poseidon62Parameters>(&mut state);
+
+    let expected = [
+        3900006444014179170,
+        2026075818914512753,
+        1504322721676926111,
+        2664550647489410783,
+        665858277740985886,
+        3287972882543168031,
+        2908040547964625356,
+        3751653555650918312,
+        1537449703602163195,
+        3732299225510298573,
+        33118972937287486,
+        2677537509406176494,
+    ];
+    assert_eq!(to_f62_elements(&expected), state);
+}
+
+#[test]
+fn hash_f62() {
+    let elements = (1..=10u64).map(f62::BaseElement::new).collect::<Vec<_>>();
+    let expected = [
+        4194836483937101507,
+        3486804871189430498,
+        1062376495773753544,
+        3985428992296170484,
+    ];
+    assert_eq!(
+        to_f62_elements(&expected),
+        Poseidon62_248::hash_elements(&elements).as_elements()
+    );
+
+    let bytes = (0..32u8).collect::<Vec<_>>();
+    let expected = [
+        175250106542830368,
+        1922104830150856167,
+        1432528272875369642,
+        2926919599526024953,
+    ];
+    assert_eq!(
+        to_f62_elements(&expected),
+        Poseidon62_248::hash(&bytes).as_elements()
+    );
+
+    // elements in an extension field are hashed as sequences of base field elements
+    let a = f62::BaseElement::new(5);
+    let b = f62::BaseElement::new(7);
+    assert_eq!(
+        Poseidon62_248::hash_elements(&[a, f62::BaseElement::ZERO, b, f62::BaseElement::ZERO]),
+        Poseidon62_248::hash_elements(&[QuadExtension::from(a), QuadExtension::from(b)])
+    );
+}
+
+#[test]
+fn merge_f62() {
+    let a = Poseidon62_248::hash_elements(&[f62::BaseElement::new(1), f62::BaseElement::new(2)]);
+    let b = Poseidon62_248::hash_elements(&[f62::BaseElement::new(3), f62::BaseElement::new(4)]);
+    let expected = [
+        392256765148312506,
+        1781734370102099073,
+        2383839528388206678,
+        75638628733203208,
+    ];
+    assert_eq!(
+        to_f62_elements(&expected),
+        Poseidon62_248::merge(&[a, b]).as_elements()
+    );
+    assert_eq!(
+        Poseidon62_248::merge(&[a, b]),
+        Poseidon62_248::merge_n(&[a, b])
+    );
+}
+
+// POSEIDON OVER F128
+// ================================================================================================
+
+#[test]
+fn permutation_f128() {
+    let mut state = (0..6u128).map(f128::BaseElement::new).collect::<Vec<_>>();
+    apply_permutation::<Poseidon128Parameters>(&mut state);
+
+    let expected = [
+        187285719657754430714936006833534955258,
+        227928764929519361516643525735240073097,
+        123394519735075931533495509049016144459,
+        95775737051250428021412789263821458795,
+        135873511904893874693039620099187753157,
+        63433547184978219290288297867783357086,
+    ];
+    assert_eq!(to_f128_elements(&expected), state);
+}
+
+#[test]
+fn hash_f128() {
+    let elements = (1..=10u128).map(f128::BaseElement::new).collect::<Vec<_>>();
+    let expected = [
+        12554422200592349471303310807820119759,
+        53449472939111470366178243894200028232,
+    ];
+    assert_eq!(
+        to_f128_elements(&expected),
+        Poseidon128_256::hash_elements(&elements).as_elements()
+    );
+
+    let bytes = (0..32u8).collect::<Vec<_>>();
+    let expected = [
+        47230491799094237179685751269820699124,
+        292168726795983018292740253629002526978,
+    ];
+    assert_eq!(
+        to_f128_elements(&expected),
+        Poseidon128_256::hash(&bytes).as_elements()
+    );
+}
+
+#[test]
+#[should_panic(expected = "elements must be defined over the field of the hash function")]
+fn hash_f128_elements_of_other_field() {
+    let elements = (1..=10u64).map(f62::BaseElement::new).collect::<Vec<_>>();
+    Poseidon128_256::hash_elements(&elements);
+}
+
+#[test]
+fn merge_f128() {
+    let a = Poseidon128_256::hash_elements(&[f128::BaseElement::new(1), f128::BaseElement::new(2)]);
+    let b = Poseidon128_256::hash_elements(&[f128::BaseElement::new(3), f128::BaseElement::new(4)]);
+    let expected = [
+        103696460758597070764399438421296083453,
+        45533354793927882641377469261721514811,
+    ];
+    assert_eq!(
+        to_f128_elements(&expected),
+        Poseidon128_256::merge(&[a, b]).as_elements()
+    );
+    assert_eq!(
+        Poseidon128_256::merge(&[a, b]),
+        Poseidon128_256::merge_n(&[a, b])
+    );
+}
+
+// DIGEST AND RANDOM COIN
+// ================================================================================================
+
+#[test]
+fn digest_serialization() {
+    let digest = Poseidon62_248::hash_elements(&[f62::BaseElement::new(1)]);
+    let bytes = digest.as_bytes();
+    assert_eq!(Some(digest), ElementDigest::from_bytes(&bytes));
+    assert_eq!(
+        None,
+        ElementDigest::<f62::BaseElement, 4>::from_bytes(&bytes[..31])
+    );
+
+    let mut bytes = bytes;
+    bytes[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert_eq!(
+        None,
+        ElementDigest::<f62::BaseElement, 4>::from_bytes(&bytes)
+    );
+}
+
+#[test]
+fn random_element_generator() {
+    let mut generator1 = DefaultRandomElementGenerator::<Poseidon62_248>::new([1; 32], 0);
+    let mut generator2 = DefaultRandomElementGenerator::<Poseidon62_248>::new([1; 32], 0);
+    let (a, b) = generator1.draw_pair::<f62::BaseElement>();
+    assert_ne!(a, b);
+    assert_eq!((a, b), generator2.draw_pair());
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn to_f62_elements(values: &[u64]) -> Vec<f62::BaseElement> {
+    values.iter().map(|&v| f62::BaseElement::new(v)).collect()
+}
+
+fn to_f128_elements(values: &[u128]) -> Vec<f128::BaseElement> {
+    values.iter().map(|&v| f128::BaseElement::new(v)).collect()
+}
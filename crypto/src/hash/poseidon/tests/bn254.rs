@@ -0,0 +1,425 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Scalar field of the BN254 curve; this field is used only to instantiate the reference Poseidon
+//! permutation `poseidonperm_x5_254_3` in tests, and thus, the arithmetic is simple rather than
+//! fast.
+
+use core::{
+    cmp::Ordering,
+    convert::{TryFrom, TryInto},
+    fmt::{Display, Formatter},
+    mem,
+    ops::{
+        Add, AddAssign, BitAnd, Div, DivAssign, Mul, MulAssign, Neg, Shl, Shr, ShrAssign, Sub,
+        SubAssign,
+    },
+    slice,
+};
+use math::{
+    errors::SerializationError,
+    field::{FieldElement, StarkField},
+};
+use utils::AsBytes;
+
+// CONSTANTS
+// ================================================================================================
+
+// Field modulus = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+const M: U256 = U256([
+    0x43e1f593f0000001,
+    0x2833e84879b97091,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+]);
+
+// 2^28 root of unity
+const G: U256 = U256([
+    0x9bd61b6e725b19f0,
+    0x402d111e41112ed4,
+    0x00e0a7eb8ef62abc,
+    0x2a3c09f0a58a7e85,
+]);
+
+// FIELD ELEMENT
+// ================================================================================================
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct BaseElement(U256);
+
+impl FieldElement for BaseElement {
+    type PositiveInteger = U256;
+    type Base = Self;
+
+    const ZERO: Self = BaseElement(U256([0; 4]));
+    const ONE: Self = BaseElement(U256([1, 0, 0, 0]));
+
+    const ELEMENT_BYTES: usize = mem::size_of::<U256>();
+
+    fn inv(self) -> Self {
+        self.exp(M.sub(U256::from(2u32)))
+    }
+
+    fn conjugate(&self) -> Self {
+        *self
+    }
+
+    fn rand() -> Self {
+        unimplemented!("random elements are not needed in tests")
+    }
+
+    fn from_random_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::try_from(bytes).ok()
+    }
+
+    fn to_canonical_bytes(self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn elements_into_bytes(elements: Vec<Self>) -> Vec<u8> {
+        Self::elements_as_bytes(&elements).to_vec()
+    }
+
+    fn elements_as_bytes(elements: &[Self]) -> &[u8] {
+        let p = elements.as_ptr();
+        let len = elements.len() * Self::ELEMENT_BYTES;
+        unsafe { slice::from_raw_parts(p as *const u8, len) }
+    }
+
+    unsafe fn bytes_as_elements(bytes: &[u8]) -> Result<&[Self], SerializationError> {
+        if !bytes.len().is_multiple_of(Self::ELEMENT_BYTES) {
+            return Err(SerializationError::NotEnoughBytesForWholeElements(
+                bytes.len(),
+            ));
+        }
+
+        let p = bytes.as_ptr();
+        if !(p as usize).is_multiple_of(mem::align_of::<U256>()) {
+            return Err(SerializationError::InvalidMemoryAlignment);
+        }
+
+        Ok(slice::from_raw_parts(
+            p as *const Self,
+            bytes.len() / Self::ELEMENT_BYTES,
+        ))
+    }
+
+    fn prng_vector(_seed: [u8; 32], _n: usize) -> Vec<Self> {
+        unimplemented!("random elements are not needed in tests")
+    }
+}
+
+impl StarkField for BaseElement {
+    const MODULUS: Self::PositiveInteger = M;
+    const MODULUS_BITS: u32 = 254;
+    const GENERATOR: Self = BaseElement(U256([5, 0, 0, 0]));
+    const TWO_ADICITY: u32 = 28;
+    const TWO_ADIC_ROOT_OF_UNITY: Self = BaseElement(G);
+
+    fn get_modulus_le_bytes() -> Vec<u8> {
+        BaseElement(M).as_bytes().to_vec()
+    }
+
+    fn as_int(&self) -> Self::PositiveInteger {
+        self.0
+    }
+}
+
+impl BaseElement {
+    /// Parses a field element from a big-endian hex string; the string must encode a value
+    /// smaller than the field modulus.
+    pub fn from_hex(hex: &str) -> Self {
+        let hex = hex.trim_start_matches("0x");
+        let hex = format!("{:0>64}", hex);
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let start = 64 - 16 * (i + 1);
+            *limb = u64::from_str_radix(&hex[start..start + 16], 16).expect("invalid hex string");
+        }
+        let value = U256(limbs);
+        assert!(value < M, "value is not smaller than the field modulus");
+        BaseElement(value)
+    }
+}
+
+impl Display for BaseElement {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        let [l0, l1, l2, l3] = (self.0).0;
+        write!(f, "0x{:016x}{:016x}{:016x}{:016x}", l3, l2, l1, l0)
+    }
+}
+
+// OVERLOADED OPERATORS
+// ================================================================================================
+
+impl Add for BaseElement {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // both values are smaller than 2^254, and thus, the sum does not overflow
+        BaseElement(reduce_once(self.0.add(rhs.0)))
+    }
+}
+
+impl AddAssign for BaseElement {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl Sub for BaseElement {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        if self.0 >= rhs.0 {
+            BaseElement(self.0.sub(rhs.0))
+        } else {
+            BaseElement(self.0.add(M).sub(rhs.0))
+        }
+    }
+}
+
+impl SubAssign for BaseElement {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for BaseElement {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        BaseElement(mul(self.0, rhs.0))
+    }
+}
+
+impl MulAssign for BaseElement {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+impl Div for BaseElement {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl DivAssign for BaseElement {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs
+    }
+}
+
+impl Neg for BaseElement {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+// TYPE CONVERSIONS
+// ================================================================================================
+
+impl From<u128> for BaseElement {
+    fn from(value: u128) -> Self {
+        BaseElement(U256([value as u64, (value >> 64) as u64, 0, 0]))
+    }
+}
+
+impl From<u64> for BaseElement {
+    fn from(value: u64) -> Self {
+        BaseElement(U256::from(value))
+    }
+}
+
+impl From<u32> for BaseElement {
+    fn from(value: u32) -> Self {
+        BaseElement(U256::from(value))
+    }
+}
+
+impl From<u16> for BaseElement {
+    fn from(value: u16) -> Self {
+        BaseElement(U256::from(value as u32))
+    }
+}
+
+impl From<u8> for BaseElement {
+    fn from(value: u8) -> Self {
+        BaseElement(U256::from(value as u32))
+    }
+}
+
+impl TryFrom<&[u8]> for BaseElement {
+    type Error = String;
+
+    /// Converts 32 little-endian bytes into a field element; returns an error if the value is
+    /// not smaller than the field modulus.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != BaseElement::ELEMENT_BYTES {
+            return Err(format!("expected 32 bytes, but was {}", bytes.len()));
+        }
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        let value = U256(limbs);
+        if value >= M {
+            return Err("value is not smaller than the field modulus".to_string());
+        }
+        Ok(BaseElement(value))
+    }
+}
+
+impl AsBytes for BaseElement {
+    fn as_bytes(&self) -> &[u8] {
+        let self_ptr: *const BaseElement = self;
+        unsafe { slice::from_raw_parts(self_ptr as *const u8, BaseElement::ELEMENT_BYTES) }
+    }
+}
+
+// 256-BIT INTEGER
+// ================================================================================================
+
+/// Unsigned 256-bit integer stored as little-endian 64-bit limbs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct U256([u64; 4]);
+
+impl U256 {
+    /// Returns self + rhs; the result must fit into 256 bits.
+    fn add(self, rhs: Self) -> Self {
+        let mut result = [0u64; 4];
+        let mut carry = false;
+        for (i, limb) in result.iter_mut().enumerate() {
+            let (sum, c1) = self.0[i].overflowing_add(rhs.0[i]);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        debug_assert!(!carry, "256-bit addition overflowed");
+        U256(result)
+    }
+
+    /// Returns self - rhs; rhs must not be greater than self.
+    fn sub(self, rhs: Self) -> Self {
+        let mut result = [0u64; 4];
+        let mut borrow = false;
+        for (i, limb) in result.iter_mut().enumerate() {
+            let (diff, b1) = self.0[i].overflowing_sub(rhs.0[i]);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = b1 || b2;
+        }
+        debug_assert!(!borrow, "256-bit subtraction underflowed");
+        U256(result)
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+}
+
+impl From<u32> for U256 {
+    fn from(value: u32) -> Self {
+        U256([value as u64, 0, 0, 0])
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(value: u64) -> Self {
+        U256([value, 0, 0, 0])
+    }
+}
+
+impl Shl<u32> for U256 {
+    type Output = Self;
+
+    fn shl(self, rhs: u32) -> Self {
+        let mut result = [0u64; 4];
+        for i in 0..256 - rhs as usize {
+            let bit = (self.0[i / 64] >> (i % 64)) & 1;
+            let j = i + rhs as usize;
+            result[j / 64] |= bit << (j % 64);
+        }
+        U256(result)
+    }
+}
+
+impl Shr<u32> for U256 {
+    type Output = Self;
+
+    fn shr(self, rhs: u32) -> Self {
+        let mut result = [0u64; 4];
+        for i in rhs as usize..256 {
+            let bit = (self.0[i / 64] >> (i % 64)) & 1;
+            let j = i - rhs as usize;
+            result[j / 64] |= bit << (j % 64);
+        }
+        U256(result)
+    }
+}
+
+impl ShrAssign for U256 {
+    fn shr_assign(&mut self, rhs: Self) {
+        *self = *self >> rhs.0[0] as u32;
+    }
+}
+
+impl BitAnd for U256 {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        U256([
+            self.0[0] & rhs.0[0],
+            self.0[1] & rhs.0[1],
+            self.0[2] & rhs.0[2],
+            self.0[3] & rhs.0[3],
+        ])
+    }
+}
+
+// FINITE FIELD ARITHMETIC
+// ================================================================================================
+
+/// Subtracts the modulus from a value smaller than twice the modulus if needed.
+fn reduce_once(value: U256) -> U256 {
+    if value >= M {
+        value.sub(M)
+    } else {
+        value
+    }
+}
+
+/// Computes (a * b) % m by reducing the 512-bit product one bit at a time.
+fn mul(a: U256, b: U256) -> U256 {
+    let mut product = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0u128;
+        for j in 0..4 {
+            let t = (a.0[i] as u128) * (b.0[j] as u128) + product[i + j] as u128 + carry;
+            product[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        product[i + 4] = carry as u64;
+    }
+
+    // the remainder is always smaller than the modulus, and thus, doubling it does not overflow
+    let mut result = U256::default();
+    for i in (0..512).rev() {
+        let bit = (product[i / 64] >> (i % 64)) & 1;
+        result = result.add(result);
+        result.0[0] |= bit;
+        result = reduce_once(result);
+    }
+    result
+}
@@ -0,0 +1,19 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Rescue Prime hash functions.
+//!
+//! Parameters of these hash functions follow the Rescue Prime specification from
+//! https://eprint.iacr.org/2020/1143.pdf. Unlike byte-oriented hash functions, these functions
+//! operate natively over elements of a specific prime field and produce digests which consist
+//! of field elements. This makes them much cheaper to describe in AIR (e.g. for recursive
+//! proof verification), but also means that `hash_elements()` can be invoked only for elements
+//! in the field of the hash function (or in an extension of this field).
+
+pub mod rp62_248;
+pub use rp62_248::Rp62_248;
+
+pub mod rp128_256;
+pub use rp128_256::Rp128_256;
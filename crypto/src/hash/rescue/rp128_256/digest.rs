@@ -0,0 +1,71 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{DIGEST_SIZE, STATE_WIDTH};
+use crate::hash::Digest;
+use core::convert::TryFrom;
+use math::field::{f128::BaseElement, FieldElement, StarkField};
+
+// DIGEST TRAIT IMPLEMENTATIONS
+// ================================================================================================
+
+/// Digest of the Rp128_256 hash function; consists of 2 elements in the 128-bit field.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct ElementDigest([BaseElement; DIGEST_SIZE]);
+
+impl ElementDigest {
+    pub fn new(value: [BaseElement; DIGEST_SIZE]) -> Self {
+        Self(value)
+    }
+
+    pub fn as_elements(&self) -> &[BaseElement] {
+        &self.0
+    }
+
+    /// Returns a digest built from the first DIGEST_SIZE elements of the sponge state.
+    pub(super) fn from_state(state: &[BaseElement; STATE_WIDTH]) -> Self {
+        let mut result = [BaseElement::ZERO; DIGEST_SIZE];
+        result.copy_from_slice(&state[..DIGEST_SIZE]);
+        Self(result)
+    }
+}
+
+impl Digest for ElementDigest {
+    const SIZE: usize = DIGEST_SIZE * BaseElement::ELEMENT_BYTES;
+
+    fn as_bytes(&self) -> [u8; 32] {
+        let mut result = [0; 32];
+        for (chunk, element) in result.chunks_mut(BaseElement::ELEMENT_BYTES).zip(&self.0) {
+            chunk.copy_from_slice(&element.as_int().to_le_bytes());
+        }
+        result
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::SIZE {
+            return None;
+        }
+        let mut result = [BaseElement::ZERO; DIGEST_SIZE];
+        for (element, chunk) in result
+            .iter_mut()
+            .zip(bytes.chunks(BaseElement::ELEMENT_BYTES))
+        {
+            *element = BaseElement::try_from(chunk).ok()?;
+        }
+        Some(Self(result))
+    }
+}
+
+impl From<[BaseElement; DIGEST_SIZE]> for ElementDigest {
+    fn from(value: [BaseElement; DIGEST_SIZE]) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ElementDigest> for [BaseElement; DIGEST_SIZE] {
+    fn from(value: ElementDigest) -> Self {
+        value.0
+    }
+}
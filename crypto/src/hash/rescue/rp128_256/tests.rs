@@ -0,0 +1,91 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    apply_permutation, BaseElement, ElementDigest, FieldElement, Hasher, Rp128_256, STATE_WIDTH,
+};
+use crate::hash::Digest;
+use math::field::QuadExtension;
+
+#[test]
+fn test_permutation() {
+    let mut state = [BaseElement::ZERO; STATE_WIDTH];
+    for (i, element) in state.iter_mut().enumerate() {
+        *element = BaseElement::new(i as u128);
+    }
+    apply_permutation(&mut state);
+
+    let expected = [
+        BaseElement::new(32369622301644990959410729772782360971),
+        BaseElement::new(229021041404520472047411496069468593859),
+        BaseElement::new(190189257949293711256698268003535785025),
+        BaseElement::new(71428566036146287810508774385293425217),
+        BaseElement::new(214518231723688629171016638468407799410),
+        BaseElement::new(246940106077755426566701795800156988331),
+    ];
+    assert_eq!(expected, state);
+}
+
+#[test]
+fn hash_elements() {
+    let elements = (1..=10u128).map(BaseElement::new).collect::<Vec<_>>();
+    let expected = ElementDigest::new([
+        BaseElement::new(183959329402688167261187269517446348052),
+        BaseElement::new(255589266529000685898277776451268442516),
+    ]);
+    assert_eq!(expected, Rp128_256::hash_elements(&elements));
+}
+
+#[test]
+fn hash_extension_elements() {
+    let a = BaseElement::new(5);
+    let b = BaseElement::new(7);
+    let base_digest = Rp128_256::hash_elements(&[a, BaseElement::ZERO, b, BaseElement::ZERO]);
+    let ext_digest = Rp128_256::hash_elements(&[QuadExtension::from(a), QuadExtension::from(b)]);
+    assert_eq!(base_digest, ext_digest);
+}
+
+#[test]
+fn hash_bytes() {
+    let bytes = (0..32u8).collect::<Vec<_>>();
+    let expected = ElementDigest::new([
+        BaseElement::new(296732863574084881907092425159923152665),
+        BaseElement::new(238984721774424645326471826555608231305),
+    ]);
+    assert_eq!(expected, Rp128_256::hash(&bytes));
+
+    // trailing zeros must not be ignored
+    assert_ne!(
+        Rp128_256::hash(&bytes[..31]),
+        Rp128_256::hash(&[&bytes[..31], &[0]].concat())
+    );
+}
+
+#[test]
+fn merge() {
+    let a = Rp128_256::hash_elements(&[BaseElement::new(1), BaseElement::new(2)]);
+    let b = Rp128_256::hash_elements(&[BaseElement::new(3), BaseElement::new(4)]);
+    let expected = ElementDigest::new([
+        BaseElement::new(90847091154448703136456424715474900074),
+        BaseElement::new(313168202035654035689341121982007178259),
+    ]);
+    assert_eq!(expected, Rp128_256::merge(&[a, b]));
+    assert_eq!(expected, Rp128_256::merge_n(&[a, b]));
+}
+
+#[test]
+fn digest_serialization() {
+    let digest = Rp128_256::hash_elements(&[BaseElement::new(1), BaseElement::new(2)]);
+    let bytes = digest.as_bytes();
+    assert_eq!(Some(digest), ElementDigest::from_bytes(&bytes));
+
+    // wrong number of bytes
+    assert_eq!(None, ElementDigest::from_bytes(&bytes[..31]));
+
+    // non-canonical field element
+    let mut bytes = bytes;
+    bytes[..16].copy_from_slice(&u128::MAX.to_le_bytes());
+    assert_eq!(None, ElementDigest::from_bytes(&bytes));
+}
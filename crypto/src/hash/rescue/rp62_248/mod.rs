@@ -0,0 +1,525 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::hash::Hasher;
+use math::field::{f62::BaseElement, FieldElement, StarkField};
+
+mod digest;
+pub use digest::ElementDigest;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Sponge state is set to 12 field elements or 96 bytes; 8 elements are reserved for rate and
+/// 4 elements are reserved for capacity.
+pub const STATE_WIDTH: usize = 12;
+
+/// The rate portion of the state is located in elements 0 through 7 (inclusive); the capacity
+/// portion of the state is located in elements 8 through 11.
+pub const RATE_WIDTH: usize = 8;
+
+/// Four elements (32 bytes) are returned as digest; since every element is 62 bits, this
+/// provides roughly 124-bit collision resistance.
+pub const DIGEST_SIZE: usize = 4;
+
+/// The number of rounds is set to 7 to provide 128-bit security level with 40% security margin;
+/// computed using algorithm 7 from https://eprint.iacr.org/2020/1143.pdf
+pub const NUM_ROUNDS: usize = 7;
+
+/// When hashing a sequence of bytes, the bytes are split into 7-byte chunks; this is the largest
+/// chunk size which is guaranteed to encode a valid field element.
+const BYTES_PER_ELEMENT: usize = 7;
+
+// HASHER IMPLEMENTATION
+// ================================================================================================
+
+/// Rescue Prime hash function over the 62-bit field with 248-bit output.
+///
+/// The function is instantiated with state width of 12 elements, rate of 8 elements, S-Box of
+/// degree 3, and 7 rounds.
+pub struct Rp62_248();
+
+impl Hasher for Rp62_248 {
+    type Digest = ElementDigest;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        let elements = bytes
+            .chunks(BYTES_PER_ELEMENT)
+            .map(|chunk| {
+                let mut buf = [0u8; 8];
+                buf[..chunk.len()].copy_from_slice(chunk);
+                BaseElement::new(u64::from_le_bytes(buf))
+            })
+            .collect::<Vec<_>>();
+        hash_base_elements(&elements, bytes.len())
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        // the two digests fill the entire rate portion of the state, and the capacity portion is
+        // left at zeros; the merged digest is then taken from the permuted state.
+        let mut state = [BaseElement::ZERO; STATE_WIDTH];
+        state[..DIGEST_SIZE].copy_from_slice(values[0].as_elements());
+        state[DIGEST_SIZE..RATE_WIDTH].copy_from_slice(values[1].as_elements());
+        apply_permutation(&mut state);
+        ElementDigest::from_state(&state)
+    }
+
+    fn merge_n(values: &[Self::Digest]) -> Self::Digest {
+        // the digests are absorbed into the rate portion of the state with the capacity portion
+        // left at zeros; thus, for two digests, the result is the same as for merge().
+        let elements = values
+            .iter()
+            .flat_map(|digest| digest.as_elements().iter().copied())
+            .collect::<Vec<_>>();
+        hash_base_elements(&elements, 0)
+    }
+
+    fn hash_elements<E: FieldElement>(elements: &[E]) -> Self::Digest {
+        debug_assert_eq!(
+            E::Base::get_modulus_le_bytes(),
+            BaseElement::get_modulus_le_bytes(),
+            "elements must be defined over the 62-bit field"
+        );
+        // elements in the base field and in its extensions are laid out in memory as sequences of
+        // base field elements; so, we can hash them directly without converting them into bytes.
+        let elements = unsafe { BaseElement::bytes_as_elements(E::elements_as_bytes(elements)) }
+            .expect("failed to interpret elements as elements of the 62-bit field");
+        hash_base_elements(elements, elements.len())
+    }
+}
+
+// RESCUE PERMUTATION
+// ================================================================================================
+
+/// Applies Rescue-XLIX permutation to the provided state.
+pub fn apply_permutation(state: &mut [BaseElement; STATE_WIDTH]) {
+    for i in 0..NUM_ROUNDS {
+        apply_round(state, i);
+    }
+}
+
+/// Rescue-XLIX round function;
+/// implementation based on algorithm 3 from https://eprint.iacr.org/2020/1143.pdf
+#[inline(always)]
+pub fn apply_round(state: &mut [BaseElement; STATE_WIDTH], round: usize) {
+    // apply first half of Rescue round
+    apply_sbox(state);
+    apply_mds(state);
+    add_constants(state, &ARK[round][..STATE_WIDTH]);
+
+    // apply second half of Rescue round
+    apply_inv_sbox(state);
+    apply_mds(state);
+    add_constants(state, &ARK[round][STATE_WIDTH..]);
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Absorbs the provided elements into a sponge and returns the resulting digest. The first
+/// capacity element is initialized to `input_len` so that inputs of different lengths which
+/// end up padded to the same sequence of elements produce different digests.
+fn hash_base_elements(elements: &[BaseElement], input_len: usize) -> ElementDigest {
+    let mut state = [BaseElement::ZERO; STATE_WIDTH];
+    state[RATE_WIDTH] = BaseElement::new(input_len as u64);
+
+    let mut i = 0;
+    for &element in elements.iter() {
+        state[i] += element;
+        i += 1;
+        if i == RATE_WIDTH {
+            apply_permutation(&mut state);
+            i = 0;
+        }
+    }
+
+    // if the last chunk of elements did not fill the entire rate, apply the permutation once
+    // more; the remainder of the rate is implicitly padded with zeros.
+    if i > 0 {
+        apply_permutation(&mut state);
+    }
+
+    ElementDigest::from_state(&state)
+}
+
+#[inline(always)]
+fn apply_sbox(state: &mut [BaseElement; STATE_WIDTH]) {
+    for element in state.iter_mut() {
+        *element = element.exp(ALPHA);
+    }
+}
+
+#[inline(always)]
+fn apply_inv_sbox(state: &mut [BaseElement; STATE_WIDTH]) {
+    for element in state.iter_mut() {
+        *element = element.exp(INV_ALPHA);
+    }
+}
+
+#[inline(always)]
+#[allow(clippy::needless_range_loop)]
+fn apply_mds(state: &mut [BaseElement; STATE_WIDTH]) {
+    let mut result = [BaseElement::ZERO; STATE_WIDTH];
+    for i in 0..STATE_WIDTH {
+        for j in 0..STATE_WIDTH {
+            result[i] += MDS[i * STATE_WIDTH + j] * state[j];
+        }
+    }
+    *state = result;
+}
+
+#[inline(always)]
+fn add_constants(state: &mut [BaseElement; STATE_WIDTH], ark: &[BaseElement]) {
+    for (element, &constant) in state.iter_mut().zip(ark) {
+        *element += constant;
+    }
+}
+
+// CONSTANTS
+// ================================================================================================
+
+/// S-Box and Inverse S-Box powers;
+/// computed using algorithm 6 from https://eprint.iacr.org/2020/1143.pdf
+const ALPHA: u64 = 3;
+const INV_ALPHA: u64 = 3074416663688030891;
+
+/// Rescue MDS matrix
+/// Computed using algorithm 4 from https://eprint.iacr.org/2020/1143.pdf
+const MDS: [BaseElement; STATE_WIDTH * STATE_WIDTH] = [
+    BaseElement::new(3950144678237376122),
+    BaseElement::new(2690153189131774333),
+    BaseElement::new(936645784682382348),
+    BaseElement::new(3107191214132265415),
+    BaseElement::new(2603209838230440664),
+    BaseElement::new(1199396433148647196),
+    BaseElement::new(1282983482067326228),
+    BaseElement::new(461437407589395643),
+    BaseElement::new(2214977176974126410),
+    BaseElement::new(360795585898440),
+    BaseElement::new(4611624977880333167),
+    BaseElement::new(265720),
+    BaseElement::new(3536793164176604955),
+    BaseElement::new(1911503332938627860),
+    BaseElement::new(3418675122760523340),
+    BaseElement::new(1504989930332511353),
+    BaseElement::new(2722575982003138843),
+    BaseElement::new(1431609872573058051),
+    BaseElement::new(1192456656548488631),
+    BaseElement::new(545546930229576032),
+    BaseElement::new(945223199513254881),
+    BaseElement::new(1241455355734630133),
+    BaseElement::new(4607295377894412377),
+    BaseElement::new(52955405230),
+    BaseElement::new(4170851182034451356),
+    BaseElement::new(4049722115827050441),
+    BaseElement::new(2592958603203603955),
+    BaseElement::new(1591126261909367400),
+    BaseElement::new(1258275846807863107),
+    BaseElement::new(1998950167196902314),
+    BaseElement::new(3042201191319512244),
+    BaseElement::new(543039388605157758),
+    BaseElement::new(1398996793391337371),
+    BaseElement::new(4366181202594792993),
+    BaseElement::new(2647705527662157444),
+    BaseElement::new(9741692640081640),
+    BaseElement::new(2734904247639408359),
+    BaseElement::new(4279587509601476247),
+    BaseElement::new(4485482368008952587),
+    BaseElement::new(3891839128198288856),
+    BaseElement::new(3605615068318190226),
+    BaseElement::new(4481033712623965820),
+    BaseElement::new(4511906145686918697),
+    BaseElement::new(3379942354449020806),
+    BaseElement::new(3990599459674901680),
+    BaseElement::new(3930378924631282611),
+    BaseElement::new(2736309679810514295),
+    BaseElement::new(4088651356677543187),
+    BaseElement::new(842258110397353220),
+    BaseElement::new(3379876823114508085),
+    BaseElement::new(1075495666387844288),
+    BaseElement::new(2308322198399190449),
+    BaseElement::new(535073101119307124),
+    BaseElement::new(2549013922555968548),
+    BaseElement::new(2089967165864721761),
+    BaseElement::new(1833259538539094178),
+    BaseElement::new(1286299364399671252),
+    BaseElement::new(3116429868056012525),
+    BaseElement::new(3765145590440791140),
+    BaseElement::new(276983628385769116),
+    BaseElement::new(1299560456850023050),
+    BaseElement::new(4414989737001639740),
+    BaseElement::new(627780834867342283),
+    BaseElement::new(1711770898052004155),
+    BaseElement::new(1979604523493335895),
+    BaseElement::new(33488920757262988),
+    BaseElement::new(3296083413419576217),
+    BaseElement::new(716111559512999319),
+    BaseElement::new(1748727787185165915),
+    BaseElement::new(2725007460252215875),
+    BaseElement::new(2185047820717910109),
+    BaseElement::new(2319951565550756140),
+    BaseElement::new(4184625686841861769),
+    BaseElement::new(1784981074793151883),
+    BaseElement::new(502457291852703062),
+    BaseElement::new(345570060311611630),
+    BaseElement::new(2471821400707240604),
+    BaseElement::new(2133038110899525730),
+    BaseElement::new(939120245208093777),
+    BaseElement::new(4151312447988641414),
+    BaseElement::new(210626922136569504),
+    BaseElement::new(2121768124528492214),
+    BaseElement::new(3469035391047007665),
+    BaseElement::new(743768221345332434),
+    BaseElement::new(2145694559473526100),
+    BaseElement::new(1632268183143575659),
+    BaseElement::new(440280249850363795),
+    BaseElement::new(1074260737240252344),
+    BaseElement::new(434235372443698697),
+    BaseElement::new(4579079558834190297),
+    BaseElement::new(507988595809300562),
+    BaseElement::new(746255436130103157),
+    BaseElement::new(1959107915115263608),
+    BaseElement::new(4030330146733953284),
+    BaseElement::new(3748621471482452510),
+    BaseElement::new(1760002751403551673),
+    BaseElement::new(2299194066166806303),
+    BaseElement::new(2406031288159683129),
+    BaseElement::new(3724303300393675060),
+    BaseElement::new(3136303930848425791),
+    BaseElement::new(842217609243732235),
+    BaseElement::new(2433222065782096659),
+    BaseElement::new(1853915347332186193),
+    BaseElement::new(3565339054535487990),
+    BaseElement::new(3159752035320462032),
+    BaseElement::new(1001592926358592140),
+    BaseElement::new(1070575826169209928),
+    BaseElement::new(2177302522881920563),
+    BaseElement::new(2207526749486243134),
+    BaseElement::new(4032720262691072240),
+    BaseElement::new(1260214313840482146),
+    BaseElement::new(3621152551536391331),
+    BaseElement::new(1609693674346558276),
+    BaseElement::new(1076797379868177960),
+    BaseElement::new(1050224695423079188),
+    BaseElement::new(1679887683779537233),
+    BaseElement::new(1053394941293588429),
+    BaseElement::new(2176319632402176708),
+    BaseElement::new(807051555764923088),
+    BaseElement::new(2483141537228001953),
+    BaseElement::new(873986056056007361),
+    BaseElement::new(2985158312969304104),
+    BaseElement::new(2082576071668149043),
+    BaseElement::new(1607709264834493266),
+    BaseElement::new(1027130385873843589),
+    BaseElement::new(3876861839368848637),
+    BaseElement::new(2999813843878199730),
+    BaseElement::new(3252530728916107838),
+    BaseElement::new(4464640832314938694),
+    BaseElement::new(1978539358398864357),
+    BaseElement::new(3425590232595452442),
+    BaseElement::new(3706838041850115299),
+    BaseElement::new(3407508207732360664),
+    BaseElement::new(2899952415584588394),
+    BaseElement::new(282047285293952955),
+    BaseElement::new(4147714396995528527),
+    BaseElement::new(1141786266584343815),
+    BaseElement::new(3523991864183271024),
+    BaseElement::new(1659008334442446407),
+    BaseElement::new(2857663046861472404),
+    BaseElement::new(1954265424153359502),
+    BaseElement::new(4018750979872307732),
+    BaseElement::new(494911809436924696),
+    BaseElement::new(1282149942051721903),
+];
+
+/// Rescue round constants;
+/// computed using algorithm 5 from https://eprint.iacr.org/2020/1143.pdf
+pub const ARK: [[BaseElement; STATE_WIDTH * 2]; NUM_ROUNDS] = [
+    [
+        BaseElement::new(3484644429707000305),
+        BaseElement::new(3716574574064417116),
+        BaseElement::new(841415170857585170),
+        BaseElement::new(568119523712749677),
+        BaseElement::new(2462802307289392788),
+        BaseElement::new(3186926331460562637),
+        BaseElement::new(741047769971601638),
+        BaseElement::new(2087142208795424920),
+        BaseElement::new(2580502116323190178),
+        BaseElement::new(2667199208682617462),
+        BaseElement::new(575604924081885105),
+        BaseElement::new(1153923700877224322),
+        BaseElement::new(1303713113191856344),
+        BaseElement::new(614792334826309732),
+        BaseElement::new(3220810414930505387),
+        BaseElement::new(1452195194303469364),
+        BaseElement::new(238358514192508055),
+        BaseElement::new(3660166767300268899),
+        BaseElement::new(3880688641427684696),
+        BaseElement::new(2780710719995136164),
+        BaseElement::new(2022805236796871204),
+        BaseElement::new(3898429504785385876),
+        BaseElement::new(2257597144383763480),
+        BaseElement::new(82991721684975982),
+    ],
+    [
+        BaseElement::new(3705273656637539757),
+        BaseElement::new(39087706526102331),
+        BaseElement::new(2963817469827213835),
+        BaseElement::new(3861443699731826916),
+        BaseElement::new(2637139715069324140),
+        BaseElement::new(2688768463237859920),
+        BaseElement::new(265410315049149644),
+        BaseElement::new(491705310003774270),
+        BaseElement::new(1857132926704753868),
+        BaseElement::new(3862430013290083725),
+        BaseElement::new(1576360491912353331),
+        BaseElement::new(791621448608857244),
+        BaseElement::new(154152795542924197),
+        BaseElement::new(1661174749518683090),
+        BaseElement::new(4391035959967217251),
+        BaseElement::new(3247028696130338974),
+        BaseElement::new(1121520376334303013),
+        BaseElement::new(1374130143129726295),
+        BaseElement::new(84908013605478268),
+        BaseElement::new(334170295973437067),
+        BaseElement::new(2830628946297541061),
+        BaseElement::new(4573040704084807009),
+        BaseElement::new(274158946129162331),
+        BaseElement::new(3849547756077698740),
+    ],
+    [
+        BaseElement::new(2168751801097496365),
+        BaseElement::new(369524711398094479),
+        BaseElement::new(634015129243141833),
+        BaseElement::new(4282069956289383801),
+        BaseElement::new(1175566366775665386),
+        BaseElement::new(4078623646978407284),
+        BaseElement::new(516417566897789120),
+        BaseElement::new(3353061138090314633),
+        BaseElement::new(3560819976972424515),
+        BaseElement::new(3607339659358716389),
+        BaseElement::new(3066051655770225551),
+        BaseElement::new(1801895101950785667),
+        BaseElement::new(166233218206248739),
+        BaseElement::new(489351169245317117),
+        BaseElement::new(2962252291723053454),
+        BaseElement::new(122541662234186638),
+        BaseElement::new(4424814474892160664),
+        BaseElement::new(1320099032209008426),
+        BaseElement::new(1529330880967342895),
+        BaseElement::new(1330695468689608589),
+        BaseElement::new(963898903617467531),
+        BaseElement::new(2275294439125298173),
+        BaseElement::new(958230387351407760),
+        BaseElement::new(755936910666563014),
+    ],
+    [
+        BaseElement::new(576203792637636256),
+        BaseElement::new(4337167377135626829),
+        BaseElement::new(1998171286474579018),
+        BaseElement::new(3219003078837549950),
+        BaseElement::new(1754029993648071413),
+        BaseElement::new(784856332924154369),
+        BaseElement::new(852615146297640398),
+        BaseElement::new(187169672533217714),
+        BaseElement::new(3372576263179842296),
+        BaseElement::new(1296629783710891782),
+        BaseElement::new(953176072684705334),
+        BaseElement::new(4287789353512716203),
+        BaseElement::new(559210870191949231),
+        BaseElement::new(424200001505666527),
+        BaseElement::new(4327997954490709567),
+        BaseElement::new(4374897776461679548),
+        BaseElement::new(650446168692508692),
+        BaseElement::new(626907883142255914),
+        BaseElement::new(3357925312477037393),
+        BaseElement::new(712991480883018031),
+        BaseElement::new(1887034140662835969),
+        BaseElement::new(48950670504446469),
+        BaseElement::new(1466730342125839704),
+        BaseElement::new(3441580939088846478),
+    ],
+    [
+        BaseElement::new(3387355467507491304),
+        BaseElement::new(1786620721277954243),
+        BaseElement::new(171704922602581228),
+        BaseElement::new(1508492616177267123),
+        BaseElement::new(1105502515283620539),
+        BaseElement::new(2432617410228045687),
+        BaseElement::new(65276993478738140),
+        BaseElement::new(2681810225145030798),
+        BaseElement::new(4495078061753996914),
+        BaseElement::new(608239777367150459),
+        BaseElement::new(4167053494651782149),
+        BaseElement::new(189593221003495887),
+        BaseElement::new(672731773721361408),
+        BaseElement::new(2838110417616694563),
+        BaseElement::new(4230032367101995400),
+        BaseElement::new(2599110095944751903),
+        BaseElement::new(3724936779503951977),
+        BaseElement::new(378768165507914674),
+        BaseElement::new(1436645826460104564),
+        BaseElement::new(2227576404959090394),
+        BaseElement::new(2044550958162794465),
+        BaseElement::new(605052128606465369),
+        BaseElement::new(2348614157913525789),
+        BaseElement::new(3365212346018202605),
+    ],
+    [
+        BaseElement::new(2445067018344495495),
+        BaseElement::new(3932635324457229744),
+        BaseElement::new(3927085919466374690),
+        BaseElement::new(4078514836304032307),
+        BaseElement::new(2553154065984596658),
+        BaseElement::new(3506096510876730178),
+        BaseElement::new(528944655919177826),
+        BaseElement::new(4136322766156875829),
+        BaseElement::new(995357311397393350),
+        BaseElement::new(2201156015503518515),
+        BaseElement::new(3868382442189041304),
+        BaseElement::new(3708622628406770),
+        BaseElement::new(369106925753389280),
+        BaseElement::new(4420698985729452741),
+        BaseElement::new(3942768350509440359),
+        BaseElement::new(3339331545789339152),
+        BaseElement::new(2269255593320557184),
+        BaseElement::new(3373354389542326501),
+        BaseElement::new(3704624929331646327),
+        BaseElement::new(2363729658510417381),
+        BaseElement::new(3847039145384626949),
+        BaseElement::new(3681741335518660619),
+        BaseElement::new(4266834984107484983),
+        BaseElement::new(1067820811644694149),
+    ],
+    [
+        BaseElement::new(2125516548967992061),
+        BaseElement::new(3264306988025685512),
+        BaseElement::new(4035495940667529309),
+        BaseElement::new(2231789614956829237),
+        BaseElement::new(651760096221577021),
+        BaseElement::new(3790481818903091761),
+        BaseElement::new(4302822773536254099),
+        BaseElement::new(4354602841674273721),
+        BaseElement::new(1209578151203190507),
+        BaseElement::new(4516229638925486426),
+        BaseElement::new(1038211697499533102),
+        BaseElement::new(3385179681193192538),
+        BaseElement::new(4530018811636524940),
+        BaseElement::new(833730118437093498),
+        BaseElement::new(1199374045316772459),
+        BaseElement::new(4051341519028588794),
+        BaseElement::new(2822156456410070171),
+        BaseElement::new(2706524218197115307),
+        BaseElement::new(3528596254323798420),
+        BaseElement::new(575791345898286339),
+        BaseElement::new(4039649721710029690),
+        BaseElement::new(806635388744790703),
+        BaseElement::new(4550190941301725009),
+        BaseElement::new(258966971328049258),
+    ],
+];
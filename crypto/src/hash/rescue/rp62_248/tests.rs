@@ -0,0 +1,118 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    apply_permutation, BaseElement, ElementDigest, FieldElement, Hasher, Rp62_248, STATE_WIDTH,
+};
+use crate::hash::Digest;
+use math::field::QuadExtension;
+
+#[test]
+fn test_permutation() {
+    let mut state = [BaseElement::ZERO; STATE_WIDTH];
+    for (i, element) in state.iter_mut().enumerate() {
+        *element = BaseElement::new(i as u64);
+    }
+    apply_permutation(&mut state);
+
+    let expected = [
+        BaseElement::new(2865148879768688036),
+        BaseElement::new(1618056533258510940),
+        BaseElement::new(1197861713862035684),
+        BaseElement::new(2446511926847927594),
+        BaseElement::new(2541204437290784585),
+        BaseElement::new(2162101160177097795),
+        BaseElement::new(2453951491581777442),
+        BaseElement::new(56193565312839165),
+        BaseElement::new(2776061647040256612),
+        BaseElement::new(4207523682710088609),
+        BaseElement::new(109209583973662600),
+        BaseElement::new(2367344321700970793),
+    ];
+    assert_eq!(expected, state);
+}
+
+#[test]
+fn hash_elements() {
+    let elements = (1..=10u64).map(BaseElement::new).collect::<Vec<_>>();
+    let expected = ElementDigest::new([
+        BaseElement::new(4288543118181662812),
+        BaseElement::new(4030002893669280),
+        BaseElement::new(3646683001966756457),
+        BaseElement::new(1049685312111448596),
+    ]);
+    assert_eq!(expected, Rp62_248::hash_elements(&elements));
+}
+
+#[test]
+fn hash_extension_elements() {
+    let a = BaseElement::new(5);
+    let b = BaseElement::new(7);
+    let base_digest = Rp62_248::hash_elements(&[a, BaseElement::ZERO, b, BaseElement::ZERO]);
+    let ext_digest = Rp62_248::hash_elements(&[QuadExtension::from(a), QuadExtension::from(b)]);
+    assert_eq!(base_digest, ext_digest);
+}
+
+#[test]
+fn hash_bytes() {
+    let bytes = (0..32u8).collect::<Vec<_>>();
+    let expected = ElementDigest::new([
+        BaseElement::new(2223850321374790696),
+        BaseElement::new(2649137375145867810),
+        BaseElement::new(1990036606956164046),
+        BaseElement::new(3863597595057524797),
+    ]);
+    assert_eq!(expected, Rp62_248::hash(&bytes));
+
+    // trailing zeros must not be ignored
+    assert_ne!(
+        Rp62_248::hash(&bytes[..31]),
+        Rp62_248::hash(&[&bytes[..31], &[0]].concat())
+    );
+}
+
+#[test]
+fn merge() {
+    let a = Rp62_248::hash_elements(&[BaseElement::new(1), BaseElement::new(2)]);
+    let b = Rp62_248::hash_elements(&[BaseElement::new(3), BaseElement::new(4)]);
+    let expected = ElementDigest::new([
+        BaseElement::new(4568778061854937470),
+        BaseElement::new(81583050339962275),
+        BaseElement::new(2286475864815774861),
+        BaseElement::new(4587268869670524535),
+    ]);
+    assert_eq!(expected, Rp62_248::merge(&[a, b]));
+    assert_eq!(expected, Rp62_248::merge_n(&[a, b]));
+}
+
+#[test]
+fn merge_n() {
+    let digests = [[1, 2], [3, 4], [5, 6], [7, 8]]
+        .iter()
+        .map(|&[a, b]| Rp62_248::hash_elements(&[BaseElement::new(a), BaseElement::new(b)]))
+        .collect::<Vec<_>>();
+    let expected = ElementDigest::new([
+        BaseElement::new(2527947831659106788),
+        BaseElement::new(4275019415723665586),
+        BaseElement::new(923676691437008995),
+        BaseElement::new(3217002371394548462),
+    ]);
+    assert_eq!(expected, Rp62_248::merge_n(&digests));
+}
+
+#[test]
+fn digest_serialization() {
+    let digest = Rp62_248::hash_elements(&[BaseElement::new(1), BaseElement::new(2)]);
+    let bytes = digest.as_bytes();
+    assert_eq!(Some(digest), ElementDigest::from_bytes(&bytes));
+
+    // wrong number of bytes
+    assert_eq!(None, ElementDigest::from_bytes(&bytes[..31]));
+
+    // non-canonical field element
+    let mut bytes = bytes;
+    bytes[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert_eq!(None, ElementDigest::from_bytes(&bytes));
+}
@@ -4,14 +4,22 @@
 // LICENSE file in the root directory of this source tree.
 
 pub mod hash;
-pub use hash::Hasher;
+pub use hash::{Digest, Hasher};
 
 pub mod merkle;
-pub use merkle::{build_merkle_nodes, BatchMerkleProof, MerkleTree};
+pub use merkle::{
+    build_merkle_nodes, build_merkle_nodes_n, BatchMerkleProof, MerkleTree, StreamingMerkleTree,
+    StreamingMerkleTreeBuilder,
+};
 
-pub mod merkle2;
+pub mod mmr;
+pub use mmr::{MerkleMountainRange, MmrConsistencyProof, MmrInclusionProof};
+
+pub mod sparse_merkle;
+pub use sparse_merkle::{LeafKey, SparseMerkleProof, SparseMerkleTree};
 
 mod random;
 pub use random::{DefaultRandomElementGenerator, RandomElementGenerator};
 
-pub type HashFunction = fn(&[u8], &mut [u8]);
+mod errors;
+pub use errors::{DigestSerializationError, MmrProofSerializationError};
@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::HashFunction;
+use crate::Hasher;
 use rayon::prelude::*;
 use std::slice;
 
@@ -18,19 +18,19 @@ pub const MIN_CONCURRENT_LEAVES: usize = 1024;
 /// Builds a all internal nodes of the Merkle using all available threads and stores the
 /// results in a single vector such that root of the tree is at position 1, nodes immediately
 /// under the root is at positions 2 and 3 etc.
-pub fn build_merkle_nodes(leaves: &[[u8; 32]], hash: HashFunction) -> Vec<[u8; 32]> {
+pub fn build_merkle_nodes<H: Hasher>(leaves: &[H::Digest]) -> Vec<H::Digest> {
     // create un-initialized array to hold all intermediate nodes
     let n = leaves.len() / 2;
-    let mut nodes = utils::uninit_vector(2 * n);
-    nodes[0] = [0u8; 32];
+    let mut nodes = utils::uninit_vector::<H::Digest>(2 * n);
+    nodes[0] = H::Digest::default();
 
     // re-interpret leaves as an array of two leaves fused together and use it to
     // build first row of internal nodes (parents of leaves)
-    let two_leaves = unsafe { slice::from_raw_parts(leaves.as_ptr() as *const [u8; 64], n) };
+    let two_leaves = unsafe { slice::from_raw_parts(leaves.as_ptr() as *const [H::Digest; 2], n) };
     nodes[n..]
         .par_iter_mut()
         .zip(two_leaves.par_iter())
-        .for_each(|(target, source)| hash(source, target));
+        .for_each(|(target, source)| *target = H::merge(source));
 
     // calculate all other tree nodes, we can't use regular iterators  here because
     // access patterns are rather complicated - so, we use regular threads instead
@@ -40,18 +40,18 @@ pub fn build_merkle_nodes(leaves: &[[u8; 32]], hash: HashFunction) -> Vec<[u8; 3
     let batch_size = n / num_subtrees;
 
     // re-interpret nodes as an array of two nodes fused together
-    let two_nodes = unsafe { slice::from_raw_parts(nodes.as_ptr() as *const [u8; 64], n) };
+    let two_nodes = unsafe { slice::from_raw_parts(nodes.as_ptr() as *const [H::Digest; 2], n) };
 
     // process each subtree in a separate thread
     rayon::scope(|s| {
         for i in 0..num_subtrees {
-            let nodes = unsafe { &mut *(&mut nodes[..] as *mut [[u8; 32]]) };
+            let nodes = unsafe { &mut *(&mut nodes[..] as *mut [H::Digest]) };
             s.spawn(move |_| {
                 let mut batch_size = batch_size / 2;
                 let mut start_idx = n / 2 + batch_size * i;
                 while start_idx >= num_subtrees {
                     for k in (start_idx..(start_idx + batch_size)).rev() {
-                        hash(&two_nodes[k], &mut nodes[k]);
+                        nodes[k] = H::merge(&two_nodes[k]);
                     }
                     start_idx /= 2;
                     batch_size /= 2;
@@ -62,7 +62,37 @@ pub fn build_merkle_nodes(leaves: &[[u8; 32]], hash: HashFunction) -> Vec<[u8; 3
 
     // finish the tip of the tree
     for i in (1..num_subtrees).rev() {
-        hash(&two_nodes[i], &mut nodes[i]);
+        nodes[i] = H::merge(&two_nodes[i]);
+    }
+
+    nodes
+}
+
+/// Builds all internal nodes of the Merkle tree with arity `N` using all available threads and
+/// stores the results in a single vector such that root of the tree is at position 1, nodes
+/// immediately under the root are at positions N..2N etc.
+///
+/// Unlike the binary version, the nodes are computed one level at a time, and all nodes of a
+/// level are computed in parallel.
+pub fn build_merkle_nodes_n<H: Hasher, const N: usize>(leaves: &[H::Digest]) -> Vec<H::Digest> {
+    let n = leaves.len() / N;
+    let mut nodes = vec![H::Digest::default(); 2 * n];
+
+    // build first row of internal nodes (parents of leaves)
+    nodes[n..]
+        .par_iter_mut()
+        .zip(leaves.par_chunks(N))
+        .for_each(|(target, source)| *target = super::merge::<H, N>(source));
+
+    // calculate all other tree nodes, one level at a time
+    let mut level_start = n / N;
+    while level_start > 0 {
+        let (parents, children) = nodes.split_at_mut(level_start * N);
+        parents[level_start..(2 * level_start)]
+            .par_iter_mut()
+            .zip(children.par_chunks(N))
+            .for_each(|(target, source)| *target = super::merge::<H, N>(source));
+        level_start /= N;
     }
 
     nodes
@@ -73,14 +103,36 @@ pub fn build_merkle_nodes(leaves: &[[u8; 32]], hash: HashFunction) -> Vec<[u8; 3
 
 #[cfg(test)]
 mod tests {
+    use crate::hash::{DomainSeparated, Sha3_256};
     use proptest::collection::vec;
     use proptest::prelude::*;
 
     proptest! {
         #[test]
         fn build_merkle_nodes_concurrent(ref data in vec(any::<[u8; 32]>(), 256..257).no_shrink()) {
-            let sequential = super::super::build_merkle_nodes(&data, crate::hash::sha3);
-            let concurrent = super::build_merkle_nodes(&data, crate::hash::sha3);
+            let sequential = super::super::build_merkle_nodes::<Sha3_256>(&data);
+            let concurrent = super::build_merkle_nodes::<Sha3_256>(&data);
+            assert_eq!(concurrent, sequential);
+        }
+
+        #[test]
+        fn build_domain_separated_merkle_nodes_concurrent(ref data in vec(any::<[u8; 32]>(), 256..257).no_shrink()) {
+            let sequential = super::super::build_merkle_nodes::<DomainSeparated<Sha3_256>>(data);
+            let concurrent = super::build_merkle_nodes::<DomainSeparated<Sha3_256>>(data);
+            assert_eq!(concurrent, sequential);
+        }
+
+        #[test]
+        fn build_merkle_nodes_4_concurrent(ref data in vec(any::<[u8; 32]>(), 256..257).no_shrink()) {
+            let sequential = super::super::build_merkle_nodes_n::<Sha3_256, 4>(data);
+            let concurrent = super::build_merkle_nodes_n::<Sha3_256, 4>(data);
+            assert_eq!(concurrent, sequential);
+        }
+
+        #[test]
+        fn build_merkle_nodes_8_concurrent(ref data in vec(any::<[u8; 32]>(), 512..513).no_shrink()) {
+            let sequential = super::super::build_merkle_nodes_n::<Sha3_256, 8>(data);
+            let concurrent = super::build_merkle_nodes_n::<Sha3_256, 8>(data);
             assert_eq!(concurrent, sequential);
         }
     }
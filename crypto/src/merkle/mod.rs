@@ -3,15 +3,16 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::HashFunction;
-use std::{
-    collections::{BTreeSet, HashMap},
-    slice,
-};
+use crate::hash::Hasher;
+use core::convert::TryInto;
+use std::collections::{BTreeSet, HashMap};
 
 mod proofs;
 pub use proofs::BatchMerkleProof;
 
+mod streaming;
+pub use streaming::{StreamingMerkleTree, StreamingMerkleTreeBuilder};
+
 #[cfg(feature = "concurrent")]
 pub mod concurrent;
 
@@ -21,196 +22,422 @@ mod tests;
 // TYPES AND INTERFACES
 // ================================================================================================
 
+/// A Merkle tree in which every internal node has `N` children.
+///
+/// By default, the tree is binary. Trees of higher arity (e.g. 4 or 8) are shallower, and thus,
+/// require fewer hash function invocations to verify a Merkle path; this is beneficial for
+/// algebraic hash functions, which can absorb several digests in a single permutation.
+///
+/// Internal nodes are stored in a single vector such that the root of the tree is at position
+/// 1, the nodes immediately under the root are at positions N..2N, the nodes on the next level
+/// are at positions N^2..2N^2 etc. For binary trees, this means that node i is the parent of
+/// nodes 2i and 2i + 1. Leaves are conceptually located at positions n..2n, where n is the
+/// number of leaves.
 #[derive(Debug)]
-pub struct MerkleTree {
-    nodes: Vec<[u8; 32]>,
-    leaves: Vec<[u8; 32]>,
+pub struct MerkleTree<H: Hasher, const N: usize = 2> {
+    nodes: Vec<H::Digest>,
+    leaves: Vec<H::Digest>,
 }
 
 // MERKLE TREE IMPLEMENTATION
 // ================================================================================================
 
-impl MerkleTree {
-    /// Returns new merkle tree built from the provide leaves using the provided hash function.
+impl<H: Hasher, const N: usize> MerkleTree<H, N> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns new merkle tree built from the provide leaves using hash function specified by the
+    /// `H` generic parameter. Panics if the number of leaves is not a power of the tree arity
+    /// `N`, or if `N` is not a power of two.
     /// When `concurrent` feature is enabled, the tree is built using as many threads as are
     /// available in Rayon's global thread pool (usually as many threads as logical cores).
     /// Otherwise, the tree is built using a single thread.
-    pub fn new(leaves: Vec<[u8; 32]>, hash: HashFunction) -> MerkleTree {
+    ///
+    /// To build a tree in which leaves and internal nodes are hashed in separate domains, the
+    /// tree should be instantiated with [DomainSeparated](crate::hash::DomainSeparated) hasher.
+    pub fn new(leaves: Vec<H::Digest>) -> Self {
+        assert!(
+            N >= 2 && N.is_power_of_two(),
+            "tree arity must be a power of 2"
+        );
+        assert!(
+            leaves.len().is_power_of_two() && leaves.len().trailing_zeros() % log2(N) == 0,
+            "number of leaves must be a power of {}",
+            N
+        );
         assert!(
-            leaves.len().is_power_of_two(),
-            "number of leaves must be a power of 2"
+            leaves.len() >= N,
+            "a tree must contain at least {} leaves",
+            N
         );
-        assert!(leaves.len() >= 2, "a tree must contain at least 2 leaves");
 
         #[cfg(not(feature = "concurrent"))]
-        let nodes = build_merkle_nodes(&leaves, hash);
+        let nodes = build_merkle_nodes_n::<H, N>(&leaves);
 
         #[cfg(feature = "concurrent")]
         let nodes = if leaves.len() <= concurrent::MIN_CONCURRENT_LEAVES {
-            build_merkle_nodes(&leaves, hash)
+            build_merkle_nodes_n::<H, N>(&leaves)
+        } else if N == 2 {
+            concurrent::build_merkle_nodes::<H>(&leaves)
         } else {
-            concurrent::build_merkle_nodes(&leaves, hash)
+            concurrent::build_merkle_nodes_n::<H, N>(&leaves)
         };
 
         MerkleTree { nodes, leaves }
     }
 
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
     /// Returns the root of the tree.
-    pub fn root(&self) -> &[u8; 32] {
+    pub fn root(&self) -> &H::Digest {
         &self.nodes[1]
     }
 
     /// Returns depth of the tree.
     pub fn depth(&self) -> usize {
-        self.leaves.len().trailing_zeros() as usize
+        (self.leaves.len().trailing_zeros() / log2(N)) as usize
     }
 
     /// Returns leaf nodes of the tree.
-    pub fn leaves(&self) -> &[[u8; 32]] {
+    pub fn leaves(&self) -> &[H::Digest] {
         &self.leaves
     }
 
+    /// Returns the cap of the tree at the specified height; the nodes of the cap are listed in
+    /// the order of their positions in the tree.
+    ///
+    /// Cap height cannot exceed depth - 1 of the tree; larger heights are reduced to depth - 1.
+    pub fn cap(&self, cap_height: usize) -> Vec<H::Digest> {
+        let cap_start = N.pow(self.clamp_cap_height(cap_height) as u32);
+        self.nodes[cap_start..(2 * cap_start)].to_vec()
+    }
+
+    // PROVING METHODS
+    // --------------------------------------------------------------------------------------------
+
     /// Computes merkle path the given leaf index.
-    pub fn prove(&self, index: usize) -> Vec<[u8; 32]> {
+    ///
+    /// The path starts with the leaf itself followed by its N - 1 siblings; after that, the path
+    /// contains N - 1 siblings for every node on the way from the leaf to the root. Siblings
+    /// are listed in the order of their positions in the tree.
+    pub fn prove(&self, index: usize) -> Vec<H::Digest> {
         assert!(index < self.leaves.len(), "invalid index {}", index);
 
-        let mut proof = vec![self.leaves[index], self.leaves[index ^ 1]];
+        let mut proof = vec![self.leaves[index]];
+        proof.extend(siblings::<N>(index).map(|i| self.leaves[i]));
 
-        let mut index = (index + self.nodes.len()) >> 1;
+        let mut index = (index + self.leaves.len()) / N;
         while index > 1 {
-            proof.push(self.nodes[index ^ 1]);
-            index >>= 1;
+            proof.extend(siblings::<N>(index).map(|i| self.nodes[i]));
+            index /= N;
         }
 
         proof
     }
 
-    /// Computes merkle paths for the provided indexes and compresses the paths into a single proof.
-    pub fn prove_batch(&self, indexes: &[usize]) -> BatchMerkleProof {
-        let n = self.leaves.len();
+    /// Computes merkle path for the given leaf index which stops at the cap of the specified
+    /// height; that is, the path does not include siblings of the cap nodes and above.
+    ///
+    /// Cap height cannot exceed depth - 1 of the tree; larger heights are reduced to depth - 1.
+    pub fn prove_to_cap(&self, index: usize, cap_height: usize) -> Vec<H::Digest> {
+        let cap_height = self.clamp_cap_height(cap_height);
+        let mut proof = self.prove(index);
+        proof.truncate(1 + (self.depth() - cap_height) * (N - 1));
+        proof
+    }
 
-        let index_map = map_indexes(indexes, n);
-        let indexes = normalize_indexes(indexes);
-        let mut values = vec![[0u8; 32]; index_map.len()];
-        let mut nodes: Vec<Vec<[u8; 32]>> = Vec::with_capacity(indexes.len());
-
-        // populate the proof with leaf node values
-        let mut next_indexes: Vec<usize> = Vec::new();
-        for index in indexes {
-            let missing: Vec<[u8; 32]> = (index..index + 2)
-                .flat_map(|i| {
-                    let v = self.leaves[i];
-                    if let Some(idx) = index_map.get(&i) {
-                        values[*idx] = v;
-                        None
-                    } else {
-                        Some(v)
-                    }
-                })
-                .collect();
-            nodes.push(missing);
-
-            next_indexes.push((index + n) >> 1);
-        }
+    /// Computes merkle paths for the provided indexes and compresses the paths into a single proof.
+    pub fn prove_batch(&self, indexes: &[usize]) -> BatchMerkleProof<H, N> {
+        self.prove_batch_to_cap(indexes, 0)
+    }
 
-        // add required internal nodes to the proof, skipping redundancies
-        let depth = self.leaves.len().trailing_zeros() as u8;
-        for _ in 1..depth {
-            let indexes = next_indexes.clone();
-            next_indexes.truncate(0);
-
-            let mut i = 0;
-            while i < indexes.len() {
-                let sibling_index = indexes[i] ^ 1;
-                if i + 1 < indexes.len() && indexes[i + 1] == sibling_index {
-                    i += 1;
-                } else {
-                    nodes[i].push(self.nodes[sibling_index]);
-                }
+    /// Computes merkle paths for the provided indexes which stop at the cap of the specified
+    /// height, and compresses the paths into a single proof.
+    ///
+    /// Cap height cannot exceed depth - 1 of the tree; larger heights are reduced to depth - 1.
+    pub fn prove_batch_to_cap(
+        &self,
+        indexes: &[usize],
+        cap_height: usize,
+    ) -> BatchMerkleProof<H, N> {
+        let n = self.leaves.len();
+        let cap_height = self.clamp_cap_height(cap_height);
+        build_batch_proof(indexes, n, cap_height, |index| {
+            if index >= n {
+                self.leaves[index - n]
+            } else {
+                self.nodes[index]
+            }
+        })
+    }
 
-                // add parent index to the set of next indexes
-                next_indexes.push(sibling_index >> 1);
+    // VERIFICATION METHODS
+    // --------------------------------------------------------------------------------------------
 
-                i += 1;
-            }
-        }
+    /// Checks whether the path for the specified index is valid.
+    pub fn verify(root: H::Digest, index: usize, proof: &[H::Digest]) -> bool {
+        Self::verify_with_cap(&[root], index, proof)
+    }
 
-        BatchMerkleProof {
-            values,
-            nodes,
-            depth,
+    /// Checks whether the path for the specified index is valid against the provided cap; the
+    /// height of the cap is inferred from the number of nodes in it.
+    pub fn verify_with_cap(cap: &[H::Digest], index: usize, proof: &[H::Digest]) -> bool {
+        let cap_height = match get_cap_height::<N>(cap.len()) {
+            Some(cap_height) => cap_height,
+            None => return false,
+        };
+        if proof.is_empty() || (proof.len() - 1) % (N - 1) != 0 {
+            return false;
         }
-    }
 
-    /// Checks whether the path for the specified index is valid.
-    pub fn verify(root: &[u8; 32], index: usize, proof: &[[u8; 32]], hash: HashFunction) -> bool {
-        let mut buf = [0u8; 64];
-        let mut v = [0u8; 32];
-
-        let r = index & 1;
-        buf[..32].copy_from_slice(&proof[r]);
-        buf[32..64].copy_from_slice(&proof[1 - r]);
-        hash(&buf, &mut v);
-
-        let mut index = (index + usize::pow(2, (proof.len() - 1) as u32)) >> 1;
-        for p in proof.iter().skip(2) {
-            if index & 1 == 0 {
-                buf[..32].copy_from_slice(&v);
-                buf[32..64].copy_from_slice(p);
-            } else {
-                buf[..32].copy_from_slice(p);
-                buf[32..64].copy_from_slice(&v);
-            }
-            hash(&buf, &mut v);
-            index >>= 1;
+        // the depth is determined by the length of the proof, and thus, the number of leaves
+        // may not fit into usize
+        let cap_start = N.pow(cap_height as u32);
+        let depth = (proof.len() - 1) / (N - 1) + cap_height;
+        let mut index = match N
+            .checked_pow(depth as u32)
+            .filter(|&num_leaves| index < num_leaves)
+            .and_then(|num_leaves| num_leaves.checked_add(index))
+        {
+            Some(index) => index,
+            None => return false,
+        };
+        let mut v = proof[0];
+        let mut group = [H::Digest::default(); N];
+        for siblings in proof[1..].chunks(N - 1) {
+            let position = index % N;
+            group[..position].copy_from_slice(&siblings[..position]);
+            group[position] = v;
+            group[(position + 1)..].copy_from_slice(&siblings[position..]);
+            v = merge::<H, N>(&group);
+            index /= N;
         }
 
-        v == *root
+        cap.get(index - cap_start) == Some(&v)
     }
 
     /// Checks whether the batch proof contains merkle paths for the of the specified indexes.
     pub fn verify_batch(
-        root: &[u8; 32],
+        root: &H::Digest,
+        indexes: &[usize],
+        proof: &BatchMerkleProof<H, N>,
+    ) -> bool {
+        Self::verify_batch_with_cap(core::slice::from_ref(root), indexes, proof)
+    }
+
+    /// Checks whether the batch proof contains merkle paths for the specified indexes which
+    /// resolve to the nodes of the provided cap; the height of the cap is inferred from the
+    /// number of nodes in it.
+    pub fn verify_batch_with_cap(
+        cap: &[H::Digest],
         indexes: &[usize],
-        proof: &BatchMerkleProof,
-        hash: HashFunction,
+        proof: &BatchMerkleProof<H, N>,
     ) -> bool {
-        match proof.get_root(indexes, hash) {
-            Some(proof_root) => *root == proof_root,
+        let cap_height = match get_cap_height::<N>(cap.len()) {
+            Some(cap_height) => cap_height,
+            None => return false,
+        };
+        let cap_start = N.pow(cap_height as u32);
+        match proof.get_cap_nodes(indexes, cap_height) {
+            Some(nodes) => nodes
+                .iter()
+                .all(|(index, node)| cap[index - cap_start] == *node),
             None => false,
         }
     }
+
+    /// Computes the root of a tree from the provided cap. Returns None if the number of nodes
+    /// in the cap is not a power of the tree arity `N`.
+    pub fn get_root_from_cap(cap: &[H::Digest]) -> Option<H::Digest> {
+        match get_cap_height::<N>(cap.len())? {
+            0 => Some(cap[0]),
+            _ => Some(build_merkle_nodes_n::<H, N>(cap)[1]),
+        }
+    }
+
+    /// Returns the number of nodes in the cap of the specified height of a tree with
+    /// `num_leaves` leaves; as in cap(), the cap height is reduced to depth - 1 of the tree, if
+    /// needed.
+    pub fn cap_len(num_leaves: usize, cap_height: usize) -> usize {
+        let depth = (num_leaves.trailing_zeros() / log2(N)) as usize;
+        N.pow(cap_height.min(depth.saturating_sub(1)) as u32)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Reduces the specified cap height to depth - 1 of this tree, if needed.
+    fn clamp_cap_height(&self, cap_height: usize) -> usize {
+        cap_height.min(self.depth() - 1)
+    }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
 
-pub fn build_merkle_nodes(leaves: &[[u8; 32]], hash: HashFunction) -> Vec<[u8; 32]> {
-    let n = leaves.len() / 2;
+/// Builds all internal nodes of the binary Merkle tree using a single thread and stores the
+/// results in a single vector such that root of the tree is at position 1, nodes immediately
+/// under the root are at positions 2 and 3 etc.
+pub fn build_merkle_nodes<H: Hasher>(leaves: &[H::Digest]) -> Vec<H::Digest> {
+    build_merkle_nodes_n::<H, 2>(leaves)
+}
 
-    // create un-initialized array to hold all intermediate nodes
-    let mut nodes = utils::uninit_vector(2 * n);
-    nodes[0] = [0u8; 32];
+/// Builds all internal nodes of the Merkle tree with arity `N` using a single thread and stores
+/// the results in a single vector such that root of the tree is at position 1, nodes immediately
+/// under the root are at positions N..2N etc.
+pub fn build_merkle_nodes_n<H: Hasher, const N: usize>(leaves: &[H::Digest]) -> Vec<H::Digest> {
+    let n = leaves.len() / N;
 
-    // re-interpret leaves as an array of two leaves fused together
-    let two_leaves = unsafe { slice::from_raw_parts(leaves.as_ptr() as *const [u8; 64], n) };
+    // for trees of arity greater than two, positions between levels are not used; these are left
+    // at default values
+    let mut nodes = vec![H::Digest::default(); 2 * n];
 
     // build first row of internal nodes (parents of leaves)
-    for (i, j) in (0..n).zip(n..nodes.len()) {
-        hash(&two_leaves[i], &mut nodes[j]);
+    for (node, children) in nodes[n..].iter_mut().zip(leaves.chunks(N)) {
+        *node = merge::<H, N>(children);
     }
 
-    // re-interpret nodes as an array of two nodes fused together
-    let two_nodes = unsafe { slice::from_raw_parts(nodes.as_ptr() as *const [u8; 64], n) };
-
-    // calculate all other tree nodes
-    for i in (1..n).rev() {
-        hash(&two_nodes[i], &mut nodes[i]);
+    // calculate all other tree nodes, one level at a time
+    let mut level_start = n / N;
+    while level_start > 0 {
+        let (parents, children) = nodes.split_at_mut(level_start * N);
+        for (node, children) in parents[level_start..(2 * level_start)]
+            .iter_mut()
+            .zip(children.chunks(N))
+        {
+            *node = merge::<H, N>(children);
+        }
+        level_start /= N;
     }
 
     nodes
 }
 
+/// Builds a batch Merkle proof for the specified leaf indexes in a tree with `num_leaves` leaves;
+/// paths in the proof stop at the cap of height `cap_height`. `get_node` must return the node at
+/// the specified position in the tree; leaves are located at positions num_leaves..2 * num_leaves.
+///
+/// Values of leaves at the specified indexes are included into the proof in the order of the
+/// indexes; the remaining leaves and internal nodes needed to compute the root are grouped by
+/// their parent nodes, and nodes which can be computed from other nodes in the proof are omitted.
+fn build_batch_proof<H: Hasher, F, const N: usize>(
+    indexes: &[usize],
+    num_leaves: usize,
+    cap_height: usize,
+    get_node: F,
+) -> BatchMerkleProof<H, N>
+where
+    F: Fn(usize) -> H::Digest,
+{
+    let index_map = map_indexes(indexes, num_leaves);
+    let indexes = normalize_indexes::<N>(indexes);
+    let mut values = vec![H::Digest::default(); index_map.len()];
+    let mut nodes: Vec<Vec<H::Digest>> = Vec::with_capacity(indexes.len());
+
+    // populate the proof with leaf node values
+    let mut next_indexes: Vec<usize> = Vec::new();
+    for index in indexes {
+        let missing: Vec<H::Digest> = (index..index + N)
+            .flat_map(|i| {
+                let v = get_node(i + num_leaves);
+                if let Some(idx) = index_map.get(&i) {
+                    values[*idx] = v;
+                    None
+                } else {
+                    Some(v)
+                }
+            })
+            .collect();
+        nodes.push(missing);
+
+        next_indexes.push((index + num_leaves) / N);
+    }
+
+    // add required internal nodes to the proof, skipping redundancies; nodes at the cap level
+    // and above are not needed
+    let depth = (num_leaves.trailing_zeros() / log2(N)) as u8;
+    for _ in 1..(depth as usize - cap_height) {
+        let indexes = next_indexes.clone();
+        next_indexes.truncate(0);
+
+        let mut i = 0;
+        while i < indexes.len() {
+            // find all nodes which share the same parent
+            let parent_index = indexes[i] / N;
+            let group_end = find_group_end::<N>(&indexes, i);
+
+            // add siblings which are not already in the set to the proof
+            for sibling_index in (parent_index * N)..(parent_index * N + N) {
+                if !indexes[i..group_end].contains(&sibling_index) {
+                    nodes[i].push(get_node(sibling_index));
+                }
+            }
+
+            // add parent index to the set of next indexes
+            next_indexes.push(parent_index);
+
+            i = group_end;
+        }
+    }
+
+    BatchMerkleProof {
+        values,
+        nodes,
+        depth,
+    }
+}
+
+/// Returns a hash of the provided N digests; for binary trees, this is the same as merge().
+#[inline(always)]
+fn merge<H: Hasher, const N: usize>(values: &[H::Digest]) -> H::Digest {
+    if N == 2 {
+        H::merge(values.try_into().expect("invalid number of digests"))
+    } else {
+        H::merge_n(values)
+    }
+}
+
+/// Returns positions of all siblings of the node at the specified position.
+fn siblings<const N: usize>(index: usize) -> impl Iterator<Item = usize> {
+    let start = index - index % N;
+    (start..start + N).filter(move |&i| i != index)
+}
+
+/// Returns the position right after the last node in the sorted list of node `indexes` which
+/// has the same parent as the node at position `start`.
+fn find_group_end<const N: usize>(indexes: &[usize], start: usize) -> usize {
+    let parent_index = indexes[start] / N;
+    let mut end = start + 1;
+    while end < indexes.len() && indexes[end] / N == parent_index {
+        end += 1;
+    }
+    end
+}
+
+/// Returns height of a cap with the specified number of nodes, or None if the number of nodes
+/// is not a power of `N`.
+fn get_cap_height<const N: usize>(cap_size: usize) -> Option<usize> {
+    let cap_height = cap_size.trailing_zeros() / log2(N);
+    if cap_size.is_power_of_two() && N.pow(cap_height) == cap_size {
+        Some(cap_height as usize)
+    } else {
+        None
+    }
+}
+
+fn log2(n: usize) -> u32 {
+    n.trailing_zeros()
+}
+
+/// Returns true if all `indexes` are distinct and refer to leaves of a tree with `num_leaves`
+/// leaves.
+fn are_valid_indexes(indexes: &[usize], num_leaves: usize) -> bool {
+    let mut set = BTreeSet::new();
+    indexes
+        .iter()
+        .all(|&index| index < num_leaves && set.insert(index))
+}
+
 fn map_indexes(indexes: &[usize], max_valid: usize) -> HashMap<usize, usize> {
     let mut map = HashMap::new();
     for (i, index) in indexes.iter().cloned().enumerate() {
@@ -221,10 +448,10 @@ fn map_indexes(indexes: &[usize], max_valid: usize) -> HashMap<usize, usize> {
     map
 }
 
-fn normalize_indexes(indexes: &[usize]) -> Vec<usize> {
+fn normalize_indexes<const N: usize>(indexes: &[usize]) -> Vec<usize> {
     let mut set = BTreeSet::new();
     for &index in indexes {
-        set.insert(index - (index & 1));
+        set.insert(index - (index % N));
     }
     set.into_iter().collect()
 }
@@ -3,21 +3,26 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::HashFunction;
-use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct BatchMerkleProof {
-    pub values: Vec<[u8; 32]>,
-    pub nodes: Vec<Vec<[u8; 32]>>,
+use crate::{
+    hash::{digests_as_bytes, read_digests_into_vec},
+    DigestSerializationError, Hasher,
+};
+use std::collections::HashMap;
+
+/// Multiple Merkle paths aggregated into a single proof for a tree with arity `N`.
+///
+/// Nodes which can be computed from other nodes in the proof (e.g. when paths for sibling
+/// leaves are aggregated) are not included into the proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchMerkleProof<H: Hasher, const N: usize = 2> {
+    pub values: Vec<H::Digest>,
+    pub nodes: Vec<Vec<H::Digest>>,
     pub depth: u8,
 }
 
-impl BatchMerkleProof {
+impl<H: Hasher, const N: usize> BatchMerkleProof<H, N> {
     /// Constructs a batch Merkle proof from individual Merkle authentication paths.
-    /// TODO: optimize this to reduce amount of vector cloning.
-    pub fn from_paths(paths: &[Vec<[u8; 32]>], indexes: &[usize]) -> BatchMerkleProof {
+    pub fn from_paths(paths: &[Vec<H::Digest>], indexes: &[usize]) -> BatchMerkleProof<H, N> {
         assert_eq!(
             paths.len(),
             indexes.len(),
@@ -25,72 +30,143 @@ impl BatchMerkleProof {
         );
         assert!(!paths.is_empty(), "at least one path must be provided");
 
-        let depth = paths[0].len();
-
-        // sort indexes in ascending order, and also re-arrange paths accordingly
-        let mut path_map = BTreeMap::new();
-        for (&index, path) in indexes.iter().zip(paths.iter().cloned()) {
-            path_map.insert(index, path);
-        }
-        let indexes = path_map.keys().cloned().collect::<Vec<_>>();
-        let paths = path_map.values().cloned().collect::<Vec<_>>();
-        path_map.clear();
-
-        let mut values = vec![[0u8; 32]; indexes.len()];
-        let mut nodes: Vec<Vec<[u8; 32]>> = Vec::with_capacity(indexes.len());
-
-        // populate values and the first layer of proof nodes
-        let mut i = 0;
-        while i < indexes.len() {
-            values[i] = paths[i][0];
-            if indexes.len() > i + 1 && are_siblings(indexes[i], indexes[i + 1]) {
-                values[i + 1] = paths[i][1];
-                nodes.push(vec![]);
-                i += 1;
-            } else {
-                nodes.push(vec![paths[i][1]]);
+        let depth = (paths[0].len() - 1) / (N - 1);
+        let num_leaves = N.pow(depth as u32);
+
+        // collect all nodes contained in the paths; each path contains N - 1 siblings for every
+        // node on the way from a leaf to the root.
+        let mut tree_nodes = HashMap::new();
+        for (&index, path) in indexes.iter().zip(paths) {
+            assert_eq!(
+                depth * (N - 1) + 1,
+                path.len(),
+                "all paths must have the same length"
+            );
+            let mut index = index + num_leaves;
+            tree_nodes.insert(index, path[0]);
+            for siblings in path[1..].chunks(N - 1) {
+                for (sibling_index, &sibling) in super::siblings::<N>(index).zip(siblings) {
+                    tree_nodes.insert(sibling_index, sibling);
+                }
+                index /= N;
             }
-            path_map.insert(indexes[i] >> 1, paths[i].clone());
-            i += 1;
         }
 
-        // populate all remaining layers of proof nodes
-        for d in 2..depth {
-            let indexes = path_map.keys().cloned().collect::<Vec<_>>();
-            let mut next_path_map = BTreeMap::new();
+        // sort indexes in ascending order and build the proof from the collected nodes
+        let mut indexes = indexes.to_vec();
+        indexes.sort_unstable();
+        super::build_batch_proof(&indexes, num_leaves, 0, |index| tree_nodes[&index])
+    }
 
-            let mut i = 0;
-            while i < indexes.len() {
-                let index = indexes[i];
-                let path = path_map.get(&index).unwrap();
-                if indexes.len() > i + 1 && are_siblings(index, indexes[i + 1]) {
-                    i += 1;
-                } else {
-                    nodes[i].push(path[d]);
+    /// Computes a node to which all Merkle paths aggregated in this proof resolve.
+    pub fn get_root(&self, indexes: &[usize]) -> Option<H::Digest> {
+        self.get_cap_nodes(indexes, 0)?.pop().map(|(_, root)| root)
+    }
+
+    /// Computes nodes at the cap of the specified height to which Merkle paths aggregated in
+    /// this proof resolve. The nodes are returned together with their positions in the tree.
+    pub fn get_cap_nodes(
+        &self,
+        indexes: &[usize],
+        cap_height: usize,
+    ) -> Option<Vec<(usize, H::Digest)>> {
+        let (cap_indexes, mut nodes) = self.get_nodes(indexes, cap_height, None)?;
+        cap_indexes
+            .into_iter()
+            .map(|index| nodes.remove(&index).map(|node| (index, node)))
+            .collect()
+    }
+
+    /// Decompresses this proof into individual Merkle authentication paths for the leaves at the
+    /// specified indexes; the paths are returned in the same order as the indexes, and have the
+    /// same form as the paths accepted by `from_paths()`.
+    pub fn get_paths(&self, indexes: &[usize]) -> Option<Vec<Vec<H::Digest>>> {
+        // record all nodes which are hashed together on the way to the root; these are exactly
+        // the nodes making up the paths
+        let mut nodes = HashMap::new();
+        self.get_nodes(indexes, 0, Some(&mut nodes))?;
+
+        let offset = N.pow(self.depth as u32);
+        let mut paths = Vec::with_capacity(indexes.len());
+        for &index in indexes {
+            let mut index = index + offset;
+            let mut path = Vec::with_capacity(self.depth as usize * (N - 1) + 1);
+            path.push(*nodes.get(&index)?);
+            while index > 1 {
+                for sibling_index in super::siblings::<N>(index) {
+                    path.push(*nodes.get(&sibling_index)?);
                 }
-                next_path_map.insert(index >> 1, path.clone());
-                i += 1;
+                index /= N;
             }
+            paths.push(path);
+        }
+
+        Some(paths)
+    }
+
+    // SERIALIZATION / DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
 
-            std::mem::swap(&mut path_map, &mut next_path_map);
+    /// Serializes internal nodes of this proof into a vector of byte vectors, one per set of
+    /// nodes. Leaf values and depth of the proof are not included in the serialized output.
+    pub fn serialize_nodes(&self) -> Vec<Vec<u8>> {
+        self.nodes
+            .iter()
+            .map(|nodes| digests_as_bytes(nodes))
+            .collect()
+    }
+
+    /// Builds a batch Merkle proof from serialized internal nodes, and the provided leaf values
+    /// and tree depth. Returns an error if the nodes could not be deserialized into digests.
+    pub fn deserialize(
+        node_bytes: &[Vec<u8>],
+        values: Vec<H::Digest>,
+        depth: u8,
+    ) -> Result<Self, DigestSerializationError> {
+        let mut nodes = Vec::with_capacity(node_bytes.len());
+        for bytes in node_bytes {
+            nodes.push(read_digests_into_vec(bytes)?);
         }
 
-        BatchMerkleProof {
+        Ok(BatchMerkleProof {
             values,
             nodes,
-            depth: (depth - 1) as u8,
-        }
+            depth,
+        })
     }
 
-    /// Computes a node to which all Merkle paths aggregated in this proof resolve.
-    pub fn get_root(&self, indexes: &[usize], hash: HashFunction) -> Option<[u8; 32]> {
-        let mut buf = [0u8; 64];
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes all nodes between the leaves at the specified indexes and the cap of the
+    /// specified height. Returns positions of the cap nodes together with a map of the computed
+    /// nodes keyed by their positions in the tree. If `siblings` is provided, all groups of
+    /// sibling nodes which are hashed into their parents are recorded into it as well.
+    #[allow(clippy::type_complexity)]
+    fn get_nodes(
+        &self,
+        indexes: &[usize],
+        cap_height: usize,
+        mut siblings: Option<&mut HashMap<usize, H::Digest>>,
+    ) -> Option<(Vec<usize>, HashMap<usize, H::Digest>)> {
+        if indexes.is_empty() || cap_height >= self.depth as usize {
+            return None;
+        }
+
+        // the depth is read from the proof, and thus, the number of leaves may not fit into
+        // usize; the indexes must be distinct and must refer to leaves of the tree
+        let offset = N.checked_pow(self.depth as u32)?;
+        if !super::are_valid_indexes(indexes, offset) {
+            return None;
+        }
+
+        let mut buf = [H::Digest::default(); N];
         let mut v = HashMap::new();
 
-        // replace odd indexes, offset, and sort in ascending order
-        let offset = usize::pow(2, self.depth as u32);
+        // replace indexes with indexes of the first nodes in their groups of siblings, offset,
+        // and sort in ascending order
         let index_map = super::map_indexes(indexes, offset - 1);
-        let indexes = super::normalize_indexes(indexes);
+        let indexes = super::normalize_indexes::<N>(indexes);
         if indexes.len() != self.nodes.len() {
             return None;
         }
@@ -99,119 +175,73 @@ impl BatchMerkleProof {
         let mut next_indexes: Vec<usize> = Vec::new();
         let mut proof_pointers: Vec<usize> = Vec::with_capacity(indexes.len());
         for (i, index) in indexes.into_iter().enumerate() {
-            // copy values of leaf sibling leaf nodes into the buffer
-            match index_map.get(&index) {
-                Some(&index1) => {
-                    if self.values.len() <= index1 {
-                        return None;
-                    }
-                    buf[..32].copy_from_slice(&self.values[index1]);
-                    match index_map.get(&(index + 1)) {
-                        Some(&index2) => {
-                            if self.values.len() <= index2 {
-                                return None;
-                            }
-                            buf[32..64].copy_from_slice(&self.values[index2]);
-                            proof_pointers.push(0);
-                        }
-                        None => {
-                            if self.nodes[i].is_empty() {
-                                return None;
-                            }
-                            buf[32..64].copy_from_slice(&self.nodes[i][0]);
-                            proof_pointers.push(1);
-                        }
-                    }
-                }
-                None => {
-                    if self.nodes[i].is_empty() {
-                        return None;
-                    }
-                    buf[..32].copy_from_slice(&self.nodes[i][0]);
-                    match index_map.get(&(index + 1)) {
-                        Some(&index2) => {
-                            if self.values.len() <= index2 {
-                                return None;
-                            }
-                            buf[32..64].copy_from_slice(&self.values[index2]);
-                        }
-                        None => return None,
+            // copy values of sibling leaf nodes into the buffer; leaves which are not among
+            // the values are read from the proof
+            let mut pointer = 0;
+            for (j, node) in buf.iter_mut().enumerate() {
+                *node = match index_map.get(&(index + j)) {
+                    Some(&value_index) => *self.values.get(value_index)?,
+                    None => {
+                        let proof_node = *self.nodes[i].get(pointer)?;
+                        pointer += 1;
+                        proof_node
                     }
-                    proof_pointers.push(1);
+                };
+            }
+            proof_pointers.push(pointer);
+            if let Some(siblings) = siblings.as_mut() {
+                for (j, &node) in buf.iter().enumerate() {
+                    siblings.insert(offset + index + j, node);
                 }
             }
 
             // hash sibling nodes into their parent
-            let mut parent = [0u8; 32];
-            hash(&buf, &mut parent);
+            let parent = super::merge::<H, N>(&buf);
 
-            let parent_index = (offset + index) >> 1;
+            let parent_index = (offset + index) / N;
             v.insert(parent_index, parent);
             next_indexes.push(parent_index);
         }
 
-        // iteratively move up, until we get to the root
-        for _ in 1..self.depth {
+        // iteratively move up, until we get to the cap
+        for _ in 1..(self.depth as usize - cap_height) {
             let indexes = next_indexes.clone();
             next_indexes.truncate(0);
 
             let mut i = 0;
             while i < indexes.len() {
-                let node_index = indexes[i];
-                let sibling_index = node_index ^ 1;
-
-                // determine the sibling
-                let sibling: &[u8; 32];
-                if i + 1 < indexes.len() && indexes[i + 1] == sibling_index {
-                    sibling = match v.get(&sibling_index) {
-                        Some(sibling) => sibling,
-                        None => return None,
+                // find all nodes which share the same parent
+                let parent_index = indexes[i] / N;
+                let group_end = super::find_group_end::<N>(&indexes, i);
+
+                // get the nodes from the map of hashed nodes, and read the missing siblings
+                // from the proof
+                for (j, node) in buf.iter_mut().enumerate() {
+                    let node_index = parent_index * N + j;
+                    *node = if indexes[i..group_end].contains(&node_index) {
+                        *v.get(&node_index)?
+                    } else {
+                        let proof_node = *self.nodes[i].get(proof_pointers[i])?;
+                        proof_pointers[i] += 1;
+                        proof_node
                     };
-                    i += 1;
-                } else {
-                    let pointer = proof_pointers[i];
-                    if self.nodes[i].len() <= pointer {
-                        return None;
-                    }
-                    sibling = &self.nodes[i][pointer];
-                    proof_pointers[i] += 1;
                 }
 
-                // get the node from the map of hashed nodes
-                let node = match v.get(&node_index) {
-                    Some(node) => node,
-                    None => return None,
-                };
-
-                // compute parent node from node and sibling
-                if node_index & 1 != 0 {
-                    buf[..32].copy_from_slice(sibling);
-                    buf[32..64].copy_from_slice(node);
-                } else {
-                    buf[..32].copy_from_slice(node);
-                    buf[32..64].copy_from_slice(sibling);
+                if let Some(siblings) = siblings.as_mut() {
+                    for (j, &node) in buf.iter().enumerate() {
+                        siblings.insert(parent_index * N + j, node);
+                    }
                 }
-                let mut parent = [0u8; 32];
-                hash(&buf, &mut parent);
 
-                // add the parent node to the next set of nodes
-                let parent_index = node_index >> 1;
+                // compute parent node from the nodes and add it to the next set of nodes
+                let parent = super::merge::<H, N>(&buf);
                 v.insert(parent_index, parent);
                 next_indexes.push(parent_index);
 
-                i += 1;
+                i = group_end;
             }
         }
 
-        v.remove(&1)
+        Some((next_indexes, v))
     }
 }
-
-// HELPER FUNCTIONS
-// ================================================================================================
-
-/// Two nodes are siblings if index of the left node is even and right node
-/// immediately follows the left node.
-fn are_siblings(left: usize, right: usize) -> bool {
-    left & 1 == 0 && right - 1 == left
-}
@@ -0,0 +1,424 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{build_batch_proof, build_merkle_nodes, BatchMerkleProof};
+use crate::{Digest, Hasher};
+use std::{
+    collections::HashMap,
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+// STREAMING MERKLE TREE BUILDER
+// ================================================================================================
+
+/// Builds a binary Merkle tree from leaves which are supplied incrementally.
+///
+/// Leaves are split into subtrees of `subtree_size` leaves each. Only the leaves of the subtree
+/// which is currently being filled are buffered; once a subtree is complete, its nodes (except
+/// for its root) are moved into node storage, and only the root is kept. Thus, during
+/// construction, the builder holds at most `subtree_size` leaves and one root per subtree in
+/// memory.
+///
+/// Node storage can be either a vector in memory, or a temporary file which is deleted when the
+/// resulting tree is dropped. In the latter case, the resulting tree holds only the nodes above
+/// the subtree roots in memory, and reads the lower nodes from the file when proofs are built.
+pub struct StreamingMerkleTreeBuilder<H: Hasher> {
+    num_leaves: usize,
+    subtree_size: usize,
+    buffer: Vec<H::Digest>,
+    subtree_roots: Vec<H::Digest>,
+    storage: NodeStorage<H::Digest>,
+}
+
+impl<H: Hasher> StreamingMerkleTreeBuilder<H> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new builder for a tree with the specified number of leaves which keeps all nodes
+    /// in memory.
+    ///
+    /// Panics if:
+    /// * `num_leaves` or `subtree_size` is not a power of two, or is smaller than two.
+    /// * `subtree_size` is greater than `num_leaves`.
+    pub fn new(num_leaves: usize, subtree_size: usize) -> Self {
+        Self::with_storage(num_leaves, subtree_size, NodeStorage::Memory(Vec::new()))
+    }
+
+    /// Returns a new builder for a tree with the specified number of leaves which moves nodes
+    /// below subtree roots into a temporary file. Returns an error if the file could not be
+    /// created.
+    ///
+    /// Panics under the same conditions as [new()](StreamingMerkleTreeBuilder::new).
+    pub fn with_temp_file(num_leaves: usize, subtree_size: usize) -> io::Result<Self> {
+        let storage = NodeStorage::File(TempFile::new()?);
+        Ok(Self::with_storage(num_leaves, subtree_size, storage))
+    }
+
+    fn with_storage(
+        num_leaves: usize,
+        subtree_size: usize,
+        storage: NodeStorage<H::Digest>,
+    ) -> Self {
+        assert!(
+            num_leaves.is_power_of_two() && num_leaves >= 2,
+            "number of leaves must be a power of 2 greater than 1"
+        );
+        assert!(
+            subtree_size.is_power_of_two() && subtree_size >= 2,
+            "subtree size must be a power of 2 greater than 1"
+        );
+        assert!(
+            subtree_size <= num_leaves,
+            "subtree size cannot be greater than the number of leaves"
+        );
+
+        StreamingMerkleTreeBuilder {
+            num_leaves,
+            subtree_size,
+            buffer: Vec::with_capacity(subtree_size),
+            subtree_roots: Vec::with_capacity(num_leaves / subtree_size),
+            storage,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of leaves appended to the builder so far.
+    pub fn num_appended(&self) -> usize {
+        self.subtree_roots.len() * self.subtree_size + self.buffer.len()
+    }
+
+    // UPDATE METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Appends a single leaf to the tree. Returns an error if nodes could not be written into
+    /// node storage.
+    ///
+    /// Panics if all leaves have already been appended.
+    pub fn append(&mut self, leaf: H::Digest) -> io::Result<()> {
+        assert!(
+            self.num_appended() < self.num_leaves,
+            "cannot append more than {} leaves",
+            self.num_leaves
+        );
+        self.buffer.push(leaf);
+        if self.buffer.len() == self.subtree_size {
+            self.flush_subtree()?;
+        }
+        Ok(())
+    }
+
+    /// Appends a chunk of leaves to the tree. Returns an error if nodes could not be written into
+    /// node storage.
+    ///
+    /// Panics if the total number of appended leaves would exceed the number of leaves in the
+    /// tree.
+    pub fn append_chunk(&mut self, leaves: &[H::Digest]) -> io::Result<()> {
+        self.extend(leaves.iter().copied())
+    }
+
+    /// Appends all leaves produced by the iterator to the tree. Returns an error if nodes could
+    /// not be written into node storage.
+    ///
+    /// Panics if the total number of appended leaves would exceed the number of leaves in the
+    /// tree.
+    pub fn extend<I: IntoIterator<Item = H::Digest>>(&mut self, leaves: I) -> io::Result<()> {
+        for leaf in leaves {
+            self.append(leaf)?;
+        }
+        Ok(())
+    }
+
+    /// Completes construction of the tree.
+    ///
+    /// Panics if not all leaves have been appended.
+    pub fn build(self) -> StreamingMerkleTree<H> {
+        assert_eq!(
+            self.num_leaves,
+            self.num_appended(),
+            "expected {} leaves, but {} were appended",
+            self.num_leaves,
+            self.num_appended()
+        );
+
+        // nodes above subtree roots are arranged in the same way as in a regular Merkle tree,
+        // with subtree roots taking place of the leaves
+        let mut top_nodes = if self.subtree_roots.len() == 1 {
+            vec![H::Digest::default()]
+        } else {
+            build_merkle_nodes::<H>(&self.subtree_roots)
+        };
+        top_nodes.extend_from_slice(&self.subtree_roots);
+
+        StreamingMerkleTree {
+            num_leaves: self.num_leaves,
+            subtree_size: self.subtree_size,
+            top_nodes,
+            storage: self.storage,
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Builds a subtree from the buffered leaves, moves all nodes of the subtree except for its
+    /// root into node storage, and keeps the root.
+    fn flush_subtree(&mut self) -> io::Result<()> {
+        #[cfg(not(feature = "concurrent"))]
+        let nodes = build_merkle_nodes::<H>(&self.buffer);
+
+        #[cfg(feature = "concurrent")]
+        let nodes = if self.buffer.len() <= super::concurrent::MIN_CONCURRENT_LEAVES {
+            build_merkle_nodes::<H>(&self.buffer)
+        } else {
+            super::concurrent::build_merkle_nodes::<H>(&self.buffer)
+        };
+
+        self.storage.write(&nodes[2..])?;
+        self.storage.write(&self.buffer)?;
+        self.subtree_roots.push(nodes[1]);
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+// STREAMING MERKLE TREE
+// ================================================================================================
+
+/// A binary Merkle tree built by [StreamingMerkleTreeBuilder].
+///
+/// Nodes at the level of subtree roots and above are held in memory; all other nodes are read
+/// from node storage when needed. Thus, methods which need the lower nodes return an error if
+/// node storage could not be read.
+pub struct StreamingMerkleTree<H: Hasher> {
+    num_leaves: usize,
+    subtree_size: usize,
+    top_nodes: Vec<H::Digest>,
+    storage: NodeStorage<H::Digest>,
+}
+
+impl<H: Hasher> StreamingMerkleTree<H> {
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the root of the tree.
+    pub fn root(&self) -> &H::Digest {
+        &self.top_nodes[1]
+    }
+
+    /// Returns depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.num_leaves.trailing_zeros() as usize
+    }
+
+    /// Returns the number of leaves in the tree.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the leaf at the specified index.
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn get_leaf(&self, index: usize) -> io::Result<H::Digest> {
+        assert!(index < self.num_leaves, "invalid index {}", index);
+        self.get_node(index + self.num_leaves)
+    }
+
+    /// Returns the cap of the tree at the specified height; the nodes of the cap are listed in
+    /// the order of their positions in the tree.
+    ///
+    /// Cap height cannot exceed depth - 1 of the tree; larger heights are reduced to depth - 1.
+    pub fn cap(&self, cap_height: usize) -> io::Result<Vec<H::Digest>> {
+        let cap_start = 1 << self.clamp_cap_height(cap_height);
+        (cap_start..(2 * cap_start))
+            .map(|index| self.get_node(index))
+            .collect()
+    }
+
+    // PROVING METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes merkle path the given leaf index. The path has the same format as the one
+    /// returned by [MerkleTree::prove()](super::MerkleTree::prove).
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn prove(&self, index: usize) -> io::Result<Vec<H::Digest>> {
+        assert!(index < self.num_leaves, "invalid index {}", index);
+
+        let mut index = index + self.num_leaves;
+        let mut proof = vec![self.get_node(index)?];
+        while index > 1 {
+            proof.push(self.get_node(index ^ 1)?);
+            index >>= 1;
+        }
+        Ok(proof)
+    }
+
+    /// Computes merkle paths for the provided indexes and compresses the paths into a single
+    /// proof. The proof is the same as the one built by
+    /// [MerkleTree::prove_batch()](super::MerkleTree::prove_batch).
+    pub fn prove_batch(&self, indexes: &[usize]) -> io::Result<BatchMerkleProof<H>> {
+        self.prove_batch_to_cap(indexes, 0)
+    }
+
+    /// Computes merkle paths for the provided indexes which stop at the cap of the specified
+    /// height, and compresses the paths into a single proof.
+    ///
+    /// Cap height cannot exceed depth - 1 of the tree; larger heights are reduced to depth - 1.
+    pub fn prove_batch_to_cap(
+        &self,
+        indexes: &[usize],
+        cap_height: usize,
+    ) -> io::Result<BatchMerkleProof<H>> {
+        // read all nodes on the paths for the requested leaves, together with their siblings,
+        // from node storage; this way, the proof can be built from the nodes held in memory
+        let mut nodes = HashMap::new();
+        for &index in indexes {
+            assert!(index < self.num_leaves, "invalid index {}", index);
+            let mut index = index + self.num_leaves;
+            while index > 1 {
+                for node_index in [index, index ^ 1].iter() {
+                    if !nodes.contains_key(node_index) {
+                        nodes.insert(*node_index, self.get_node(*node_index)?);
+                    }
+                }
+                index >>= 1;
+            }
+        }
+
+        let cap_height = self.clamp_cap_height(cap_height);
+        Ok(build_batch_proof(
+            indexes,
+            self.num_leaves,
+            cap_height,
+            |index| nodes[&index],
+        ))
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the node at the specified position in the tree; positions are assigned in the
+    /// same way as in [MerkleTree](super::MerkleTree).
+    fn get_node(&self, index: usize) -> io::Result<H::Digest> {
+        if index < self.top_nodes.len() {
+            return Ok(self.top_nodes[index]);
+        }
+
+        // locate the subtree containing the node and the position of the node in the subtree;
+        // nodes of each subtree are stored in a single block which starts with the children of
+        // the subtree root
+        let num_subtrees = self.num_leaves / self.subtree_size;
+        let subtree_height = (log2(index) - log2(num_subtrees)) as usize;
+        let subtree_index = (index >> subtree_height) - num_subtrees;
+        let position = (1 << subtree_height) | (index & ((1 << subtree_height) - 1));
+        let block_size = 2 * self.subtree_size - 2;
+        self.storage.read(subtree_index * block_size + position - 2)
+    }
+
+    /// Reduces the specified cap height to depth - 1 of this tree, if needed.
+    fn clamp_cap_height(&self, cap_height: usize) -> usize {
+        cap_height.min(self.depth() - 1)
+    }
+}
+
+// NODE STORAGE
+// ================================================================================================
+
+/// Append-only storage for Merkle tree nodes.
+enum NodeStorage<D: Digest> {
+    Memory(Vec<D>),
+    File(TempFile),
+}
+
+impl<D: Digest> NodeStorage<D> {
+    /// Appends the specified nodes to the end of the storage.
+    fn write(&mut self, nodes: &[D]) -> io::Result<()> {
+        match self {
+            NodeStorage::Memory(storage) => {
+                storage.extend_from_slice(nodes);
+                Ok(())
+            }
+            NodeStorage::File(file) => {
+                let mut bytes = Vec::with_capacity(nodes.len() * D::SIZE);
+                for node in nodes {
+                    bytes.extend_from_slice(&node.as_bytes()[..D::SIZE]);
+                }
+                file.write(&bytes)
+            }
+        }
+    }
+
+    /// Returns the node at the specified position in the storage.
+    fn read(&self, position: usize) -> io::Result<D> {
+        match self {
+            NodeStorage::Memory(storage) => Ok(storage[position]),
+            NodeStorage::File(file) => {
+                let mut bytes = [0u8; 32];
+                file.read((position * D::SIZE) as u64, &mut bytes[..D::SIZE])?;
+                D::from_bytes(&bytes[..D::SIZE]).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "failed to read digest")
+                })
+            }
+        }
+    }
+}
+
+/// A file in the system temporary directory which is deleted when dropped.
+struct TempFile {
+    file: Mutex<File>,
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn new() -> io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = env::temp_dir().join(format!(
+            "winter-merkle-{}-{}.tmp",
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(TempFile {
+            file: Mutex::new(file),
+            path,
+        })
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.file.get_mut().unwrap().write_all(bytes)
+    }
+
+    fn read(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        let mut file = self.file.lock().unwrap();
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(buf)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn log2(n: usize) -> u32 {
+    usize::BITS - 1 - n.leading_zeros()
+}
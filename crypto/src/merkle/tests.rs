@@ -6,7 +6,7 @@
 use proptest::prelude::*;
 
 use super::*;
-use crate::hash;
+use crate::hash::{Blake3_192, Blake3_256, DomainSeparated, NODE_DOMAIN_TAG};
 
 static LEAVES4: [[u8; 32]; 4] = [
     [
@@ -65,7 +65,7 @@ static LEAVES8: [[u8; 32]; 8] = [
 #[test]
 fn new_tree() {
     let leaves = LEAVES4.to_vec();
-    let tree = MerkleTree::new(leaves, hash::blake3);
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
     let root = hash_2x1(
         &hash_2x1(&LEAVES4[0], &LEAVES4[1]),
         &hash_2x1(&LEAVES4[2], &LEAVES4[3]),
@@ -73,7 +73,7 @@ fn new_tree() {
     assert_eq!(&root, tree.root());
 
     let leaves = LEAVES8.to_vec();
-    let tree = MerkleTree::new(leaves, hash::blake3);
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
     let root = hash_2x1(
         &hash_2x1(
             &hash_2x1(&LEAVES8[0], &LEAVES8[1]),
@@ -91,7 +91,7 @@ fn new_tree() {
 fn prove() {
     // depth 4
     let leaves = LEAVES4.to_vec();
-    let tree = MerkleTree::new(leaves, hash::blake3);
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
 
     let proof = vec![LEAVES4[1], LEAVES4[0], hash_2x1(&LEAVES4[2], &LEAVES4[3])];
     assert_eq!(proof, tree.prove(1));
@@ -101,7 +101,7 @@ fn prove() {
 
     // depth 5
     let leaves = LEAVES8.to_vec();
-    let tree = MerkleTree::new(leaves, hash::blake3);
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
 
     let proof = vec![
         LEAVES8[1],
@@ -130,39 +130,39 @@ fn prove() {
 fn verify() {
     // depth 4
     let leaves = LEAVES4.to_vec();
-    let tree = MerkleTree::new(leaves, hash::blake3);
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
     let proof = tree.prove(1);
     assert_eq!(
         true,
-        MerkleTree::verify(tree.root(), 1, &proof, hash::blake3)
+        MerkleTree::<Blake3_256>::verify(*tree.root(), 1, &proof)
     );
 
     let proof = tree.prove(2);
     assert_eq!(
         true,
-        MerkleTree::verify(tree.root(), 2, &proof, hash::blake3)
+        MerkleTree::<Blake3_256>::verify(*tree.root(), 2, &proof)
     );
 
     // depth 5
     let leaves = LEAVES8.to_vec();
-    let tree = MerkleTree::new(leaves, hash::blake3);
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
     let proof = tree.prove(1);
     assert_eq!(
         true,
-        MerkleTree::verify(tree.root(), 1, &proof, hash::blake3)
+        MerkleTree::<Blake3_256>::verify(*tree.root(), 1, &proof)
     );
 
     let proof = tree.prove(6);
     assert_eq!(
         true,
-        MerkleTree::verify(tree.root(), 6, &proof, hash::blake3)
+        MerkleTree::<Blake3_256>::verify(*tree.root(), 6, &proof)
     );
 }
 
 #[test]
 fn prove_batch() {
     let leaves = LEAVES8.to_vec();
-    let tree = MerkleTree::new(leaves, hash::blake3);
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
 
     // 1 index
     let proof = tree.prove_batch(&[1]);
@@ -219,53 +219,315 @@ fn prove_batch() {
 #[test]
 fn verify_batch() {
     let leaves = LEAVES8.to_vec();
-    let tree = MerkleTree::new(leaves, hash::blake3);
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
 
     let proof = tree.prove_batch(&[1]);
+    assert_eq!(true, MerkleTree::verify_batch(tree.root(), &[1], &proof));
+    assert_eq!(false, MerkleTree::verify_batch(tree.root(), &[2], &proof));
+
+    let proof = tree.prove_batch(&[1, 2]);
+    assert_eq!(true, MerkleTree::verify_batch(tree.root(), &[1, 2], &proof));
+    assert_eq!(false, MerkleTree::verify_batch(tree.root(), &[1], &proof));
     assert_eq!(
-        true,
-        MerkleTree::verify_batch(tree.root(), &[1], &proof, hash::blake3)
+        false,
+        MerkleTree::verify_batch(tree.root(), &[1, 3], &proof)
     );
     assert_eq!(
         false,
-        MerkleTree::verify_batch(tree.root(), &[2], &proof, hash::blake3)
+        MerkleTree::verify_batch(tree.root(), &[1, 2, 3], &proof)
     );
 
-    let proof = tree.prove_batch(&[1, 2]);
+    let proof = tree.prove_batch(&[1, 6]);
+    assert_eq!(true, MerkleTree::verify_batch(tree.root(), &[1, 6], &proof));
+
+    let proof = tree.prove_batch(&[1, 3, 6]);
     assert_eq!(
         true,
-        MerkleTree::verify_batch(tree.root(), &[1, 2], &proof, hash::blake3)
+        MerkleTree::verify_batch(tree.root(), &[1, 3, 6], &proof)
     );
+
+    let proof = tree.prove_batch(&[0, 1, 2, 3, 4, 5, 6, 7]);
     assert_eq!(
-        false,
-        MerkleTree::verify_batch(tree.root(), &[1], &proof, hash::blake3)
+        true,
+        MerkleTree::verify_batch(tree.root(), &[0, 1, 2, 3, 4, 5, 6, 7], &proof)
     );
+}
+
+#[test]
+fn verify_with_invalid_depth() {
+    let leaves = LEAVES4.to_vec();
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
+
+    // the number of leaves implied by this path does not fit into usize
+    let proof = vec![LEAVES4[0]; 70];
+    assert!(!MerkleTree::<Blake3_256>::verify(*tree.root(), 0, &proof));
+
+    // the index is outside of the tree
+    let proof = tree.prove(1);
+    assert!(!MerkleTree::<Blake3_256>::verify(*tree.root(), 5, &proof));
+}
+
+#[test]
+fn verify_batch_with_invalid_indexes() {
+    let leaves = LEAVES8.to_vec();
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
+    let proof = tree.prove_batch(&[1, 6]);
+
+    // repeated indexes and indexes outside of the tree are rejected
+    assert!(!MerkleTree::verify_batch(tree.root(), &[1, 1], &proof));
+    assert!(!MerkleTree::verify_batch(tree.root(), &[1, 14], &proof));
+    assert_eq!(None, proof.get_paths(&[6, 6]));
+
+    // the number of leaves implied by the depth of the proof does not fit into usize
+    let mut proof = proof;
+    proof.depth = 100;
+    assert!(!MerkleTree::verify_batch(tree.root(), &[1, 6], &proof));
+}
+
+#[test]
+fn domain_separated_tree() {
+    // without domain separation, a hash of a 64-byte leaf is the same as an internal node
+    let leaves = LEAVES4.to_vec();
+    let tree = MerkleTree::<Blake3_256>::new(leaves.clone());
+    let node_bytes = [LEAVES4[0], LEAVES4[1]].concat();
     assert_eq!(
-        false,
-        MerkleTree::verify_batch(tree.root(), &[1, 3], &proof, hash::blake3)
+        hash_2x1(&LEAVES4[0], &LEAVES4[1]),
+        Blake3_256::hash(&node_bytes)
+    );
+
+    // with domain separation, leaf data and internal nodes are hashed differently
+    type Tagged = DomainSeparated<Blake3_256>;
+    let tagged_tree = MerkleTree::<Tagged>::new(leaves);
+    assert_ne!(tree.root(), tagged_tree.root());
+    assert_ne!(
+        Tagged::merge(&[LEAVES4[0], LEAVES4[1]]),
+        Tagged::hash(&node_bytes)
+    );
+
+    // internal nodes are hashed as the node tag followed by the children, for any digest size
+    let node_data = [&[NODE_DOMAIN_TAG][..], &node_bytes].concat();
+    assert_eq!(
+        Blake3_256::hash(&node_data),
+        Tagged::merge(&[LEAVES4[0], LEAVES4[1]])
     );
+    let children = [Blake3_192::hash(&LEAVES4[0]), Blake3_192::hash(&LEAVES4[1])];
+    let node_data = [&[NODE_DOMAIN_TAG][..], &children[0], &children[1]].concat();
     assert_eq!(
-        false,
-        MerkleTree::verify_batch(tree.root(), &[1, 2, 3], &proof, hash::blake3)
+        Blake3_192::hash(&node_data),
+        DomainSeparated::<Blake3_192>::merge(&children)
     );
 
-    let proof = tree.prove_batch(&[1, 6]);
+    let root = Tagged::merge(&[
+        Tagged::merge(&[LEAVES4[0], LEAVES4[1]]),
+        Tagged::merge(&[LEAVES4[2], LEAVES4[3]]),
+    ]);
+    assert_eq!(&root, tagged_tree.root());
+
+    // single and batch proofs are verified against the domain-separated root
+    let proof = tagged_tree.prove(2);
+    assert!(MerkleTree::<Tagged>::verify(*tagged_tree.root(), 2, &proof));
+    assert!(!MerkleTree::<Blake3_256>::verify(
+        *tagged_tree.root(),
+        2,
+        &proof
+    ));
+
+    let proof = tagged_tree.prove_batch(&[0, 3]);
+    assert_eq!(Some(root), proof.get_root(&[0, 3]));
+    assert!(MerkleTree::verify_batch(
+        tagged_tree.root(),
+        &[0, 3],
+        &proof
+    ));
+}
+
+#[test]
+fn new_tree_n() {
+    // 4-ary tree of depth 1
+    let tree = MerkleTree::<Blake3_256, 4>::new(LEAVES4.to_vec());
+    assert_eq!(&Blake3_256::merge_n(&LEAVES4), tree.root());
+    assert_eq!(1, tree.depth());
+
+    // 4-ary tree of depth 2
+    let leaves = [LEAVES8, LEAVES8].concat();
+    let tree = MerkleTree::<Blake3_256, 4>::new(leaves.clone());
+    let root = Blake3_256::merge_n(&[
+        Blake3_256::merge_n(&leaves[0..4]),
+        Blake3_256::merge_n(&leaves[4..8]),
+        Blake3_256::merge_n(&leaves[8..12]),
+        Blake3_256::merge_n(&leaves[12..16]),
+    ]);
+    assert_eq!(&root, tree.root());
+    assert_eq!(2, tree.depth());
+
+    // 8-ary tree of depth 1
+    let tree = MerkleTree::<Blake3_256, 8>::new(LEAVES8.to_vec());
+    assert_eq!(&Blake3_256::merge_n(&LEAVES8), tree.root());
+
+    // merging two digests is the same for binary and higher arity trees
     assert_eq!(
-        true,
-        MerkleTree::verify_batch(tree.root(), &[1, 6], &proof, hash::blake3)
+        hash_2x1(&LEAVES4[0], &LEAVES4[1]),
+        Blake3_256::merge_n(&LEAVES4[..2])
     );
+}
 
-    let proof = tree.prove_batch(&[1, 3, 6]);
+#[test]
+#[should_panic(expected = "number of leaves must be a power of 4")]
+fn new_tree_n_invalid_leaves() {
+    MerkleTree::<Blake3_256, 4>::new(LEAVES8.to_vec());
+}
+
+#[test]
+fn prove_n() {
+    let leaves = [LEAVES8, LEAVES8].concat();
+    let tree = MerkleTree::<Blake3_256, 4>::new(leaves.clone());
+
+    let proof = tree.prove(6);
+    let expected = vec![
+        leaves[6],
+        leaves[4],
+        leaves[5],
+        leaves[7],
+        Blake3_256::merge_n(&leaves[0..4]),
+        Blake3_256::merge_n(&leaves[8..12]),
+        Blake3_256::merge_n(&leaves[12..16]),
+    ];
+    assert_eq!(expected, proof);
+    assert!(MerkleTree::<Blake3_256, 4>::verify(*tree.root(), 6, &proof));
+    assert!(!MerkleTree::<Blake3_256, 4>::verify(
+        *tree.root(),
+        5,
+        &proof
+    ));
+    assert!(!MerkleTree::<Blake3_256, 4>::verify(
+        *tree.root(),
+        6,
+        &proof[..6]
+    ));
+}
+
+#[test]
+fn prove_batch_n() {
+    let leaves = [LEAVES8, LEAVES8].concat();
+    let tree = MerkleTree::<Blake3_256, 4>::new(leaves.clone());
+
+    // siblings of the leaves included in the batch are not duplicated in the proof
+    let proof = tree.prove_batch(&[5, 6, 13]);
+    let expected_values = vec![leaves[5], leaves[6], leaves[13]];
+    let expected_nodes = vec![
+        vec![
+            leaves[4],
+            leaves[7],
+            Blake3_256::merge_n(&leaves[0..4]),
+            Blake3_256::merge_n(&leaves[8..12]),
+        ],
+        vec![leaves[12], leaves[14], leaves[15]],
+    ];
+    assert_eq!(expected_values, proof.values);
+    assert_eq!(expected_nodes, proof.nodes);
+    assert_eq!(2, proof.depth);
+
+    assert!(MerkleTree::verify_batch(tree.root(), &[5, 6, 13], &proof));
+    assert!(!MerkleTree::verify_batch(tree.root(), &[5, 6, 12], &proof));
+    assert!(!MerkleTree::verify_batch(tree.root(), &[5, 13], &proof));
+}
+
+#[test]
+fn cap() {
+    let leaves = LEAVES8.to_vec();
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
+
+    assert_eq!(vec![*tree.root()], tree.cap(0));
+
+    let cap = vec![
+        hash_2x1(
+            &hash_2x1(&LEAVES8[0], &LEAVES8[1]),
+            &hash_2x1(&LEAVES8[2], &LEAVES8[3]),
+        ),
+        hash_2x1(
+            &hash_2x1(&LEAVES8[4], &LEAVES8[5]),
+            &hash_2x1(&LEAVES8[6], &LEAVES8[7]),
+        ),
+    ];
+    assert_eq!(cap, tree.cap(1));
     assert_eq!(
-        true,
-        MerkleTree::verify_batch(tree.root(), &[1, 3, 6], &proof, hash::blake3)
+        Some(*tree.root()),
+        MerkleTree::<Blake3_256>::get_root_from_cap(&cap)
     );
 
-    let proof = tree.prove_batch(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    // cap height is limited by depth - 1 of the tree
+    assert_eq!(4, tree.cap(2).len());
+    assert_eq!(tree.cap(2), tree.cap(5));
+
+    let leaves = [LEAVES8, LEAVES8].concat();
+    let tree = MerkleTree::<Blake3_256, 4>::new(leaves);
+    let cap = tree.cap(1);
+    assert_eq!(4, cap.len());
     assert_eq!(
-        true,
-        MerkleTree::verify_batch(tree.root(), &[0, 1, 2, 3, 4, 5, 6, 7], &proof, hash::blake3)
+        Some(*tree.root()),
+        MerkleTree::<Blake3_256, 4>::get_root_from_cap(&cap)
     );
+    assert_eq!(
+        None,
+        MerkleTree::<Blake3_256, 4>::get_root_from_cap(&cap[..2])
+    );
+}
+
+#[test]
+fn prove_to_cap() {
+    let leaves = LEAVES8.to_vec();
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
+    let cap = tree.cap(1);
+
+    let proof = tree.prove_to_cap(5, 1);
+    assert_eq!(tree.prove(5)[..3], proof[..]);
+    assert!(MerkleTree::<Blake3_256>::verify_with_cap(&cap, 5, &proof));
+    assert!(!MerkleTree::<Blake3_256>::verify_with_cap(&cap, 1, &proof));
+    assert!(!MerkleTree::<Blake3_256>::verify_with_cap(
+        &cap[..1],
+        5,
+        &proof
+    ));
+    assert!(!MerkleTree::<Blake3_256>::verify(*tree.root(), 5, &proof));
+
+    // a full path can be verified against a cap of height 0
+    let proof = tree.prove_to_cap(5, 0);
+    assert!(MerkleTree::<Blake3_256>::verify_with_cap(
+        &tree.cap(0),
+        5,
+        &proof
+    ));
+}
+
+#[test]
+fn prove_batch_to_cap() {
+    let leaves = LEAVES8.to_vec();
+    let tree = MerkleTree::<Blake3_256>::new(leaves);
+    let cap = tree.cap(1);
+
+    // the path above the cap is not included in the proof
+    let proof = tree.prove_batch_to_cap(&[1, 2], 1);
+    let expected_nodes = vec![vec![LEAVES8[0]], vec![LEAVES8[3]]];
+    assert_eq!(expected_nodes, proof.nodes);
+    assert_eq!(3, proof.depth);
+
+    assert!(MerkleTree::verify_batch_with_cap(&cap, &[1, 2], &proof));
+    assert!(!MerkleTree::verify_batch_with_cap(&cap, &[1, 3], &proof));
+    assert!(!MerkleTree::verify_batch_with_cap(
+        &cap[..1],
+        &[1, 2],
+        &proof
+    ));
+    assert!(!MerkleTree::verify_batch(tree.root(), &[1, 2], &proof));
+
+    let proof = tree.prove_batch_to_cap(&[1, 2, 6], 1);
+    let expected_nodes = vec![
+        vec![LEAVES8[0]],
+        vec![LEAVES8[3]],
+        vec![LEAVES8[7], hash_2x1(&LEAVES8[4], &LEAVES8[5])],
+    ];
+    assert_eq!(expected_nodes, proof.nodes);
+    assert!(MerkleTree::verify_batch_with_cap(&cap, &[1, 2, 6], &proof));
 }
 
 proptest! {
@@ -275,7 +537,7 @@ proptest! {
     )  {
         for proof_index in proof_indices{
             let proof = tree.prove(proof_index.index(128));
-            prop_assert!(MerkleTree::verify(tree.root(), proof_index.index(128), &proof, hash::blake3))
+            prop_assert!(MerkleTree::<Blake3_256>::verify(*tree.root(), proof_index.index(128), &proof))
         }
     }
 
@@ -286,7 +548,7 @@ proptest! {
         let mut indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(128)).collect();
         indices.sort_unstable(); indices.dedup();
         let proof = tree.prove_batch(&indices[..]);
-        prop_assert!(MerkleTree::verify_batch(tree.root(), &indices[..], &proof, hash::blake3));
+        prop_assert!(MerkleTree::verify_batch(tree.root(), &indices[..], &proof));
     }
 
     #[test]
@@ -305,21 +567,171 @@ proptest! {
 
         prop_assert!(proof1 == proof2);
     }
+
+    #[test]
+    fn batch_proof_to_paths(tree in random_blake3_merkle_tree(128),
+                      proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20)
+    )  {
+        let mut indices: Vec<usize> = Vec::new();
+        for idx in proof_indices.iter().map(|idx| idx.index(128)) {
+            if !indices.contains(&idx) { indices.push(idx); }
+        }
+        let proof = tree.prove_batch(&indices[..]);
+
+        let paths = proof.get_paths(&indices).unwrap();
+        for (&idx, path) in indices.iter().zip(paths) {
+            prop_assert!(path == tree.prove(idx));
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn prove_n_verify_n(tree in random_blake3_merkle_tree_n::<4>(256),
+                      proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20)
+    )  {
+        for proof_index in proof_indices{
+            let proof = tree.prove(proof_index.index(256));
+            prop_assert!(MerkleTree::<Blake3_256, 4>::verify(*tree.root(), proof_index.index(256), &proof))
+        }
+    }
+
+    #[test]
+    fn prove_batch_n_verify_n(tree in random_blake3_merkle_tree_n::<8>(512),
+                      proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20)
+    )  {
+        let mut indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(512)).collect();
+        indices.sort_unstable(); indices.dedup();
+        let proof = tree.prove_batch(&indices[..]);
+        prop_assert!(MerkleTree::verify_batch(tree.root(), &indices[..], &proof));
+    }
+
+    #[test]
+    fn batch_proof_from_paths_n(tree in random_blake3_merkle_tree_n::<4>(256),
+                      proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20)
+    )  {
+        let mut indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(256)).collect();
+        indices.sort_unstable(); indices.dedup();
+        let proof1 = tree.prove_batch(&indices[..]);
+
+        let mut paths = Vec::new();
+        for &idx in indices.iter() {
+            paths.push(tree.prove(idx));
+        }
+        let proof2 = BatchMerkleProof::from_paths(&paths, &indices);
+
+        prop_assert!(proof1 == proof2);
+    }
+}
+
+proptest! {
+    #[test]
+    fn prove_batch_to_cap_verify(tree in random_blake3_merkle_tree(128),
+                      cap_height in 0..7usize,
+                      proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20)
+    )  {
+        let mut indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(128)).collect();
+        indices.sort_unstable(); indices.dedup();
+        let cap = tree.cap(cap_height);
+        let proof = tree.prove_batch_to_cap(&indices[..], cap_height);
+        prop_assert!(MerkleTree::verify_batch_with_cap(&cap, &indices[..], &proof));
+        for &index in indices.iter() {
+            let path = tree.prove_to_cap(index, cap_height);
+            prop_assert!(MerkleTree::<Blake3_256>::verify_with_cap(&cap, index, &path));
+        }
+    }
+
+    #[test]
+    fn prove_batch_to_cap_verify_n(tree in random_blake3_merkle_tree_n::<4>(256),
+                      cap_height in 0..4usize,
+                      proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20)
+    )  {
+        let mut indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(256)).collect();
+        indices.sort_unstable(); indices.dedup();
+        let cap = tree.cap(cap_height);
+        let proof = tree.prove_batch_to_cap(&indices[..], cap_height);
+        prop_assert!(MerkleTree::verify_batch_with_cap(&cap, &indices[..], &proof));
+    }
+}
+
+#[test]
+fn streaming_tree() {
+    let leaves = (0..64u64)
+        .map(|i| Blake3_256::hash(&i.to_le_bytes()))
+        .collect::<Vec<_>>();
+    let tree = MerkleTree::<Blake3_256>::new(leaves.clone());
+    let indexes = [0, 1, 7, 30, 31, 63];
+
+    for &subtree_size in [2, 8, 64].iter() {
+        let mut builder = StreamingMerkleTreeBuilder::<Blake3_256>::new(64, subtree_size);
+        builder.append_chunk(&leaves[..5]).unwrap();
+        builder.extend(leaves[5..].iter().copied()).unwrap();
+        let streaming_tree = builder.build();
+
+        assert_eq!(tree.root(), streaming_tree.root());
+        assert_eq!(tree.depth(), streaming_tree.depth());
+        assert_eq!(leaves[30], streaming_tree.get_leaf(30).unwrap());
+        assert_eq!(tree.cap(3), streaming_tree.cap(3).unwrap());
+        for &index in indexes.iter() {
+            assert_eq!(tree.prove(index), streaming_tree.prove(index).unwrap());
+        }
+        assert_eq!(
+            tree.prove_batch(&indexes),
+            streaming_tree.prove_batch(&indexes).unwrap()
+        );
+        assert_eq!(
+            tree.prove_batch_to_cap(&indexes, 2),
+            streaming_tree.prove_batch_to_cap(&indexes, 2).unwrap()
+        );
+    }
+}
+
+#[test]
+fn streaming_tree_with_temp_file() {
+    let leaves = (0..256u64)
+        .map(|i| Blake3_256::hash(&i.to_le_bytes()))
+        .collect::<Vec<_>>();
+    let tree = MerkleTree::<Blake3_256>::new(leaves.clone());
+    let indexes = [3, 4, 100, 255];
+
+    let mut builder = StreamingMerkleTreeBuilder::<Blake3_256>::with_temp_file(256, 16).unwrap();
+    for &leaf in leaves.iter() {
+        builder.append(leaf).unwrap();
+    }
+    let streaming_tree = builder.build();
+
+    assert_eq!(tree.root(), streaming_tree.root());
+    assert_eq!(leaves[100], streaming_tree.get_leaf(100).unwrap());
+    assert_eq!(tree.prove(100), streaming_tree.prove(100).unwrap());
+    assert_eq!(
+        tree.prove_batch(&indexes),
+        streaming_tree.prove_batch(&indexes).unwrap()
+    );
+}
+
+#[test]
+#[should_panic(expected = "expected 16 leaves, but 15 were appended")]
+fn streaming_tree_missing_leaves() {
+    let mut builder = StreamingMerkleTreeBuilder::<Blake3_256>::new(16, 4);
+    builder.append_chunk(&[[1; 32]; 15]).unwrap();
+    builder.build();
 }
 
 // HELPER FUNCTIONS
 // --------------------------------------------------------------------------------------------
 fn hash_2x1(v1: &[u8; 32], v2: &[u8; 32]) -> [u8; 32] {
-    let mut buf = [0u8; 64];
-    buf[..32].copy_from_slice(v1);
-    buf[32..].copy_from_slice(v2);
-
-    let mut result = [0u8; 32];
-    hash::blake3(&buf, &mut result);
-    result
+    Blake3_256::merge(&[*v1, *v2])
 }
 
-pub fn random_blake3_merkle_tree(leave_count: usize) -> impl Strategy<Value = MerkleTree> {
+pub fn random_blake3_merkle_tree(
+    leave_count: usize,
+) -> impl Strategy<Value = MerkleTree<Blake3_256>> {
     prop::collection::vec(any::<[u8; 32]>(), leave_count)
-        .prop_map(|leaves| MerkleTree::new(leaves, hash::blake3))
+        .prop_map(|leaves| MerkleTree::<Blake3_256>::new(leaves))
+}
+
+pub fn random_blake3_merkle_tree_n<const N: usize>(
+    leave_count: usize,
+) -> impl Strategy<Value = MerkleTree<Blake3_256, N>> {
+    prop::collection::vec(any::<[u8; 32]>(), leave_count).prop_map(MerkleTree::<Blake3_256, N>::new)
 }
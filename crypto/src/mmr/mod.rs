@@ -0,0 +1,300 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::hash::Hasher;
+
+mod proofs;
+pub use proofs::{MmrConsistencyProof, MmrInclusionProof};
+
+#[cfg(test)]
+mod tests;
+
+// TYPES AND INTERFACES
+// ================================================================================================
+
+/// An append-only Merkle Mountain Range (MMR) accumulator.
+///
+/// An MMR with n leaves is a list of perfect binary Merkle trees (peaks), one for every bit set
+/// in the binary representation of n, ordered from the tallest tree to the shortest one. When a
+/// leaf is appended, it becomes a new peak of height 0, and then adjacent peaks of equal heights
+/// are merged until all peaks have distinct heights.
+///
+/// Nodes are never modified once created, and thus, peaks of the MMR at any historical size
+/// remain available. This makes it possible to prove inclusion of a leaf against the peaks of
+/// any past size of the MMR, and to prove that the MMR at a given size is an extension of the
+/// MMR at a smaller size.
+///
+/// Nodes are stored level by level: node i at height h is a root of a subtree covering leaves
+/// i * 2^h..(i + 1) * 2^h.
+#[derive(Debug, Clone)]
+pub struct MerkleMountainRange<H: Hasher> {
+    levels: Vec<Vec<H::Digest>>,
+}
+
+// MERKLE MOUNTAIN RANGE IMPLEMENTATION
+// ================================================================================================
+
+impl<H: Hasher> MerkleMountainRange<H> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new empty MMR.
+    pub fn new() -> Self {
+        MerkleMountainRange {
+            levels: vec![Vec::new()],
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of leaves in the MMR.
+    pub fn num_leaves(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Returns true if no leaves have been appended to the MMR.
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// Returns the leaf at the specified index, or None if the index is out of bounds.
+    pub fn get_leaf(&self, index: usize) -> Option<H::Digest> {
+        self.levels[0].get(index).copied()
+    }
+
+    /// Returns the peaks of the MMR ordered from the tallest to the shortest.
+    pub fn peaks(&self) -> Vec<H::Digest> {
+        self.peaks_at(self.num_leaves()).unwrap()
+    }
+
+    /// Returns the peaks the MMR had when it contained the specified number of leaves, or None if
+    /// the MMR has never been of this size.
+    pub fn peaks_at(&self, size: usize) -> Option<Vec<H::Digest>> {
+        if size > self.num_leaves() {
+            return None;
+        }
+        let result = get_peak_heights(size)
+            .map(|height| self.levels[height][(size >> height) - 1])
+            .collect();
+        Some(result)
+    }
+
+    /// Returns a single digest committing to the current state of the MMR. See
+    /// [bag_peaks()](MerkleMountainRange::bag_peaks) for details.
+    pub fn root(&self) -> H::Digest {
+        Self::bag_peaks(&self.peaks())
+    }
+
+    // UPDATE METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Appends a leaf to the MMR and returns the index of the leaf.
+    pub fn append(&mut self, leaf: H::Digest) -> usize {
+        let index = self.num_leaves();
+        self.levels[0].push(leaf);
+
+        // merge peaks of equal heights; a new node at height h + 1 is created whenever the
+        // number of nodes at height h becomes even
+        let mut height = 0;
+        while self.levels[height].len() & 1 == 0 {
+            let nodes = &self.levels[height];
+            let parent = H::merge(&[nodes[nodes.len() - 2], nodes[nodes.len() - 1]]);
+            if self.levels.len() == height + 1 {
+                self.levels.push(Vec::new());
+            }
+            self.levels[height + 1].push(parent);
+            height += 1;
+        }
+
+        index
+    }
+
+    // PROVING METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a proof that the leaf at the specified index is included in the current state of
+    /// the MMR. Returns None if the index is out of bounds.
+    pub fn prove(&self, index: usize) -> Option<MmrInclusionProof<H>> {
+        self.prove_at(index, self.num_leaves())
+    }
+
+    /// Returns a proof that the leaf at the specified index is included in the MMR when it
+    /// contained the specified number of leaves; the proof can be verified against the peaks
+    /// returned by [peaks_at()](MerkleMountainRange::peaks_at) for the same size.
+    ///
+    /// Returns None if the index is not smaller than the size, or if the MMR has never been of
+    /// the specified size.
+    pub fn prove_at(&self, index: usize, size: usize) -> Option<MmrInclusionProof<H>> {
+        if index >= size || size > self.num_leaves() {
+            return None;
+        }
+        let (_, peak_height) = find_peak(size, index);
+        Some(MmrInclusionProof {
+            index,
+            size,
+            path: self.get_path(0, index, peak_height),
+        })
+    }
+
+    /// Returns a proof that the MMR of the new size is an extension of the MMR of the old size;
+    /// the proof can be verified against the peaks of the MMR at both sizes.
+    ///
+    /// Returns None if the old size is greater than the new size, or if the MMR has never been
+    /// of the new size.
+    pub fn prove_consistency(
+        &self,
+        old_size: usize,
+        new_size: usize,
+    ) -> Option<MmrConsistencyProof<H>> {
+        if old_size > new_size || new_size > self.num_leaves() {
+            return None;
+        }
+
+        // for every old peak, include a path from the peak to the new peak containing it
+        let paths = get_peak_nodes(old_size)
+            .map(|(height, index)| {
+                let (_, peak_height) = find_peak(new_size, index << height);
+                self.get_path(height, index, peak_height)
+            })
+            .collect();
+
+        Some(MmrConsistencyProof {
+            old_size,
+            new_size,
+            paths,
+        })
+    }
+
+    // VERIFICATION METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks whether the proof shows that the specified leaf is included in the MMR with the
+    /// specified peaks.
+    pub fn verify_inclusion(
+        peaks: &[H::Digest],
+        leaf: &H::Digest,
+        proof: &MmrInclusionProof<H>,
+    ) -> bool {
+        if proof.index >= proof.size || peaks.len() != proof.size.count_ones() as usize {
+            return false;
+        }
+
+        let (peak_position, peak_height) = find_peak(proof.size, proof.index);
+        match get_path_root::<H>(*leaf, proof.index, &proof.path, peak_height) {
+            Some(peak) => peak == peaks[peak_position],
+            None => false,
+        }
+    }
+
+    /// Checks whether the proof shows that the MMR with the new peaks is an extension of the MMR
+    /// with the old peaks.
+    pub fn verify_consistency(
+        old_peaks: &[H::Digest],
+        new_peaks: &[H::Digest],
+        proof: &MmrConsistencyProof<H>,
+    ) -> bool {
+        if proof.old_size > proof.new_size
+            || old_peaks.len() != proof.old_size.count_ones() as usize
+            || new_peaks.len() != proof.new_size.count_ones() as usize
+            || proof.paths.len() != old_peaks.len()
+        {
+            return false;
+        }
+
+        get_peak_nodes(proof.old_size)
+            .zip(old_peaks.iter().zip(proof.paths.iter()))
+            .all(|((height, index), (&old_peak, path))| {
+                let (peak_position, peak_height) = find_peak(proof.new_size, index << height);
+                match get_path_root::<H>(old_peak, index, path, peak_height - height) {
+                    Some(peak) => peak == new_peaks[peak_position],
+                    None => false,
+                }
+            })
+    }
+
+    /// Combines the specified peaks into a single digest by hashing them from right to left;
+    /// for an empty MMR, the default digest is returned.
+    pub fn bag_peaks(peaks: &[H::Digest]) -> H::Digest {
+        match peaks.split_last() {
+            Some((&last, rest)) => rest
+                .iter()
+                .rev()
+                .fold(last, |acc, &peak| H::merge(&[peak, acc])),
+            None => H::Digest::default(),
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns siblings of the nodes on the path from the node at the specified height and index
+    /// up to (but not including) the specified height.
+    fn get_path(&self, height: usize, index: usize, peak_height: usize) -> Vec<H::Digest> {
+        (height..peak_height)
+            .map(|h| self.levels[h][(index >> (h - height)) ^ 1])
+            .collect()
+    }
+}
+
+impl<H: Hasher> Default for MerkleMountainRange<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns heights of the peaks of an MMR of the specified size ordered from the tallest to the
+/// shortest.
+fn get_peak_heights(size: usize) -> impl Iterator<Item = usize> {
+    (0..usize::BITS as usize)
+        .rev()
+        .filter(move |&height| (size >> height) & 1 == 1)
+}
+
+/// Returns (height, index) pairs of the peaks of an MMR of the specified size.
+fn get_peak_nodes(size: usize) -> impl Iterator<Item = (usize, usize)> {
+    get_peak_heights(size).map(move |height| (height, (size >> height) - 1))
+}
+
+/// Returns position and height of the peak which contains the specified leaf in an MMR of the
+/// specified size. The leaf index must be smaller than the size.
+fn find_peak(size: usize, leaf_index: usize) -> (usize, usize) {
+    let mut peak_end = 0;
+    for (position, height) in get_peak_heights(size).enumerate() {
+        peak_end += 1 << height;
+        if leaf_index < peak_end {
+            return (position, height);
+        }
+    }
+    unreachable!(
+        "leaf index {} is out of bounds for size {}",
+        leaf_index, size
+    )
+}
+
+/// Computes the node at the top of the path starting with the specified node; `index` is the
+/// index of the starting node at its height. Returns None if the path does not contain exactly
+/// `path_length` siblings.
+fn get_path_root<H: Hasher>(
+    node: H::Digest,
+    index: usize,
+    path: &[H::Digest],
+    path_length: usize,
+) -> Option<H::Digest> {
+    if path.len() != path_length {
+        return None;
+    }
+    let mut node = node;
+    for (i, sibling) in path.iter().enumerate() {
+        node = if (index >> i) & 1 == 0 {
+            H::merge(&[node, *sibling])
+        } else {
+            H::merge(&[*sibling, node])
+        };
+    }
+    Some(node)
+}
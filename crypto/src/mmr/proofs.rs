@@ -0,0 +1,140 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{find_peak, get_peak_nodes};
+use crate::{
+    hash::{digests_as_bytes, read_digests_into_vec},
+    Digest, Hasher, MmrProofSerializationError,
+};
+use core::convert::TryInto;
+
+// INCLUSION PROOF
+// ================================================================================================
+
+/// A proof that a leaf is included in a Merkle Mountain Range of a given size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MmrInclusionProof<H: Hasher> {
+    /// Index of the leaf.
+    pub index: usize,
+    /// Number of leaves in the MMR against which the proof is made.
+    pub size: usize,
+    /// Siblings of the nodes on the path from the leaf to the peak containing it.
+    pub path: Vec<H::Digest>,
+}
+
+impl<H: Hasher> MmrInclusionProof<H> {
+    /// Serializes this proof into a vector of bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(16 + self.path.len() * H::Digest::SIZE);
+        result.extend_from_slice(&(self.index as u64).to_le_bytes());
+        result.extend_from_slice(&(self.size as u64).to_le_bytes());
+        result.extend_from_slice(&digests_as_bytes(&self.path));
+        result
+    }
+
+    /// Reads an inclusion proof from the provided bytes. Returns an error if the bytes do not
+    /// encode a well-formed proof.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MmrProofSerializationError> {
+        let (index, size, path) = read_header(bytes)?;
+        if index >= size {
+            return Err(MmrProofSerializationError::InvalidLeafIndex(index, size));
+        }
+
+        let (_, expected) = find_peak(size, index);
+        let path = read_digests_into_vec(path)?;
+        if path.len() != expected {
+            return Err(MmrProofSerializationError::WrongNumberOfNodes(
+                expected,
+                path.len(),
+            ));
+        }
+
+        Ok(MmrInclusionProof { index, size, path })
+    }
+}
+
+// CONSISTENCY PROOF
+// ================================================================================================
+
+/// A proof that a Merkle Mountain Range of a given size is an extension of the same Merkle
+/// Mountain Range at a smaller size.
+///
+/// The proof contains a path for every peak of the smaller MMR leading from this peak to the
+/// peak of the larger MMR which contains it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MmrConsistencyProof<H: Hasher> {
+    /// Number of leaves in the smaller MMR.
+    pub old_size: usize,
+    /// Number of leaves in the larger MMR.
+    pub new_size: usize,
+    /// Paths from the peaks of the smaller MMR to the peaks of the larger MMR ordered the same
+    /// way as the peaks of the smaller MMR.
+    pub paths: Vec<Vec<H::Digest>>,
+}
+
+impl<H: Hasher> MmrConsistencyProof<H> {
+    /// Serializes this proof into a vector of bytes. Lengths of the paths are not included in
+    /// the serialized output as they are implied by the sizes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let num_nodes = self.paths.iter().map(|path| path.len()).sum::<usize>();
+        let mut result = Vec::with_capacity(16 + num_nodes * H::Digest::SIZE);
+        result.extend_from_slice(&(self.old_size as u64).to_le_bytes());
+        result.extend_from_slice(&(self.new_size as u64).to_le_bytes());
+        for path in self.paths.iter() {
+            result.extend_from_slice(&digests_as_bytes(path));
+        }
+        result
+    }
+
+    /// Reads a consistency proof from the provided bytes. Returns an error if the bytes do not
+    /// encode a well-formed proof.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MmrProofSerializationError> {
+        let (old_size, new_size, nodes) = read_header(bytes)?;
+        if old_size > new_size {
+            return Err(MmrProofSerializationError::InvalidSizes(old_size, new_size));
+        }
+
+        let path_lengths = get_peak_nodes(old_size)
+            .map(|(height, index)| find_peak(new_size, index << height).1 - height)
+            .collect::<Vec<_>>();
+        let expected = path_lengths.iter().sum::<usize>();
+        let mut nodes = read_digests_into_vec(nodes)?.into_iter();
+        if nodes.len() != expected {
+            return Err(MmrProofSerializationError::WrongNumberOfNodes(
+                expected,
+                nodes.len(),
+            ));
+        }
+
+        let paths = path_lengths
+            .into_iter()
+            .map(|length| nodes.by_ref().take(length).collect())
+            .collect();
+        Ok(MmrConsistencyProof {
+            old_size,
+            new_size,
+            paths,
+        })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reads two 8-byte little-endian integers from the start of the provided bytes, and returns
+/// them together with the remaining bytes.
+fn read_header(bytes: &[u8]) -> Result<(usize, usize, &[u8]), MmrProofSerializationError> {
+    if bytes.len() < 16 {
+        return Err(MmrProofSerializationError::NotEnoughBytesForHeader(
+            bytes.len(),
+        ));
+    }
+    let first = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+    let second = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+    match (first.try_into(), second.try_into()) {
+        (Ok(first), Ok(second)) => Ok((first, second, &bytes[16..])),
+        _ => Err(MmrProofSerializationError::SizeTooLarge),
+    }
+}
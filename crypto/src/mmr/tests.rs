@@ -0,0 +1,189 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use proptest::prelude::*;
+
+use super::*;
+use crate::{hash::Blake3_256, MerkleTree, MmrProofSerializationError};
+
+type Digest = <Blake3_256 as Hasher>::Digest;
+type Mmr = MerkleMountainRange<Blake3_256>;
+
+#[test]
+fn append_and_peaks() {
+    let leaves = build_leaves(11);
+    let mut mmr = Mmr::new();
+    assert!(mmr.is_empty());
+    assert_eq!(Digest::default(), mmr.root());
+
+    for (i, &leaf) in leaves.iter().enumerate() {
+        assert_eq!(i, mmr.append(leaf));
+        assert_eq!((i + 1).count_ones() as usize, mmr.peaks().len());
+    }
+    assert_eq!(11, mmr.num_leaves());
+    assert_eq!(Some(leaves[10]), mmr.get_leaf(10));
+    assert_eq!(None, mmr.get_leaf(11));
+
+    // for sizes which are powers of two, the only peak is the root of a Merkle tree
+    let tree = MerkleTree::<Blake3_256>::new(leaves[..8].to_vec());
+    assert_eq!(Some(vec![*tree.root()]), mmr.peaks_at(8));
+
+    let peaks = vec![
+        *tree.root(),
+        Blake3_256::merge(&[leaves[8], leaves[9]]),
+        leaves[10],
+    ];
+    assert_eq!(peaks, mmr.peaks());
+    assert_eq!(
+        Blake3_256::merge(&[peaks[0], Blake3_256::merge(&[peaks[1], peaks[2]])]),
+        mmr.root()
+    );
+    assert_eq!(None, mmr.peaks_at(12));
+}
+
+#[test]
+fn prove_inclusion() {
+    let leaves = build_leaves(11);
+    let mmr = build_mmr(&leaves);
+    let peaks = mmr.peaks();
+
+    for (i, leaf) in leaves.iter().enumerate() {
+        let proof = mmr.prove(i).unwrap();
+        assert!(Mmr::verify_inclusion(&peaks, leaf, &proof));
+        assert!(!Mmr::verify_inclusion(&peaks, &[0; 32], &proof));
+    }
+    assert_eq!(None, mmr.prove(11));
+
+    // the proof for the last leaf is empty because the leaf is a peak
+    assert!(mmr.prove(10).unwrap().path.is_empty());
+
+    // proofs against historical peaks
+    let old_peaks = mmr.peaks_at(5).unwrap();
+    let proof = mmr.prove_at(3, 5).unwrap();
+    assert_eq!(2, proof.path.len());
+    assert!(Mmr::verify_inclusion(&old_peaks, &leaves[3], &proof));
+    assert!(!Mmr::verify_inclusion(&peaks, &leaves[3], &proof));
+    assert_eq!(None, mmr.prove_at(5, 5));
+    assert_eq!(None, mmr.prove_at(3, 12));
+
+    // malformed proofs are rejected
+    let mut proof = mmr.prove(3).unwrap();
+    proof.path.pop();
+    assert!(!Mmr::verify_inclusion(&peaks, &leaves[3], &proof));
+    let mut proof = mmr.prove(3).unwrap();
+    proof.size = 12;
+    assert!(!Mmr::verify_inclusion(&peaks, &leaves[3], &proof));
+}
+
+#[test]
+fn prove_consistency() {
+    let leaves = build_leaves(13);
+    let mmr = build_mmr(&leaves);
+
+    for new_size in 0..=13 {
+        let new_peaks = mmr.peaks_at(new_size).unwrap();
+        for old_size in 0..=new_size {
+            let old_peaks = mmr.peaks_at(old_size).unwrap();
+            let proof = mmr.prove_consistency(old_size, new_size).unwrap();
+            assert!(Mmr::verify_consistency(&old_peaks, &new_peaks, &proof));
+        }
+    }
+    assert_eq!(None, mmr.prove_consistency(5, 4));
+    assert_eq!(None, mmr.prove_consistency(5, 14));
+
+    // peaks of a different MMR are not consistent with the peaks of this MMR
+    let proof = mmr.prove_consistency(6, 13).unwrap();
+    let mut other_leaves = leaves.clone();
+    other_leaves[4] = [0; 32];
+    let other_mmr = build_mmr(&other_leaves);
+    assert!(!Mmr::verify_consistency(
+        &other_mmr.peaks_at(6).unwrap(),
+        &mmr.peaks(),
+        &proof
+    ));
+    assert!(!Mmr::verify_consistency(
+        &mmr.peaks_at(6).unwrap(),
+        &other_mmr.peaks(),
+        &proof
+    ));
+    assert!(!Mmr::verify_consistency(
+        &mmr.peaks_at(7).unwrap(),
+        &mmr.peaks(),
+        &proof
+    ));
+}
+
+#[test]
+fn proof_serialization() {
+    let leaves = build_leaves(13);
+    let mmr = build_mmr(&leaves);
+
+    let proof = mmr.prove_at(4, 11).unwrap();
+    let bytes = proof.to_bytes();
+    assert_eq!(16 + 32 * proof.path.len(), bytes.len());
+    assert_eq!(Ok(proof), MmrInclusionProof::from_bytes(&bytes));
+
+    let proof = mmr.prove_consistency(7, 13).unwrap();
+    let bytes = proof.to_bytes();
+    assert_eq!(Ok(proof), MmrConsistencyProof::from_bytes(&bytes));
+
+    // malformed proofs are rejected
+    assert_eq!(
+        Err(MmrProofSerializationError::NotEnoughBytesForHeader(15)),
+        MmrInclusionProof::<Blake3_256>::from_bytes(&bytes[..15])
+    );
+    assert_eq!(
+        Err(MmrProofSerializationError::WrongNumberOfNodes(6, 5)),
+        MmrConsistencyProof::<Blake3_256>::from_bytes(&bytes[..bytes.len() - 32])
+    );
+    let bytes = mmr.prove_consistency(7, 13).unwrap().to_bytes();
+    let mut swapped = bytes[8..16].to_vec();
+    swapped.extend_from_slice(&bytes[..8]);
+    swapped.extend_from_slice(&bytes[16..]);
+    assert_eq!(
+        Err(MmrProofSerializationError::InvalidSizes(13, 7)),
+        MmrConsistencyProof::<Blake3_256>::from_bytes(&swapped)
+    );
+}
+
+proptest! {
+    #[test]
+    fn prove_verify_historical(
+        size in 1..200usize,
+        old_size in 0..200usize,
+        index in 0..200usize,
+    ) {
+        let leaves = build_leaves(size);
+        let mmr = build_mmr(&leaves);
+        let old_size = old_size % (size + 1);
+
+        let proof = mmr.prove_consistency(old_size, size).unwrap();
+        let old_peaks = mmr.peaks_at(old_size).unwrap();
+        prop_assert!(Mmr::verify_consistency(&old_peaks, &mmr.peaks(), &proof));
+
+        if old_size > 0 {
+            let index = index % old_size;
+            let proof = mmr.prove_at(index, old_size).unwrap();
+            prop_assert!(Mmr::verify_inclusion(&old_peaks, &leaves[index], &proof));
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_leaves(num_leaves: usize) -> Vec<Digest> {
+    (0..num_leaves)
+        .map(|i| Blake3_256::hash(&i.to_le_bytes()))
+        .collect()
+}
+
+fn build_mmr(leaves: &[Digest]) -> Mmr {
+    let mut mmr = Mmr::new();
+    for &leaf in leaves {
+        mmr.append(leaf);
+    }
+    mmr
+}
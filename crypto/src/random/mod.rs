@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::Hasher;
+use crate::{Digest, Hasher};
 use math::field::FieldElement;
 use std::{convert::TryInto, marker::PhantomData};
 
@@ -61,12 +61,18 @@ impl<H: Hasher> RandomElementGenerator for DefaultRandomElementGenerator<H> {
     }
 
     fn draw<E: FieldElement>(&mut self) -> E {
-        let hash_fn = H::hash_fn();
-        let mut result = [0u8; 32];
         loop {
             // updated the seed by incrementing its counter and then hash the result
             self.increment_counter();
-            hash_fn(&self.seed, &mut result);
+            let mut result = H::hash(&self.seed).as_bytes();
+
+            // if the digest is shorter than the element (e.g. for truncated hash functions), fill
+            // the remaining bytes with a hash of the digest rather than leave them as zeros
+            let digest_size = <H::Digest as Digest>::SIZE;
+            if E::ELEMENT_BYTES > digest_size {
+                let tail = H::hash(&result[..digest_size]).as_bytes();
+                result[digest_size..].copy_from_slice(&tail[..(32 - digest_size)]);
+            }
 
             // take the first ELEMENT_BYTES from the hashed seed and check if they can be converted
             // into a valid field element; if the can, return; otherwise try again
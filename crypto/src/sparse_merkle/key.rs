@@ -0,0 +1,100 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::{convert::TryInto, fmt};
+
+// LEAF KEY
+// ================================================================================================
+
+/// A 256-bit key of a leaf in a sparse Merkle tree; the key is also the index of the leaf.
+///
+/// Internally, the key is stored as four 64-bit limbs in little-endian order.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LeafKey([u64; 4]);
+
+impl LeafKey {
+    /// Returns a key constructed from 32 bytes interpreted as a little-endian integer.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        LeafKey(limbs)
+    }
+
+    /// Returns this key serialized into 32 bytes in little-endian byte order.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut result = [0u8; 32];
+        for (chunk, limb) in result.chunks_mut(8).zip(self.0.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        result
+    }
+
+    /// Returns the i-th least significant bit of this key.
+    pub fn bit(&self, i: usize) -> bool {
+        (self.0[i / 64] >> (i % 64)) & 1 == 1
+    }
+
+    /// Returns the number of bits needed to represent this key.
+    pub fn num_bits(&self) -> usize {
+        for (i, &limb) in self.0.iter().enumerate().rev() {
+            if limb != 0 {
+                return i * 64 + 64 - limb.leading_zeros() as usize;
+            }
+        }
+        0
+    }
+
+    /// Returns index of the parent of the node at this index (i.e. this key shifted right by 1).
+    pub(super) fn parent(&self) -> Self {
+        let mut limbs = self.0;
+        for i in 0..3 {
+            limbs[i] = (limbs[i] >> 1) | (limbs[i + 1] << 63);
+        }
+        limbs[3] >>= 1;
+        LeafKey(limbs)
+    }
+
+    /// Returns index of the left (`is_right` = false) or the right (`is_right` = true) child of
+    /// the node at this index.
+    pub(super) fn child(&self, is_right: bool) -> Self {
+        let mut limbs = self.0;
+        for i in (1..4).rev() {
+            limbs[i] = (limbs[i] << 1) | (limbs[i - 1] >> 63);
+        }
+        limbs[0] = (limbs[0] << 1) | is_right as u64;
+        LeafKey(limbs)
+    }
+
+    /// Returns index of the sibling of the node at this index.
+    pub(super) fn sibling(&self) -> Self {
+        let mut limbs = self.0;
+        limbs[0] ^= 1;
+        LeafKey(limbs)
+    }
+}
+
+impl From<u64> for LeafKey {
+    fn from(value: u64) -> Self {
+        LeafKey([value, 0, 0, 0])
+    }
+}
+
+impl From<u128> for LeafKey {
+    fn from(value: u128) -> Self {
+        LeafKey([value as u64, (value >> 64) as u64, 0, 0])
+    }
+}
+
+impl fmt::Display for LeafKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x")?;
+        for limb in self.0.iter().rev() {
+            write!(f, "{:016x}", limb)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::hash::Hasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+mod key;
+pub use key::LeafKey;
+
+mod proofs;
+pub use proofs::SparseMerkleProof;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum depth of a sparse Merkle tree; this is the number of bits in a leaf key.
+pub const MAX_DEPTH: usize = 256;
+
+// TYPES AND INTERFACES
+// ================================================================================================
+
+/// A key-value sparse Merkle tree of a fixed depth.
+///
+/// The tree has 2^depth leaves, and a value with key k is located at the leaf with index k; bit
+/// i of the key determines whether the node at height i on the path from the leaf to the root is
+/// a left (0) or a right (1) child. Leaves which have not been set contain the default digest, and
+/// thus, a proof of non-membership for a key is a Merkle path from the default digest at the
+/// leaf for this key to the root. For the same reason, setting a leaf to the default digest
+/// removes the corresponding key from the tree.
+///
+/// Only leaves and internal nodes which differ from the default node at the same height are
+/// stored; the default nodes are computed once when the tree is instantiated. A tree of depth d
+/// with n values set thus requires O(n * d) memory regardless of d.
+#[derive(Debug, Clone)]
+pub struct SparseMerkleTree<H: Hasher> {
+    depth: usize,
+    leaves: BTreeMap<LeafKey, H::Digest>,
+    nodes: HashMap<(usize, LeafKey), H::Digest>,
+    default_nodes: Vec<H::Digest>,
+}
+
+// SPARSE MERKLE TREE IMPLEMENTATION
+// ================================================================================================
+
+impl<H: Hasher> SparseMerkleTree<H> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new empty sparse Merkle tree of the specified depth. Panics if depth is zero or
+    /// greater than 256.
+    pub fn new(depth: usize) -> Self {
+        assert!(depth > 0, "tree depth must be greater than zero");
+        assert!(
+            depth <= MAX_DEPTH,
+            "tree depth cannot be greater than {}",
+            MAX_DEPTH
+        );
+
+        SparseMerkleTree {
+            depth,
+            leaves: BTreeMap::new(),
+            nodes: HashMap::new(),
+            default_nodes: build_default_nodes::<H>(depth),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the root of the tree.
+    pub fn root(&self) -> H::Digest {
+        self.get_node(self.depth, LeafKey::default())
+    }
+
+    /// Returns the number of non-default leaves in the tree.
+    pub fn num_leaves(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Returns the value stored under the specified key, or None if the key is not in the tree.
+    pub fn get(&self, key: &LeafKey) -> Option<H::Digest> {
+        self.leaves.get(key).copied()
+    }
+
+    /// Returns an iterator over all key-value pairs in the tree.
+    pub fn iter(&self) -> impl Iterator<Item = (&LeafKey, &H::Digest)> {
+        self.leaves.iter()
+    }
+
+    // UPDATE METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Sets the value of the leaf with the specified key, and returns the value previously stored
+    /// under this key. Setting a leaf to the default digest removes the key from the tree.
+    ///
+    /// Panics if the key does not fit into the tree (i.e. key >= 2^depth).
+    pub fn insert(&mut self, key: LeafKey, value: H::Digest) -> Option<H::Digest> {
+        let old_value = self.get(&key);
+        self.update_batch(&[(key, value)]);
+        old_value
+    }
+
+    /// Removes the specified key from the tree, and returns the value previously stored under
+    /// this key.
+    ///
+    /// Panics if the key does not fit into the tree (i.e. key >= 2^depth).
+    pub fn remove(&mut self, key: &LeafKey) -> Option<H::Digest> {
+        self.insert(*key, H::Digest::default())
+    }
+
+    /// Applies the specified updates to the tree and returns the new root of the tree. Updates
+    /// are applied in order, and thus, if a key appears multiple times, the last value wins.
+    ///
+    /// Internal nodes affected by several updates are re-computed only once; this makes a batch
+    /// update cheaper than applying updates one by one when keys share paths.
+    ///
+    /// Panics if any of the keys does not fit into the tree (i.e. key >= 2^depth); in this case,
+    /// none of the updates is applied.
+    pub fn update_batch(&mut self, updates: &[(LeafKey, H::Digest)]) -> H::Digest {
+        // check all keys before applying any of the updates so that an invalid key does not
+        // leave the tree partially updated
+        for (key, _) in updates.iter() {
+            assert!(
+                key.num_bits() <= self.depth,
+                "key {} does not fit into a tree of depth {}",
+                key,
+                self.depth
+            );
+        }
+
+        let mut dirty = BTreeSet::new();
+        for (key, value) in updates.iter() {
+            if *value == self.default_nodes[0] {
+                self.leaves.remove(key);
+            } else {
+                self.leaves.insert(*key, *value);
+            }
+            dirty.insert(*key);
+        }
+
+        // recompute internal nodes affected by the updates one level at a time; a node which
+        // becomes equal to the default node is removed from the tree
+        for height in 1..=self.depth {
+            let parents = dirty
+                .iter()
+                .map(|key| key.parent())
+                .collect::<BTreeSet<_>>();
+            for &index in parents.iter() {
+                let left = self.get_node(height - 1, index.child(false));
+                let right = self.get_node(height - 1, index.child(true));
+                let node = H::merge(&[left, right]);
+                if node == self.default_nodes[height] {
+                    self.nodes.remove(&(height, index));
+                } else {
+                    self.nodes.insert((height, index), node);
+                }
+            }
+            dirty = parents;
+        }
+
+        self.root()
+    }
+
+    // PROVING METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a Merkle path from the leaf with the specified key to the root of the tree.
+    ///
+    /// If the key is in the tree, the proof is a proof of membership for the value stored under
+    /// the key; otherwise, it is a proof of non-membership (i.e. a proof that the leaf contains
+    /// the default digest).
+    ///
+    /// Panics if the key does not fit into the tree (i.e. key >= 2^depth).
+    pub fn prove(&self, key: &LeafKey) -> SparseMerkleProof<H> {
+        assert!(
+            key.num_bits() <= self.depth,
+            "key {} does not fit into a tree of depth {}",
+            key,
+            self.depth
+        );
+
+        let mut siblings = Vec::new();
+        let mut default_mask = Vec::with_capacity(self.depth);
+        let mut index = *key;
+        for height in 0..self.depth {
+            match self.find_node(height, index.sibling()) {
+                Some(sibling) => {
+                    siblings.push(sibling);
+                    default_mask.push(false);
+                }
+                None => default_mask.push(true),
+            }
+            index = index.parent();
+        }
+
+        SparseMerkleProof {
+            siblings,
+            default_mask,
+        }
+    }
+
+    // VERIFICATION METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks whether the proof shows that the specified value is stored under the specified key
+    /// in the tree with the specified root.
+    pub fn verify_membership(
+        root: &H::Digest,
+        key: &LeafKey,
+        value: &H::Digest,
+        proof: &SparseMerkleProof<H>,
+    ) -> bool {
+        match proof.get_root(key, value) {
+            Some(proof_root) => *root == proof_root,
+            None => false,
+        }
+    }
+
+    /// Checks whether the proof shows that the specified key is not in the tree with the
+    /// specified root.
+    pub fn verify_non_membership(
+        root: &H::Digest,
+        key: &LeafKey,
+        proof: &SparseMerkleProof<H>,
+    ) -> bool {
+        Self::verify_membership(root, key, &H::Digest::default(), proof)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the node at the specified height and index, or None if the node is a default node.
+    fn find_node(&self, height: usize, index: LeafKey) -> Option<H::Digest> {
+        if height == 0 {
+            self.leaves.get(&index).copied()
+        } else {
+            self.nodes.get(&(height, index)).copied()
+        }
+    }
+
+    /// Returns the node at the specified height and index.
+    fn get_node(&self, height: usize, index: LeafKey) -> H::Digest {
+        self.find_node(height, index)
+            .unwrap_or(self.default_nodes[height])
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns default nodes for all heights of a tree of the specified depth: default node at height
+/// 0 is the default digest, and default node at height i is a hash of two default nodes at height
+/// i - 1.
+pub(crate) fn build_default_nodes<H: Hasher>(depth: usize) -> Vec<H::Digest> {
+    let mut result = Vec::with_capacity(depth + 1);
+    result.push(H::Digest::default());
+    for i in 0..depth {
+        result.push(H::merge(&[result[i], result[i]]));
+    }
+    result
+}
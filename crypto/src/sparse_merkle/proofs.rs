@@ -0,0 +1,77 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{build_default_nodes, LeafKey};
+use crate::Hasher;
+
+/// A Merkle path from a leaf of a sparse Merkle tree to the root of the tree.
+///
+/// Siblings which are default nodes are not included into the proof; instead, the positions of
+/// such siblings are recorded in `default_mask`. For sparsely populated trees most siblings on
+/// any path are default nodes, and thus, the proof is usually much smaller than the depth of the
+/// tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseMerkleProof<H: Hasher> {
+    /// Siblings of the nodes on the path which are not default nodes, starting at the leaf level.
+    pub siblings: Vec<H::Digest>,
+    /// For every level of the tree starting at the leaf level, specifies whether the sibling of
+    /// the node on the path is a default node.
+    pub default_mask: Vec<bool>,
+}
+
+impl<H: Hasher> SparseMerkleProof<H> {
+    /// Returns depth of the tree for which this proof was built.
+    pub fn depth(&self) -> usize {
+        self.default_mask.len()
+    }
+
+    /// Returns all siblings of the nodes on the path starting at the leaf level; omitted default
+    /// nodes are filled in. Returns None if the proof is malformed.
+    pub fn get_siblings(&self) -> Option<Vec<H::Digest>> {
+        let num_defaults = self
+            .default_mask
+            .iter()
+            .filter(|&&is_default| is_default)
+            .count();
+        if self.siblings.len() + num_defaults != self.depth() {
+            return None;
+        }
+
+        let default_nodes = build_default_nodes::<H>(self.depth());
+        let mut siblings = self.siblings.iter();
+        let result = self
+            .default_mask
+            .iter()
+            .enumerate()
+            .map(|(height, &is_default)| {
+                if is_default {
+                    default_nodes[height]
+                } else {
+                    *siblings.next().unwrap()
+                }
+            })
+            .collect();
+        Some(result)
+    }
+
+    /// Computes the root to which this path resolves when the leaf with the specified key holds
+    /// the specified value. Returns None if the proof is malformed or if the key does not fit
+    /// into a tree of the proof's depth.
+    pub fn get_root(&self, key: &LeafKey, value: &H::Digest) -> Option<H::Digest> {
+        if key.num_bits() > self.depth() {
+            return None;
+        }
+
+        let mut node = *value;
+        for (height, sibling) in self.get_siblings()?.into_iter().enumerate() {
+            node = if key.bit(height) {
+                H::merge(&[sibling, node])
+            } else {
+                H::merge(&[node, sibling])
+            };
+        }
+        Some(node)
+    }
+}
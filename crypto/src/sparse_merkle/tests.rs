@@ -0,0 +1,204 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use proptest::prelude::*;
+
+use super::*;
+use crate::{hash::Blake3_256, MerkleTree};
+
+type Digest = <Blake3_256 as Hasher>::Digest;
+
+#[test]
+fn empty_tree() {
+    let tree = SparseMerkleTree::<Blake3_256>::new(4);
+    let dense_tree = MerkleTree::<Blake3_256>::new(vec![Digest::default(); 16]);
+    assert_eq!(*dense_tree.root(), tree.root());
+    assert_eq!(0, tree.num_leaves());
+
+    let tree = SparseMerkleTree::<Blake3_256>::new(256);
+    let default_nodes = build_default_nodes::<Blake3_256>(256);
+    assert_eq!(default_nodes[256], tree.root());
+}
+
+#[test]
+fn insert_get_remove() {
+    let mut tree = SparseMerkleTree::<Blake3_256>::new(4);
+    let mut leaves = vec![Digest::default(); 16];
+
+    assert_eq!(None, tree.insert(LeafKey::from(3u64), [1; 32]));
+    assert_eq!(None, tree.insert(LeafKey::from(12u64), [2; 32]));
+    assert_eq!(Some([1; 32]), tree.insert(LeafKey::from(3u64), [3; 32]));
+    leaves[3] = [3; 32];
+    leaves[12] = [2; 32];
+    assert_eq!(2, tree.num_leaves());
+    assert_eq!(Some([3; 32]), tree.get(&LeafKey::from(3u64)));
+    assert_eq!(None, tree.get(&LeafKey::from(4u64)));
+    assert_eq!(
+        *MerkleTree::<Blake3_256>::new(leaves.clone()).root(),
+        tree.root()
+    );
+
+    // removing a key resets the leaf to the default digest
+    assert_eq!(Some([2; 32]), tree.remove(&LeafKey::from(12u64)));
+    leaves[12] = Digest::default();
+    assert_eq!(1, tree.num_leaves());
+    assert_eq!(*MerkleTree::<Blake3_256>::new(leaves).root(), tree.root());
+
+    // once all keys are removed, only default nodes remain
+    tree.remove(&LeafKey::from(3u64));
+    assert_eq!(SparseMerkleTree::<Blake3_256>::new(4).root(), tree.root());
+    assert!(tree.nodes.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "does not fit into a tree of depth 4")]
+fn insert_invalid_key() {
+    let mut tree = SparseMerkleTree::<Blake3_256>::new(4);
+    tree.insert(LeafKey::from(16u64), [1; 32]);
+}
+
+#[test]
+fn update_batch_invalid_key() {
+    let mut tree = SparseMerkleTree::<Blake3_256>::new(4);
+    tree.insert(LeafKey::from(3u64), [1; 32]);
+    let root = tree.root();
+
+    // the valid update preceding the invalid key must not be applied
+    let updates = [
+        (LeafKey::from(5u64), [2; 32]),
+        (LeafKey::from(16u64), [3; 32]),
+    ];
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        tree.update_batch(&updates);
+    }));
+    assert!(result.is_err());
+    assert_eq!(root, tree.root());
+    assert_eq!(None, tree.get(&LeafKey::from(5u64)));
+}
+
+#[test]
+fn update_batch() {
+    let updates = vec![
+        (LeafKey::from(u64::MAX), [1; 32]),
+        (LeafKey::from(0u64), [2; 32]),
+        (LeafKey::from(u64::MAX - 1), [3; 32]),
+        (LeafKey::from(0u64), [4; 32]),
+    ];
+
+    let mut tree1 = SparseMerkleTree::<Blake3_256>::new(64);
+    let root = tree1.update_batch(&updates);
+    assert_eq!(root, tree1.root());
+    assert_eq!(Some([4; 32]), tree1.get(&LeafKey::from(0u64)));
+
+    let mut tree2 = SparseMerkleTree::<Blake3_256>::new(64);
+    for &(key, value) in updates.iter() {
+        tree2.insert(key, value);
+    }
+    assert_eq!(tree2.root(), tree1.root());
+    assert_eq!(tree2.nodes, tree1.nodes);
+}
+
+#[test]
+fn prove_membership() {
+    let mut tree = SparseMerkleTree::<Blake3_256>::new(256);
+    let key1 = LeafKey::from_bytes(&[255; 32]);
+    let key2 = LeafKey::from_bytes(&[7; 32]);
+    tree.insert(key1, [1; 32]);
+    tree.insert(key2, [2; 32]);
+    let root = tree.root();
+
+    let proof = tree.prove(&key1);
+    assert_eq!(256, proof.depth());
+    // paths of the two keys diverge right below the root, and thus the only non-default
+    // sibling on the path is the node at the top
+    assert_eq!(1, proof.siblings.len());
+    assert!(SparseMerkleTree::verify_membership(
+        &root, &key1, &[1; 32], &proof
+    ));
+    assert!(!SparseMerkleTree::verify_membership(
+        &root, &key1, &[2; 32], &proof
+    ));
+    assert!(!SparseMerkleTree::verify_membership(
+        &root, &key2, &[1; 32], &proof
+    ));
+    assert!(!SparseMerkleTree::verify_non_membership(
+        &root, &key1, &proof
+    ));
+}
+
+#[test]
+fn prove_non_membership() {
+    let mut tree = SparseMerkleTree::<Blake3_256>::new(64);
+    tree.insert(LeafKey::from(5u64), [1; 32]);
+    tree.insert(LeafKey::from(1u64 << 40), [2; 32]);
+    let root = tree.root();
+
+    let key = LeafKey::from(4u64);
+    let proof = tree.prove(&key);
+    assert_eq!(2, proof.siblings.len());
+    assert!(SparseMerkleTree::verify_non_membership(&root, &key, &proof));
+    assert!(!SparseMerkleTree::verify_membership(
+        &root, &key, &[1; 32], &proof
+    ));
+
+    // a proof of non-membership becomes invalid once the key is inserted
+    tree.insert(key, [3; 32]);
+    assert!(!SparseMerkleTree::verify_non_membership(
+        &tree.root(),
+        &key,
+        &proof
+    ));
+
+    // malformed proofs are rejected
+    let mut proof = tree.prove(&key);
+    proof.siblings.pop();
+    assert!(!SparseMerkleTree::verify_membership(
+        &tree.root(),
+        &key,
+        &[3; 32],
+        &proof
+    ));
+}
+
+#[test]
+fn leaf_key() {
+    let key = LeafKey::from(u128::MAX);
+    assert_eq!(128, key.num_bits());
+    assert_eq!(127, key.parent().num_bits());
+    assert_eq!(key, key.parent().child(true));
+    assert_eq!(key.sibling(), key.parent().child(false));
+
+    let key = LeafKey::from_bytes(&[255; 32]);
+    assert_eq!(256, key.num_bits());
+    assert!(key.bit(255));
+    assert_eq!([255; 32], key.to_bytes());
+    assert_eq!(0, LeafKey::default().num_bits());
+}
+
+proptest! {
+    #[test]
+    fn update_batch_matches_dense_tree(
+        updates in prop::collection::vec((0..1024u64, any::<[u8; 32]>()), 1..64)
+    ) {
+        let mut leaves = vec![Digest::default(); 1024];
+        let mut tree = SparseMerkleTree::<Blake3_256>::new(10);
+        let updates = updates
+            .into_iter()
+            .map(|(key, value)| {
+                leaves[key as usize] = value;
+                (LeafKey::from(key), value)
+            })
+            .collect::<Vec<_>>();
+        let root = tree.update_batch(&updates);
+        let dense_tree = MerkleTree::<Blake3_256>::new(leaves);
+        prop_assert_eq!(*dense_tree.root(), root);
+
+        for (key, value) in updates {
+            let value = tree.get(&key).unwrap_or(value);
+            let proof = tree.prove(&key);
+            prop_assert!(SparseMerkleTree::verify_membership(&root, &key, &value, &proof));
+        }
+    }
+}
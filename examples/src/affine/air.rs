@@ -0,0 +1,116 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::utils::are_equal;
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    Air, Assertion, ComputationContext, EvaluationFrame, ExecutionTrace, ProofOptions, TraceInfo,
+    TransitionConstraintDegree,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+const TRACE_WIDTH: usize = 1;
+const NUM_PREPROCESSED_COLUMNS: usize = 2;
+
+// indexes of preprocessed columns in the evaluation frame
+const MUL_COEFF: usize = TRACE_WIDTH;
+const ADD_COEFF: usize = TRACE_WIDTH + 1;
+
+// AFFINE PROGRAM AIR
+// ================================================================================================
+
+#[derive(Clone, Copy)]
+pub struct PublicInputs {
+    pub seed: BaseElement,
+    pub result: BaseElement,
+}
+
+pub struct AffineAir {
+    context: ComputationContext,
+    seed: BaseElement,
+    result: BaseElement,
+}
+
+impl Air for AffineAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        // the constraint multiplies a trace register by a preprocessed column
+        let degrees = vec![TransitionConstraintDegree::new(2)];
+        let context = ComputationContext::new(TRACE_WIDTH, trace_info.length, degrees, options)
+            .with_preprocessed_columns(NUM_PREPROCESSED_COLUMNS);
+        AffineAir {
+            context,
+            seed: pub_inputs.seed,
+            result: pub_inputs.result,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        // expected frame width is 1 trace register followed by 2 preprocessed columns
+        debug_assert_eq!(TRACE_WIDTH + NUM_PREPROCESSED_COLUMNS, current.len());
+        debug_assert_eq!(TRACE_WIDTH + NUM_PREPROCESSED_COLUMNS, next.len());
+
+        // s_{i+1} = s_i * a_i + b_i, where a_i and b_i are defined by the program
+        result[0] = are_equal(
+            next[0],
+            current[0] * current[MUL_COEFF] + current[ADD_COEFF],
+        );
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, self.seed),
+            Assertion::single(0, last_step, self.result),
+        ]
+    }
+
+    fn get_preprocessed_columns(&self) -> Vec<Vec<Self::BaseElement>> {
+        let (mul_coeffs, add_coeffs) = build_program(self.trace_length());
+        vec![mul_coeffs, add_coeffs]
+    }
+}
+
+// PROGRAM AND TRACE BUILDERS
+// ================================================================================================
+
+/// Returns coefficients (a_i, b_i) of affine transformations s -> s * a_i + b_i applied at each
+/// step of a program of the specified length; the coefficients are fixed for a given length.
+pub fn build_program(length: usize) -> (Vec<BaseElement>, Vec<BaseElement>) {
+    let mul_coeffs = BaseElement::prng_vector([3; 32], length);
+    let add_coeffs = BaseElement::prng_vector([4; 32], length);
+    (mul_coeffs, add_coeffs)
+}
+
+pub fn build_trace(seed: BaseElement, length: usize) -> ExecutionTrace<BaseElement> {
+    let (mul_coeffs, add_coeffs) = build_program(length);
+    let mut trace = ExecutionTrace::new(TRACE_WIDTH, length);
+    trace.fill(
+        |state| {
+            state[0] = seed;
+        },
+        |step, state| {
+            state[0] = state[0] * mul_coeffs[step] + add_coeffs[step];
+        },
+    );
+    trace
+}
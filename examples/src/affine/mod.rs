@@ -0,0 +1,129 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Example, ExampleOptions};
+use log::debug;
+use prover::{
+    self,
+    math::{
+        field::{f128::BaseElement, FieldElement},
+        utils::log2,
+    },
+    ProofOptions, Prover, StarkProof,
+};
+use std::time::Instant;
+use verifier::{self, VerifierError};
+
+mod air;
+use air::{build_program, build_trace, AffineAir, PublicInputs};
+
+#[cfg(test)]
+mod tests;
+
+// AFFINE PROGRAM EXAMPLE
+// ================================================================================================
+
+pub fn get_example(options: ExampleOptions, num_steps: usize) -> Box<dyn Example> {
+    Box::new(AffineExample::new(
+        num_steps,
+        options.to_proof_options(28, 8),
+    ))
+}
+
+pub struct AffineExample {
+    num_steps: usize,
+    pub_inputs: PublicInputs,
+    prover: Prover<AffineAir>,
+}
+
+impl AffineExample {
+    pub fn new(num_steps: usize, options: ProofOptions) -> AffineExample {
+        assert!(
+            num_steps.is_power_of_two(),
+            "number of steps must be a power of 2"
+        );
+
+        // execute the program
+        let now = Instant::now();
+        let seed = BaseElement::new(42);
+        let result = execute(seed, num_steps);
+        debug!(
+            "Executed affine program of {} steps in {} ms",
+            num_steps,
+            now.elapsed().as_millis()
+        );
+        let pub_inputs = PublicInputs { seed, result };
+
+        // build the prover once; this extends and commits to the program, and the resulting
+        // proving key is reused for all proofs
+        let now = Instant::now();
+        let prover = Prover::<AffineAir>::new(num_steps, pub_inputs, options).unwrap();
+        debug!(
+            "Initialized prover for the program in {} ms",
+            now.elapsed().as_millis()
+        );
+
+        AffineExample {
+            num_steps,
+            pub_inputs,
+            prover,
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl Example for AffineExample {
+    fn prove(&self) -> StarkProof {
+        debug!(
+            "Generating proof for executing an affine program of {} steps\n\
+            ---------------------",
+            self.num_steps
+        );
+
+        // generate execution trace
+        let now = Instant::now();
+        let trace = build_trace(self.pub_inputs.seed, self.num_steps);
+
+        let trace_width = trace.width();
+        let trace_length = trace.len();
+        debug!(
+            "Generated execution trace of {} registers and 2^{} steps in {} ms",
+            trace_width,
+            log2(trace_length),
+            now.elapsed().as_millis()
+        );
+
+        // generate the proof
+        self.prover.prove(trace, self.pub_inputs).unwrap()
+    }
+
+    fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let verifying_key = self.prover.verifying_key().unwrap();
+        verifier::verify_with_key::<AffineAir>(proof, self.pub_inputs, verifying_key)
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            seed: self.pub_inputs.seed,
+            result: self.pub_inputs.result + BaseElement::ONE,
+        };
+        let verifying_key = self.prover.verifying_key().unwrap();
+        verifier::verify_with_key::<AffineAir>(proof, pub_inputs, verifying_key)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn execute(seed: BaseElement, num_steps: usize) -> BaseElement {
+    let (mul_coeffs, add_coeffs) = build_program(num_steps);
+    let mut result = seed;
+    for step in 0..(num_steps - 1) {
+        result = result * mul_coeffs[step] + add_coeffs[step];
+    }
+    result
+}
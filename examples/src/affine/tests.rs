@@ -0,0 +1,261 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{air::build_trace, AffineAir, AffineExample};
+use crate::Example;
+use prover::{
+    crypto::hash::{Blake3_256, Sha3_256},
+    math::field::{f128::BaseElement, QuadExtension},
+    FieldExtension, HashFunction, ProofOptions, ProverError,
+};
+use verifier::VerifierError;
+
+#[test]
+fn affine_test_basic_proof_verification() {
+    let affine = Box::new(AffineExample::new(64, build_options(false)));
+    crate::tests::test_basic_proof_verification(affine);
+}
+
+#[test]
+fn affine_test_basic_proof_verification_extension() {
+    let affine = Box::new(AffineExample::new(64, build_options(true)));
+    crate::tests::test_basic_proof_verification(affine);
+}
+
+#[test]
+fn affine_test_basic_proof_verification_fail() {
+    let affine = Box::new(AffineExample::new(64, build_options(false)));
+    crate::tests::test_basic_proof_verification_fail(affine);
+}
+
+#[test]
+fn affine_test_verifying_key() {
+    let affine = AffineExample::new(64, build_options(false));
+
+    // a proof generated without a proving key commits to the same preprocessed columns
+    let trace = build_trace(affine.pub_inputs.seed, 64);
+    let proof = prover::prove::<AffineAir>(trace, affine.pub_inputs, build_options(false)).unwrap();
+    assert!(affine.verify(proof.clone()).is_ok());
+
+    // the proof cannot be verified without a verifying key
+    let result = verifier::verify::<AffineAir>(proof.clone(), affine.pub_inputs);
+    assert!(matches!(result, Err(VerifierError::MissingVerifyingKey)));
+
+    // the proof cannot be verified with a key generated for a different trace length
+    let other = AffineExample::new(32, build_options(false));
+    let verifying_key = other.prover.verifying_key().unwrap();
+    let result = verifier::verify_with_key::<AffineAir>(proof, affine.pub_inputs, verifying_key);
+    assert!(matches!(
+        result,
+        Err(VerifierError::IncompatibleVerifyingKey)
+    ));
+}
+
+#[test]
+fn affine_test_prover_reuse() {
+    let affine = AffineExample::new(64, build_options(false));
+
+    // the prover can be used to generate several proofs
+    for _ in 0..2 {
+        let trace = build_trace(affine.pub_inputs.seed, 64);
+        let proof = affine.prover.prove(trace, affine.pub_inputs).unwrap();
+        assert!(affine.verify(proof).is_ok());
+    }
+
+    // a proof generated with a separately set up proving key is verified with the same key
+    let key = prover::setup::<AffineAir>(64, affine.pub_inputs, build_options(false)).unwrap();
+    let trace = build_trace(affine.pub_inputs.seed, 64);
+    let proof =
+        prover::prove_with_key::<AffineAir>(trace, affine.pub_inputs, build_options(false), &key)
+            .unwrap();
+    assert!(affine.verify(proof).is_ok());
+
+    // the prover rejects traces of a different length
+    let trace = build_trace(affine.pub_inputs.seed, 32);
+    let result = affine.prover.prove(trace, affine.pub_inputs);
+    assert!(matches!(
+        result,
+        Err(ProverError::InconsistentTraceLength(64, 32))
+    ));
+}
+
+#[test]
+fn affine_test_staged_proof() {
+    let affine = AffineExample::new(64, build_options(false));
+
+    // run the proving pipeline stage by stage, and inspect intermediate results
+    let trace = build_trace(affine.pub_inputs.seed, 64);
+    let trace_committed = affine
+        .prover
+        .commit_trace::<BaseElement, Blake3_256>(trace, affine.pub_inputs)
+        .unwrap();
+    assert_eq!(3, trace_committed.extended_trace().width());
+    assert_eq!(64 * 8, trace_committed.extended_trace().len());
+
+    let constraints_evaluated = trace_committed.evaluate_constraints();
+    assert_eq!(
+        128,
+        constraints_evaluated.constraint_evaluations().num_rows()
+    );
+
+    let constraints_committed = constraints_evaluated.commit_constraints().unwrap();
+    assert_eq!(64, constraints_committed.constraint_poly().degree());
+
+    let composition_built = constraints_committed.build_composition();
+    assert_eq!(3, composition_built.ood_frame().current.len());
+
+    let fri_committed = composition_built.run_fri();
+    assert_eq!(28, fri_committed.query_positions().len());
+
+    let proof = fri_committed.build_proof().unwrap();
+    assert!(affine.verify(proof).is_ok());
+
+    // the pipeline can also start from a trace extended separately
+    let trace = build_trace(affine.pub_inputs.seed, 64);
+    let (extended_trace, trace_polys) = affine.prover.extend_trace(trace).unwrap();
+    let proof = affine
+        .prover
+        .commit_extended_trace::<BaseElement, Blake3_256>(
+            extended_trace,
+            trace_polys,
+            affine.pub_inputs,
+        )
+        .unwrap()
+        .evaluate_constraints()
+        .commit_constraints()
+        .unwrap()
+        .build_composition()
+        .run_fri()
+        .build_proof()
+        .unwrap();
+    assert!(affine.verify(proof).is_ok());
+
+    // the trace commitment can also be provided by the caller; the execution trace register and
+    // the 2 preprocessed columns are committed to separately
+    let trace = build_trace(affine.pub_inputs.seed, 64);
+    let (extended_trace, trace_polys) = affine.prover.extend_trace(trace).unwrap();
+    let trace_trees = extended_trace.build_commitments::<Blake3_256>(&[1, 2]);
+    let proof = affine
+        .prover
+        .commit_extended_trace_with_trees::<BaseElement, Blake3_256>(
+            extended_trace,
+            trace_polys,
+            trace_trees,
+            affine.pub_inputs,
+        )
+        .unwrap()
+        .evaluate_constraints()
+        .commit_constraints()
+        .unwrap()
+        .build_composition()
+        .run_fri()
+        .build_proof()
+        .unwrap();
+    assert!(affine.verify(proof).is_ok());
+}
+
+#[test]
+fn affine_test_staged_proof_errors() {
+    let affine = AffineExample::new(64, build_options(false));
+
+    // the field and the hash function must be the ones specified in the proof options
+    let trace = build_trace(affine.pub_inputs.seed, 64);
+    let result = affine
+        .prover
+        .commit_trace::<QuadExtension<BaseElement>, Blake3_256>(trace, affine.pub_inputs);
+    assert!(matches!(
+        result,
+        Err(ProverError::InconsistentFieldExtension)
+    ));
+
+    let trace = build_trace(affine.pub_inputs.seed, 64);
+    let result = affine
+        .prover
+        .commit_trace::<BaseElement, Sha3_256>(trace, affine.pub_inputs);
+    assert!(matches!(result, Err(ProverError::InconsistentHashFunction)));
+
+    // the extended trace must be consistent with the trace polynomials
+    let other = AffineExample::new(32, build_options(false));
+    let trace = build_trace(other.pub_inputs.seed, 32);
+    let (extended_trace, _) = other.prover.extend_trace(trace).unwrap();
+    let trace = build_trace(affine.pub_inputs.seed, 64);
+    let (_, trace_polys) = affine.prover.extend_trace(trace).unwrap();
+    let result = affine
+        .prover
+        .commit_extended_trace::<BaseElement, Blake3_256>(
+            extended_trace,
+            trace_polys,
+            affine.pub_inputs,
+        );
+    assert!(matches!(
+        result,
+        Err(ProverError::InconsistentExtendedTraceLength(512, 256))
+    ));
+
+    // the provided trees must commit to the segments of the extended trace
+    let trace = build_trace(affine.pub_inputs.seed, 64);
+    let (extended_trace, trace_polys) = affine.prover.extend_trace(trace).unwrap();
+    let trace_trees = extended_trace.build_commitments::<Blake3_256>(&[2, 1]);
+    let result = affine
+        .prover
+        .commit_extended_trace_with_trees::<BaseElement, Blake3_256>(
+            extended_trace,
+            trace_polys,
+            trace_trees,
+            affine.pub_inputs,
+        );
+    assert!(matches!(
+        result,
+        Err(ProverError::InconsistentTraceCommitment)
+    ));
+}
+
+#[test]
+fn affine_test_proving_report() {
+    let affine = AffineExample::new(64, build_options(false));
+
+    let trace = build_trace(affine.pub_inputs.seed, 64);
+    let (proof, report) = affine
+        .prover
+        .prove_with_report(trace, affine.pub_inputs)
+        .unwrap();
+
+    // the extended trace contains 1 execution trace and 2 preprocessed columns of 16-byte
+    // elements over a domain of 64 * 8 elements
+    assert_eq!(3 * 512 * 16, report.buffer_sizes.extended_trace);
+    assert_eq!(512 * 16, report.buffer_sizes.composition_evaluations);
+    assert!(report.buffer_sizes.peak() >= report.buffer_sizes.extended_trace);
+    // the last FRI layer is included in the proof as the remainder
+    assert_eq!(proof.fri_proof.layers.len() + 1, report.num_fri_layers);
+    assert!(report.timings.total() >= report.timings.trace_extension);
+
+    // the proof sections make up the bulk of the serialized proof
+    let proof_bytes = bincode::serialize(&proof).unwrap();
+    assert!(report.proof_sizes.total() > 0);
+    assert!(report.proof_sizes.total() < proof_bytes.len());
+    assert_eq!(
+        proof.ood_frame.trace_at_z1.len() * 2,
+        report.proof_sizes.ood_frame
+    );
+
+    assert!(affine.verify(proof).is_ok());
+
+    // a report can also be generated without instantiating a prover
+    let trace = build_trace(affine.pub_inputs.seed, 64);
+    let (proof, report) =
+        prover::prove_with_report::<AffineAir>(trace, affine.pub_inputs, build_options(false))
+            .unwrap();
+    assert!(report.timings.total() >= report.timings.grinding + report.timings.query_drawing);
+    assert!(affine.verify(proof).is_ok());
+}
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, HashFunction::Blake3_256, extension)
+}
@@ -18,7 +18,7 @@ use std::time::Instant;
 use verifier::{self, VerifierError};
 
 mod air;
-use air::{build_trace, FibAir};
+pub use air::{build_trace, FibAir};
 
 #[cfg(test)]
 mod tests;
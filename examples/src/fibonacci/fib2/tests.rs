@@ -3,7 +3,14 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::super::utils::build_proof_options;
+use super::{super::utils::build_proof_options, build_trace, FibAir};
+use crate::Example;
+use prover::{
+    crypto::{hash::Blake3_256, MerkleTree},
+    math::field::{f128::BaseElement, FieldElement},
+    Commitments, EvaluationFrame, ExecutionTrace, TransitionConstraintDegree,
+};
+use verifier::{LinkConstraints, ProductAir, ProductInputs, VerifierError};
 
 #[test]
 fn fib2_test_basic_proof_verification() {
@@ -22,3 +29,97 @@ fn fib2_test_basic_proof_verification_fail() {
     let fib = Box::new(super::FibExample::new(16, build_proof_options(false)));
     crate::tests::test_basic_proof_verification_fail(fib);
 }
+
+#[test]
+fn fib2_test_verify_with_invalid_cap() {
+    let options = build_proof_options(false).with_merkle_cap_height(2);
+    let fib = super::FibExample::new(16, options);
+    let mut proof = fib.prove();
+
+    // a root of the trace tree is a valid cap, but not of the height set in the proof options
+    let mut commitments = Commitments::<[u8; 32]>::deserialize(&proof.commitments).unwrap();
+    let trace_root = MerkleTree::<Blake3_256>::get_root_from_cap(&commitments.trace_caps[0]);
+    commitments.trace_caps[0] = vec![trace_root.unwrap()];
+    proof.commitments = commitments.serialize();
+    assert!(matches!(
+        fib.verify(proof),
+        Err(VerifierError::CommitmentDeserializationFailed)
+    ));
+}
+
+#[test]
+fn fib2_test_verify_many() {
+    let options = build_proof_options(false);
+    let mut proofs = Vec::new();
+    for &sequence_length in [16, 32, 16, 64].iter() {
+        let fib = super::FibExample::new(sequence_length, options.clone());
+        proofs.push((fib.prove(), fib.result));
+    }
+
+    // a proof verified against wrong public inputs is rejected without affecting other proofs
+    proofs[2].1 += BaseElement::ONE;
+    let results = verifier::verify_many::<FibAir>(proofs);
+    assert_eq!(4, results.len());
+    assert!(results[0].is_ok());
+    assert!(results[1].is_ok());
+    assert!(results[2].is_err());
+    assert!(results[3].is_ok());
+}
+
+#[test]
+fn fib2_test_linked_product() {
+    // both computations run over the same sequence, and thus, the first register of the second
+    // computation at the next step is the sum of registers of the first computation
+    let trace = build_trace(16);
+    let result = trace.get(1, trace.len() - 1);
+    let registers = (0..4).map(|i| trace.get_register(i % 2).to_vec()).collect();
+    let trace = ExecutionTrace::init(registers);
+    let pub_inputs = ProductInputs {
+        first: result,
+        second: result,
+        links: BaseElement::ZERO,
+    };
+    let proof =
+        prover::prove::<LinkedFibAir>(trace, pub_inputs.clone(), build_proof_options(false))
+            .unwrap();
+    assert!(verifier::verify::<LinkedFibAir>(proof.clone(), pub_inputs.clone()).is_ok());
+
+    // the proof is rejected when the linked values are claimed to disagree
+    let pub_inputs = ProductInputs {
+        links: BaseElement::ONE,
+        ..pub_inputs
+    };
+    assert!(verifier::verify::<LinkedFibAir>(proof, pub_inputs).is_err());
+}
+
+// HELPER TYPES
+// ================================================================================================
+
+type LinkedFibAir = ProductAir<FibAir, FibAir, SumLink>;
+
+/// Requires the first register of the second computation at the next step to be equal to the
+/// sum of registers of the first computation at the current step plus a public offset.
+struct SumLink {
+    offset: BaseElement,
+}
+
+impl LinkConstraints<FibAir, FibAir> for SumLink {
+    type PublicInputs = BaseElement;
+
+    fn new(_first: &FibAir, _second: &FibAir, offset: BaseElement) -> Self {
+        SumLink { offset }
+    }
+
+    fn get_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        vec![TransitionConstraintDegree::new(1)]
+    }
+
+    fn evaluate<E: FieldElement + From<BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        result[0] = frame.next[2] - frame.current[0] - frame.current[1] - E::from(self.offset);
+    }
+}
@@ -7,5 +7,6 @@ pub mod fib2;
 pub mod fib8;
 pub mod mulfib2;
 pub mod mulfib8;
+pub mod segments;
 
 mod utils;
@@ -0,0 +1,80 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::utils::are_equal;
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    Air, Assertion, ComputationContext, EvaluationFrame, ProofOptions, SegmentBoundary, TraceInfo,
+    TransitionConstraintDegree,
+};
+
+// FIBONACCI SEGMENT AIR
+// ================================================================================================
+
+pub const TRACE_WIDTH: usize = 2;
+
+/// AIR for a single segment of a Fibonacci sequence (2 terms per step); instead of starting with
+/// two ones and terminating with the expected result, the segment starts and ends in the states
+/// specified by its boundary values.
+pub struct FibSegmentAir {
+    context: ComputationContext,
+    boundary: SegmentBoundary<BaseElement>,
+}
+
+impl Air for FibSegmentAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = SegmentBoundary<BaseElement>;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: Self::PublicInputs, options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::new(1),
+        ];
+        let context = ComputationContext::new(TRACE_WIDTH, trace_info.length, degrees, options);
+        FibSegmentAir {
+            context,
+            boundary: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        // the first and the last states of the segment must match its boundary values
+        self.boundary.get_assertions(self.trace_length())
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        // expected state width is 2 field elements
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        // constraints of Fibonacci sequence (2 terms per step):
+        // s_{0, i+1} = s_{0, i} + s_{1, i}
+        // s_{1, i+1} = s_{1, i} + s_{0, i+1}
+        result[0] = are_equal(next[0], current[0] + current[1]);
+        result[1] = are_equal(next[1], current[1] + next[0]);
+    }
+}
+
+// FIBONACCI STATE TRANSITION
+// ================================================================================================
+
+/// Advances the Fibonacci sequence by two terms.
+pub fn update_state(_step: usize, state: &mut [BaseElement]) {
+    state[0] += state[1];
+    state[1] += state[0];
+}
@@ -0,0 +1,93 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use log::debug;
+use prover::{
+    self,
+    math::field::{f128::BaseElement, FieldElement},
+    ProofOptions, SegmentBoundary, StarkProof,
+};
+use std::time::Instant;
+use verifier::{self, VerifierError};
+
+mod air;
+use air::{update_state, FibSegmentAir, TRACE_WIDTH};
+
+#[cfg(test)]
+mod tests;
+
+// FIBONACCI SEGMENTS EXAMPLE
+// ================================================================================================
+
+/// Computes a Fibonacci sequence which is too long to fit into a single execution trace by
+/// splitting it into segments of equal length, and proves each segment separately.
+pub struct FibSegmentsExample {
+    options: ProofOptions,
+    num_segments: usize,
+    segment_length: usize,
+    initial_state: Vec<BaseElement>,
+    final_state: Vec<BaseElement>,
+}
+
+impl FibSegmentsExample {
+    pub fn new(num_segments: usize, segment_length: usize, options: ProofOptions) -> Self {
+        assert!(
+            segment_length.is_power_of_two(),
+            "segment length must be a power of 2"
+        );
+
+        // compute the final state of the sequence; the last state of each segment is repeated
+        // as the first state of the next segment
+        let now = Instant::now();
+        let initial_state = vec![BaseElement::ONE; TRACE_WIDTH];
+        let mut final_state = initial_state.clone();
+        let num_steps = num_segments * (segment_length - 1);
+        for step in 0..num_steps {
+            update_state(step, &mut final_state);
+        }
+        debug!(
+            "Computed Fibonacci sequence up to {}th term in {} ms",
+            num_steps * 2 + 2,
+            now.elapsed().as_millis()
+        );
+
+        FibSegmentsExample {
+            options,
+            num_segments,
+            segment_length,
+            initial_state,
+            final_state,
+        }
+    }
+
+    /// Generates a proof for each segment of the sequence.
+    pub fn prove(&self) -> Vec<(StarkProof, SegmentBoundary<BaseElement>)> {
+        debug!(
+            "Generating proofs for computing Fibonacci sequence in {} segments of {} steps\n\
+            ---------------------",
+            self.num_segments, self.segment_length
+        );
+        prover::prove_segments::<_, FibSegmentAir, _>(
+            self.initial_state.clone(),
+            self.num_segments,
+            self.segment_length,
+            update_state,
+            self.options.clone(),
+        )
+        .unwrap()
+    }
+
+    /// Verifies that the segments are linked together and compute the expected final state.
+    pub fn verify(
+        &self,
+        segments: Vec<(StarkProof, SegmentBoundary<BaseElement>)>,
+    ) -> Result<(), VerifierError> {
+        verifier::verify_segments::<_, FibSegmentAir>(
+            segments,
+            &self.initial_state,
+            &self.final_state,
+        )
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{super::utils::build_proof_options, FibSegmentsExample};
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    SegmentBoundary,
+};
+use verifier::VerifierError;
+
+#[test]
+fn fib_segments_test_proof_verification() {
+    let fib = FibSegmentsExample::new(4, 16, build_proof_options(false));
+    let segments = fib.prove();
+    assert_eq!(4, segments.len());
+    for pair in segments.windows(2) {
+        assert!(pair[0].1.links_to(&pair[1].1));
+    }
+    assert!(fib.verify(segments).is_ok());
+}
+
+#[test]
+fn fib_segments_test_proof_verification_fail() {
+    let fib = FibSegmentsExample::new(4, 16, build_proof_options(false));
+    let segments = fib.prove();
+
+    // the computation must end in the expected state
+    let mut wrong_fib = FibSegmentsExample::new(4, 16, build_proof_options(false));
+    wrong_fib.final_state[1] += BaseElement::ONE;
+    let result = wrong_fib.verify(segments.clone());
+    assert!(matches!(result, Err(VerifierError::FinalStateMismatch)));
+
+    // all segments must be present
+    let mut missing = segments.clone();
+    missing.remove(1);
+    let result = fib.verify(missing);
+    assert!(matches!(result, Err(VerifierError::SegmentsNotLinked(1))));
+
+    // boundary values of linked segments must match their proofs
+    let mut tampered = segments;
+    let mut state = tampered[1].1.final_state().to_vec();
+    state[0] += BaseElement::ONE;
+    tampered[1].1 = SegmentBoundary::new(tampered[1].1.initial_state().to_vec(), state.clone());
+    tampered[2].1 = SegmentBoundary::new(state, tampered[2].1.final_state().to_vec());
+    let result = fib.verify(tampered);
+    assert!(matches!(
+        result,
+        Err(VerifierError::SegmentVerificationFailed(1, _))
+    ));
+}
@@ -7,9 +7,12 @@ use prover::{FieldExtension, HashFunction, ProofOptions, StarkProof};
 use structopt::StructOpt;
 use verifier::VerifierError;
 
+pub mod affine;
 pub mod fibonacci;
 pub mod merkle;
+pub mod recursive;
 pub mod rescue;
+pub mod sparse_merkle;
 pub mod utils;
 
 #[cfg(test)]
@@ -97,6 +100,12 @@ pub enum ExampleType {
         #[structopt(short = "n", default_value = "1048576")]
         sequence_length: usize,
     },
+    /// Execute a fixed program of affine transformations defined by preprocessed columns
+    Affine {
+        /// Number of steps in the program; must be a power of two
+        #[structopt(short = "n", default_value = "1048576")]
+        num_steps: usize,
+    },
     /// Compute a hash chain using Rescue hash function
     Rescue {
         /// Length of the hash chain; must be a power of two
@@ -109,4 +118,24 @@ pub enum ExampleType {
         #[structopt(short = "n", default_value = "7")]
         tree_depth: usize,
     },
+    /// Verify a proof of computing a Fibonacci sequence in a STARK
+    Recursive {
+        /// Length of Fibonacci sequence in the inner proof; must be a power of two, at least 128
+        #[structopt(short = "n", default_value = "256")]
+        sequence_length: usize,
+
+        /// Number of queries in the inner proof
+        #[structopt(short = "i", default_value = "28")]
+        num_inner_queries: usize,
+    },
+    /// Apply a batch of updates to a sparse Merkle tree using Rescue hash function
+    SparseMerkle {
+        /// Depth of the sparse Merkle tree; must be a power of two
+        #[structopt(short = "n", default_value = "64")]
+        tree_depth: usize,
+
+        /// Number of updates; must be a power of two
+        #[structopt(short = "u", default_value = "16")]
+        num_updates: usize,
+    },
 }
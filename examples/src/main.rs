@@ -7,7 +7,9 @@ use log::debug;
 use std::io::Write;
 use std::time::Instant;
 use structopt::StructOpt;
-use winterfell::{fibonacci, merkle, rescue, ExampleOptions, ExampleType};
+use winterfell::{
+    affine, fibonacci, merkle, recursive, rescue, sparse_merkle, ExampleOptions, ExampleType,
+};
 
 // EXAMPLE RUNNER
 // ================================================================================================
@@ -38,8 +40,17 @@ fn main() {
         ExampleType::Mulfib8 { sequence_length } => {
             fibonacci::mulfib8::get_example(options, sequence_length)
         }
+        ExampleType::Affine { num_steps } => affine::get_example(options, num_steps),
         ExampleType::Rescue { chain_length } => rescue::get_example(options, chain_length),
         ExampleType::Merkle { tree_depth } => merkle::get_example(options, tree_depth),
+        ExampleType::Recursive {
+            sequence_length,
+            num_inner_queries,
+        } => recursive::get_example(options, sequence_length, num_inner_queries),
+        ExampleType::SparseMerkle {
+            tree_depth,
+            num_updates,
+        } => sparse_merkle::get_example(options, tree_depth, num_updates),
     };
 
     // generate proof
@@ -3,14 +3,14 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{
-    utils::{bytes_to_node, node_to_bytes, rescue, TreeNode},
-    Example, ExampleOptions,
-};
+use crate::{utils::TreeNode, Example, ExampleOptions};
 use log::debug;
 use prover::{
     self,
-    crypto::MerkleTree,
+    crypto::{
+        hash::{rp128_256::ElementDigest, Rp128_256},
+        Digest, Hasher, MerkleTree,
+    },
     math::{
         field::{f128::BaseElement, FieldElement, StarkField},
         utils::log2,
@@ -66,18 +66,18 @@ impl MerkleExample {
         let path = tree
             .prove(index)
             .into_iter()
-            .map(bytes_to_node)
+            .map(hash_to_node)
             .collect::<Vec<_>>();
         debug!(
             "Computed Merkle path from leaf {} to root {} in {} ms",
             index,
-            hex::encode(tree.root()),
+            hex::encode(tree.root().as_bytes()),
             now.elapsed().as_millis(),
         );
 
         MerkleExample {
             options,
-            tree_root: bytes_to_node(*tree.root()),
+            tree_root: hash_to_node(*tree.root()),
             value,
             index,
             path,
@@ -130,17 +130,23 @@ impl Example for MerkleExample {
 
 // HELPER FUNCTIONS
 // ================================================================================================
-fn build_merkle_tree(depth: usize, value: TreeNode, index: usize) -> MerkleTree {
+fn build_merkle_tree(depth: usize, value: TreeNode, index: usize) -> MerkleTree<Rp128_256> {
     let num_leaves = usize::pow(2, depth as u32);
     let leaf_elements = BaseElement::prng_vector([1; 32], num_leaves * 2);
     let mut leaves = Vec::new();
     for i in (0..leaf_elements.len()).step_by(2) {
-        leaves.push(node_to_bytes((leaf_elements[i], leaf_elements[i + 1])));
+        leaves.push(ElementDigest::new([leaf_elements[i], leaf_elements[i + 1]]));
     }
 
-    let mut value_bytes = [0; 32];
-    rescue::hash(&node_to_bytes(value), &mut value_bytes);
-    leaves[index] = value_bytes;
+    // the AIR hashes the value by applying Rescue permutation to a state in which the value
+    // occupies the first two elements and the rest of the state is set to zeros; this is
+    // exactly how a value is merged with an all-zero digest.
+    let value = ElementDigest::new([value.0, value.1]);
+    leaves[index] = Rp128_256::merge(&[value, ElementDigest::default()]);
+    MerkleTree::new(leaves)
+}
 
-    MerkleTree::new(leaves, rescue::hash)
+fn hash_to_node(hash: ElementDigest) -> TreeNode {
+    let elements = hash.as_elements();
+    (elements[0], elements[1])
 }
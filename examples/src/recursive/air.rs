@@ -0,0 +1,437 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::inputs::{
+    LeafOpening, PublicInputs, ACC_WEIGHT, CHECK_FLAG, CONSTANT, NUM_LEAF_COEFFICIENTS, SELECTORS,
+    WEIGHTS,
+};
+use crate::utils::{
+    are_equal, is_binary, is_zero, not,
+    rescue::{
+        self, CYCLE_LENGTH as HASH_CYCLE_LEN, NUM_ROUNDS as NUM_HASH_ROUNDS, RATE_WIDTH,
+        STATE_WIDTH as HASH_STATE_WIDTH,
+    },
+    EvaluationResult,
+};
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    Air, Assertion, ComputationContext, EvaluationFrame, ExecutionTrace, ProofOptions, TraceInfo,
+    TransitionConstraintDegree,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+const TRACE_WIDTH: usize = 9 + NUM_LEAF_COEFFICIENTS;
+
+/// Register holding the bit of a leaf index which defines the position of the accumulated hash
+/// in the hash state for the next merge.
+const BIT_REG: usize = 6;
+
+/// Register in which the index of the leaf is accumulated from its bits.
+const INDEX_REG: usize = 7;
+
+/// Register in which the values of DEEP composition and FRI layers are accumulated.
+const ACC_REG: usize = 8;
+
+/// First register holding coefficients of the currently opened leaf.
+const COEFFICIENTS_REG: usize = 9;
+
+// RECURSIVE VERIFICATION AIR
+// ================================================================================================
+
+/// AIR for verifying queries of a STARK proof generated using Rescue Prime hash function.
+///
+/// The execution trace consists of a block of rows for every query. Each block contains a slot
+/// for every Merkle authentication path opened by the query: a path from a leaf of the trace
+/// commitment tree, a path from a leaf of the constraint commitment tree, and a path from a leaf
+/// of every FRI layer tree. A slot consists of 8-step hash cycles; the leaf is hashed in the
+/// first cycle of the path, and the path is resolved to the root of the tree in the last cycle
+/// of the slot.
+///
+/// When a leaf is hashed, its values are also combined into the accumulator register according
+/// to the public coefficients of the leaf. The accumulator starts as an evaluation of DEEP
+/// composition polynomial at the queried position, and becomes an evaluation of the next FRI
+/// layer after every FRI leaf; the last value of the accumulator must be equal to the value of
+/// the FRI remainder.
+pub struct RecursiveAir {
+    context: ComputationContext,
+    layout: QueryLayout,
+    inputs: PublicInputs,
+}
+
+impl Air for RecursiveAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        let layout = QueryLayout::new(&pub_inputs.path_depths);
+        let block_length = layout.length();
+        let degrees = vec![
+            TransitionConstraintDegree::with_cycles(5, vec![HASH_CYCLE_LEN]),
+            TransitionConstraintDegree::with_cycles(5, vec![HASH_CYCLE_LEN]),
+            TransitionConstraintDegree::with_cycles(5, vec![HASH_CYCLE_LEN]),
+            TransitionConstraintDegree::with_cycles(5, vec![HASH_CYCLE_LEN]),
+            TransitionConstraintDegree::with_cycles(5, vec![HASH_CYCLE_LEN]),
+            TransitionConstraintDegree::with_cycles(5, vec![HASH_CYCLE_LEN]),
+            TransitionConstraintDegree::new(2),
+            TransitionConstraintDegree::with_cycles(1, vec![block_length]),
+            TransitionConstraintDegree::with_cycles(2, vec![block_length]),
+            TransitionConstraintDegree::with_cycles(2, vec![block_length]),
+        ];
+        assert!(
+            block_length * pub_inputs.queries.len() == trace_info.length,
+            "expected trace length of {}, but was {}",
+            block_length * pub_inputs.queries.len(),
+            trace_info.length
+        );
+        let context = ComputationContext::new(TRACE_WIDTH, trace_info.length, degrees, options);
+        RecursiveAir {
+            context,
+            layout,
+            inputs: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        let mut result = vec![HASH_CYCLE_MASK.to_vec()];
+        result.append(&mut self.layout.get_periodic_columns());
+        result.append(&mut rescue::get_round_constants());
+        result
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        let block_length = self.layout.length();
+        let queries = &self.inputs.queries;
+        let mut result = Vec::new();
+
+        for (path_idx, &leaf_length) in self.inputs.leaf_lengths.iter().enumerate() {
+            // capacity registers of the hash state are initialized to the length of the leaf,
+            // and the unused part of the rate is set to ZEROs
+            let leaf_step = self.layout.leaf_step(path_idx);
+            let capacity = BaseElement::new(leaf_length as u128);
+            result.push(Assertion::periodic(4, leaf_step, block_length, capacity));
+            result.push(Assertion::periodic(
+                5,
+                leaf_step,
+                block_length,
+                BaseElement::ZERO,
+            ));
+            for register in leaf_length..RATE_WIDTH {
+                result.push(Assertion::periodic(
+                    register,
+                    leaf_step,
+                    block_length,
+                    BaseElement::ZERO,
+                ));
+            }
+
+            // coefficients of the leaf are set to the values computed by the verifier
+            for i in 0..NUM_LEAF_COEFFICIENTS {
+                let values = queries
+                    .iter()
+                    .map(|query| query.leaf_coefficients[path_idx][i])
+                    .collect();
+                result.push(Assertion::sequence(
+                    COEFFICIENTS_REG + i,
+                    leaf_step,
+                    block_length,
+                    values,
+                ));
+            }
+
+            // the path must resolve to the root of the tree, and the index accumulated from the
+            // bits of the path must be equal to the index of the leaf
+            let root_step = self.layout.root_step(path_idx);
+            let root = self.inputs.roots[path_idx];
+            result.push(Assertion::periodic(0, root_step, block_length, root[0]));
+            result.push(Assertion::periodic(1, root_step, block_length, root[1]));
+            let indexes = queries
+                .iter()
+                .map(|query| BaseElement::new(query.leaf_indexes[path_idx] as u128))
+                .collect();
+            result.push(Assertion::sequence(
+                INDEX_REG,
+                root_step,
+                block_length,
+                indexes,
+            ));
+        }
+
+        // after all FRI layers are folded, the accumulator must hold the value of the remainder
+        let remainder_values = queries.iter().map(|query| query.remainder_value).collect();
+        result.push(Assertion::sequence(
+            ACC_REG,
+            block_length - 1,
+            block_length,
+            remainder_values,
+        ));
+
+        result
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        // split periodic values into masks, flags of the query layout, and Rescue round constants
+        let hash_flag = periodic_values[0];
+        let leaf_flag = periodic_values[1];
+        let merge_flag = periodic_values[2];
+        let index_weight = periodic_values[3];
+        let ark = &periodic_values[4..];
+
+        // when hash_flag = 1, constraints for Rescue round are enforced
+        rescue::enforce_round(
+            result,
+            &current[..HASH_STATE_WIDTH],
+            &next[..HASH_STATE_WIDTH],
+            ark,
+            hash_flag,
+        );
+
+        // when merge_flag = 1, make sure accumulated hash is placed in the right place in the
+        // hash state for the next merge: into registers [0, 1] when index bit = 0, and into
+        // registers [2, 3] when index bit = 1; capacity registers must be reset to ZEROs
+        let bit = next[BIT_REG];
+        let not_bit = not(bit);
+        result.agg_constraint(0, merge_flag, not_bit * are_equal(current[0], next[0]));
+        result.agg_constraint(1, merge_flag, not_bit * are_equal(current[1], next[1]));
+        result.agg_constraint(2, merge_flag, bit * are_equal(current[0], next[2]));
+        result.agg_constraint(3, merge_flag, bit * are_equal(current[1], next[3]));
+        result.agg_constraint(4, merge_flag, is_zero(next[4]));
+        result.agg_constraint(5, merge_flag, is_zero(next[5]));
+
+        // values in the bit register must be binary
+        result[6] = is_binary(current[BIT_REG]);
+
+        // leaf index is reset when a leaf is hashed, and then accumulated from the bits used in
+        // merges; index_weight is 2^i before the i-th merge of a path, and ZERO otherwise
+        result[7] = are_equal(
+            next[INDEX_REG],
+            not(leaf_flag) * current[INDEX_REG] + index_weight * bit,
+        );
+
+        // when a leaf is hashed, the accumulator is updated with the values of the leaf, and
+        // when the check flag is set, the value picked from the leaf by the selectors must be
+        // equal to the accumulator; otherwise, the accumulator remains unchanged
+        let leaf = &current[..RATE_WIDTH];
+        let coefficients = &current[COEFFICIENTS_REG..];
+        let acc = current[ACC_REG];
+        let selected = combine(leaf, &coefficients[SELECTORS..SELECTORS + RATE_WIDTH]);
+        let combined = combine(leaf, &coefficients[WEIGHTS..WEIGHTS + RATE_WIDTH])
+            + coefficients[CONSTANT]
+            + coefficients[ACC_WEIGHT] * acc;
+        result[8] = are_equal(next[ACC_REG], acc + leaf_flag * (combined - acc));
+        result[9] = leaf_flag * are_equal(selected, coefficients[CHECK_FLAG] * acc);
+    }
+}
+
+// QUERY LAYOUT
+// ================================================================================================
+
+/// Describes placement of Merkle authentication paths opened by a single query within a block
+/// of the execution trace.
+///
+/// The block consists of equal slots, one for each path; a slot contains enough hash cycles to
+/// hash a leaf and to merge it with all nodes of the longest path. A shorter path starts later
+/// in its slot, so that all paths end in the last cycle of their slots. The numbers of slots and
+/// of cycles in a slot are powers of two; unused cycles and slots hash ZEROs.
+pub struct QueryLayout {
+    path_depths: Vec<usize>,
+    num_slots: usize,
+    slot_cycles: usize,
+}
+
+impl QueryLayout {
+    pub fn new(path_depths: &[usize]) -> Self {
+        let max_depth = path_depths.iter().copied().max().expect("no paths");
+        QueryLayout {
+            path_depths: path_depths.to_vec(),
+            num_slots: path_depths.len().next_power_of_two(),
+            slot_cycles: (max_depth + 1).next_power_of_two(),
+        }
+    }
+
+    /// Returns the number of steps in a block for a single query.
+    pub fn length(&self) -> usize {
+        self.num_slots * self.slot_length()
+    }
+
+    /// Returns the step within a block at which the leaf of the specified path is hashed.
+    pub fn leaf_step(&self, path_idx: usize) -> usize {
+        path_idx * self.slot_length() + self.first_cycle(path_idx) * HASH_CYCLE_LEN
+    }
+
+    /// Returns the step within a block at which the specified path resolves to the root.
+    pub fn root_step(&self, path_idx: usize) -> usize {
+        (path_idx + 1) * self.slot_length() - 1
+    }
+
+    /// Returns periodic columns describing the layout: leaf flags which are set at the steps
+    /// at which leaves are hashed, merge flags which are set at the steps preceding merges, and
+    /// weights of index bits used in the merges.
+    pub fn get_periodic_columns(&self) -> Vec<Vec<BaseElement>> {
+        let mut leaf_flags = vec![BaseElement::ZERO; self.length()];
+        let mut merge_flags = vec![BaseElement::ZERO; self.length()];
+        let mut index_weights = vec![BaseElement::ZERO; self.length()];
+        for (path_idx, &depth) in self.path_depths.iter().enumerate() {
+            let leaf_step = self.leaf_step(path_idx);
+            leaf_flags[leaf_step] = BaseElement::ONE;
+            for level in 0..depth {
+                let step = leaf_step + (level + 1) * HASH_CYCLE_LEN - 1;
+                merge_flags[step] = BaseElement::ONE;
+                index_weights[step] = BaseElement::new(1 << level);
+            }
+        }
+        vec![leaf_flags, merge_flags, index_weights]
+    }
+
+    fn slot_length(&self) -> usize {
+        self.slot_cycles * HASH_CYCLE_LEN
+    }
+
+    /// Returns the cycle within the slot at which the leaf of the specified path is hashed.
+    fn first_cycle(&self, path_idx: usize) -> usize {
+        self.slot_cycles - 1 - self.path_depths[path_idx]
+    }
+}
+
+// TRACE GENERATOR
+// ================================================================================================
+
+pub fn build_trace(
+    inputs: &PublicInputs,
+    openings: &[Vec<LeafOpening>],
+) -> ExecutionTrace<BaseElement> {
+    let layout = QueryLayout::new(&inputs.path_depths);
+    let block_length = layout.length();
+    let trace_length = block_length * inputs.queries.len();
+    let mut trace = vec![vec![BaseElement::ZERO; trace_length]; TRACE_WIDTH];
+
+    // fill the hash state and index bit registers one hash cycle at a time
+    let mut state = [BaseElement::ZERO; HASH_STATE_WIDTH];
+    for (query_idx, (query, query_openings)) in inputs.queries.iter().zip(openings).enumerate() {
+        let block_start = query_idx * block_length;
+        for slot_idx in 0..layout.num_slots {
+            for cycle in 0..layout.slot_cycles {
+                let mut bit = BaseElement::ZERO;
+                match query_openings.get(slot_idx) {
+                    Some(opening) if cycle == layout.first_cycle(slot_idx) => {
+                        // hash leaf values with the capacity initialized to the length of the leaf
+                        state[..RATE_WIDTH].copy_from_slice(&opening.values);
+                        state[4] = BaseElement::new(inputs.leaf_lengths[slot_idx] as u128);
+                        state[5] = BaseElement::ZERO;
+
+                        // set coefficients of the leaf
+                        let step = block_start + layout.leaf_step(slot_idx);
+                        let coefficients = &query.leaf_coefficients[slot_idx];
+                        for (i, &coefficient) in coefficients.iter().enumerate() {
+                            trace[COEFFICIENTS_REG + i][step] = coefficient;
+                        }
+                    }
+                    Some(opening) if cycle > layout.first_cycle(slot_idx) => {
+                        // merge the accumulated hash with the next node of the path
+                        let level = cycle - layout.first_cycle(slot_idx) - 1;
+                        let node = opening.path[level];
+                        if (query.leaf_indexes[slot_idx] >> level) & 1 == 0 {
+                            state[2] = node[0];
+                            state[3] = node[1];
+                        } else {
+                            state[2] = state[0];
+                            state[3] = state[1];
+                            state[0] = node[0];
+                            state[1] = node[1];
+                            bit = BaseElement::ONE;
+                        }
+                        state[4] = BaseElement::ZERO;
+                        state[5] = BaseElement::ZERO;
+                    }
+                    _ => state = [BaseElement::ZERO; HASH_STATE_WIDTH],
+                }
+
+                let cycle_start =
+                    block_start + (slot_idx * layout.slot_cycles + cycle) * HASH_CYCLE_LEN;
+                for step in cycle_start..cycle_start + HASH_CYCLE_LEN {
+                    for (register, &value) in trace.iter_mut().zip(state.iter()) {
+                        register[step] = value;
+                    }
+                    trace[BIT_REG][step] = bit;
+                    if step - cycle_start < NUM_HASH_ROUNDS {
+                        rescue::apply_round(&mut state, step);
+                    }
+                }
+            }
+        }
+    }
+
+    // fill the index and the accumulator registers by applying their transition functions
+    let periodic_columns = layout.get_periodic_columns();
+    let (leaf_flags, index_weights) = (&periodic_columns[0], &periodic_columns[2]);
+    for step in 0..trace_length - 1 {
+        let i = step % block_length;
+        let is_leaf = leaf_flags[i] == BaseElement::ONE;
+
+        let index = if is_leaf {
+            BaseElement::ZERO
+        } else {
+            trace[INDEX_REG][step]
+        };
+        trace[INDEX_REG][step + 1] = index + index_weights[i] * trace[BIT_REG][step + 1];
+
+        let mut acc = trace[ACC_REG][step];
+        if is_leaf {
+            let coefficients = &trace[COEFFICIENTS_REG..];
+            let leaf = trace[..RATE_WIDTH].iter().map(|register| register[step]);
+            let weights = coefficients[WEIGHTS..]
+                .iter()
+                .map(|register| register[step]);
+            acc = leaf.zip(weights).fold(
+                coefficients[CONSTANT][step] + coefficients[ACC_WEIGHT][step] * acc,
+                |acc, (value, weight)| acc + value * weight,
+            );
+        }
+        trace[ACC_REG][step + 1] = acc;
+    }
+
+    ExecutionTrace::init(trace)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Computes a linear combination of the `values` with the specified `weights`.
+fn combine<E: FieldElement>(values: &[E], weights: &[E]) -> E {
+    values
+        .iter()
+        .zip(weights)
+        .fold(E::ZERO, |acc, (&value, &weight)| acc + value * weight)
+}
+
+// MASKS
+// ================================================================================================
+const HASH_CYCLE_MASK: [BaseElement; HASH_CYCLE_LEN] = [
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ZERO,
+];
@@ -0,0 +1,336 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::utils::rescue::RATE_WIDTH;
+use prover::{
+    crypto::{hash::Rp128_256, BatchMerkleProof, Hasher},
+    math::{
+        field::{f128::BaseElement, FieldElement},
+        utils::{log2, read_elements_into_vec},
+    },
+    Air, FieldExtension, HashFunction, Queries, StarkProof,
+};
+use verifier::{fri, ProofTranscript, VerifierError};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of coefficients which describe how values of a single opened leaf are used to verify
+/// a query. The coefficients are laid out as follows:
+/// * 4 selectors, which pick a value from the leaf to be compared with the accumulator;
+/// * 4 weights of the leaf values in the new value of the accumulator;
+/// * a constant term of the new value of the accumulator;
+/// * a weight of the current value of the accumulator in its new value;
+/// * a flag which is set to ONE when the selected value must be equal to the accumulator.
+pub const NUM_LEAF_COEFFICIENTS: usize = 11;
+
+pub const SELECTORS: usize = 0;
+pub const WEIGHTS: usize = 4;
+pub const CONSTANT: usize = 8;
+pub const ACC_WEIGHT: usize = 9;
+pub const CHECK_FLAG: usize = 10;
+
+/// Number of constraint evaluations committed to in a single leaf of the constraint commitment
+/// tree; this is also the number of FRI layer evaluations committed to in a single leaf. This
+/// holds for proofs generated using Rp128_256 hash function without field extension, and with
+/// FRI folding factor 4.
+const EVALUATIONS_PER_LEAF: usize = 4;
+
+// PUBLIC INPUTS
+// ================================================================================================
+
+/// Public inputs of the recursive verification AIR derived from a transcript of the inner proof.
+///
+/// The inner proof is queried at a set of positions, and for each query, leaves of the trace
+/// commitment tree, the constraint commitment tree, and the trees of all FRI layers are opened.
+/// DEEP composition and FRI folding are linear in the opened values; thus, for each opened leaf,
+/// the verifier can compute coefficients which describe how the values of the leaf are combined
+/// with the values of the leaves opened before it.
+///
+/// The coefficients are derived from the transcript by the outer verifier natively, and are not
+/// constrained by the recursive AIR; the work of deriving them is linear in the number of queries
+/// and FRI layers of the inner proof.
+#[derive(Clone)]
+pub struct PublicInputs {
+    /// Depths of the opened trees in the order in which they are verified: execution trace,
+    /// constraint evaluations, and then FRI layers.
+    pub path_depths: Vec<usize>,
+    /// Number of elements hashed into a single leaf of each of the trees.
+    pub leaf_lengths: Vec<usize>,
+    /// Roots of each of the trees.
+    pub roots: Vec<[BaseElement; 2]>,
+    /// Inputs for each of the queries; the number of queries is padded to the next power of two
+    /// by repeating the last query.
+    pub queries: Vec<QueryInputs>,
+}
+
+/// Public inputs describing a single query against the inner proof.
+#[derive(Clone)]
+pub struct QueryInputs {
+    /// Index of the opened leaf in each of the trees.
+    pub leaf_indexes: Vec<usize>,
+    /// Coefficients describing how values of the opened leaf in each of the trees are used.
+    pub leaf_coefficients: Vec<[BaseElement; NUM_LEAF_COEFFICIENTS]>,
+    /// Value of the FRI remainder at the position into which the query is folded.
+    pub remainder_value: BaseElement,
+}
+
+impl PublicInputs {
+    /// Builds inputs for verifying the queries of the inner proof described by the `transcript`
+    /// against the `air` instance of the inner computation.
+    ///
+    /// Returns an error if the inner proof options imply a number of evaluations per leaf other
+    /// than `EVALUATIONS_PER_LEAF`, if the inner trace has more registers than fit into a single
+    /// leaf hash, or if the inner proof has no FRI layers or no queries.
+    pub fn new<A: Air<BaseElement = BaseElement>>(
+        air: &A,
+        transcript: &ProofTranscript<BaseElement, Rp128_256>,
+    ) -> Result<Self, VerifierError> {
+        let context = air.context();
+        let options = context.options();
+        if options.hash_fn() != HashFunction::Rp128_256 {
+            return Err(VerifierError::UnsupportedHashFunction);
+        }
+        if options.field_extension() != FieldExtension::None {
+            return Err(VerifierError::UnsupportedFieldExtension);
+        }
+        let folding_factor = options.to_fri_options::<BaseElement>().folding_factor();
+        if folding_factor != EVALUATIONS_PER_LEAF {
+            return Err(VerifierError::UnsupportedFoldingFactor(folding_factor));
+        }
+        if air.trace_width() > RATE_WIDTH {
+            return Err(VerifierError::UnsupportedTraceLayout);
+        }
+        if transcript.fri_alphas.is_empty() {
+            return Err(VerifierError::FriVerificationFailed(
+                fri::VerifierError::LayerCountMismatch(1, 0),
+            ));
+        }
+
+        // leaves of the constraint tree and of the trees of FRI layers contain 4 evaluations
+        // each; the domain of each FRI layer is 4 times smaller than the domain of the previous
+        // layer
+        let lde_depth = log2(context.lde_domain_size()) as usize;
+        let mut path_depths = vec![lde_depth, lde_depth - 2];
+        let mut leaf_lengths = vec![air.trace_width(), EVALUATIONS_PER_LEAF];
+        for i in 0..transcript.fri_alphas.len() {
+            path_depths.push(lde_depth - 2 * (i + 1));
+            leaf_lengths.push(EVALUATIONS_PER_LEAF);
+        }
+
+        // the last FRI root is the commitment to the remainder which is checked separately
+        let mut roots = vec![transcript.trace_root, transcript.constraint_root];
+        roots.extend_from_slice(&transcript.fri_roots[..transcript.fri_alphas.len()]);
+        let roots = roots
+            .iter()
+            .map(|root| [root.as_elements()[0], root.as_elements()[1]])
+            .collect();
+
+        let mut positions = transcript.query_positions.clone();
+        let last_position = *positions.last().ok_or(VerifierError::NoQueries)?;
+        positions.resize(positions.len().next_power_of_two(), last_position);
+        let queries = positions
+            .into_iter()
+            .map(|position| QueryInputs::new(air, transcript, position))
+            .collect();
+
+        Ok(PublicInputs {
+            path_depths,
+            leaf_lengths,
+            roots,
+            queries,
+        })
+    }
+}
+
+impl QueryInputs {
+    /// Computes inputs for a query at the specified position of the LDE domain.
+    fn new<A: Air<BaseElement = BaseElement>>(
+        air: &A,
+        transcript: &ProofTranscript<BaseElement, Rp128_256>,
+        position: usize,
+    ) -> Self {
+        let context = air.context();
+        let cc = &transcript.composition_coefficients;
+        let ood_frame = &transcript.ood_frame;
+        let domain_offset = context.domain_offset::<BaseElement>();
+        let mut domain_generator = context.get_lde_domain_generator::<BaseElement>();
+        let x = domain_generator.exp((position as u64).into()) * domain_offset;
+
+        // DEEP composition of trace registers is a linear combination of the register values
+        // T(x) with weights cc_1 / (x - z) + cc_2 / (x - z * g) multiplied by the degree
+        // adjustment factor; values of the registers at z and z * g go into the constant term
+        let z = transcript.z;
+        let next_z = z * air.trace_domain_generator();
+        let inv_z = (x - z).inv();
+        let inv_next_z = (x - next_z).inv();
+        let incremental_degree = context.deep_composition_degree() - (air.trace_length() - 2);
+        let adjustment =
+            cc.trace_degree.0 + x.exp((incremental_degree as u64).into()) * cc.trace_degree.1;
+
+        let mut trace_coefficients = [BaseElement::ZERO; NUM_LEAF_COEFFICIENTS];
+        for (i, &(cc_z, cc_next_z, _)) in cc.trace.iter().enumerate() {
+            let weight_z = cc_z * inv_z * adjustment;
+            let weight_next_z = cc_next_z * inv_next_z * adjustment;
+            trace_coefficients[WEIGHTS + i] = weight_z + weight_next_z;
+            trace_coefficients[CONSTANT] -=
+                ood_frame.current[i] * weight_z + ood_frame.next[i] * weight_next_z;
+        }
+
+        // DEEP composition of constraints picks the evaluation at x from the leaf, and adds the
+        // result to the composition of trace registers
+        let weight = cc.constraints * inv_z;
+        let mut constraint_coefficients = [BaseElement::ZERO; NUM_LEAF_COEFFICIENTS];
+        constraint_coefficients[WEIGHTS + position % EVALUATIONS_PER_LEAF] = weight;
+        constraint_coefficients[CONSTANT] = -transcript.constraint_evaluation_at_z * weight;
+        constraint_coefficients[ACC_WEIGHT] = BaseElement::ONE;
+
+        let mut leaf_indexes = vec![position, position / EVALUATIONS_PER_LEAF];
+        let mut leaf_coefficients = vec![trace_coefficients, constraint_coefficients];
+
+        // a leaf of a FRI layer contains evaluations at x * r^k, where r is a 4th root of unity;
+        // the evaluation at the queried position must be equal to the accumulated value, and
+        // the layer is folded by evaluating the polynomial interpolated from the leaf values at
+        // alpha, which is a linear combination of the values with Lagrange basis weights
+        let mut domain_size = context.lde_domain_size();
+        let mut position = position;
+        for &alpha in transcript.fri_alphas.iter() {
+            let row_length = domain_size / EVALUATIONS_PER_LEAF;
+            let index = position % row_length;
+            let xe = domain_generator.exp((index as u64).into()) * domain_offset;
+            let root = domain_generator.exp((row_length as u64).into());
+            let xs = [xe, xe * root, xe * root.exp(2), xe * root.exp(3)];
+
+            let mut coefficients = [BaseElement::ZERO; NUM_LEAF_COEFFICIENTS];
+            coefficients[SELECTORS + position / row_length] = BaseElement::ONE;
+            coefficients[CHECK_FLAG] = BaseElement::ONE;
+            for (k, &xk) in xs.iter().enumerate() {
+                let mut weight = BaseElement::ONE;
+                for (m, &xm) in xs.iter().enumerate() {
+                    if m != k {
+                        weight *= (alpha - xm) / (xk - xm);
+                    }
+                }
+                coefficients[WEIGHTS + k] = weight;
+            }
+
+            leaf_indexes.push(index);
+            leaf_coefficients.push(coefficients);
+            position = index;
+            domain_size = row_length;
+            domain_generator = domain_generator.exp(4);
+        }
+
+        QueryInputs {
+            leaf_indexes,
+            leaf_coefficients,
+            remainder_value: transcript.fri_remainder[position],
+        }
+    }
+}
+
+// QUERY OPENINGS
+// ================================================================================================
+
+/// A leaf opened in one of the trees of the inner proof together with its authentication path.
+pub struct LeafOpening {
+    /// Values hashed into the leaf, padded with ZEROs to the rate width of the hash function.
+    pub values: [BaseElement; RATE_WIDTH],
+    /// Sibling nodes on the path from the leaf to the root of the tree.
+    pub path: Vec<[BaseElement; 2]>,
+}
+
+/// Reads openings of the leaves specified by the `inputs` from the inner `proof`; openings are
+/// returned for each query, and for each query, for each of the trees.
+///
+/// Returns an error if the proof does not have exactly one trace segment and the FRI layers
+/// described by the `inputs`, or if any of the queries cannot be parsed or do not resolve to
+/// authentication paths.
+pub fn read_openings(
+    proof: &StarkProof,
+    inputs: &PublicInputs,
+) -> Result<Vec<Vec<LeafOpening>>, VerifierError> {
+    if proof.trace_queries.len() != 1 {
+        return Err(VerifierError::UnsupportedTraceLayout);
+    }
+    let num_fri_layers = inputs.path_depths.len() - 2;
+    if proof.fri_proof.layers.len() < num_fri_layers {
+        return Err(VerifierError::FriVerificationFailed(
+            fri::VerifierError::LayerCountMismatch(num_fri_layers, proof.fri_proof.layers.len()),
+        ));
+    }
+
+    let lde_domain_size = 1 << inputs.path_depths[0];
+    let mut result: Vec<Vec<LeafOpening>> = inputs.queries.iter().map(|_| Vec::new()).collect();
+
+    for tree_idx in 0..inputs.path_depths.len() {
+        // leaves of each tree were opened in the order of the queries, with duplicates removed
+        let mut indexes = Vec::new();
+        for query in inputs.queries.iter() {
+            if !indexes.contains(&query.leaf_indexes[tree_idx]) {
+                indexes.push(query.leaf_indexes[tree_idx]);
+            }
+        }
+
+        let (batch_proof, values) = match tree_idx {
+            0 => Queries::deserialize(&proof.trace_queries[0])
+                .and_then(|queries| queries.into_batch::<Rp128_256, BaseElement>(lde_domain_size))
+                .map_err(|_| VerifierError::TraceQueryDeserializationFailed)?,
+            1 => Queries::deserialize(&proof.constraint_queries)
+                .and_then(|queries| {
+                    queries.into_batch::<Rp128_256, BaseElement>(
+                        lde_domain_size / EVALUATIONS_PER_LEAF,
+                    )
+                })
+                .map_err(|_| VerifierError::ConstraintQueryDeserializationFailed)?,
+            _ => {
+                let layer_idx = tree_idx - 2;
+                let layer = &proof.fri_proof.layers[layer_idx];
+                let fri_error = |err: String| {
+                    VerifierError::FriVerificationFailed(
+                        fri::VerifierError::LayerDeserializationError(layer_idx, err),
+                    )
+                };
+                let values = layer
+                    .values
+                    .iter()
+                    .map(|bytes| read_elements_into_vec(bytes))
+                    .collect::<Result<Vec<Vec<BaseElement>>, _>>()
+                    .map_err(|err| fri_error(err.to_string()))?;
+                let leaves = values.iter().map(|v| Rp128_256::hash_elements(v)).collect();
+                let batch_proof = BatchMerkleProof::deserialize(&layer.paths, leaves, layer.depth)
+                    .map_err(|err| fri_error(err.to_string()))?;
+                (batch_proof, values)
+            }
+        };
+        let paths = batch_proof
+            .get_paths(&indexes)
+            .ok_or_else(|| match tree_idx {
+                0 => VerifierError::TraceQueryDoesNotMatchCommitment,
+                1 => VerifierError::ConstraintQueryDoesNotMatchCommitment,
+                _ => VerifierError::FriVerificationFailed(
+                    fri::VerifierError::LayerCommitmentMismatch(tree_idx - 2),
+                ),
+            })?;
+
+        for (query, openings) in inputs.queries.iter().zip(result.iter_mut()) {
+            let i = indexes
+                .iter()
+                .position(|&index| index == query.leaf_indexes[tree_idx])
+                .unwrap();
+            let mut leaf = [BaseElement::ZERO; RATE_WIDTH];
+            leaf[..values[i].len()].copy_from_slice(&values[i]);
+            openings.push(LeafOpening {
+                values: leaf,
+                path: paths[i][1..]
+                    .iter()
+                    .map(|node| [node.as_elements()[0], node.as_elements()[1]])
+                    .collect(),
+            });
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,195 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{fibonacci::fib2, Example, ExampleOptions};
+use log::debug;
+use prover::{
+    self,
+    crypto::hash::Rp128_256,
+    math::{
+        field::{f128::BaseElement, FieldElement},
+        utils::log2,
+    },
+    Air, FieldExtension, HashFunction, ProofOptions, StarkProof, TraceInfo,
+};
+use std::time::Instant;
+use verifier::{self, VerifierError};
+
+mod air;
+use air::{build_trace, RecursiveAir};
+
+mod inputs;
+use inputs::{read_openings, PublicInputs};
+
+#[cfg(test)]
+mod tests;
+
+// RECURSIVE VERIFICATION EXAMPLE
+// ================================================================================================
+
+pub fn get_example(
+    options: ExampleOptions,
+    sequence_length: usize,
+    num_inner_queries: usize,
+) -> Box<dyn Example> {
+    Box::new(RecursiveExample::new(
+        sequence_length,
+        build_inner_options(num_inner_queries),
+        options.to_proof_options(28, 16),
+    ))
+}
+
+/// Proves that a STARK proof of computing a Fibonacci sequence (with 2 terms per step) is valid.
+///
+/// The inner proof is generated using Rescue Prime hash function, and Merkle authentication
+/// paths of its queries are verified by `RecursiveAir`. The rest of the inner proof (commitments,
+/// out-of-domain frame, FRI remainder, and proof-of-work nonce) is checked by the verifier of the
+/// outer proof directly; this requires a small number of hashes regardless of the number of
+/// queries.
+///
+/// The outer proof does not remove all of the work of the inner verifier which depends on the
+/// number of queries: coefficients of DEEP composition and FRI folding for every query are
+/// computed by the outer verifier natively, and only the combination of the opened values with
+/// these coefficients is checked by `RecursiveAir`.
+pub struct RecursiveExample {
+    options: ProofOptions,
+    inner_proof: StarkProof,
+    result: BaseElement,
+}
+
+impl RecursiveExample {
+    pub fn new(
+        sequence_length: usize,
+        inner_options: ProofOptions,
+        options: ProofOptions,
+    ) -> RecursiveExample {
+        assert!(
+            sequence_length.is_power_of_two(),
+            "sequence length must be a power of 2"
+        );
+        assert!(
+            inner_options.hash_fn() == HashFunction::Rp128_256,
+            "inner proof must be generated using Rp128_256 hash function"
+        );
+        assert!(
+            inner_options.field_extension().is_none(),
+            "inner proof cannot use field extension"
+        );
+
+        // generate the inner proof; the last term of the sequence is in the last row of the trace
+        let now = Instant::now();
+        let trace = fib2::build_trace(sequence_length);
+        let result = trace.get(1, trace.len() - 1);
+        let inner_proof = prover::prove::<fib2::FibAir>(trace, result, inner_options).unwrap();
+        debug!(
+            "Generated inner proof for computing Fibonacci sequence up to {}th term in {} ms",
+            sequence_length,
+            now.elapsed().as_millis()
+        );
+
+        RecursiveExample {
+            options,
+            inner_proof,
+            result,
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl Example for RecursiveExample {
+    fn prove(&self) -> StarkProof {
+        debug!(
+            "Generating proof for verifying {} queries of the inner proof\n\
+            ---------------------",
+            self.inner_proof.options().num_queries()
+        );
+
+        // read the transcript of the inner proof and compute the inputs for the recursive AIR
+        let now = Instant::now();
+        let inputs = build_inputs(self.inner_proof.clone(), self.result)
+            .expect("failed to read the transcript of the inner proof");
+        let openings = read_openings(&self.inner_proof, &inputs)
+            .expect("failed to read queries of the inner proof");
+        debug!(
+            "Read {} queries of the inner proof in {} ms",
+            inputs.queries.len(),
+            now.elapsed().as_millis()
+        );
+
+        // generate execution trace
+        let now = Instant::now();
+        let trace = build_trace(&inputs, &openings);
+        let trace_width = trace.width();
+        let trace_length = trace.len();
+        debug!(
+            "Generated execution trace of {} registers and 2^{} steps in {} ms",
+            trace_width,
+            log2(trace_length),
+            now.elapsed().as_millis()
+        );
+
+        // generate the proof
+        prover::prove::<RecursiveAir>(trace, inputs, self.options.clone()).unwrap()
+    }
+
+    fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        // the verifier does not need the queries of the inner proof
+        let inner_proof = remove_queries(self.inner_proof.clone());
+        let inputs = build_inputs(inner_proof, self.result)?;
+        verifier::verify::<RecursiveAir>(proof, inputs)
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let inner_proof = remove_queries(self.inner_proof.clone());
+        let inputs = build_inputs(inner_proof, self.result + BaseElement::ONE)?;
+        verifier::verify::<RecursiveAir>(proof, inputs)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns options for the inner proof; the proof must be generated using Rescue Prime hash
+/// function, and without field extension.
+fn build_inner_options(num_queries: usize) -> ProofOptions {
+    ProofOptions::new(
+        num_queries,
+        8,
+        0,
+        HashFunction::Rp128_256,
+        FieldExtension::None,
+    )
+}
+
+/// Reads the transcript of the inner proof and builds the inputs of the recursive AIR from it.
+fn build_inputs(
+    inner_proof: StarkProof,
+    result: BaseElement,
+) -> Result<PublicInputs, VerifierError> {
+    let trace_info = TraceInfo {
+        length: inner_proof.trace_length(),
+        meta: Vec::new(),
+    };
+    let inner_air = fib2::FibAir::new(trace_info, result, inner_proof.options().clone());
+    let transcript = verifier::read_transcript::<fib2::FibAir, Rp128_256>(inner_proof, result)?;
+    PublicInputs::new(&inner_air, &transcript)
+}
+
+/// Removes query openings of the trace, constraint evaluations, and FRI layers from the proof.
+fn remove_queries(mut proof: StarkProof) -> StarkProof {
+    for queries in proof.trace_queries.iter_mut() {
+        queries.paths.clear();
+        queries.values.clear();
+    }
+    proof.constraint_queries.paths.clear();
+    proof.constraint_queries.values.clear();
+    for layer in proof.fri_proof.layers.iter_mut() {
+        layer.paths.clear();
+        layer.values.clear();
+    }
+    proof
+}
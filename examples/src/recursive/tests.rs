@@ -0,0 +1,103 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{fibonacci::fib2, Example};
+use prover::{
+    crypto::hash::Rp128_256, Air, FieldExtension, HashFunction, ProofOptions, Prover, ProverError,
+    TraceInfo,
+};
+use verifier::VerifierError;
+
+#[test]
+fn recursive_test_basic_proof_verification() {
+    let recursive = Box::new(super::RecursiveExample::new(
+        256,
+        super::build_inner_options(8),
+        build_options(),
+    ));
+    crate::tests::test_basic_proof_verification(recursive);
+}
+
+#[test]
+fn recursive_test_basic_proof_verification_fail() {
+    let recursive = Box::new(super::RecursiveExample::new(
+        256,
+        super::build_inner_options(8),
+        build_options(),
+    ));
+    crate::tests::test_basic_proof_verification_fail(recursive);
+}
+
+#[test]
+fn recursive_test_verify_many_with_different_layouts() {
+    // inner proofs of different lengths have Merkle paths of different depths, and thus, the
+    // outer proofs have different periodic columns despite having the same trace length
+    let mut proofs = Vec::new();
+    for &sequence_length in [256, 512].iter() {
+        let recursive = super::RecursiveExample::new(
+            sequence_length,
+            super::build_inner_options(8),
+            build_options(),
+        );
+        let inner_proof = super::remove_queries(recursive.inner_proof.clone());
+        let inputs = super::build_inputs(inner_proof, recursive.result).unwrap();
+        proofs.push((recursive.prove(), inputs));
+    }
+    assert_eq!(proofs[0].0.trace_length(), proofs[1].0.trace_length());
+
+    let results = verifier::verify_many::<super::RecursiveAir>(proofs);
+    assert!(results.iter().all(|result| result.is_ok()));
+}
+
+#[test]
+fn recursive_test_prover_reuse_with_different_layouts() {
+    // periodic columns of the recursive AIR depend on its public inputs, and thus, a prover
+    // cannot be reused for inputs which describe a different layout of the inner proof
+    let mut examples = Vec::new();
+    for &sequence_length in [256, 512].iter() {
+        let recursive = super::RecursiveExample::new(
+            sequence_length,
+            super::build_inner_options(8),
+            build_options(),
+        );
+        let inputs = super::build_inputs(recursive.inner_proof.clone(), recursive.result).unwrap();
+        let openings = super::read_openings(&recursive.inner_proof, &inputs).unwrap();
+        let trace = super::build_trace(&inputs, &openings);
+        examples.push((trace, inputs));
+    }
+    let (trace, inputs) = examples.pop().unwrap();
+    let prover =
+        Prover::<super::RecursiveAir>::new(trace.len(), examples[0].1.clone(), build_options())
+            .unwrap();
+    assert!(matches!(
+        prover.prove(trace, inputs),
+        Err(ProverError::InconsistentPeriodicColumns)
+    ));
+}
+
+#[test]
+fn recursive_test_inputs_without_queries() {
+    let recursive =
+        super::RecursiveExample::new(256, super::build_inner_options(8), build_options());
+    let inner_proof = recursive.inner_proof.clone();
+    let trace_info = TraceInfo {
+        length: inner_proof.trace_length(),
+        meta: Vec::new(),
+    };
+    let inner_air = fib2::FibAir::new(trace_info, recursive.result, inner_proof.options().clone());
+    let mut transcript =
+        verifier::read_transcript::<fib2::FibAir, Rp128_256>(inner_proof, recursive.result)
+            .unwrap();
+
+    transcript.query_positions.clear();
+    assert!(matches!(
+        super::PublicInputs::new(&inner_air, &transcript),
+        Err(VerifierError::NoQueries)
+    ));
+}
+
+fn build_options() -> ProofOptions {
+    ProofOptions::new(32, 16, 0, HashFunction::Blake3_256, FieldExtension::None)
+}
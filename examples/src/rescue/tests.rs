@@ -3,6 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crate::Example;
 use prover::{FieldExtension, HashFunction, ProofOptions};
 
 #[test]
@@ -23,6 +24,62 @@ fn rescue_test_basic_proof_verification_fail() {
     crate::tests::test_basic_proof_verification_fail(rescue_eg);
 }
 
+#[test]
+fn rescue_test_basic_proof_verification_rescue_hash() {
+    let options = ProofOptions::new(32, 16, 0, HashFunction::Rp128_256, FieldExtension::None);
+    let rescue_eg = Box::new(super::RescueExample::new(128, options));
+    crate::tests::test_basic_proof_verification(rescue_eg);
+}
+
+#[test]
+fn rescue_test_basic_proof_verification_poseidon_hash() {
+    let options = ProofOptions::new(
+        32,
+        16,
+        0,
+        HashFunction::Poseidon128_256,
+        FieldExtension::None,
+    );
+    let rescue_eg = Box::new(super::RescueExample::new(128, options));
+    crate::tests::test_basic_proof_verification(rescue_eg);
+}
+
+#[test]
+fn rescue_test_basic_proof_verification_truncated_hash() {
+    let options = ProofOptions::new(
+        32,
+        16,
+        0,
+        HashFunction::Blake3_192,
+        FieldExtension::Quadratic,
+    );
+    let rescue_eg = super::RescueExample::new(128, options);
+    let proof = rescue_eg.prove();
+    assert!(proof.security_level(true) <= 96);
+    assert!(rescue_eg.verify(proof).is_ok());
+}
+
+#[test]
+fn rescue_test_basic_proof_verification_merkle_cap() {
+    let options = build_options(false).with_merkle_cap_height(4);
+    let rescue_eg = super::RescueExample::new(128, options);
+    let proof = rescue_eg.prove();
+    assert_eq!(16 * 32, proof.commitments.trace_caps[0].len());
+    assert!(rescue_eg.verify(proof).is_ok());
+
+    // cap height exceeding depth of some of the trees is reduced for these trees
+    let options = build_options(true).with_merkle_cap_height(16);
+    let rescue_eg = Box::new(super::RescueExample::new(128, options));
+    crate::tests::test_basic_proof_verification(rescue_eg);
+}
+
+#[test]
+fn rescue_test_basic_proof_verification_merkle_cap_fail() {
+    let options = build_options(false).with_merkle_cap_height(4);
+    let rescue_eg = Box::new(super::RescueExample::new(128, options));
+    crate::tests::test_basic_proof_verification_fail(rescue_eg);
+}
+
 fn build_options(use_extension_field: bool) -> ProofOptions {
     let extension = if use_extension_field {
         FieldExtension::Quadratic
@@ -0,0 +1,342 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::utils::{
+    are_equal, is_binary, is_zero, not,
+    rescue::{
+        self, CYCLE_LENGTH as HASH_CYCLE_LEN, NUM_ROUNDS as NUM_HASH_ROUNDS,
+        STATE_WIDTH as HASH_STATE_WIDTH,
+    },
+    EvaluationResult, TreeNode,
+};
+use prover::{
+    crypto::LeafKey,
+    math::field::{f128::BaseElement, FieldElement},
+    Air, Assertion, ComputationContext, EvaluationFrame, ExecutionTrace, ProofOptions, TraceInfo,
+    TransitionConstraintDegree,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+const TRACE_WIDTH: usize = 15;
+
+/// Offset of the hash state which computes the root of the tree before an update.
+const OLD_HASH: usize = 0;
+
+/// Offset of the hash state which computes the root of the tree after an update.
+const NEW_HASH: usize = HASH_STATE_WIDTH;
+
+/// Register holding bits of the key of the leaf being updated.
+const KEY_BIT: usize = 2 * HASH_STATE_WIDTH;
+
+/// Offset of the two registers holding the root of the tree before the current update.
+const ROOT: usize = KEY_BIT + 1;
+
+// SPARSE MERKLE TREE UPDATE AIR
+// ================================================================================================
+
+pub struct PublicInputs {
+    pub old_root: [BaseElement; 2],
+    pub new_root: [BaseElement; 2],
+    pub tree_depth: usize,
+}
+
+/// A single update of a leaf in a sparse Merkle tree.
+pub struct TreeUpdate {
+    pub key: LeafKey,
+    pub old_value: TreeNode,
+    pub new_value: TreeNode,
+    /// Siblings of the nodes on the path from the leaf to the root, starting at the leaf level.
+    pub path: Vec<TreeNode>,
+}
+
+/// AIR for applying a sequence of updates to a sparse Merkle tree.
+///
+/// Every update occupies a segment of tree_depth * 8 steps in which two Merkle paths are
+/// computed side by side: the path from the old value of the leaf, and the path from the new
+/// value of the leaf. Both paths share the key bits and the siblings, and thus, if the first one
+/// resolves to the root of the tree before the update, the second one resolves to the root of
+/// the tree after the update. The root computed by the second path becomes the current root for
+/// the next update.
+pub struct SparseMerkleAir {
+    context: ComputationContext,
+    old_root: [BaseElement; 2],
+    new_root: [BaseElement; 2],
+    segment_length: usize,
+}
+
+impl Air for SparseMerkleAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        let segment_length = pub_inputs.tree_depth * HASH_CYCLE_LEN;
+        let mut degrees = Vec::with_capacity(19);
+        for _ in 0..(2 * HASH_STATE_WIDTH) {
+            degrees.push(TransitionConstraintDegree::with_cycles(
+                5,
+                vec![HASH_CYCLE_LEN],
+            ));
+        }
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            2,
+            vec![HASH_CYCLE_LEN],
+        ));
+        degrees.push(TransitionConstraintDegree::with_cycles(
+            2,
+            vec![HASH_CYCLE_LEN],
+        ));
+        degrees.push(TransitionConstraintDegree::new(2));
+        for _ in 0..4 {
+            degrees.push(TransitionConstraintDegree::with_cycles(
+                1,
+                vec![segment_length],
+            ));
+        }
+
+        // registers of the two hash states are committed to separately from the registers which
+        // describe the updates (key bits and the current root)
+        let context = ComputationContext::new(TRACE_WIDTH, trace_info.length, degrees, options)
+            .with_trace_segments(vec![KEY_BIT, TRACE_WIDTH - KEY_BIT]);
+        SparseMerkleAir {
+            context,
+            old_root: pub_inputs.old_root,
+            new_root: pub_inputs.new_root,
+            segment_length,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        let mut result = vec![
+            HASH_CYCLE_MASK.to_vec(),
+            HASH_START_MASK.to_vec(),
+            build_segment_mask(self.segment_length, self.segment_length - 2),
+            build_segment_mask(self.segment_length, self.segment_length - 1),
+        ];
+        result.append(&mut rescue::get_round_constants());
+        result
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        // assert that the first update starts from the old root, that the path computed for the
+        // last update resolves to the new root, and that capacity registers of both hash states
+        // are reset to ZERO every 8 steps
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(ROOT, 0, self.old_root[0]),
+            Assertion::single(ROOT + 1, 0, self.old_root[1]),
+            Assertion::single(NEW_HASH, last_step, self.new_root[0]),
+            Assertion::single(NEW_HASH + 1, last_step, self.new_root[1]),
+            Assertion::periodic(OLD_HASH + 4, 0, HASH_CYCLE_LEN, BaseElement::ZERO),
+            Assertion::periodic(OLD_HASH + 5, 0, HASH_CYCLE_LEN, BaseElement::ZERO),
+            Assertion::periodic(NEW_HASH + 4, 0, HASH_CYCLE_LEN, BaseElement::ZERO),
+            Assertion::periodic(NEW_HASH + 5, 0, HASH_CYCLE_LEN, BaseElement::ZERO),
+        ]
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        // split periodic values into masks and Rescue round constants
+        let hash_flag = periodic_values[0];
+        let hash_start_flag = periodic_values[1];
+        let check_flag = periodic_values[2];
+        let segment_end_flag = periodic_values[3];
+        let ark = &periodic_values[4..];
+
+        // when hash_flag = 1, constraints for Rescue rounds are enforced for both hash states
+        for &offset in [OLD_HASH, NEW_HASH].iter() {
+            let state = offset..(offset + HASH_STATE_WIDTH);
+            rescue::enforce_round(
+                &mut result[state.clone()],
+                &current[state.clone()],
+                &next[state],
+                ark,
+                hash_flag,
+            );
+        }
+
+        // when hash_flag = 0 and the update is not yet complete, make sure accumulated hashes
+        // are placed into the right places in both hash states: when key bit = 0, accumulated
+        // hash must go into registers [0, 1] of the state, and when key bit = 1, it must go into
+        // registers [2, 3]; at the end of a segment, the hash states are initialized with
+        // the values of the next leaf, and thus, these constraints are not enforced
+        let hash_init_flag = not(hash_flag);
+        let carry_flag = hash_init_flag * not(segment_end_flag);
+        let bit = next[KEY_BIT];
+        let not_bit = not(bit);
+        for &i in [OLD_HASH, NEW_HASH].iter() {
+            result.agg_constraint(i, carry_flag, not_bit * are_equal(current[i], next[i]));
+            let value = not_bit * are_equal(current[i + 1], next[i + 1]);
+            result.agg_constraint(i + 1, carry_flag, value);
+            result.agg_constraint(i + 2, carry_flag, bit * are_equal(current[i], next[i + 2]));
+            let value = bit * are_equal(current[i + 1], next[i + 3]);
+            result.agg_constraint(i + 3, carry_flag, value);
+
+            // make sure capacity registers of the hash state are reset to zeros
+            result.agg_constraint(i + 4, hash_init_flag, is_zero(next[i + 4]));
+            result.agg_constraint(i + 5, hash_init_flag, is_zero(next[i + 5]));
+        }
+
+        // at the start of every hash cycle, both hash states must contain the same sibling
+        let bit = current[KEY_BIT];
+        let not_bit = not(bit);
+        let left = not_bit * are_equal(current[OLD_HASH + 2], current[NEW_HASH + 2])
+            + bit * are_equal(current[OLD_HASH], current[NEW_HASH]);
+        let right = not_bit * are_equal(current[OLD_HASH + 3], current[NEW_HASH + 3])
+            + bit * are_equal(current[OLD_HASH + 1], current[NEW_HASH + 1]);
+        result.agg_constraint(12, hash_start_flag, left);
+        result.agg_constraint(13, hash_start_flag, right);
+
+        // values in the bit register must be binary
+        result[14] = is_binary(current[KEY_BIT]);
+
+        // the current root remains the same throughout a segment, and at the end of a segment it
+        // is replaced with the root computed from the new value of the leaf
+        for i in 0..2 {
+            result[15 + i] = not(segment_end_flag) * are_equal(next[ROOT + i], current[ROOT + i])
+                + segment_end_flag * are_equal(next[ROOT + i], current[NEW_HASH + i]);
+        }
+
+        // make sure the root computed from the old value of the leaf is the current root
+        result.agg_constraint(17, check_flag, are_equal(next[OLD_HASH], current[ROOT]));
+        result.agg_constraint(
+            18,
+            check_flag,
+            are_equal(next[OLD_HASH + 1], current[ROOT + 1]),
+        );
+    }
+}
+
+// TRACE GENERATOR
+// ================================================================================================
+
+pub fn build_trace(old_root: TreeNode, updates: &[TreeUpdate]) -> ExecutionTrace<BaseElement> {
+    // allocate memory to hold the trace table
+    let tree_depth = updates[0].path.len();
+    let segment_length = tree_depth * HASH_CYCLE_LEN;
+    let trace_length = updates.len() * segment_length;
+    let mut trace = ExecutionTrace::new(TRACE_WIDTH, trace_length);
+
+    trace.fill(
+        |state| {
+            // initialize hash states with the values of the first leaf, and set the current root
+            // to the old root of the tree
+            init_segment(state, &updates[0]);
+            state[ROOT] = old_root.0;
+            state[ROOT + 1] = old_root.1;
+        },
+        |step, state| {
+            // execute the transition function for all steps
+            //
+            // For the first 7 steps of each 8-step cycle, compute a single round of Rescue hash in
+            // both hash states. On the 8th step, insert the next sibling into both hash states in
+            // the positions defined by the next bit of the key; or, if this is the last cycle of
+            // a segment, move on to the next update.
+            let update = &updates[step / segment_length];
+            let cycle_num = (step % segment_length) / HASH_CYCLE_LEN;
+            let cycle_pos = step % HASH_CYCLE_LEN;
+
+            if cycle_pos < NUM_HASH_ROUNDS {
+                rescue::apply_round(&mut state[OLD_HASH..(OLD_HASH + HASH_STATE_WIDTH)], step);
+                rescue::apply_round(&mut state[NEW_HASH..(NEW_HASH + HASH_STATE_WIDTH)], step);
+            } else if cycle_num < tree_depth - 1 {
+                let level = cycle_num + 1;
+                let index_bit = update.key.bit(level);
+                let sibling = update.path[level];
+                for &offset in [OLD_HASH, NEW_HASH].iter() {
+                    let node = (state[offset], state[offset + 1]);
+                    set_hash_inputs(&mut state[offset..], node, sibling, index_bit);
+                }
+                state[KEY_BIT] = BaseElement::from(index_bit as u8);
+            } else {
+                // the root computed from the new value of the leaf becomes the current root
+                state[ROOT] = state[NEW_HASH];
+                state[ROOT + 1] = state[NEW_HASH + 1];
+                init_segment(state, &updates[step / segment_length + 1]);
+            }
+        },
+    );
+
+    // set key bit at the second step to one; this still results in a valid execution trace
+    // because key bits are read only at the first step of every hash cycle, but it ensures
+    // that there are no repeating patterns in the key bit register, and thus the degree
+    // of the key bit constraint is stable.
+    trace.set(KEY_BIT, 1, FieldElement::ONE);
+
+    trace
+}
+
+/// Initializes both hash states with the first sibling on the path of the specified update
+/// and the old and the new values of the updated leaf respectively.
+fn init_segment(state: &mut [BaseElement], update: &TreeUpdate) {
+    let index_bit = update.key.bit(0);
+    let sibling = update.path[0];
+    set_hash_inputs(&mut state[OLD_HASH..], update.old_value, sibling, index_bit);
+    set_hash_inputs(&mut state[NEW_HASH..], update.new_value, sibling, index_bit);
+    state[KEY_BIT] = BaseElement::from(index_bit as u8);
+}
+
+/// Places the node and its sibling into registers [0..4] of the hash state in the order defined
+/// by the index bit, and resets the capacity registers of the state to ZERO.
+fn set_hash_inputs(state: &mut [BaseElement], node: TreeNode, sibling: TreeNode, index_bit: bool) {
+    let (left, right) = if index_bit {
+        (sibling, node)
+    } else {
+        (node, sibling)
+    };
+    state[0] = left.0;
+    state[1] = left.1;
+    state[2] = right.0;
+    state[3] = right.1;
+    state[4] = BaseElement::ZERO;
+    state[5] = BaseElement::ZERO;
+}
+
+// MASKS
+// ================================================================================================
+const HASH_CYCLE_MASK: [BaseElement; HASH_CYCLE_LEN] = [
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ONE,
+    BaseElement::ZERO,
+];
+
+const HASH_START_MASK: [BaseElement; HASH_CYCLE_LEN] = [
+    BaseElement::ONE,
+    BaseElement::ZERO,
+    BaseElement::ZERO,
+    BaseElement::ZERO,
+    BaseElement::ZERO,
+    BaseElement::ZERO,
+    BaseElement::ZERO,
+    BaseElement::ZERO,
+];
+
+/// Returns a mask of the specified length with ONE at the specified position and ZEROs elsewhere.
+fn build_segment_mask(length: usize, position: usize) -> Vec<BaseElement> {
+    let mut result = vec![BaseElement::ZERO; length];
+    result[position] = BaseElement::ONE;
+    result
+}
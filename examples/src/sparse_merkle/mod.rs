@@ -0,0 +1,204 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{utils::TreeNode, Example, ExampleOptions};
+use log::debug;
+use prover::{
+    self,
+    crypto::{
+        hash::{rp128_256::ElementDigest, Rp128_256},
+        Digest, LeafKey, SparseMerkleTree,
+    },
+    math::{
+        field::{f128::BaseElement, FieldElement, StarkField},
+        utils::log2,
+    },
+    ProofOptions, StarkProof,
+};
+use std::time::Instant;
+use verifier::{self, VerifierError};
+
+mod air;
+use air::{build_trace, PublicInputs, SparseMerkleAir, TreeUpdate};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of keys inserted into the tree before the batch of updates is applied.
+const NUM_INITIAL_KEYS: usize = 64;
+
+// SPARSE MERKLE TREE UPDATE EXAMPLE
+// ================================================================================================
+pub fn get_example(
+    options: ExampleOptions,
+    tree_depth: usize,
+    num_updates: usize,
+) -> Box<dyn Example> {
+    Box::new(SparseMerkleExample::new(
+        tree_depth,
+        num_updates,
+        options.to_proof_options(28, 16),
+    ))
+}
+
+pub struct SparseMerkleExample {
+    options: ProofOptions,
+    old_root: TreeNode,
+    new_root: TreeNode,
+    updates: Vec<TreeUpdate>,
+}
+
+impl SparseMerkleExample {
+    pub fn new(tree_depth: usize, num_updates: usize, options: ProofOptions) -> Self {
+        assert!(
+            tree_depth.is_power_of_two(),
+            "tree depth must be a power of 2"
+        );
+        assert!(tree_depth <= 64, "tree depth cannot be greater than 64");
+        assert!(
+            num_updates.is_power_of_two(),
+            "number of updates must be a power of 2"
+        );
+
+        // populate the tree with some initial values
+        let now = Instant::now();
+        let mut tree = SparseMerkleTree::<Rp128_256>::new(tree_depth);
+        let keys = build_keys(tree_depth, NUM_INITIAL_KEYS + num_updates);
+        let values = BaseElement::prng_vector([1; 32], (NUM_INITIAL_KEYS + num_updates) * 2);
+        let initial_values = keys[..NUM_INITIAL_KEYS]
+            .iter()
+            .zip(values.chunks(2))
+            .map(|(&key, value)| (key, ElementDigest::new([value[0], value[1]])))
+            .collect::<Vec<_>>();
+        let old_root = tree.update_batch(&initial_values);
+        debug!(
+            "Built sparse Merkle tree of depth {} with {} keys in {} ms",
+            tree_depth,
+            tree.num_leaves(),
+            now.elapsed().as_millis(),
+        );
+
+        // apply the updates one by one and record the path of each updated leaf; the first half
+        // of the updates modifies existing keys and the second half inserts new keys, except for
+        // the last update which removes one of the existing keys from the tree
+        let now = Instant::now();
+        let mut updates = Vec::with_capacity(num_updates);
+        for i in 0..num_updates {
+            let (key, new_value) = if i == num_updates - 1 && num_updates > 1 {
+                (keys[NUM_INITIAL_KEYS - 1], ElementDigest::default())
+            } else {
+                let key = if i < num_updates / 2 {
+                    keys[i % NUM_INITIAL_KEYS]
+                } else {
+                    keys[NUM_INITIAL_KEYS + i]
+                };
+                let value = &values[(NUM_INITIAL_KEYS + i) * 2..];
+                (key, ElementDigest::new([value[0], value[1]]))
+            };
+
+            let old_value = tree.get(&key).unwrap_or_default();
+            let path = tree
+                .prove(&key)
+                .get_siblings()
+                .expect("failed to expand sparse Merkle path");
+            tree.insert(key, new_value);
+
+            updates.push(TreeUpdate {
+                key,
+                old_value: hash_to_node(old_value),
+                new_value: hash_to_node(new_value),
+                path: path.into_iter().map(hash_to_node).collect(),
+            });
+        }
+        debug!(
+            "Applied {} updates to the tree resulting in root {} in {} ms",
+            num_updates,
+            hex::encode(tree.root().as_bytes()),
+            now.elapsed().as_millis(),
+        );
+
+        SparseMerkleExample {
+            options,
+            old_root: hash_to_node(old_root),
+            new_root: hash_to_node(tree.root()),
+            updates,
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl Example for SparseMerkleExample {
+    fn prove(&self) -> StarkProof {
+        // generate the execution trace
+        debug!(
+            "Generating proof for applying {} updates to a sparse Merkle tree of depth {}\n\
+            ---------------------",
+            self.updates.len(),
+            self.updates[0].path.len()
+        );
+        let now = Instant::now();
+        let trace = build_trace(self.old_root, &self.updates);
+        let trace_length = trace.len();
+        debug!(
+            "Generated execution trace of {} registers and 2^{} steps in {} ms",
+            trace.width(),
+            log2(trace_length),
+            now.elapsed().as_millis()
+        );
+
+        // generate the proof
+        let pub_inputs = PublicInputs {
+            old_root: [self.old_root.0, self.old_root.1],
+            new_root: [self.new_root.0, self.new_root.1],
+            tree_depth: self.updates[0].path.len(),
+        };
+        prover::prove::<SparseMerkleAir>(trace, pub_inputs, self.options.clone()).unwrap()
+    }
+
+    fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            old_root: [self.old_root.0, self.old_root.1],
+            new_root: [self.new_root.0, self.new_root.1],
+            tree_depth: self.updates[0].path.len(),
+        };
+        verifier::verify::<SparseMerkleAir>(proof, pub_inputs)
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            old_root: [self.old_root.0, self.old_root.1],
+            new_root: [self.new_root.1, self.new_root.0],
+            tree_depth: self.updates[0].path.len(),
+        };
+        verifier::verify::<SparseMerkleAir>(proof, pub_inputs)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Generates the specified number of pseudo-random keys which fit into a tree of the
+/// specified depth.
+fn build_keys(tree_depth: usize, num_keys: usize) -> Vec<LeafKey> {
+    let mask = if tree_depth == 64 {
+        u64::MAX
+    } else {
+        (1u64 << tree_depth) - 1
+    };
+    BaseElement::prng_vector([2; 32], num_keys)
+        .into_iter()
+        .map(|e| LeafKey::from(e.as_int() as u64 & mask))
+        .collect()
+}
+
+fn hash_to_node(hash: ElementDigest) -> TreeNode {
+    let elements = hash.as_elements();
+    (elements[0], elements[1])
+}
@@ -0,0 +1,63 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::Example;
+use prover::{crypto::hash::Blake3_256, FieldExtension, HashFunction, ProofOptions};
+use verifier::VerifierError;
+
+#[test]
+fn sparse_merkle_test_basic_proof_verification() {
+    let example = Box::new(super::SparseMerkleExample::new(16, 4, build_options(false)));
+    crate::tests::test_basic_proof_verification(example);
+}
+
+#[test]
+fn sparse_merkle_test_basic_proof_verification_extension() {
+    let example = Box::new(super::SparseMerkleExample::new(16, 4, build_options(true)));
+    crate::tests::test_basic_proof_verification(example);
+}
+
+#[test]
+fn sparse_merkle_test_trace_segment_commitments() {
+    let example = super::SparseMerkleExample::new(16, 4, build_options(false));
+    let proof = example.prove();
+    assert_eq!(2, proof.commitments.trace_caps.len());
+    assert_eq!(2, proof.trace_queries.len());
+    assert!(example.verify(proof.clone()).is_ok());
+
+    // trace segment queries cannot be swapped
+    let mut proof = proof;
+    proof.trace_queries.swap(0, 1);
+    assert!(example.verify(proof).is_err());
+}
+
+#[test]
+fn sparse_merkle_test_transcript_unsupported() {
+    // transcripts cannot be read from proofs with multiple trace segments
+    let example = super::SparseMerkleExample::new(16, 4, build_options(false));
+    let proof = example.prove();
+    let pub_inputs = super::PublicInputs {
+        old_root: [example.old_root.0, example.old_root.1],
+        new_root: [example.new_root.0, example.new_root.1],
+        tree_depth: example.updates[0].path.len(),
+    };
+    let result = verifier::read_transcript::<super::SparseMerkleAir, Blake3_256>(proof, pub_inputs);
+    assert!(matches!(result, Err(VerifierError::UnsupportedTraceLayout)));
+}
+
+#[test]
+fn sparse_merkle_test_basic_proof_verification_fail() {
+    let example = Box::new(super::SparseMerkleExample::new(16, 4, build_options(false)));
+    crate::tests::test_basic_proof_verification_fail(example);
+}
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 16, 0, HashFunction::Blake3_256, extension)
+}
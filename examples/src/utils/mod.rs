@@ -4,13 +4,10 @@
 // LICENSE file in the root directory of this source tree.
 
 use prover::{
-    math::{
-        field::{f128::BaseElement, FieldElement, StarkField},
-        utils::read_elements_into_vec,
-    },
+    math::field::{f128::BaseElement, FieldElement, StarkField},
     ExecutionTrace,
 };
-use std::{convert::TryInto, ops::Range};
+use std::ops::Range;
 
 pub mod rescue;
 
@@ -61,17 +58,6 @@ impl<E: FieldElement> EvaluationResult<E> for Vec<E> {
 
 pub type TreeNode = (BaseElement, BaseElement);
 
-pub fn node_to_bytes(node: TreeNode) -> [u8; 32] {
-    BaseElement::elements_as_bytes(&[node.0, node.1])
-        .try_into()
-        .unwrap()
-}
-
-pub fn bytes_to_node(bytes: [u8; 32]) -> TreeNode {
-    let elements = read_elements_into_vec(&bytes).unwrap();
-    (elements[0], elements[1])
-}
-
 // OTHER FUNCTIONS
 // ================================================================================================
 
@@ -8,7 +8,8 @@ pub use prover::{DefaultProverChannel, FriProver, ProverChannel};
 
 mod verifier;
 pub use verifier::{
-    verify, DefaultVerifierChannel, VerifierChannel, VerifierContext, VerifierError,
+    verify, verify_batched, verify_remainder, DefaultVerifierChannel, VerifierChannel,
+    VerifierContext, VerifierError,
 };
 
 mod options;
@@ -20,5 +21,11 @@ pub use proof::{FriProof, FriProofLayer};
 mod public_coin;
 pub use public_coin::PublicCoin;
 
+mod low_degree;
+pub use low_degree::{prove_low_degree, verify_low_degree, LowDegreeOptions, LowDegreeProof};
+
+mod pcs;
+pub use pcs::{verify_opening, OpeningProof, PolyCommitment, PolyCommitter};
+
 pub mod folding;
 pub mod utils;
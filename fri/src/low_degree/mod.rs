@@ -0,0 +1,382 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{
+    utils, verifier, DefaultProverChannel, DefaultVerifierChannel, FriOptions, FriProof, FriProver,
+    VerifierChannel, VerifierContext, VerifierError,
+};
+use crypto::{
+    hash::{digests_as_bytes, read_digests_into_vec},
+    Digest, Hasher, MerkleTree,
+};
+use math::{
+    field::{FieldElement, StarkField},
+    utils::{get_power_series_with_offset, log2, read_elements_into_vec},
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+// LOW DEGREE PROOF OPTIONS
+// ================================================================================================
+
+/// Parameters of a standalone FRI low-degree proof.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LowDegreeOptions {
+    blowup_factor: usize,
+    num_queries: usize,
+    grinding_factor: u32,
+    merkle_cap_height: usize,
+}
+
+impl LowDegreeOptions {
+    /// Returns new options constructed from the specified parameters, which must comply with the
+    /// following:
+    /// * blowup_factor must be a power of two greater than 1; the evaluation domain is this many
+    ///   times larger than the smallest power of two greater than the degree of the polynomial;
+    /// * num_queries must be an integer between 1 and 128;
+    /// * grinding_factor must be an integer between 0 and 32.
+    pub fn new(blowup_factor: usize, num_queries: usize, grinding_factor: u32) -> Self {
+        assert!(
+            blowup_factor.is_power_of_two(),
+            "blowup_factor must be a power of 2"
+        );
+        assert!(blowup_factor >= 2, "blowup_factor cannot be smaller than 2");
+
+        assert!(num_queries > 0, "num_queries must be greater than 0");
+        assert!(num_queries <= 128, "num_queries cannot be greater than 128");
+
+        assert!(
+            grinding_factor <= 32,
+            "grinding factor cannot be greater than 32"
+        );
+
+        LowDegreeOptions {
+            blowup_factor,
+            num_queries,
+            grinding_factor,
+            merkle_cap_height: 0,
+        }
+    }
+
+    /// Returns these options with FRI layers committed to via Merkle tree caps of the specified
+    /// height rather than via tree roots.
+    pub fn with_merkle_cap_height(mut self, cap_height: usize) -> Self {
+        self.merkle_cap_height = cap_height;
+        self
+    }
+
+    pub fn blowup_factor(&self) -> usize {
+        self.blowup_factor
+    }
+
+    pub fn num_queries(&self) -> usize {
+        self.num_queries
+    }
+
+    pub fn grinding_factor(&self) -> u32 {
+        self.grinding_factor
+    }
+
+    pub fn merkle_cap_height(&self) -> usize {
+        self.merkle_cap_height
+    }
+
+    /// Returns the size of the evaluation domain for polynomials of degree at most `max_degree`.
+    pub fn domain_size(&self, max_degree: usize) -> usize {
+        (max_degree + 1).next_power_of_two() * self.blowup_factor
+    }
+
+    /// Returns FRI options for the evaluation domain shifted by the specified offset.
+    pub(crate) fn to_fri_options<B: StarkField>(&self, domain_offset: B) -> FriOptions<B> {
+        FriOptions::new(self.blowup_factor, domain_offset)
+            .with_merkle_cap_height(self.merkle_cap_height)
+    }
+}
+
+// LOW DEGREE PROOF
+// ================================================================================================
+
+/// A self-contained proof that a set of evaluations is a low-degree polynomial.
+///
+/// Besides the FRI proof, the proof contains caps of FRI layer commitments, the evaluations at
+/// the queried positions, and the proof-of-work nonce for the query seed. Digests and field
+/// elements are stored in serialized form so that the proof does not depend on the hash function
+/// or the field used to generate it.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LowDegreeProof {
+    pub layer_caps: Vec<Vec<u8>>,
+    pub queried_evaluations: Vec<u8>,
+    pub fri_proof: FriProof,
+    pub pow_nonce: u64,
+}
+
+// PROVER
+// ================================================================================================
+
+/// Generates a proof that `evaluations` are evaluations of a polynomial of degree at most
+/// `max_degree` over a multiplicative subgroup of the field shifted by `domain_offset`.
+///
+/// The subgroup is the one of size `options.domain_size(max_degree)`, and FRI layers are committed
+/// to using hash function `H`. Randomness for folding and query positions is derived from the
+/// layer commitments, and the query seed is ground according to the options.
+///
+/// # Panics
+/// Panics if the number of evaluations does not match the domain size.
+pub fn prove_low_degree<B, E, H>(
+    evaluations: Vec<E>,
+    domain_offset: B,
+    max_degree: usize,
+    options: &LowDegreeOptions,
+) -> LowDegreeProof
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+    H: Hasher,
+{
+    build_proof::<B, E, H>(vec![evaluations], domain_offset, max_degree, options).0
+}
+
+/// Builds a low-degree proof for a batch of evaluations as described in
+/// `FriProver::build_layers_batched()`, and returns it together with the positions at which the
+/// first evaluation vector was queried. Each of the remaining evaluation vectors is queried at
+/// these positions folded into its domain.
+pub(crate) fn build_proof<B, E, H>(
+    evaluations: Vec<Vec<E>>,
+    domain_offset: B,
+    max_degree: usize,
+    options: &LowDegreeOptions,
+) -> (LowDegreeProof, Vec<usize>)
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+    H: Hasher,
+{
+    let domain_size = options.domain_size(max_degree);
+    assert_eq!(
+        domain_size,
+        evaluations[0].len(),
+        "expected {} evaluations for a polynomial of degree {}, but received {}",
+        domain_size,
+        max_degree,
+        evaluations[0].len()
+    );
+    let domain_generator = B::get_root_of_unity(log2(domain_size));
+    let domain = get_power_series_with_offset(domain_generator, domain_offset, domain_size);
+
+    // commit to the FRI layers
+    let fri_options = options.to_fri_options(domain_offset);
+    let folding_factor = fri_options.folding_factor();
+    let mut channel = DefaultProverChannel::<H>::new(domain_size, options.num_queries());
+    let mut prover = FriProver::new(fri_options);
+    prover.build_layers_batched(&mut channel, evaluations.clone(), &domain);
+    let layer_caps = channel.layer_caps().to_vec();
+
+    // apply proof-of-work to the query seed, and use the result to draw query positions
+    let layer_roots = get_layer_roots::<H>(&layer_caps).expect("invalid layer cap");
+    let (query_seed, pow_nonce) = utils::find_pow_nonce::<H>(
+        build_query_seed::<H>(&layer_roots),
+        options.grinding_factor(),
+    );
+    let positions =
+        utils::draw_query_positions::<H>(query_seed, domain_size, options.num_queries());
+
+    // build the proof
+    let fri_proof = prover.build_proof(&positions);
+    let queried_evaluations = evaluations
+        .iter()
+        .flat_map(|evaluations| {
+            utils::fold_positions_to(&positions, domain_size, evaluations.len(), folding_factor)
+                .into_iter()
+                .map(move |p| evaluations[p])
+        })
+        .collect::<Vec<_>>();
+
+    let proof = LowDegreeProof {
+        layer_caps: layer_caps.iter().map(|cap| digests_as_bytes(cap)).collect(),
+        queried_evaluations: E::elements_as_bytes(&queried_evaluations).to_vec(),
+        fri_proof,
+        pow_nonce,
+    };
+    (proof, positions)
+}
+
+// VERIFIER
+// ================================================================================================
+
+/// Verifies that the specified `proof` attests to evaluations of a polynomial of degree at most
+/// `max_degree` over the domain shifted by `domain_offset`.
+///
+/// The parameters must be the same as the ones used to generate the proof with
+/// `prove_low_degree()`; returns an error if the proof is not valid.
+pub fn verify_low_degree<B, E, H>(
+    proof: LowDegreeProof,
+    domain_offset: B,
+    max_degree: usize,
+    options: &LowDegreeOptions,
+) -> Result<(), VerifierError>
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+    H: Hasher,
+{
+    let (layer_caps, positions) = read_query_positions::<B, H>(&proof, max_degree, options)?;
+    verify_queries::<B, E, H>(
+        proof,
+        layer_caps,
+        &positions,
+        &[options.domain_size(max_degree)],
+        domain_offset,
+        max_degree,
+        options,
+    )?;
+    Ok(())
+}
+
+/// Parses FRI layer commitments of the specified proof, verifies proof-of-work for the query
+/// seed, and draws query positions; returns the parsed layer caps together with the positions.
+#[allow(clippy::type_complexity)]
+pub(crate) fn read_query_positions<B, H>(
+    proof: &LowDegreeProof,
+    max_degree: usize,
+    options: &LowDegreeOptions,
+) -> Result<(Vec<Vec<H::Digest>>, Vec<usize>), VerifierError>
+where
+    B: StarkField,
+    H: Hasher,
+{
+    let domain_size = options.domain_size(max_degree);
+
+    // parse layer commitments; there is one commitment for each FRI layer and one for the
+    // remainder
+    let num_layers = options
+        .to_fri_options(B::GENERATOR)
+        .num_fri_layers(domain_size);
+    if proof.fri_proof.layers.len() != num_layers {
+        return Err(VerifierError::LayerCountMismatch(
+            num_layers,
+            proof.fri_proof.layers.len(),
+        ));
+    }
+    if proof.layer_caps.len() != num_layers + 1 {
+        return Err(VerifierError::LayerCommitmentCountMismatch(
+            num_layers + 1,
+            proof.layer_caps.len(),
+        ));
+    }
+    let mut layer_caps = Vec::with_capacity(proof.layer_caps.len());
+    for (layer_idx, cap_bytes) in proof.layer_caps.iter().enumerate() {
+        let cap = read_digests_into_vec::<H::Digest>(cap_bytes)
+            .map_err(|_| VerifierError::LayerCapNotValid(layer_idx))?;
+        layer_caps.push(cap);
+    }
+
+    // verify proof-of-work and draw query positions
+    let layer_roots = get_layer_roots::<H>(&layer_caps)?;
+    let query_seed = utils::verify_pow_nonce::<H>(
+        build_query_seed::<H>(&layer_roots),
+        proof.pow_nonce,
+        options.grinding_factor(),
+    )
+    .ok_or(VerifierError::QuerySeedProofOfWorkVerificationFailed)?;
+    let positions =
+        utils::draw_query_positions::<H>(query_seed, domain_size, options.num_queries());
+
+    Ok((layer_caps, positions))
+}
+
+/// Verifies the FRI proof contained in the specified proof against queried evaluations of a
+/// batch of functions over domains of the specified sizes, and returns the evaluations of each
+/// function; `layer_caps` and `positions` must be the ones returned from `read_query_positions()`
+/// for the same proof.
+pub(crate) fn verify_queries<B, E, H>(
+    proof: LowDegreeProof,
+    layer_caps: Vec<Vec<H::Digest>>,
+    positions: &[usize],
+    domain_sizes: &[usize],
+    domain_offset: B,
+    max_degree: usize,
+    options: &LowDegreeOptions,
+) -> Result<Vec<Vec<E>>, VerifierError>
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+    H: Hasher,
+{
+    let domain_size = options.domain_size(max_degree);
+    let fri_options = options.to_fri_options(domain_offset);
+
+    // read evaluations at the queried positions; each function is queried at the positions
+    // folded into its domain
+    let num_evaluations = domain_sizes
+        .iter()
+        .map(|&size| {
+            utils::fold_positions_to(positions, domain_size, size, fri_options.folding_factor())
+                .len()
+        })
+        .collect::<Vec<_>>();
+    let evaluations = read_elements_into_vec::<E>(&proof.queried_evaluations)
+        .map_err(|err| VerifierError::QueryValuesDeserializationError(err.to_string()))?;
+    let expected_len = num_evaluations.iter().sum::<usize>();
+    if evaluations.len() != expected_len {
+        return Err(VerifierError::QueryValuesDeserializationError(format!(
+            "expected {} values, but was {}",
+            expected_len,
+            evaluations.len()
+        )));
+    }
+    let mut remaining = evaluations.as_slice();
+    let mut batch = Vec::with_capacity(domain_sizes.len());
+    for &n in num_evaluations.iter() {
+        let (evaluations, rest) = remaining.split_at(n);
+        batch.push(evaluations.to_vec());
+        remaining = rest;
+    }
+
+    // verify the FRI proof against the evaluations
+    let channel = DefaultVerifierChannel::<E, H>::new(
+        proof.fri_proof,
+        layer_caps,
+        domain_size,
+        &fri_options,
+    )?;
+    let context = VerifierContext::new(
+        domain_size,
+        max_degree,
+        channel.num_fri_partitions(),
+        fri_options,
+    );
+    let evaluations = batch.iter().map(|e| e.as_slice()).collect::<Vec<_>>();
+    verifier::verify_batched(&context, &channel, &evaluations, domain_sizes, positions)?;
+
+    Ok(batch)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Computes roots of FRI layer Merkle trees from the layer caps.
+fn get_layer_roots<H: Hasher>(
+    layer_caps: &[Vec<H::Digest>],
+) -> Result<Vec<H::Digest>, VerifierError> {
+    layer_caps
+        .iter()
+        .enumerate()
+        .map(|(layer_idx, cap)| {
+            MerkleTree::<H>::get_root_from_cap(cap)
+                .ok_or(VerifierError::LayerCapNotValid(layer_idx))
+        })
+        .collect()
+}
+
+/// Hashes roots of all FRI layers into a single digest.
+fn build_query_seed<H: Hasher>(layer_roots: &[H::Digest]) -> H::Digest {
+    let mut root_bytes: Vec<u8> = Vec::with_capacity(layer_roots.len() * 32);
+    for root in layer_roots.iter() {
+        root_bytes.extend_from_slice(&root.as_bytes());
+    }
+    H::hash(&root_bytes)
+}
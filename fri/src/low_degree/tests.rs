@@ -0,0 +1,112 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{prove_low_degree, verify_low_degree, LowDegreeOptions};
+use crate::VerifierError;
+use crypto::hash::Blake3_256;
+use math::{
+    fft,
+    field::{f128::BaseElement, FieldElement, QuadExtension, StarkField},
+};
+
+type QuadElement = QuadExtension<BaseElement>;
+
+#[test]
+fn low_degree_prove_verify() {
+    let options = LowDegreeOptions::new(8, 32, 0);
+    let evaluations = build_evaluations::<BaseElement>(1024, 8);
+    let proof =
+        prove_low_degree::<_, _, Blake3_256>(evaluations, BaseElement::GENERATOR, 1023, &options);
+    let result = verify_low_degree::<_, BaseElement, Blake3_256>(
+        proof,
+        BaseElement::GENERATOR,
+        1023,
+        &options,
+    );
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+}
+
+#[test]
+fn low_degree_prove_verify_with_grinding_and_cap() {
+    let options = LowDegreeOptions::new(4, 16, 8).with_merkle_cap_height(2);
+    let evaluations = build_evaluations::<QuadElement>(2048, 4);
+    let proof =
+        prove_low_degree::<_, _, Blake3_256>(evaluations, BaseElement::GENERATOR, 2047, &options);
+
+    // the proof can be serialized and deserialized
+    let proof_bytes = bincode::serialize(&proof).unwrap();
+    let proof = bincode::deserialize(&proof_bytes).unwrap();
+
+    let result = verify_low_degree::<_, QuadElement, Blake3_256>(
+        proof,
+        BaseElement::GENERATOR,
+        2047,
+        &options,
+    );
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+}
+
+#[test]
+fn low_degree_verify_fail() {
+    let options = LowDegreeOptions::new(8, 32, 8);
+
+    // evaluations of a polynomial of degree 2047 cannot pass as a degree 1023 polynomial
+    let evaluations = build_evaluations::<BaseElement>(2048, 4);
+    let proof = prove_low_degree::<_, _, Blake3_256>(
+        evaluations.clone(),
+        BaseElement::GENERATOR,
+        1023,
+        &options,
+    );
+    let result = verify_low_degree::<_, BaseElement, Blake3_256>(
+        proof,
+        BaseElement::GENERATOR,
+        1023,
+        &options,
+    );
+    assert!(result.is_err());
+
+    // a proof with invalid proof-of-work is rejected
+    let evaluations = build_evaluations::<BaseElement>(1024, 8);
+    let mut proof =
+        prove_low_degree::<_, _, Blake3_256>(evaluations, BaseElement::GENERATOR, 1023, &options);
+    proof.pow_nonce += 1;
+    let result = verify_low_degree::<_, BaseElement, Blake3_256>(
+        proof.clone(),
+        BaseElement::GENERATOR,
+        1023,
+        &options,
+    );
+    assert!(matches!(
+        result,
+        Err(VerifierError::QuerySeedProofOfWorkVerificationFailed)
+    ));
+
+    // a proof cannot be verified against a different degree bound
+    let result = verify_low_degree::<_, BaseElement, Blake3_256>(
+        proof,
+        BaseElement::GENERATOR,
+        4095,
+        &options,
+    );
+    assert!(matches!(
+        result,
+        Err(VerifierError::LayerCountMismatch(_, _))
+    ));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns evaluations of a random polynomial with `num_coefficients` coefficients over a domain
+/// `blowup_factor` times larger and shifted by the field generator.
+fn build_evaluations<E: FieldElement + From<BaseElement>>(
+    num_coefficients: usize,
+    blowup_factor: usize,
+) -> Vec<E> {
+    let poly = E::prng_vector([1; 32], num_coefficients);
+    let twiddles = fft::get_twiddles::<BaseElement>(num_coefficients);
+    fft::evaluate_poly_with_offset(&poly, &twiddles, BaseElement::GENERATOR, blowup_factor)
+}
@@ -21,6 +21,8 @@ pub struct FriOptions<B: StarkField> {
     folding_factor: usize,
     max_remainder_length: usize,
     blowup_factor: usize,
+    merkle_cap_height: usize,
+    partitioned: bool,
 }
 
 impl<B: StarkField> FriOptions<B> {
@@ -30,9 +32,26 @@ impl<B: StarkField> FriOptions<B> {
             folding_factor: FOLDING_FACTOR,
             max_remainder_length: MAX_REMAINDER_LENGTH,
             blowup_factor,
+            merkle_cap_height: 0,
+            partitioned: false,
         }
     }
 
+    /// Returns these options with FRI layers committed to via Merkle tree caps of the specified
+    /// height rather than via tree roots.
+    pub fn with_merkle_cap_height(mut self, cap_height: usize) -> Self {
+        self.merkle_cap_height = cap_height;
+        self
+    }
+
+    /// Returns these options with FRI proofs split into partitions which can be verified
+    /// independently. Queries are partitioned by their position in the remainder, and leaves of
+    /// layer commitment trees are arranged so that each partition forms a contiguous subtree.
+    pub fn with_partitions(mut self) -> Self {
+        self.partitioned = true;
+        self
+    }
+
     pub fn domain_offset(&self) -> B {
         self.domain_offset
     }
@@ -49,6 +68,24 @@ impl<B: StarkField> FriOptions<B> {
         self.blowup_factor
     }
 
+    pub fn merkle_cap_height(&self) -> usize {
+        self.merkle_cap_height
+    }
+
+    pub fn partitioned(&self) -> bool {
+        self.partitioned
+    }
+
+    /// Returns the number of partitions for FRI proofs over the domain of the specified size; this
+    /// is the length of the remainder for partitioned proofs, and 1 otherwise.
+    pub fn num_fri_partitions(&self, domain_size: usize) -> usize {
+        if self.partitioned {
+            self.fri_remainder_length(domain_size)
+        } else {
+            1
+        }
+    }
+
     pub fn num_fri_layers(&self, mut domain_size: usize) -> usize {
         let mut result = 0;
         while domain_size > self.max_remainder_length {
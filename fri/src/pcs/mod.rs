@@ -0,0 +1,500 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{
+    low_degree::{build_proof, read_query_positions, verify_queries},
+    options::FOLDING_FACTOR,
+    utils::fold_positions_to,
+    LowDegreeOptions, LowDegreeProof, VerifierError,
+};
+use crypto::{
+    hash::{digests_as_bytes, read_digests_into_vec},
+    BatchMerkleProof, DefaultRandomElementGenerator, Digest, Hasher, MerkleTree,
+    RandomElementGenerator,
+};
+use math::{
+    fft,
+    field::{FieldElement, StarkField},
+    utils::{batch_inversion, get_power_series_with_offset, log2, read_elements_into_vec},
+};
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+#[cfg(test)]
+mod tests;
+
+// POLYNOMIAL COMMITMENT
+// ================================================================================================
+
+/// A commitment to a set of polynomials.
+///
+/// The commitment consists of the degree bounds of the committed polynomials, and of one Merkle
+/// tree cap per group of polynomials; see [PolyCommitter] for how polynomials are grouped.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PolyCommitment {
+    pub caps: Vec<Vec<u8>>,
+    pub degree_bounds: Vec<usize>,
+}
+
+impl PolyCommitment {
+    /// Returns the number of committed polynomials.
+    pub fn num_polys(&self) -> usize {
+        self.degree_bounds.len()
+    }
+
+    /// Returns the size of the domain over which the polynomials of the largest degree were
+    /// evaluated.
+    pub fn domain_size(&self, options: &LowDegreeOptions) -> usize {
+        options.domain_size(self.composition_degree())
+    }
+
+    /// Returns the degree of the combined quotients of the polynomials of the largest degree;
+    /// this is the degree proven by the low-degree test.
+    fn composition_degree(&self) -> usize {
+        let max_degree = self.degree_bounds.iter().max().copied().unwrap_or(0);
+        (max_degree + 1).next_power_of_two() - 1
+    }
+
+    /// Splits the committed polynomials into groups, one per FRI layer into which the combined
+    /// quotients of the group are injected; groups are sorted by the depth of their layer.
+    ///
+    /// A polynomial is placed into the deepest layer whose degree can still accommodate its
+    /// quotients, and the first group always contains the polynomials of the largest degree.
+    fn groups<B: StarkField>(&self, options: &LowDegreeOptions) -> Vec<PolyGroup> {
+        let fri_options = options.to_fri_options(B::GENERATOR);
+        let folding_factor = fri_options.folding_factor();
+        let domain_size = self.domain_size(options);
+        let num_layers = fri_options.num_fri_layers(domain_size);
+
+        let mut groups: Vec<PolyGroup> = Vec::new();
+        for (j, &degree_bound) in self.degree_bounds.iter().enumerate() {
+            let mut depth = 0;
+            let mut layer_size = self.composition_degree() + 1;
+            while depth < num_layers && degree_bound.max(1) <= layer_size / folding_factor {
+                layer_size /= folding_factor;
+                depth += 1;
+            }
+            match groups.iter_mut().find(|group| group.depth == depth) {
+                Some(group) => group.polys.push(j),
+                None => groups.push(PolyGroup {
+                    depth,
+                    domain_size: domain_size / folding_factor.pow(depth as u32),
+                    composition_degree: layer_size - 1,
+                    polys: vec![j],
+                }),
+            }
+        }
+        groups.sort_by_key(|group| group.depth);
+        groups
+    }
+}
+
+/// A group of polynomials committed to with the same Merkle tree.
+struct PolyGroup {
+    /// Depth of the FRI layer into which the combined quotients of the group are injected.
+    depth: usize,
+    /// Size of the domain over which the polynomials of the group are evaluated.
+    domain_size: usize,
+    /// Degree to which the quotients of the group are adjusted before being combined.
+    composition_degree: usize,
+    /// Indexes of the polynomials in the group.
+    polys: Vec<usize>,
+}
+
+/// A proof that a set of committed polynomials evaluates to the specified values at a set of
+/// points.
+///
+/// The proof contains evaluations of all polynomials at the points, rows of the committed
+/// evaluations of each group of polynomials at query positions together with their Merkle
+/// authentication paths, and a batched low-degree proof for the random linear combinations of
+/// quotients of the polynomials in each group.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OpeningProof {
+    pub values: Vec<u8>,
+    pub queried_rows: Vec<Vec<u8>>,
+    pub paths: Vec<Vec<Vec<u8>>>,
+    pub low_degree_proof: LowDegreeProof,
+}
+
+// PROVER
+// ================================================================================================
+
+/// Commits to a set of polynomials and opens them at arbitrary points.
+///
+/// Polynomials are grouped by degree so that the degree bounds of all polynomials in a group fit
+/// into the same FRI layer. Polynomials of each group are evaluated over a multiplicative coset
+/// of the size of that layer, and the evaluations are committed to with a Merkle tree in which
+/// each leaf is a hash of evaluations of all polynomials of the group at one point of the domain.
+/// An opening at a set of points is proven with a single batched FRI instance regardless of the
+/// number of polynomials and points: the combined quotients of the first group are folded by
+/// FRI, and those of every other group are injected into the layer of the group.
+pub struct PolyCommitter<B: StarkField, E: FieldElement + From<B>, H: Hasher> {
+    polys: Vec<Vec<E>>,
+    groups: Vec<PolyGroup>,
+    rows: Vec<Vec<Vec<E>>>,
+    trees: Vec<MerkleTree<H>>,
+    commitment: PolyCommitment,
+    options: LowDegreeOptions,
+    _base_field: PhantomData<B>,
+}
+
+impl<B: StarkField, E: FieldElement + From<B>, H: Hasher> PolyCommitter<B, E, H> {
+    /// Returns a new committer for the specified polynomials; each polynomial is described by
+    /// its coefficients in reverse order, and its degree bound is the number of coefficients
+    /// minus one.
+    ///
+    /// # Panics
+    /// Panics if `polys` is empty or if any of the polynomials has no coefficients.
+    pub fn new(polys: Vec<Vec<E>>, options: LowDegreeOptions) -> Self {
+        assert!(
+            !polys.is_empty(),
+            "at least one polynomial must be provided"
+        );
+        assert!(
+            polys.iter().all(|poly| !poly.is_empty()),
+            "polynomials must have at least one coefficient"
+        );
+
+        let mut commitment = PolyCommitment {
+            caps: Vec::new(),
+            degree_bounds: polys.iter().map(|poly| poly.len() - 1).collect(),
+        };
+        let groups = commitment.groups::<B>(&options);
+
+        let mut rows = Vec::with_capacity(groups.len());
+        let mut trees = Vec::with_capacity(groups.len());
+        for group in groups.iter() {
+            // evaluate polynomials of the group over the domain of the group, and transpose
+            // evaluations into rows
+            let columns = group
+                .polys
+                .iter()
+                .map(|&j| {
+                    // FFT requires at least two coefficients, so constants are padded as well
+                    let mut poly = polys[j].clone();
+                    poly.resize(poly.len().next_power_of_two().max(2), E::ZERO);
+                    let twiddles = fft::get_twiddles::<B>(poly.len());
+                    let blowup_factor = group.domain_size / poly.len();
+                    fft::evaluate_poly_with_offset(&poly, &twiddles, B::GENERATOR, blowup_factor)
+                })
+                .collect::<Vec<_>>();
+            let group_rows = (0..group.domain_size)
+                .map(|i| columns.iter().map(|column| column[i]).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+
+            // commit to the rows
+            let leaves = group_rows.iter().map(|row| H::hash_elements(row)).collect();
+            let tree = MerkleTree::<H>::new(leaves);
+            commitment
+                .caps
+                .push(digests_as_bytes(&tree.cap(options.merkle_cap_height())));
+            rows.push(group_rows);
+            trees.push(tree);
+        }
+
+        PolyCommitter {
+            polys,
+            groups,
+            rows,
+            trees,
+            commitment,
+            options,
+            _base_field: PhantomData,
+        }
+    }
+
+    /// Returns a commitment to the polynomials of this committer.
+    pub fn commitment(&self) -> &PolyCommitment {
+        &self.commitment
+    }
+
+    /// Returns evaluations of all polynomials at each of the specified points.
+    pub fn evaluate(&self, points: &[E]) -> Vec<Vec<E>> {
+        points
+            .iter()
+            .map(|&z| {
+                self.polys
+                    .iter()
+                    .map(|poly| math::polynom::eval(poly, z))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns a proof of evaluations of all polynomials at each of the specified points.
+    ///
+    /// # Panics
+    /// Panics if `points` is empty or if any of the points is in the evaluation domain.
+    pub fn open(&self, points: &[E]) -> OpeningProof {
+        assert!(!points.is_empty(), "at least one point must be provided");
+        let values = self.evaluate(points);
+
+        // draw random coefficients for combining quotients of all polynomials
+        let coefficients = Coefficients::draw::<H>(&self.commitment, points, &values);
+
+        // evaluate the combination of quotients of each group over the domain of the group
+        let mut evaluations = Vec::with_capacity(self.groups.len());
+        for (group, rows) in self.groups.iter().zip(self.rows.iter()) {
+            let domain_generator = B::get_root_of_unity(log2(group.domain_size));
+            let domain =
+                get_power_series_with_offset(domain_generator, B::GENERATOR, group.domain_size);
+            let denominators = domain
+                .iter()
+                .flat_map(|&x| points.iter().map(move |&z| E::from(x) - z))
+                .collect::<Vec<_>>();
+            assert!(
+                !denominators.contains(&E::ZERO),
+                "opening points cannot be in the evaluation domain"
+            );
+            let inv_denominators = batch_inversion(&denominators);
+            let group_evaluations = domain
+                .iter()
+                .zip(rows.iter())
+                .zip(inv_denominators.chunks(points.len()))
+                .map(|((&x, row), inv_denominators)| {
+                    let degree_bounds = &self.commitment.degree_bounds;
+                    coefficients.compose(x, row, group, &values, inv_denominators, degree_bounds)
+                })
+                .collect::<Vec<_>>();
+            evaluations.push(group_evaluations);
+        }
+
+        // prove that the combinations are of low degree, and open the rows of each group at the
+        // positions queried by the low-degree proof
+        let domain_size = self.commitment.domain_size(&self.options);
+        let (low_degree_proof, positions) = build_proof::<B, E, H>(
+            evaluations,
+            B::GENERATOR,
+            self.commitment.composition_degree(),
+            &self.options,
+        );
+        let mut queried_rows = Vec::with_capacity(self.groups.len());
+        let mut paths = Vec::with_capacity(self.groups.len());
+        for ((group, rows), tree) in self.groups.iter().zip(&self.rows).zip(&self.trees) {
+            let positions =
+                fold_positions_to(&positions, domain_size, group.domain_size, FOLDING_FACTOR);
+            let group_rows = positions
+                .iter()
+                .flat_map(|&p| rows[p].iter().copied())
+                .collect::<Vec<_>>();
+            queried_rows.push(E::elements_as_bytes(&group_rows).to_vec());
+            let merkle_proof =
+                tree.prove_batch_to_cap(&positions, self.options.merkle_cap_height());
+            paths.push(merkle_proof.serialize_nodes());
+        }
+
+        OpeningProof {
+            values: E::elements_as_bytes(&values.concat()).to_vec(),
+            queried_rows,
+            paths,
+            low_degree_proof,
+        }
+    }
+}
+
+// VERIFIER
+// ================================================================================================
+
+/// Verifies that polynomials described by the `commitment` evaluate to the values contained in
+/// the `proof` at each of the specified points, and returns the values.
+///
+/// The values are returned as one vector per point, with each vector containing a value for
+/// every committed polynomial. The options must be the same as the ones used to commit to the
+/// polynomials; returns an error if the proof is not valid.
+pub fn verify_opening<B, E, H>(
+    commitment: &PolyCommitment,
+    points: &[E],
+    proof: OpeningProof,
+    options: &LowDegreeOptions,
+) -> Result<Vec<Vec<E>>, VerifierError>
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+    H: Hasher,
+{
+    let num_polys = commitment.num_polys();
+    let domain_size = commitment.domain_size(options);
+    let groups = commitment.groups::<B>(options);
+    if commitment.caps.len() != groups.len() {
+        return Err(VerifierError::OpeningDeserializationError(format!(
+            "expected {} commitment caps, but was {}",
+            groups.len(),
+            commitment.caps.len()
+        )));
+    }
+
+    // parse opened values
+    let values = read_elements_into_vec::<E>(&proof.values)
+        .map_err(|err| VerifierError::OpeningDeserializationError(err.to_string()))?;
+    if num_polys == 0 || points.is_empty() || values.len() != num_polys * points.len() {
+        return Err(VerifierError::OpeningDeserializationError(format!(
+            "expected {} values, but was {}",
+            num_polys * points.len(),
+            values.len()
+        )));
+    }
+    let values = values
+        .chunks(num_polys)
+        .map(|v| v.to_vec())
+        .collect::<Vec<_>>();
+
+    // draw query positions
+    let max_degree = commitment.composition_degree();
+    let (layer_caps, positions) =
+        read_query_positions::<B, H>(&proof.low_degree_proof, max_degree, options)?;
+    if proof.queried_rows.len() != groups.len() || proof.paths.len() != groups.len() {
+        return Err(VerifierError::OpeningDeserializationError(format!(
+            "expected queried rows for {} groups, but was {}",
+            groups.len(),
+            proof.queried_rows.len()
+        )));
+    }
+
+    // for each group, make sure the queried rows are in the commitment, and compute the
+    // combination of quotients at the queried positions from the queried rows
+    let coefficients = Coefficients::draw::<H>(commitment, points, &values);
+    let mut expected_evaluations = Vec::with_capacity(groups.len());
+    for (i, group) in groups.iter().enumerate() {
+        let positions =
+            fold_positions_to(&positions, domain_size, group.domain_size, FOLDING_FACTOR);
+        let cap = read_digests_into_vec::<H::Digest>(&commitment.caps[i])
+            .map_err(|err| VerifierError::OpeningDeserializationError(err.to_string()))?;
+        let rows = read_elements_into_vec::<E>(&proof.queried_rows[i])
+            .map_err(|err| VerifierError::OpeningDeserializationError(err.to_string()))?;
+        if rows.len() != positions.len() * group.polys.len() {
+            return Err(VerifierError::OpeningDeserializationError(format!(
+                "expected {} queried values in group {}, but was {}",
+                positions.len() * group.polys.len(),
+                i,
+                rows.len()
+            )));
+        }
+        let rows = rows.chunks(group.polys.len()).collect::<Vec<_>>();
+        let leaves = rows.iter().map(|row| H::hash_elements(row)).collect();
+        let merkle_proof = BatchMerkleProof::<H>::deserialize(
+            &proof.paths[i],
+            leaves,
+            log2(group.domain_size) as u8,
+        )
+        .map_err(|err| VerifierError::OpeningDeserializationError(err.to_string()))?;
+        if !MerkleTree::<H>::verify_batch_with_cap(&cap, &positions, &merkle_proof) {
+            return Err(VerifierError::OpeningCommitmentMismatch);
+        }
+
+        let domain_generator = B::get_root_of_unity(log2(group.domain_size));
+        let xs = positions
+            .iter()
+            .map(|&p| B::GENERATOR * domain_generator.exp((p as u32).into()))
+            .collect::<Vec<_>>();
+        let denominators = xs
+            .iter()
+            .flat_map(|&x| points.iter().map(move |&z| E::from(x) - z))
+            .collect::<Vec<_>>();
+        if denominators.contains(&E::ZERO) {
+            return Err(VerifierError::OpeningValuesNotConsistent);
+        }
+        let inv_denominators = batch_inversion(&denominators);
+        let degree_bounds = &commitment.degree_bounds;
+        let group_evaluations = xs
+            .iter()
+            .zip(rows.iter())
+            .zip(inv_denominators.chunks(points.len()))
+            .map(|((&x, row), inv_denominators)| {
+                coefficients.compose(x, row, group, &values, inv_denominators, degree_bounds)
+            })
+            .collect::<Vec<_>>();
+        expected_evaluations.push(group_evaluations);
+    }
+
+    // verify that the combinations are of low degree and are consistent with the queried rows
+    let domain_sizes = groups
+        .iter()
+        .map(|group| group.domain_size)
+        .collect::<Vec<_>>();
+    let evaluations = verify_queries::<B, E, H>(
+        proof.low_degree_proof,
+        layer_caps,
+        &positions,
+        &domain_sizes,
+        B::GENERATOR,
+        max_degree,
+        options,
+    )?;
+    if evaluations != expected_evaluations {
+        return Err(VerifierError::OpeningValuesNotConsistent);
+    }
+
+    Ok(values)
+}
+
+// COMBINATION COEFFICIENTS
+// ================================================================================================
+
+/// Random coefficients for combining quotients of all polynomials into a single polynomial.
+struct Coefficients<E: FieldElement> {
+    /// One coefficient per polynomial per point, for combining quotients of each polynomial.
+    quotients: Vec<Vec<E>>,
+    /// Pairs of coefficients for adjusting degrees of the combined quotients of each polynomial.
+    degrees: Vec<(E, E)>,
+}
+
+impl<E: FieldElement> Coefficients<E> {
+    /// Draws coefficients from a seed derived from the commitment, the points, and the values
+    /// of the polynomials at these points.
+    fn draw<H: Hasher>(commitment: &PolyCommitment, points: &[E], values: &[Vec<E>]) -> Self {
+        let mut seed_bytes = commitment.caps.concat();
+        for &degree_bound in commitment.degree_bounds.iter() {
+            seed_bytes.extend_from_slice(&(degree_bound as u64).to_le_bytes());
+        }
+        seed_bytes.extend_from_slice(E::elements_as_bytes(points));
+        for v in values.iter() {
+            seed_bytes.extend_from_slice(E::elements_as_bytes(v));
+        }
+
+        let seed = H::hash(&seed_bytes).as_bytes();
+        let mut generator = DefaultRandomElementGenerator::<H>::new(seed, 0);
+        let num_polys = commitment.num_polys();
+        Coefficients {
+            quotients: (0..num_polys)
+                .map(|_| (0..points.len()).map(|_| generator.draw()).collect())
+                .collect(),
+            degrees: (0..num_polys).map(|_| generator.draw_pair()).collect(),
+        }
+    }
+
+    /// Computes the combination of quotients (p_j(x) - v_jl) / (x - z_l) at the specified x for
+    /// polynomials p_j of the specified group, with the quotient of each polynomial adjusted to
+    /// the composition degree of the group; `row` contains evaluations of the polynomials of
+    /// the group at x.
+    fn compose<B: StarkField>(
+        &self,
+        x: B,
+        row: &[E],
+        group: &PolyGroup,
+        values: &[Vec<E>],
+        inv_denominators: &[E],
+        degree_bounds: &[usize],
+    ) -> E
+    where
+        E: From<B>,
+    {
+        let mut result = E::ZERO;
+        for (&p_x, &j) in row.iter().zip(&group.polys) {
+            let degree_bound = degree_bounds[j];
+            let quotient = values
+                .iter()
+                .zip(inv_denominators)
+                .zip(&self.quotients[j])
+                .fold(E::ZERO, |acc, ((v, &inv_denominator), &coefficient)| {
+                    acc + (p_x - v[j]) * inv_denominator * coefficient
+                });
+            let degree_adjustment = (group.composition_degree + 1 - degree_bound) as u32;
+            let xp = E::from(x.exp(degree_adjustment.into()));
+            let (alpha, beta) = self.degrees[j];
+            result += quotient * (alpha + beta * xp);
+        }
+        result
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{verify_opening, PolyCommitter};
+use crate::{LowDegreeOptions, VerifierError};
+use crypto::hash::Blake3_256;
+use math::{
+    field::{f128::BaseElement, FieldElement, QuadExtension},
+    polynom,
+};
+
+type QuadElement = QuadExtension<BaseElement>;
+
+#[test]
+fn pcs_open_verify() {
+    let options = LowDegreeOptions::new(8, 32, 0).with_merkle_cap_height(2);
+    let polys = build_polys::<BaseElement>(&[1024, 300, 17, 1]);
+    let committer =
+        PolyCommitter::<BaseElement, _, Blake3_256>::new(polys.clone(), options.clone());
+
+    // the commitment records degree bounds of all polynomials
+    let commitment = committer.commitment().clone();
+    assert_eq!(vec![1023, 299, 16, 0], commitment.degree_bounds);
+    assert_eq!(8192, commitment.domain_size(&options));
+
+    // polynomials of degree 1023 and 299 share the first group, while polynomials of degree 16
+    // and 0 are injected into the last FRI layer
+    assert_eq!(2, commitment.caps.len());
+
+    // open all polynomials at two points
+    let points = BaseElement::prng_vector([7; 32], 2);
+    let proof = committer.open(&points);
+
+    // the proof can be serialized and deserialized
+    let proof_bytes = bincode::serialize(&proof).unwrap();
+    let proof = bincode::deserialize(&proof_bytes).unwrap();
+
+    let values =
+        verify_opening::<BaseElement, _, Blake3_256>(&commitment, &points, proof, &options)
+            .unwrap();
+    for (&z, values) in points.iter().zip(values.iter()) {
+        let expected = polys
+            .iter()
+            .map(|p| polynom::eval(p, z))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, *values);
+    }
+}
+
+#[test]
+fn pcs_open_verify_extension() {
+    let options = LowDegreeOptions::new(4, 16, 4);
+    let polys = build_polys::<QuadElement>(&[512, 64]);
+    let committer = PolyCommitter::<BaseElement, _, Blake3_256>::new(polys, options.clone());
+
+    let points = QuadElement::prng_vector([42; 32], 1);
+    let proof = committer.open(&points);
+    let result = verify_opening::<BaseElement, QuadElement, Blake3_256>(
+        committer.commitment(),
+        &points,
+        proof,
+        &options,
+    );
+    assert_eq!(committer.evaluate(&points), result.unwrap());
+}
+
+#[test]
+fn pcs_verify_fail() {
+    let options = LowDegreeOptions::new(8, 32, 0);
+    let polys = build_polys::<BaseElement>(&[256, 100, 10]);
+    let committer = PolyCommitter::<BaseElement, _, Blake3_256>::new(polys, options.clone());
+    let commitment = committer.commitment().clone();
+    let points = vec![BaseElement::from(11u8), BaseElement::from(13u8)];
+
+    // a proof cannot be verified against different points
+    let proof = committer.open(&points);
+    let result = verify_opening::<BaseElement, _, Blake3_256>(
+        &commitment,
+        &[BaseElement::from(11u8), BaseElement::from(17u8)],
+        proof.clone(),
+        &options,
+    );
+    assert!(result.is_err());
+
+    // tampered values are rejected
+    let mut tampered = proof.clone();
+    let mut values = committer.evaluate(&points).concat();
+    values[1] += BaseElement::ONE;
+    tampered.values = BaseElement::elements_as_bytes(&values).to_vec();
+    let result =
+        verify_opening::<BaseElement, _, Blake3_256>(&commitment, &points, tampered, &options);
+    assert!(matches!(
+        result,
+        Err(VerifierError::OpeningValuesNotConsistent)
+    ));
+
+    // tampered queried rows are rejected
+    let mut tampered = proof.clone();
+    tampered.queried_rows[0][0] ^= 1;
+    let result =
+        verify_opening::<BaseElement, _, Blake3_256>(&commitment, &points, tampered, &options);
+    assert!(matches!(
+        result,
+        Err(VerifierError::OpeningCommitmentMismatch)
+    ));
+
+    // tampered queried rows of an injected group are rejected as well
+    assert_eq!(2, commitment.caps.len());
+    let mut tampered = proof;
+    tampered.queried_rows[1][0] ^= 1;
+    let result =
+        verify_opening::<BaseElement, _, Blake3_256>(&commitment, &points, tampered, &options);
+    assert!(matches!(
+        result,
+        Err(VerifierError::OpeningCommitmentMismatch)
+    ));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns random polynomials with the specified numbers of coefficients.
+fn build_polys<E: FieldElement>(lengths: &[usize]) -> Vec<Vec<E>> {
+    lengths
+        .iter()
+        .enumerate()
+        .map(|(i, &len)| E::prng_vector([i as u8 + 1; 32], len))
+        .collect()
+}
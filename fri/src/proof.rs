@@ -3,6 +3,8 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crypto::{BatchMerkleProof, Hasher};
+use math::field::FieldElement;
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::convert::TryInto;
@@ -10,7 +12,7 @@ use std::convert::TryInto;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FriProofLayer {
     pub values: Vec<Vec<u8>>,
-    pub paths: Vec<Vec<[u8; 32]>>,
+    pub paths: Vec<Vec<u8>>,
     pub depth: u8,
 }
 
@@ -22,6 +24,23 @@ pub struct FriProof {
 }
 
 impl FriProofLayer {
+    /// Returns a new proof layer constructed from the queried layer values and a batch Merkle
+    /// proof against the layer commitment. Merkle path nodes are stored in serialized form so
+    /// that the layer does not depend on the digest type of the hash function.
+    pub fn new<H: Hasher, E: FieldElement, const N: usize>(
+        query_values: Vec<[E; N]>,
+        proof: BatchMerkleProof<H>,
+    ) -> Self {
+        FriProofLayer {
+            values: query_values
+                .iter()
+                .map(|v| E::elements_as_bytes(v).to_vec())
+                .collect(),
+            paths: proof.serialize_nodes(),
+            depth: proof.depth,
+        }
+    }
+
     fn to_bytes(&self) -> Vec<u8> {
         let mut ret: Vec<u8> = Vec::new();
         let values_len = u32::try_from(self.values.len()).expect(
@@ -40,7 +59,7 @@ impl FriProofLayer {
             let path_len = u32::try_from(path.len()).expect(
                 "cannot serializes path_len as u32");
             ret.extend_from_slice(&path_len.to_be_bytes());
-            path.iter().for_each(|hash| ret.extend_from_slice(hash));
+            ret.extend_from_slice(path);
         }
         ret.push(self.depth);
         let ret_len = u32::try_from(ret.len()).unwrap().to_be_bytes();
@@ -66,15 +85,13 @@ impl FriProofLayer {
         let paths_len: usize = u32::from_be_bytes(v[index..4].try_into().unwrap())
             .try_into().unwrap();
         index += 4;
-        let mut paths: Vec<Vec<[u8; 32]>> = Vec::with_capacity(paths_len.try_into().unwrap());
-        for i in 0..paths_len {
-            let path_len = u32::from_be_bytes(v[index..4].try_into().unwrap())
+        let mut paths: Vec<Vec<u8>> = Vec::with_capacity(paths_len.try_into().unwrap());
+        for _ in 0..paths_len {
+            let path_len: usize = u32::from_be_bytes(v[index..4].try_into().unwrap())
                 .try_into().unwrap();
             index += 4;
-            for _j in 0..path_len {
-                paths[i].push(v[index..index+32].try_into().unwrap());
-                index += 32;
-            }
+            paths.push(v[index..index+path_len].to_vec());
+            index += path_len;
         }
         let depth:u8 = v[index];
         Self { values, paths, depth }
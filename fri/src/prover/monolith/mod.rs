@@ -30,17 +30,18 @@ where
     H: Hasher,
 {
     options: FriOptions<B>,
-    layers: Vec<FriLayer<B, E>>,
+    layers: Vec<FriLayer<B, E, H>>,
     _coin: PhantomData<C>,
     _hasher: PhantomData<H>,
 }
 
-struct FriLayer<B, E>
+struct FriLayer<B, E, H>
 where
     B: StarkField,
     E: FieldElement + From<B>,
+    H: Hasher,
 {
-    tree: MerkleTree,
+    tree: MerkleTree<H>,
     evaluations: Vec<[E; FOLDING_FACTOR]>,
     _b_marker: PhantomData<B>,
 }
@@ -64,13 +65,39 @@ where
         }
     }
 
-    /// Executes commit phase of FRI protocol which recursively applies a degree-respecting projection
-    /// to evaluations of some function F over a larger domain. The degree of the function implied
-    /// but evaluations is reduced by FOLDING_FACTOR at every step until the remaining evaluations
-    /// can fit into a vector of at most max_remainder_length. At each layer of recursion the
-    /// current evaluations are committed to using a Merkle tree, and the root of this tree is used
-    /// to derive randomness for the subsequent application of degree-respecting projection.
-    pub fn build_layers(&mut self, channel: &mut C, mut evaluations: Vec<E>, domain: &[B]) {
+    /// Executes commit phase of FRI protocol which recursively applies a degree-respecting
+    /// projection to evaluations of some function F over a larger domain. The degree of the
+    /// function implied but evaluations is reduced by FOLDING_FACTOR at every step until the
+    /// remaining evaluations can fit into a vector of at most max_remainder_length. At each layer
+    /// of recursion the current evaluations are committed to using a Merkle tree, and the cap of
+    /// this tree is sent to the channel; the root of the tree is used to derive randomness for
+    /// the subsequent application of degree-respecting projection.
+    pub fn build_layers(&mut self, channel: &mut C, evaluations: Vec<E>, domain: &[B]) {
+        self.build_layers_batched(channel, vec![evaluations], domain);
+    }
+
+    /// Executes commit phase of FRI protocol for a batch of functions with evaluation domains of
+    /// different sizes.
+    ///
+    /// The first function must be evaluated over the entire `domain`, and each subsequent function
+    /// over a domain FOLDING_FACTOR^k times smaller for some k in 1..=num_fri_layers, with k
+    /// strictly increasing from one function to the next. All domains share the offset of the
+    /// `domain`. A function is injected into the layer with the matching domain size by adding
+    /// its evaluations, multiplied by alpha^FOLDING_FACTOR, to the evaluations folded from the
+    /// previous layer. Thus, a function evaluated over a domain FOLDING_FACTOR^k times smaller is
+    /// proven to have degree FOLDING_FACTOR^k times smaller than the first function, without any
+    /// degree adjustment.
+    pub fn build_layers_batched(
+        &mut self,
+        channel: &mut C,
+        evaluations: Vec<Vec<E>>,
+        domain: &[B],
+    ) {
+        let mut inputs = evaluations.into_iter();
+        let mut evaluations = inputs
+            .next()
+            .expect("at least one function must be provided");
+        let mut inputs = inputs.peekable();
         assert!(
             evaluations.len() == domain.len(),
             "number of evaluations must match the domain size"
@@ -87,25 +114,41 @@ where
             "a prior proof generation request has not been completed yet"
         );
 
-        let hash_fn = H::hash_fn();
-
         // reduce the degree by 4 at each iteration until the remaining polynomial is small enough;
         // + 1 is for the remainder
-        for depth in 0..self.options.num_fri_layers(domain.len()) + 1 {
+        let num_layers = self.options.num_fri_layers(domain.len());
+        let num_partitions = self.options.num_fri_partitions(domain.len());
+        for depth in 0..num_layers + 1 {
             // commit to the evaluations at the current layer; we do this by first transposing the
             // evaluations into a matrix of 4 columns, and then building a Merkle tree from the
             // rows of this matrix; we do this so that we could de-commit to 4 values with a sing
             // Merkle authentication path.
             let transposed_evaluations = quartic::transpose(&evaluations, 1);
-            let hashed_evaluations = quartic::hash_values(&transposed_evaluations, hash_fn);
-            let evaluation_tree = MerkleTree::new(hashed_evaluations, hash_fn);
-            channel.commit_fri_layer(*evaluation_tree.root());
+            let mut hashed_evaluations = quartic::hash_values::<H, E>(&transposed_evaluations);
+            // for partitioned proofs, arrange the leaves of all layers except the remainder so
+            // that rows of each partition are next to each other
+            if num_partitions > 1 && depth < num_layers {
+                hashed_evaluations = partition_leaves(hashed_evaluations, num_partitions);
+            }
+            let evaluation_tree = MerkleTree::<H>::new(hashed_evaluations);
+            channel.commit_fri_layer(evaluation_tree.cap(self.options.merkle_cap_height()));
 
             // draw a pseudo-random coefficient from the channel, and use it in degree-respecting
             // projection to reduce the degree of evaluations by 4
             let alpha = channel.draw_fri_alpha::<E>(depth as usize);
             evaluations = apply_drp(&transposed_evaluations, domain, depth, alpha);
 
+            // inject the next function into the folded layer if its domain size matches; a
+            // function may be injected into the remainder (folded at depth num_layers - 1), but
+            // not into the evaluations folded from the remainder, since these are discarded
+            if depth < num_layers && inputs.peek().map(|v| v.len()) == Some(evaluations.len()) {
+                let input = inputs.next().unwrap();
+                let alpha_pow = alpha.exp((FOLDING_FACTOR as u32).into());
+                for (evaluation, &value) in evaluations.iter_mut().zip(input.iter()) {
+                    *evaluation += value * alpha_pow;
+                }
+            }
+
             self.layers.push(FriLayer {
                 tree: evaluation_tree,
                 evaluations: transposed_evaluations,
@@ -113,6 +156,13 @@ where
             });
         }
 
+        assert!(
+            inputs.next().is_none(),
+            "evaluation domains of all functions must be FOLDING_FACTOR^k times smaller than \
+            the domain for strictly increasing k between 1 and {}",
+            num_layers
+        );
+
         // make sure remainder length does not exceed max allowed value
         let last_layer = &self.layers[self.layers.len() - 1];
         let remainder_length = last_layer.evaluations.len() * FOLDING_FACTOR;
@@ -126,7 +176,7 @@ where
 
     /// Executes query phase of FRI protocol. For each of the provided `positions`, corresponding
     /// evaluations from each of the layers are recorded into the proof together with Merkle
-    /// authentication paths from the root of layer commitment trees.
+    /// authentication paths from the caps of layer commitment trees.
     pub fn build_proof(&mut self, positions: &[usize]) -> FriProof {
         assert!(
             !self.layers.is_empty(),
@@ -134,6 +184,7 @@ where
         );
         let mut positions = positions.to_vec();
         let mut domain_size = self.layers[0].evaluations.len() * FOLDING_FACTOR;
+        let num_partitions = self.options.num_fri_partitions(domain_size);
 
         // for all trees, except the last one, record tree root, authentication paths
         // to row evaluations, and values for row evaluations
@@ -142,21 +193,22 @@ where
             positions =
                 utils::fold_positions(&positions, domain_size, self.options.folding_factor());
 
-            let proof = self.layers[i].tree.prove_batch(&positions);
+            let indexes = utils::map_positions_to_indexes(
+                &positions,
+                domain_size,
+                self.options.folding_factor(),
+                num_partitions,
+            );
+            let proof = self.layers[i]
+                .tree
+                .prove_batch_to_cap(&indexes, self.options.merkle_cap_height());
 
             let mut queried_values: Vec<[E; FOLDING_FACTOR]> = Vec::with_capacity(positions.len());
             for &position in positions.iter() {
                 queried_values.push(self.layers[i].evaluations[position]);
             }
 
-            layers.push(FriProofLayer {
-                values: queried_values
-                    .into_iter()
-                    .map(|v| E::elements_as_bytes(&v).to_vec())
-                    .collect(),
-                paths: proof.nodes,
-                depth: proof.depth,
-            });
+            layers.push(FriProofLayer::new(queried_values, proof));
             domain_size /= FOLDING_FACTOR;
         }
 
@@ -178,7 +230,7 @@ where
         FriProof {
             layers,
             rem_values: E::elements_as_bytes(&remainder).to_vec(),
-            partitioned: false,
+            partitioned: num_partitions > 1,
         }
     }
 
@@ -196,6 +248,18 @@ where
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Rearranges leaves of a layer commitment tree so that the leaf at position i is moved to the
+/// index assigned to it by `utils::map_positions_to_indexes()`.
+fn partition_leaves<D: Copy>(leaves: Vec<D>, num_partitions: usize) -> Vec<D> {
+    let partition_size = leaves.len() / num_partitions;
+    let mut result = leaves.clone();
+    for (position, leaf) in leaves.into_iter().enumerate() {
+        let partition_idx = position % num_partitions;
+        result[partition_idx * partition_size + position / num_partitions] = leaf;
+    }
+    result
+}
+
 /// Applies degree-respecting projection to the `evaluations` reducing the degree of evaluations
 /// by FOLDING_FACTOR. This is equivalent to the following:
 /// - Let `evaluations` contain the evaluations of polynomial f(x) of degree k
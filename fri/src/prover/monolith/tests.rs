@@ -4,20 +4,181 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    super::tests::{build_evaluations, build_lde_domain, build_prover_channel, verify_proof},
+    super::tests::{
+        build_evaluations, build_lde_domain, build_prover_channel, verify_batched_proof,
+        verify_proof,
+    },
     FriProver,
 };
-use crate::{FriOptions, PublicCoin};
+use crate::{FriOptions, FriProof, VerifierError};
+use crypto::{hash::Blake3_256, Hasher, MerkleTree};
 use math::field::{f128::BaseElement, StarkField};
 
+const TRACE_LENGTH: usize = 4096;
+const CE_BLOWUP: usize = 2;
+
 #[test]
 fn sequential_fri_prove_verify() {
-    let trace_length = 4096;
-    let ce_blowup = 2;
-    let lde_blowup = 8;
-    let offset = BaseElement::GENERATOR;
+    let options = FriOptions::new(8, BaseElement::GENERATOR);
+    fri_prove_verify(options);
+}
+
+#[test]
+fn sequential_fri_prove_verify_with_cap() {
+    let options = FriOptions::new(8, BaseElement::GENERATOR).with_merkle_cap_height(4);
+    fri_prove_verify(options);
+}
+
+#[test]
+fn sequential_fri_verify_with_invalid_cap() {
+    let options = FriOptions::new(8, BaseElement::GENERATOR).with_merkle_cap_height(4);
+    let batch = vec![build_evaluations(TRACE_LENGTH, 8, CE_BLOWUP)];
+    let (proof, mut commitments, positions) = build_batched_proof(&options, &batch);
+
+    // a root of the first layer tree is a valid cap, but not of the height set in the options
+    commitments[0] = vec![MerkleTree::<Blake3_256>::get_root_from_cap(&commitments[0]).unwrap()];
+    let max_degree = TRACE_LENGTH * CE_BLOWUP - 1;
+    let result = verify_proof(
+        proof,
+        commitments,
+        &batch[0],
+        max_degree,
+        &positions,
+        &options,
+    );
+    assert!(matches!(result, Err(VerifierError::LayerCapNotValid(0))));
+}
+
+#[test]
+fn sequential_fri_verify_with_invalid_depth() {
+    let options = FriOptions::new(8, BaseElement::GENERATOR);
+    let batch = vec![build_evaluations(TRACE_LENGTH, 8, CE_BLOWUP)];
+    let (mut proof, commitments, positions) = build_batched_proof(&options, &batch);
+
+    // the second layer tree has 2048 leaves, and thus, its depth must be 11
+    assert_eq!(11, proof.layers[1].depth);
+    proof.layers[1].depth = 12;
+    let max_degree = TRACE_LENGTH * CE_BLOWUP - 1;
+    let result = verify_proof(
+        proof,
+        commitments,
+        &batch[0],
+        max_degree,
+        &positions,
+        &options,
+    );
+    assert!(matches!(result, Err(VerifierError::LayerDepthNotValid(1))));
+}
+
+#[test]
+fn sequential_fri_batched_prove_verify() {
+    let options = FriOptions::new(8, BaseElement::GENERATOR);
+
+    // the domain of 32768 elements is folded into 8192, 2048, 512, and 128 elements; inject
+    // polynomials into the first folded layer and into the remainder
+    let batch = vec![
+        build_evaluations(4096, 8, 2),
+        build_evaluations(1024, 8, 2),
+        build_evaluations(16, 8, 2),
+    ];
+    let result = fri_batched_prove_verify(options.clone(), batch);
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+
+    // an injected polynomial of a degree too high for its layer is rejected
+    let batch = vec![
+        build_evaluations(4096, 8, 2),
+        build_evaluations(1024, 8, 2),
+        build_evaluations(16, 8, 4),
+    ];
+    let result = fri_batched_prove_verify(options, batch);
+    assert!(matches!(
+        result,
+        Err(VerifierError::RemainderDegreeMismatch(_))
+    ));
+}
+
+#[test]
+fn sequential_fri_partitioned_prove_verify() {
+    let options = FriOptions::new(8, BaseElement::GENERATOR).with_partitions();
+    fri_prove_verify(options.clone());
+
+    // partitions are contiguous subtrees of layer trees, and thus can be committed to with caps
+    fri_prove_verify(options.clone().with_merkle_cap_height(4));
+
+    // batched proofs can be partitioned as well
+    let batch = vec![
+        build_evaluations(TRACE_LENGTH, 8, CE_BLOWUP),
+        build_evaluations(16, 8, CE_BLOWUP),
+    ];
+    let result = fri_batched_prove_verify(options, batch);
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+}
+
+#[test]
+fn sequential_fri_partitioned_verify_fail() {
+    let options = FriOptions::new(8, BaseElement::GENERATOR).with_partitions();
+    let batch = vec![build_evaluations(TRACE_LENGTH, 8, CE_BLOWUP)];
+    let (proof, commitments, positions) = build_batched_proof(&options, &batch);
+    assert!(proof.partitioned);
+
+    // a partitioned proof cannot be verified as a non-partitioned one
+    let mut tampered = proof.clone();
+    tampered.partitioned = false;
+    let result = verify_batched_proof(
+        tampered,
+        commitments.clone(),
+        &batch,
+        TRACE_LENGTH * CE_BLOWUP - 1,
+        &positions,
+        &options,
+    );
+    assert!(matches!(
+        result,
+        Err(VerifierError::LayerCommitmentMismatch(0))
+    ));
+
+    // a tampered remainder value of a partition is rejected
+    let mut tampered = proof.clone();
+    tampered.rem_values[0] ^= 1;
+    let result = verify_batched_proof(
+        tampered,
+        commitments.clone(),
+        &batch,
+        TRACE_LENGTH * CE_BLOWUP - 1,
+        &positions,
+        &options,
+    );
+    assert!(matches!(
+        result,
+        Err(VerifierError::RemainderCommitmentMismatch)
+    ));
+
+    // a tampered query of a partition is rejected
+    let mut tampered = proof;
+    tampered.layers[1].values[0][0] ^= 1;
+    let result = verify_batched_proof(
+        tampered,
+        commitments,
+        &batch,
+        TRACE_LENGTH * CE_BLOWUP - 1,
+        &positions,
+        &options,
+    );
+    assert!(matches!(
+        result,
+        Err(VerifierError::LayerCommitmentMismatch(1))
+    ));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn fri_prove_verify(options: FriOptions<BaseElement>) {
+    let trace_length = TRACE_LENGTH;
+    let ce_blowup = CE_BLOWUP;
+    let lde_blowup = options.blowup_factor();
+    let offset = options.domain_offset();
 
-    let options = FriOptions::new(lde_blowup, offset);
     let mut channel = build_prover_channel(trace_length, &options);
     let evaluations = build_evaluations(trace_length, lde_blowup, ce_blowup);
     let lde_domain = build_lde_domain(trace_length, lde_blowup, offset);
@@ -29,7 +190,7 @@ fn sequential_fri_prove_verify() {
     let proof = prover.build_proof(&positions);
 
     // make sure the proof can be verified
-    let commitments = channel.fri_layer_commitments().to_vec();
+    let commitments = channel.layer_caps().to_vec();
     let max_degree = trace_length * ce_blowup - 1;
     let result = verify_proof(
         proof,
@@ -41,3 +202,36 @@ fn sequential_fri_prove_verify() {
     );
     assert!(result.is_ok(), "{:}", result.err().unwrap());
 }
+
+fn fri_batched_prove_verify(
+    options: FriOptions<BaseElement>,
+    batch: Vec<Vec<BaseElement>>,
+) -> Result<(), VerifierError> {
+    let (proof, commitments, positions) = build_batched_proof(&options, &batch);
+    let max_degree = TRACE_LENGTH * CE_BLOWUP - 1;
+    verify_batched_proof(proof, commitments, &batch, max_degree, &positions, &options)
+}
+
+#[allow(clippy::type_complexity)]
+fn build_batched_proof(
+    options: &FriOptions<BaseElement>,
+    batch: &[Vec<BaseElement>],
+) -> (
+    FriProof,
+    Vec<Vec<<Blake3_256 as Hasher>::Digest>>,
+    Vec<usize>,
+) {
+    let lde_blowup = options.blowup_factor();
+    let offset = options.domain_offset();
+
+    let mut channel = build_prover_channel(TRACE_LENGTH, options);
+    let lde_domain = build_lde_domain(TRACE_LENGTH, lde_blowup, offset);
+
+    // instantiate the prover and generate the proof
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers_batched(&mut channel, batch.to_vec(), &lde_domain);
+    let positions = channel.draw_query_positions();
+    let proof = prover.build_proof(&positions);
+
+    (proof, channel.layer_caps().to_vec(), positions)
+}
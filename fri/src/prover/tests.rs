@@ -4,10 +4,10 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::{
-    verifier, DefaultProverChannel, DefaultVerifierChannel, FriOptions, FriProof, VerifierChannel,
-    VerifierContext, VerifierError,
+    utils, verifier, DefaultProverChannel, DefaultVerifierChannel, FriOptions, FriProof,
+    VerifierChannel, VerifierContext, VerifierError,
 };
-use crypto::hash;
+use crypto::{hash, Hasher};
 use math::{
     fft,
     field::{f128::BaseElement, FieldElement, StarkField},
@@ -52,13 +52,18 @@ pub fn build_evaluations(
 
 pub fn verify_proof(
     proof: FriProof,
-    commitments: Vec<[u8; 32]>,
+    commitments: Vec<Vec<<hash::Blake3_256 as Hasher>::Digest>>,
     evaluations: &[BaseElement],
     max_degree: usize,
     positions: &[usize],
     options: &FriOptions<BaseElement>,
 ) -> Result<(), VerifierError> {
-    let channel = DefaultVerifierChannel::<BaseElement, hash::Blake3_256>::new(proof, commitments);
+    let channel = DefaultVerifierChannel::<BaseElement, hash::Blake3_256>::new(
+        proof,
+        commitments,
+        evaluations.len(),
+        options,
+    )?;
     let context = VerifierContext::new(
         evaluations.len(),
         max_degree,
@@ -71,3 +76,52 @@ pub fn verify_proof(
         .collect::<Vec<_>>();
     verifier::verify(&context, &channel, &queried_evaluations, &positions)
 }
+
+pub fn verify_batched_proof(
+    proof: FriProof,
+    commitments: Vec<Vec<<hash::Blake3_256 as Hasher>::Digest>>,
+    batch: &[Vec<BaseElement>],
+    max_degree: usize,
+    positions: &[usize],
+    options: &FriOptions<BaseElement>,
+) -> Result<(), VerifierError> {
+    let domain_size = batch[0].len();
+    let channel = DefaultVerifierChannel::<BaseElement, hash::Blake3_256>::new(
+        proof,
+        commitments,
+        domain_size,
+        options,
+    )?;
+    let context = VerifierContext::new(
+        domain_size,
+        max_degree,
+        channel.num_fri_partitions(),
+        options.clone(),
+    );
+    let domain_sizes = batch.iter().map(|e| e.len()).collect::<Vec<_>>();
+    let queried_evaluations = batch
+        .iter()
+        .map(|evaluations| {
+            utils::fold_positions_to(
+                positions,
+                domain_size,
+                evaluations.len(),
+                options.folding_factor(),
+            )
+            .iter()
+            .map(|&p| evaluations[p])
+            .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let queried_evaluations = queried_evaluations
+        .iter()
+        .map(|e| e.as_slice())
+        .collect::<Vec<_>>();
+    verifier::verify_batched(
+        &context,
+        &channel,
+        &queried_evaluations,
+        &domain_sizes,
+        positions,
+    )
+}
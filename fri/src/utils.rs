@@ -3,6 +3,62 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crypto::{Digest, Hasher};
+use std::{convert::TryInto, mem::size_of};
+
+#[cfg(feature = "concurrent")]
+use rayon::prelude::*;
+
+/// Draws `num_queries` unique pseudo-random positions in a domain of the specified size (which
+/// must be a power of two) using the provided `seed`.
+pub fn draw_query_positions<H: Hasher>(
+    seed: H::Digest,
+    domain_size: usize,
+    num_queries: usize,
+) -> Vec<usize> {
+    // determine how many bits are needed to represent valid indexes in the domain
+    let value_mask = domain_size - 1;
+    // values are read from the end of the digest; truncated digests are padded with zeros
+    // when serialized, and thus the padding must be skipped
+    let digest_size = <H::Digest as Digest>::SIZE;
+    let value_offset = digest_size - size_of::<usize>();
+
+    // initialize the seed for PRNG
+    let mut seed_bytes = [0u8; 64];
+    seed_bytes[..32].copy_from_slice(&seed.as_bytes());
+
+    // draw values from PRNG until we get as many unique values as specified by num_queries
+    let mut result = Vec::new();
+    for i in 0usize..1000 {
+        // update the seed with the new counter and hash the result
+        seed_bytes[56..].copy_from_slice(&i.to_le_bytes());
+        let value_bytes = H::hash(&seed_bytes).as_bytes();
+
+        // read the required number of bits from the hashed value
+        let value =
+            usize::from_le_bytes(value_bytes[value_offset..digest_size].try_into().unwrap())
+                & value_mask;
+
+        if result.contains(&value) {
+            continue;
+        }
+        result.push(value);
+        if result.len() >= num_queries {
+            break;
+        }
+    }
+
+    assert_eq!(
+        result.len(),
+        num_queries,
+        "needed to generate {} query positions, but generated only {}",
+        num_queries,
+        result.len()
+    );
+
+    result
+}
+
 /// Maps positions in the current evaluation domain, to positions in the folded domain.
 pub fn fold_positions(
     positions: &[usize],
@@ -23,6 +79,24 @@ pub fn fold_positions(
     result
 }
 
+/// Maps positions in the current evaluation domain to positions in the target domain by folding
+/// them repeatedly; the order of the resulting positions is the same as the order in which FRI
+/// verifier queries the layer with the target domain.
+pub fn fold_positions_to(
+    positions: &[usize],
+    source_domain_size: usize,
+    target_domain_size: usize,
+    folding_factor: usize,
+) -> Vec<usize> {
+    let mut positions = positions.to_vec();
+    let mut domain_size = source_domain_size;
+    while domain_size > target_domain_size {
+        positions = fold_positions(&positions, domain_size, folding_factor);
+        domain_size /= folding_factor;
+    }
+    positions
+}
+
 /// Maps positions in the evaluation domain to indexes of commitment Merkle tree.
 pub fn map_positions_to_indexes(
     positions: &[usize],
@@ -49,3 +123,50 @@ pub fn map_positions_to_indexes(
 
     result
 }
+
+/// Finds a nonce which, when hashed together with the `seed`, satisfies the grinding factor;
+/// returns the resulting hash together with the nonce. When `concurrent` feature is enabled,
+/// the search is done in multiple threads.
+pub fn find_pow_nonce<H: Hasher>(seed: H::Digest, grinding_factor: u32) -> (H::Digest, u64) {
+    #[cfg(not(feature = "concurrent"))]
+    let nonce = (1..u64::MAX)
+        .find(|&nonce| is_valid_pow::<H>(&hash_with_nonce::<H>(seed, nonce), grinding_factor))
+        .expect("nonce not found");
+
+    #[cfg(feature = "concurrent")]
+    let nonce = (1..u64::MAX)
+        .into_par_iter()
+        .find_any(|&nonce| is_valid_pow::<H>(&hash_with_nonce::<H>(seed, nonce), grinding_factor))
+        .expect("nonce not found");
+
+    (hash_with_nonce::<H>(seed, nonce), nonce)
+}
+
+/// Returns the hash of the `seed` and the `nonce` if it satisfies the grinding factor, or None
+/// otherwise.
+pub fn verify_pow_nonce<H: Hasher>(
+    seed: H::Digest,
+    nonce: u64,
+    grinding_factor: u32,
+) -> Option<H::Digest> {
+    let result = hash_with_nonce::<H>(seed, nonce);
+    if is_valid_pow::<H>(&result, grinding_factor) {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Hashes the seed together with the nonce.
+fn hash_with_nonce<H: Hasher>(seed: H::Digest, nonce: u64) -> H::Digest {
+    let mut buf = [0u8; 64];
+    buf[0..32].copy_from_slice(&seed.as_bytes());
+    buf[56..].copy_from_slice(&nonce.to_le_bytes());
+    H::hash(&buf)
+}
+
+/// Returns true if the first 8 bytes of the hash have at least `grinding_factor` trailing zeros.
+fn is_valid_pow<H: Hasher>(hash: &H::Digest, grinding_factor: u32) -> bool {
+    let head = u64::from_le_bytes(hash.as_bytes()[..8].try_into().unwrap());
+    head.trailing_zeros() >= grinding_factor
+}
@@ -3,24 +3,29 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{folding::quartic, FriProof, PublicCoin, VerifierError};
+use crate::{folding::quartic, FriOptions, FriProof, FriProofLayer, PublicCoin, VerifierError};
 use crypto::{BatchMerkleProof, DefaultRandomElementGenerator, Hasher, MerkleTree};
-use math::{field::FieldElement, utils::read_elements_into_vec};
+use math::{
+    field::{FieldElement, StarkField},
+    utils::{log2, read_elements_into_vec},
+};
 use std::{convert::TryInto, marker::PhantomData};
 
+#[cfg(feature = "concurrent")]
+use rayon::prelude::*;
+
 type Bytes = Vec<u8>;
 
 // VERIFIER CHANNEL TRAIT
 // ================================================================================================
 
 pub trait VerifierChannel<E: FieldElement>: PublicCoin {
-    type Hasher: Hasher;
-
     // REQUIRED METHODS
     // --------------------------------------------------------------------------------------------
 
-    fn fri_layer_proofs(&self) -> &[BatchMerkleProof];
-    fn fri_layer_queries(&self) -> &[Vec<Bytes>];
+    fn fri_layer_caps(&self) -> &[Vec<<Self::Hasher as Hasher>::Digest>];
+    fn fri_layer_proofs(&self) -> &[BatchMerkleProof<Self::Hasher>];
+    fn fri_layer_queries(&self) -> &[Vec<[E; 4]>];
     fn fri_remainder(&self) -> &[u8];
     fn fri_partitioned(&self) -> bool;
 
@@ -28,52 +33,33 @@ pub trait VerifierChannel<E: FieldElement>: PublicCoin {
     // --------------------------------------------------------------------------------------------
 
     /// Returns FRI query values at the specified positions from the FRI layer at the
-    /// specified index. This also checks if the values are valid against the FRI layer
-    /// commitment sent by the prover.
+    /// specified index. This also checks if the values are valid against the cap of the FRI
+    /// layer commitment sent by the prover.
     fn read_layer_queries(
         &self,
         layer_idx: usize,
         positions: &[usize],
     ) -> Result<Vec<[E; 4]>, VerifierError> {
-        let hash_fn = Self::Hasher::hash_fn();
-        let layer_root = self.fri_layer_commitments()[layer_idx];
+        let layer_cap = &self.fri_layer_caps()[layer_idx];
         let layer_proof = &self.fri_layer_proofs()[layer_idx];
-        if !MerkleTree::verify_batch(&layer_root, &positions, &layer_proof, hash_fn) {
+        if !MerkleTree::verify_batch_with_cap(layer_cap, positions, layer_proof) {
             return Err(VerifierError::LayerCommitmentMismatch(layer_idx));
         }
 
-        // convert query bytes into field elements of appropriate type
-        let mut queries = Vec::new();
-        for query_bytes in self.fri_layer_queries()[layer_idx].iter() {
-            let query: [E; 4] = read_elements_into_vec(query_bytes)
-                .map_err(|err| {
-                    VerifierError::LayerDeserializationError(layer_idx, err.to_string())
-                })?
-                .try_into()
-                .map_err(|_| {
-                    VerifierError::LayerDeserializationError(
-                        layer_idx,
-                        "failed to convert vec of elements to array of 4 element".to_string(),
-                    )
-                })?;
-            queries.push(query);
-        }
-
-        Ok(queries)
+        Ok(self.fri_layer_queries()[layer_idx].clone())
     }
 
     /// Reads FRI remainder values (last FRI layer). This also checks that the remainder is
     /// valid against the commitment sent by the prover.
     fn read_remainder(&self) -> Result<Vec<E>, VerifierError> {
-        let hash_fn = Self::Hasher::hash_fn();
         // convert remainder bytes into field elements of appropriate type
-        let remainder = read_elements_into_vec(&self.fri_remainder())
+        let remainder = read_elements_into_vec(self.fri_remainder())
             .map_err(|err| VerifierError::RemainderDeserializationError(err.to_string()))?;
 
         // build remainder Merkle tree
         let remainder_values = quartic::transpose(&remainder, 1);
-        let hashed_values = quartic::hash_values(&remainder_values, hash_fn);
-        let remainder_tree = MerkleTree::new(hashed_values, hash_fn);
+        let hashed_values = quartic::hash_values::<Self::Hasher, E>(&remainder_values);
+        let remainder_tree = MerkleTree::<Self::Hasher>::new(hashed_values);
 
         // make sure the root of the tree matches the committed root of the last layer
         let committed_root = self.fri_layer_commitments().last().unwrap();
@@ -85,28 +71,43 @@ pub trait VerifierChannel<E: FieldElement>: PublicCoin {
     }
 
     /// Decomposes FRI proof struct into batch Merkle proofs and query values for each
-    /// FRI layer, as well as remainder (the last FRI layer).
-    fn parse_fri_proof(proof: FriProof) -> (Vec<BatchMerkleProof>, Vec<Vec<Vec<u8>>>, Vec<u8>) {
-        let hash_fn = Self::Hasher::hash_fn();
-        let mut fri_queries = Vec::with_capacity(proof.layers.len());
-        let mut fri_proofs = Vec::with_capacity(proof.layers.len());
-        for layer in proof.layers.into_iter() {
-            let mut hashed_values = Vec::new();
-            for value_bytes in layer.values.iter() {
-                let mut buf = [0u8; 32];
-                hash_fn(value_bytes, &mut buf);
-                hashed_values.push(buf);
-            }
-
-            fri_proofs.push(BatchMerkleProof {
-                values: hashed_values,
-                nodes: layer.paths.clone(),
-                depth: layer.depth,
-            });
-            fri_queries.push(layer.values);
+    /// FRI layer, as well as remainder (the last FRI layer). When `concurrent` feature is
+    /// enabled, the layers are parsed in multiple threads.
+    ///
+    /// Layers are expected to be built for the evaluation domain of `domain_size` folded by
+    /// `folding_factor` at each layer; an error is returned if depth of any of the layer proofs
+    /// is inconsistent with the number of leaves in the layer tree.
+    #[allow(clippy::type_complexity)]
+    fn parse_fri_proof(
+        proof: FriProof,
+        domain_size: usize,
+        folding_factor: usize,
+    ) -> Result<(Vec<BatchMerkleProof<Self::Hasher>>, Vec<Vec<[E; 4]>>, Bytes), VerifierError> {
+        #[cfg(not(feature = "concurrent"))]
+        let layers = proof.layers.into_iter();
+
+        #[cfg(feature = "concurrent")]
+        let layers = proof.layers.into_par_iter();
+
+        // results are collected before errors are checked so that the error for the first
+        // malformed layer is returned regardless of the order in which layers were parsed
+        let layers = layers
+            .enumerate()
+            .map(|(layer_idx, layer)| {
+                let num_leaves = get_layer_num_leaves(domain_size, folding_factor, layer_idx);
+                parse_fri_layer::<Self::Hasher, E>(layer_idx, layer, num_leaves)
+            })
+            .collect::<Vec<_>>();
+
+        let mut fri_queries = Vec::with_capacity(layers.len());
+        let mut fri_proofs = Vec::with_capacity(layers.len());
+        for layer in layers {
+            let (layer_proof, queries) = layer?;
+            fri_proofs.push(layer_proof);
+            fri_queries.push(queries);
         }
 
-        (fri_proofs, fri_queries, proof.rem_values)
+        Ok((fri_proofs, fri_queries, proof.rem_values))
     }
 
     fn num_fri_partitions(&self) -> usize {
@@ -122,41 +123,67 @@ pub trait VerifierChannel<E: FieldElement>: PublicCoin {
 // ================================================================================================
 
 pub struct DefaultVerifierChannel<E: FieldElement, H: Hasher> {
-    commitments: Vec<[u8; 32]>,
-    proofs: Vec<BatchMerkleProof>,
-    queries: Vec<Vec<Bytes>>,
+    commitments: Vec<H::Digest>,
+    layer_caps: Vec<Vec<H::Digest>>,
+    proofs: Vec<BatchMerkleProof<H>>,
+    queries: Vec<Vec<[E; 4]>>,
     remainder: Bytes,
     partitioned: bool,
     _element: PhantomData<E>,
-    _hasher: PhantomData<H>,
 }
 
 impl<E: FieldElement, H: Hasher> DefaultVerifierChannel<E, H> {
-    /// Builds a new verifier channel from the specified parameters.
-    pub fn new(proof: FriProof, commitments: Vec<[u8; 32]>) -> Self {
+    /// Builds a new verifier channel from the specified parameters. Layer commitments are
+    /// expected to be Merkle tree caps of FRI layers built for the specified `domain_size` and
+    /// `options`. Returns an error if the proof could not be parsed, or if any of the caps is
+    /// malformed or does not have the number of nodes implied by `options`.
+    pub fn new<B: StarkField>(
+        proof: FriProof,
+        layer_caps: Vec<Vec<H::Digest>>,
+        domain_size: usize,
+        options: &FriOptions<B>,
+    ) -> Result<Self, VerifierError> {
         let partitioned = proof.partitioned;
-        let (proofs, queries, remainder) = Self::parse_fri_proof(proof);
+        let (proofs, queries, remainder) =
+            Self::parse_fri_proof(proof, domain_size, options.folding_factor())?;
+
+        // layer roots are used to draw randomness, and thus, need to be computed from the caps;
+        // each leaf of a layer tree contains as many evaluations as the folding factor
+        let mut commitments = Vec::with_capacity(layer_caps.len());
+        let mut num_leaves = domain_size;
+        for (layer_idx, layer_cap) in layer_caps.iter().enumerate() {
+            num_leaves /= options.folding_factor();
+            let cap_len = MerkleTree::<H>::cap_len(num_leaves, options.merkle_cap_height());
+            if layer_cap.len() != cap_len {
+                return Err(VerifierError::LayerCapNotValid(layer_idx));
+            }
+            let layer_root = MerkleTree::<H>::get_root_from_cap(layer_cap)
+                .ok_or(VerifierError::LayerCapNotValid(layer_idx))?;
+            commitments.push(layer_root);
+        }
 
-        DefaultVerifierChannel {
+        Ok(DefaultVerifierChannel {
             commitments,
+            layer_caps,
             proofs,
             queries,
             remainder,
             partitioned,
             _element: PhantomData,
-            _hasher: PhantomData,
-        }
+        })
     }
 }
 
 impl<E: FieldElement, H: Hasher> VerifierChannel<E> for DefaultVerifierChannel<E, H> {
-    type Hasher = H;
+    fn fri_layer_caps(&self) -> &[Vec<H::Digest>] {
+        &self.layer_caps
+    }
 
-    fn fri_layer_proofs(&self) -> &[BatchMerkleProof] {
+    fn fri_layer_proofs(&self) -> &[BatchMerkleProof<H>] {
         &self.proofs
     }
 
-    fn fri_layer_queries(&self) -> &[Vec<Bytes>] {
+    fn fri_layer_queries(&self) -> &[Vec<[E; 4]>] {
         &self.queries
     }
 
@@ -170,9 +197,58 @@ impl<E: FieldElement, H: Hasher> VerifierChannel<E> for DefaultVerifierChannel<E
 }
 
 impl<E: FieldElement, H: Hasher> PublicCoin for DefaultVerifierChannel<E, H> {
+    type Hasher = H;
     type RandomElementGenerator = DefaultRandomElementGenerator<H>;
 
-    fn fri_layer_commitments(&self) -> &[[u8; 32]] {
+    fn fri_layer_commitments(&self) -> &[H::Digest] {
         &self.commitments
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of leaves in the tree of the FRI layer at the specified index, or 0 if the
+/// domain cannot be folded that many times.
+fn get_layer_num_leaves(domain_size: usize, folding_factor: usize, layer_idx: usize) -> usize {
+    (0..=layer_idx).fold(domain_size, |num_leaves, _| num_leaves / folding_factor)
+}
+
+/// Converts query values of a single FRI layer into field elements, and hashes them into leaves
+/// of the batch Merkle proof for the layer; the proof must have depth implied by `num_leaves`.
+#[allow(clippy::type_complexity)]
+fn parse_fri_layer<H: Hasher, E: FieldElement>(
+    layer_idx: usize,
+    layer: FriProofLayer,
+    num_leaves: usize,
+) -> Result<(BatchMerkleProof<H>, Vec<[E; 4]>), VerifierError> {
+    // depth is supplied by the prover, and thus, must be checked against the expected tree size
+    if num_leaves == 0 || layer.depth as usize != log2(num_leaves) as usize {
+        return Err(VerifierError::LayerDepthNotValid(layer_idx));
+    }
+
+    // convert query bytes into field elements of appropriate type
+    let mut queries = Vec::with_capacity(layer.values.len());
+    for query_bytes in layer.values.iter() {
+        let query: [E; 4] = read_elements_into_vec(query_bytes)
+            .map_err(|err| VerifierError::LayerDeserializationError(layer_idx, err.to_string()))?
+            .try_into()
+            .map_err(|_| {
+                VerifierError::LayerDeserializationError(
+                    layer_idx,
+                    "failed to convert vec of elements to array of 4 element".to_string(),
+                )
+            })?;
+        queries.push(query);
+    }
+
+    // hash query values into leaves of the batch Merkle proof
+    let hashed_values = queries
+        .iter()
+        .map(|query| H::hash_elements(query))
+        .collect();
+    let layer_proof = BatchMerkleProof::deserialize(&layer.paths, hashed_values, layer.depth)
+        .map_err(|err| VerifierError::LayerDeserializationError(layer_idx, err.to_string()))?;
+
+    Ok((layer_proof, queries))
+}
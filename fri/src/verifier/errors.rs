@@ -10,9 +10,15 @@ pub enum VerifierError {
     #[error("FRI queries did not match the commitment at layer {0}")]
     LayerCommitmentMismatch(usize),
 
+    #[error("FRI commitment at layer {0} is not a valid Merkle tree cap")]
+    LayerCapNotValid(usize),
+
     #[error("FRI queries at layer {0} could not be deserialized: {1}")]
     LayerDeserializationError(usize, String),
 
+    #[error("FRI queries at layer {0} did not have the depth implied by the evaluation domain")]
+    LayerDepthNotValid(usize),
+
     #[error("FRI evaluations did not match query values at depth {0}")]
     LayerValuesNotConsistent(usize),
 
@@ -30,4 +36,31 @@ pub enum VerifierError {
 
     #[error("FRI remainder is not a valid degree {0} polynomial")]
     RemainderDegreeMismatch(usize),
+
+    #[error("expected {0} values of an injected polynomial, but received {1}")]
+    InjectedValuesCountMismatch(usize, usize),
+
+    #[error("evaluation domain of an injected polynomial does not match any FRI layer")]
+    InjectedDomainSizeNotValid,
+
+    #[error("expected {0} FRI layers, but proof contained {1}")]
+    LayerCountMismatch(usize, usize),
+
+    #[error("expected {0} FRI layer commitments, but proof contained {1}")]
+    LayerCommitmentCountMismatch(usize, usize),
+
+    #[error("queried evaluations could not be deserialized: {0}")]
+    QueryValuesDeserializationError(String),
+
+    #[error("query seed proof-of-work verification failed")]
+    QuerySeedProofOfWorkVerificationFailed,
+
+    #[error("queried rows did not match the polynomial commitment")]
+    OpeningCommitmentMismatch,
+
+    #[error("opened values are inconsistent with the low-degree proof")]
+    OpeningValuesNotConsistent,
+
+    #[error("opening proof could not be deserialized: {0}")]
+    OpeningDeserializationError(String),
 }
@@ -11,6 +11,9 @@ use math::{
 };
 use std::mem;
 
+#[cfg(feature = "concurrent")]
+use rayon::prelude::*;
+
 mod context;
 pub use context::VerifierContext;
 
@@ -33,39 +36,64 @@ pub fn verify<B, E, C>(
     evaluations: &[E],
     positions: &[usize],
 ) -> Result<(), VerifierError>
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+    C: VerifierChannel<E>,
+{
+    verify_batched(
+        context,
+        channel,
+        &[evaluations],
+        &[context.domain_size()],
+        positions,
+    )
+}
+
+/// Returns OK(()) if values in the `evaluations` slices represent evaluations of a batch of
+/// polynomials committed to with `FriProver::build_layers_batched()`.
+///
+/// The first polynomial must have degree <= context.max_degree() and is evaluated over the
+/// domain of size context.domain_size(); each subsequent polynomial i is evaluated over a domain
+/// of size `domain_sizes[i]`, and must have degree proportionally smaller. Values of the first
+/// polynomial are at x coordinates specified by the `positions` slice, and values of each
+/// subsequent polynomial are at these positions folded into its domain with
+/// `utils::fold_positions_to()`.
+pub fn verify_batched<B, E, C>(
+    context: &VerifierContext<B>,
+    channel: &C,
+    evaluations: &[&[E]],
+    domain_sizes: &[usize],
+    positions: &[usize],
+) -> Result<(), VerifierError>
 where
     B: StarkField,
     E: FieldElement + From<B>,
     C: VerifierChannel<E>,
 {
     assert!(
-        evaluations.len() == positions.len(),
+        evaluations.len() == domain_sizes.len(),
+        "number of domain sizes must match the number of evaluation vectors"
+    );
+    assert!(
+        domain_sizes.first() == Some(&context.domain_size()),
+        "the first evaluation vector must be over the domain of size {}",
+        context.domain_size()
+    );
+    assert!(
+        evaluations[0].len() == positions.len(),
         "number of positions must match the number of evaluations"
     );
-    let domain_size = context.domain_size();
-    let domain_generator = context.domain_generator();
-    let domain_offset = context.domain_offset();
     let num_partitions = channel.num_fri_partitions();
 
-    // powers of the given root of unity 1, p, p^2, p^3 such that p^4 = 1
-    let quartic_roots = [
-        B::ONE,
-        domain_generator.exp((domain_size as u32 / 4).into()),
-        domain_generator.exp((domain_size as u32 / 2).into()),
-        domain_generator.exp((domain_size as u32 * 3 / 4).into()),
-    ];
-
-    // 1 ----- verify the recursive components of the FRI proof -----------------------------------
-    let mut domain_generator = domain_generator;
-    let mut domain_size = domain_size;
-    let mut max_degree_plus_1 = context.max_degree() + 1;
-    let mut positions = positions.to_vec();
-    let mut evaluations = evaluations.to_vec();
-
+    // 1 ----- read queries of all FRI layers -----------------------------------------------------
+    let mut layers = Vec::with_capacity(context.num_fri_layers());
+    let mut layer_positions = positions.to_vec();
+    let mut domain_size = context.domain_size();
     for depth in 0..context.num_fri_layers() {
         // determine which evaluations were queried in the folded layer
-        let mut folded_positions =
-            utils::fold_positions(&positions, domain_size, context.folding_factor());
+        let folded_positions =
+            utils::fold_positions(&layer_positions, domain_size, context.folding_factor());
         // determine where these evaluations are in the commitment Merkle tree
         let position_indexes = utils::map_positions_to_indexes(
             &folded_positions,
@@ -74,7 +102,126 @@ where
             num_partitions,
         );
         // read query values from the specified indexes in the Merkle tree
-        let layer_values = channel.read_layer_queries(depth, &position_indexes)?;
+        let values = channel.read_layer_queries(depth, &position_indexes)?;
+        // calculate the pseudo-random value used for linear combination in layer folding
+        let alpha = channel.draw_fri_alpha(depth);
+
+        layers.push(LayerQueries {
+            positions: folded_positions.clone(),
+            values,
+            alpha,
+            injected: None,
+        });
+        layer_positions = folded_positions;
+        domain_size /= context.folding_factor();
+    }
+
+    // assign values of injected polynomials to the layers into which they were folded
+    let mut min_depth = 0;
+    for (&input, &input_domain_size) in evaluations.iter().zip(domain_sizes).skip(1) {
+        let depth = (min_depth..layers.len())
+            .find(|&depth| {
+                context.domain_size() / context.folding_factor().pow(depth as u32 + 1)
+                    == input_domain_size
+            })
+            .ok_or(VerifierError::InjectedDomainSizeNotValid)?;
+        if input.len() != layers[depth].positions.len() {
+            return Err(VerifierError::InjectedValuesCountMismatch(
+                layers[depth].positions.len(),
+                input.len(),
+            ));
+        }
+        layers[depth].injected = Some(input);
+        min_depth = depth + 1;
+    }
+
+    // 2 ----- verify the recursive components of the FRI proof -----------------------------------
+
+    // read the remainder from the channel; this also makes sure it matches the commitment
+    let remainder = channel.read_remainder()?;
+
+    // a query at position p belongs to partition p % num_partitions, and all positions folded
+    // from it belong to the same partition; thus, each partition can be verified independently
+    let mut partitions = vec![(Vec::new(), Vec::new()); num_partitions];
+    for (&position, &evaluation) in positions.iter().zip(evaluations[0]) {
+        let partition = &mut partitions[position % num_partitions];
+        partition.0.push(position);
+        partition.1.push(evaluation);
+    }
+
+    #[cfg(not(feature = "concurrent"))]
+    let partitions = partitions.into_iter();
+
+    #[cfg(feature = "concurrent")]
+    let partitions = partitions.into_par_iter();
+
+    partitions
+        .filter(|(positions, _)| !positions.is_empty())
+        .try_for_each(|(positions, evaluations)| {
+            verify_partition(context, &layers, &remainder, positions, evaluations)
+        })?;
+
+    // 3 ----- verify the remainder of the FRI proof ----------------------------------------------
+
+    // make sure the remainder values satisfy the degree
+    verify_remainder(context, remainder)
+}
+
+/// Returns Ok(()) if values in the `remainder` slice (the last FRI layer) represent evaluations
+/// of a polynomial with degree implied by the `context` and the number of FRI layers.
+///
+/// This does not check the remainder against its commitment; this is done when the remainder
+/// is read from the channel with `VerifierChannel::read_remainder()`.
+pub fn verify_remainder<B, E>(
+    context: &VerifierContext<B>,
+    remainder: Vec<E>,
+) -> Result<(), VerifierError>
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+{
+    let num_layers = context.num_fri_layers() as u32;
+    verify_remainder_degree(
+        remainder,
+        (context.max_degree() + 1) / 4usize.pow(num_layers),
+        context.domain_generator().exp(4u32.pow(num_layers).into()),
+        context.blowup_factor(),
+    )
+}
+
+/// Returns Ok(()) if the queries at the specified positions are consistent with the queried
+/// values of all FRI layers and with the `remainder`.
+fn verify_partition<B, E>(
+    context: &VerifierContext<B>,
+    layers: &[LayerQueries<E>],
+    remainder: &[E],
+    mut positions: Vec<usize>,
+    mut evaluations: Vec<E>,
+) -> Result<(), VerifierError>
+where
+    B: StarkField,
+    E: FieldElement + From<B>,
+{
+    let mut domain_generator = context.domain_generator();
+    let mut domain_size = context.domain_size();
+    let domain_offset = context.domain_offset();
+
+    // powers of the given root of unity 1, p, p^2, p^3 such that p^4 = 1
+    let quartic_roots = [
+        B::ONE,
+        domain_generator.exp((domain_size as u32 / 4).into()),
+        domain_generator.exp((domain_size as u32 / 2).into()),
+        domain_generator.exp((domain_size as u32 * 3 / 4).into()),
+    ];
+
+    for (depth, layer) in layers.iter().enumerate() {
+        // determine which evaluations of the layer were queried by this partition
+        let mut folded_positions =
+            utils::fold_positions(&positions, domain_size, context.folding_factor());
+        let layer_values = folded_positions
+            .iter()
+            .map(|&p| layer.values[layer.index_of(p)])
+            .collect::<Vec<_>>();
         let query_values = get_query_values(
             &layer_values,
             &positions,
@@ -101,43 +248,37 @@ where
         // interpolate x and y values into row polynomials
         let row_polys = quartic::interpolate_batch(&xs, &layer_values);
 
-        // calculate the pseudo-random value used for linear combination in layer folding
-        let alpha = channel.draw_fri_alpha(depth);
-
         // check that when the polynomials are evaluated at alpha, the result is equal to
         // the corresponding column value
-        evaluations = quartic::evaluate_batch(&row_polys, alpha);
+        evaluations = quartic::evaluate_batch(&row_polys, layer.alpha);
+
+        // add values of the polynomial injected into this layer, if any
+        if let Some(input) = layer.injected {
+            let alpha_pow = layer.alpha.exp(4u32.into());
+            for (evaluation, &p) in evaluations.iter_mut().zip(folded_positions.iter()) {
+                *evaluation += input[layer.index_of(p)] * alpha_pow;
+            }
+        }
 
         // update variables for the next iteration of the loop
         domain_generator = domain_generator.exp(4u32.into());
-        max_degree_plus_1 /= 4;
         domain_size /= 4;
         mem::swap(&mut positions, &mut folded_positions);
     }
 
-    // 2 ----- verify the remainder of the FRI proof ----------------------------------------------
-
-    // read the remainder from the channel and make sure it matches with the columns
-    // of the previous layer
-    let remainder = channel.read_remainder()?;
+    // make sure the evaluations match the remainder
     for (&position, evaluation) in positions.iter().zip(evaluations) {
         if remainder[position] != evaluation {
             return Err(VerifierError::RemainderValuesNotConsistent);
         }
     }
 
-    // make sure the remainder values satisfy the degree
-    verify_remainder(
-        remainder,
-        max_degree_plus_1,
-        domain_generator,
-        context.blowup_factor(),
-    )
+    Ok(())
 }
 
 /// Returns Ok(true) if values in the `remainder` slice represent evaluations of a polynomial
 /// with degree < max_degree_plus_1 against a domain specified by the `domain_generator`.
-fn verify_remainder<B, E>(
+fn verify_remainder_degree<B, E>(
     remainder: Vec<E>,
     max_degree_plus_1: usize,
     domain_generator: B,
@@ -180,6 +321,28 @@ where
     Ok(())
 }
 
+// LAYER QUERIES
+// ================================================================================================
+
+/// Values queried from a single FRI layer, together with the randomness used to fold the layer
+/// and values of the polynomial injected into the folded layer, if any.
+struct LayerQueries<'a, E: FieldElement> {
+    positions: Vec<usize>,
+    values: Vec<[E; 4]>,
+    alpha: E,
+    injected: Option<&'a [E]>,
+}
+
+impl<'a, E: FieldElement> LayerQueries<'a, E> {
+    /// Returns the index of the specified position in the folded layer among queried positions.
+    fn index_of(&self, position: usize) -> usize {
+        self.positions
+            .iter()
+            .position(|&p| p == position)
+            .expect("position was not queried")
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 fn get_query_values<E: FieldElement>(
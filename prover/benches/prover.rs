@@ -0,0 +1,127 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    Air, Assertion, ComputationContext, EvaluationFrame, ExecutionTrace, FieldExtension,
+    HashFunction, ProofOptions, Prover, TraceInfo, TransitionConstraintDegree,
+};
+use std::time::Duration;
+
+const SIZES: [usize; 2] = [4_096, 16_384];
+const CYCLE_LENGTH: usize = 1_024;
+
+fn prover(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prover");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(20));
+
+    let options = ProofOptions::new(32, 8, 0, HashFunction::Blake3_256, FieldExtension::None);
+
+    for &size in SIZES.iter() {
+        let (_, result) = build_trace(size);
+
+        // every proof rebuilds the domain and the periodic value table from scratch
+        group.bench_function(BenchmarkId::new("prove", size), |bench| {
+            bench.iter_batched(
+                || build_trace(size).0,
+                |trace| prover::prove::<PeriodicAir>(trace, result, options.clone()).unwrap(),
+                BatchSize::LargeInput,
+            );
+        });
+
+        // the domain and the periodic value table are built once and reused for all proofs
+        let prover = Prover::<PeriodicAir>::new(size, result, options.clone()).unwrap();
+        group.bench_function(BenchmarkId::new("prover", size), |bench| {
+            bench.iter_batched(
+                || build_trace(size).0,
+                |trace| prover.prove(trace, result).unwrap(),
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(prover_group, prover);
+criterion_main!(prover_group);
+
+// PERIODIC AIR
+// ================================================================================================
+
+/// A computation which mixes a periodic column of pseudo-random constants into a Fibonacci-like
+/// sequence: s_{0, i+1} = s_{0, i} + s_{1, i} * k_i, s_{1, i+1} = s_{1, i} + s_{0, i}.
+struct PeriodicAir {
+    context: ComputationContext,
+    result: BaseElement,
+}
+
+impl Air for PeriodicAir {
+    type BaseElement = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::with_cycles(1, vec![CYCLE_LENGTH]),
+            TransitionConstraintDegree::new(1),
+        ];
+        PeriodicAir {
+            context: ComputationContext::new(2, trace_info.length, degrees, options),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        result[0] = next[0] - (current[0] + current[1] * periodic_values[0]);
+        result[1] = next[1] - (current[1] + current[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+            Assertion::single(0, last_step, self.result),
+        ]
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseElement>> {
+        vec![get_constants()]
+    }
+}
+
+fn get_constants() -> Vec<BaseElement> {
+    BaseElement::prng_vector([1; 32], CYCLE_LENGTH)
+}
+
+fn build_trace(length: usize) -> (ExecutionTrace<BaseElement>, BaseElement) {
+    let constants = get_constants();
+    let mut trace = ExecutionTrace::new(2, length);
+    trace.fill(
+        |state| {
+            state[0] = BaseElement::ONE;
+            state[1] = BaseElement::ONE;
+        },
+        |step, state| {
+            let s0 = state[0] + state[1] * constants[step % CYCLE_LENGTH];
+            state[1] += state[0];
+            state[0] = s0;
+        },
+    );
+    let result = trace.get(0, length - 1);
+    (trace, result)
+}
@@ -5,28 +5,28 @@
 
 use common::{
     proof::{Commitments, Context, OodEvaluationFrame, Queries, StarkProof},
-    ComputationContext, EvaluationFrame, PublicCoin,
+    utils, ComputationContext, EvaluationFrame, PublicCoin,
 };
-use crypto::{DefaultRandomElementGenerator, Hasher};
+use crypto::{DefaultRandomElementGenerator, Digest, Hasher, MerkleTree};
 use fri::{self, FriProof};
 use math::{
     field::{FieldElement, StarkField},
     utils::log2,
 };
-use std::{convert::TryInto, marker::PhantomData};
-
-#[cfg(feature = "concurrent")]
-use rayon::prelude::*;
+use std::marker::PhantomData;
 
 // TYPES AND INTERFACES
 // ================================================================================================
 
 pub struct ProverChannel<H: Hasher> {
     context: ComputationContext,
-    trace_root: Option<[u8; 32]>,
-    constraint_root: Option<[u8; 32]>,
-    fri_roots: Vec<[u8; 32]>,
-    query_seed: Option<[u8; 32]>,
+    trace_root: Option<H::Digest>,
+    trace_caps: Vec<Vec<H::Digest>>,
+    constraint_root: Option<H::Digest>,
+    constraint_cap: Vec<H::Digest>,
+    fri_roots: Vec<H::Digest>,
+    fri_caps: Vec<Vec<H::Digest>>,
+    query_seed: Option<H::Digest>,
     pow_nonce: u64,
     _hasher: PhantomData<H>,
 }
@@ -40,30 +40,51 @@ impl<H: Hasher> ProverChannel<H> {
         ProverChannel {
             context: context.clone(),
             trace_root: None,
+            trace_caps: Vec::new(),
             constraint_root: None,
+            constraint_cap: Vec::new(),
             fri_roots: Vec::new(),
+            fri_caps: Vec::new(),
             query_seed: None,
             pow_nonce: 0,
             _hasher: PhantomData,
         }
     }
 
-    /// Commits the prover the extended execution trace.
-    pub fn commit_trace(&mut self, trace_root: [u8; 32]) {
+    /// Commits the prover the extended execution trace; the commitment consists of a cap of the
+    /// Merkle tree for every trace segment, and roots of these trees are combined into a seed for
+    /// drawing randomness. The cap of the preprocessed columns, if any, must be the last one; it
+    /// is bound to the seed but is not included in the proof.
+    pub fn commit_trace(&mut self, trace_caps: Vec<Vec<H::Digest>>) {
         assert!(
             self.trace_root.is_none(),
             "trace root has already been committed"
         );
-        self.trace_root = Some(trace_root);
+        let num_segments = self.context.committed_segment_widths().len();
+        assert_eq!(
+            num_segments,
+            trace_caps.len(),
+            "expected a commitment for each of {} trace segments, but received {}",
+            num_segments,
+            trace_caps.len()
+        );
+        let trace_roots = trace_caps
+            .iter()
+            .map(|cap| get_root_from_cap::<H>(cap))
+            .collect::<Vec<_>>();
+        self.trace_root = Some(utils::merge_trace_roots::<H>(&trace_roots));
+        self.trace_caps = trace_caps;
     }
 
-    /// Commits the prover the the constraint evaluations.
-    pub fn commit_constraints(&mut self, constraint_root: [u8; 32]) {
+    /// Commits the prover the the constraint evaluations; the commitment is a cap of the
+    /// constraint Merkle tree, and the root of the tree is used as a seed for drawing randomness.
+    pub fn commit_constraints(&mut self, constraint_cap: Vec<H::Digest>) {
         assert!(
             self.constraint_root.is_none(),
             "constraint root has already been committed"
         );
-        self.constraint_root = Some(constraint_root);
+        self.constraint_root = Some(get_root_from_cap::<H>(&constraint_cap));
+        self.constraint_cap = constraint_cap;
     }
 
     /// Computes query seed from a combination of FRI layers and applies PoW to the seed
@@ -79,7 +100,7 @@ impl<H: Hasher> ProverChannel<H> {
         );
         let options = self.context().options();
         let seed = build_query_seed::<H>(&self.fri_roots);
-        let (seed, nonce) = find_pow_nonce::<H>(seed, options.grinding_factor());
+        let (seed, nonce) = fri::utils::find_pow_nonce::<H>(seed, options.grinding_factor());
         self.query_seed = Some(seed);
         self.pow_nonce = nonce;
     }
@@ -87,12 +108,14 @@ impl<H: Hasher> ProverChannel<H> {
     /// Builds a proof from the previously committed values as well as values
     /// passed in to this method
     pub fn build_proof<B: StarkField, E: FieldElement + From<B>>(
-        self,
-        trace_queries: Queries,
-        constraint_queries: Queries,
+        mut self,
+        trace_queries: Vec<Queries<H::Digest>>,
+        constraint_queries: Queries<H::Digest>,
         ood_frame: EvaluationFrame<E>,
         fri_proof: FriProof,
     ) -> StarkProof {
+        // the verifier gets the commitment to preprocessed columns from the verifying key
+        self.trace_caps.truncate(self.context.num_trace_segments());
         StarkProof {
             context: Context {
                 lde_domain_depth: log2(self.context.lde_domain_size()) as u8,
@@ -100,13 +123,13 @@ impl<H: Hasher> ProverChannel<H> {
                 field_modulus_bytes: B::get_modulus_le_bytes(),
                 options: self.context().options().clone(),
             },
-            commitments: Commitments {
-                trace_root: self.trace_root.unwrap(),
-                constraint_root: self.constraint_root.unwrap(),
-                fri_roots: self.fri_roots,
-            },
-            trace_queries,
-            constraint_queries,
+            commitments: Commitments::new(self.trace_caps, self.constraint_cap, self.fri_caps)
+                .serialize(),
+            trace_queries: trace_queries
+                .iter()
+                .map(|queries| queries.serialize())
+                .collect(),
+            constraint_queries: constraint_queries.serialize(),
             ood_frame: OodEvaluationFrame {
                 trace_at_z1: E::elements_as_bytes(&ood_frame.current).to_vec(),
                 trace_at_z2: E::elements_as_bytes(&ood_frame.next).to_vec(),
@@ -118,11 +141,10 @@ impl<H: Hasher> ProverChannel<H> {
 }
 
 impl<H: Hasher> fri::ProverChannel for ProverChannel<H> {
-    type Hasher = H;
-
     /// Commits the prover to the a FRI layer.
-    fn commit_fri_layer(&mut self, layer_root: [u8; 32]) {
-        self.fri_roots.push(layer_root);
+    fn commit_fri_layer(&mut self, layer_cap: Vec<H::Digest>) {
+        self.fri_roots.push(get_root_from_cap::<H>(&layer_cap));
+        self.fri_caps.push(layer_cap);
     }
 }
 
@@ -130,18 +152,16 @@ impl<H: Hasher> fri::ProverChannel for ProverChannel<H> {
 // ================================================================================================
 
 impl<H: Hasher> PublicCoin for ProverChannel<H> {
-    type Hasher = H;
-
     fn context(&self) -> &ComputationContext {
         &self.context
     }
 
-    fn constraint_seed(&self) -> [u8; 32] {
+    fn constraint_seed(&self) -> H::Digest {
         assert!(self.trace_root.is_some(), "constraint seed is not set");
         self.trace_root.unwrap()
     }
 
-    fn composition_seed(&self) -> [u8; 32] {
+    fn composition_seed(&self) -> H::Digest {
         assert!(
             self.constraint_root.is_some(),
             "composition seed is not set"
@@ -149,16 +169,17 @@ impl<H: Hasher> PublicCoin for ProverChannel<H> {
         self.constraint_root.unwrap()
     }
 
-    fn query_seed(&self) -> [u8; 32] {
+    fn query_seed(&self) -> H::Digest {
         assert!(self.query_seed.is_some(), "query seed is not set");
         self.query_seed.unwrap()
     }
 }
 
 impl<H: Hasher> fri::PublicCoin for ProverChannel<H> {
+    type Hasher = H;
     type RandomElementGenerator = DefaultRandomElementGenerator<H>;
 
-    fn fri_layer_commitments(&self) -> &[[u8; 32]] {
+    fn fri_layer_commitments(&self) -> &[H::Digest] {
         assert!(!self.fri_roots.is_empty(), "FRI layers are not set");
         &self.fri_roots
     }
@@ -166,54 +187,17 @@ impl<H: Hasher> fri::PublicCoin for ProverChannel<H> {
 
 // HELPER FUNCTIONS
 // ================================================================================================
-fn build_query_seed<H: Hasher>(fri_roots: &[[u8; 32]]) -> [u8; 32] {
-    let hash_fn = H::hash_fn();
+fn get_root_from_cap<H: Hasher>(cap: &[H::Digest]) -> H::Digest {
+    MerkleTree::<H>::get_root_from_cap(cap).expect("invalid Merkle tree cap")
+}
+
+fn build_query_seed<H: Hasher>(fri_roots: &[H::Digest]) -> H::Digest {
     // combine roots of all FIR layers into a single array of bytes
     let mut root_bytes: Vec<u8> = Vec::with_capacity(fri_roots.len() * 32);
     for root in fri_roots.iter() {
-        root.iter().for_each(|&v| root_bytes.push(v));
+        root_bytes.extend_from_slice(&root.as_bytes());
     }
 
-    // hash the array of bytes into a single 32-byte value
-    let mut query_seed = [0u8; 32];
-    hash_fn(&root_bytes, &mut query_seed);
-
-    query_seed
-}
-
-fn find_pow_nonce<H: Hasher>(seed: [u8; 32], grinding_factor: u32) -> ([u8; 32], u64) {
-    let hash_fn = H::hash_fn();
-    let mut buf = [0u8; 64];
-    buf[0..32].copy_from_slice(&seed);
-
-    #[cfg(not(feature = "concurrent"))]
-    let nonce = (1..u64::MAX)
-        .find(|nonce| {
-            let mut result = [0u8; 32];
-            let mut buf = buf;
-
-            buf[56..].copy_from_slice(&nonce.to_le_bytes());
-            hash_fn(&buf, &mut result);
-            u64::from_le_bytes(result[..8].try_into().unwrap()).trailing_zeros() >= grinding_factor
-        })
-        .expect("nonce not found");
-
-    #[cfg(feature = "concurrent")]
-    let nonce = (1..u64::MAX)
-        .into_par_iter()
-        .find_any(|nonce| {
-            let mut result = [0u8; 32];
-            let mut buf = buf;
-
-            buf[56..].copy_from_slice(&nonce.to_le_bytes());
-            hash_fn(&buf, &mut result);
-            u64::from_le_bytes(result[..8].try_into().unwrap()).trailing_zeros() >= grinding_factor
-        })
-        .expect("nonce not found");
-
-    let mut result = [0u8; 32];
-    buf[56..].copy_from_slice(&nonce.to_le_bytes());
-    hash_fn(&buf, &mut result);
-
-    (result, nonce)
+    // hash the array of bytes into a single digest
+    H::hash(&root_bytes)
 }
@@ -4,13 +4,21 @@
 // LICENSE file in the root directory of this source tree.
 
 mod monolith;
-pub use monolith::{prove, ExecutionTrace, ExecutionTraceFragment};
+pub use monolith::{
+    prove, prove_segments, prove_with_key, prove_with_report, setup, BufferSizes, CompositionBuilt,
+    CompositionPoly, ConstraintEvaluationTable, ConstraintPoly, ConstraintsCommitted,
+    ConstraintsEvaluated, ExecutionTrace, ExecutionTraceFragment, FriCommitted, PhaseTimings,
+    ProofSizes, Prover, ProvingKey, ProvingReport, TraceCommitted, TracePolyTable, TraceTable,
+};
 
 mod channel;
 
 pub use common::{
-    proof::StarkProof, Air, Assertion, ComputationContext, EvaluationFrame, FieldExtension,
-    HashFunction, ProofOptions, TraceInfo, TransitionConstraintDegree, TransitionConstraintGroup,
+    errors::ProverError,
+    proof::{Commitments, Queries, StarkProof},
+    Air, Assertion, ComputationContext, EvaluationFrame, FieldExtension, HashFunction,
+    LinkConstraints, NoLinks, ProductAir, ProductInputs, ProofOptions, SegmentBoundary, TraceInfo,
+    TransitionConstraintDegree, TransitionConstraintGroup, VerifyingKey,
 };
 pub use crypto;
 pub use math;
@@ -20,9 +20,8 @@ use rayon::prelude::*;
 
 pub struct ConstraintCommitment<E: FieldElement, H: Hasher> {
     evaluations: Vec<E>,
-    commitment: MerkleTree,
+    commitment: MerkleTree<H>,
     _element: PhantomData<E>,
-    _hasher: PhantomData<H>,
 }
 
 impl<E: FieldElement, H: Hasher> ConstraintCommitment<E, H> {
@@ -50,15 +49,14 @@ impl<E: FieldElement, H: Hasher> ConstraintCommitment<E, H> {
         // build Merkle tree out of hashed evaluation values
         ConstraintCommitment {
             evaluations,
-            commitment: MerkleTree::new(hashed_evaluations, H::hash_fn()),
+            commitment: MerkleTree::new(hashed_evaluations),
             _element: PhantomData,
-            _hasher: PhantomData,
         }
     }
 
-    /// Returns the root of the commitment Merkle tree.
-    pub fn root(&self) -> [u8; 32] {
-        *self.commitment.root()
+    /// Returns the cap of the specified height of the commitment Merkle tree.
+    pub fn cap(&self, cap_height: usize) -> Vec<H::Digest> {
+        self.commitment.cap(cap_height)
     }
 
     /// Returns the depth of the commitment Merkle tree.
@@ -67,8 +65,9 @@ impl<E: FieldElement, H: Hasher> ConstraintCommitment<E, H> {
     }
 
     /// Returns constraint evaluations at the specified positions along with Merkle
-    /// authentication paths from the root of the commitment to these evaluations.
-    pub fn query(self, trace_positions: &[usize]) -> Queries {
+    /// authentication paths from the cap of the specified height of the commitment to these
+    /// evaluations.
+    pub fn query(self, trace_positions: &[usize], cap_height: usize) -> Queries<H::Digest> {
         // first, map trace positions to the corresponding positions in the constraint tree;
         // we do this because multiple constraint evaluations may be stored in a single leaf
         let evaluations_per_leaf = evaluations_per_leaf::<E, H>();
@@ -76,7 +75,9 @@ impl<E: FieldElement, H: Hasher> ConstraintCommitment<E, H> {
             map_trace_to_constraint_positions(trace_positions, evaluations_per_leaf);
 
         // build Merkle authentication paths to the leaves specified by constraint positions
-        let merkle_proof = self.commitment.prove_batch(&constraint_positions);
+        let merkle_proof = self
+            .commitment
+            .prove_batch_to_cap(&constraint_positions, cap_height);
 
         // determine a set of evaluations corresponding to each position
         let mut evaluations = Vec::new();
@@ -95,22 +96,20 @@ impl<E: FieldElement, H: Hasher> ConstraintCommitment<E, H> {
 /// Computes hashes of evaluations grouped by N elements and returns the resulting hashes.
 fn hash_evaluations<E: FieldElement, H: Hasher, const N: usize>(
     evaluations: &[E],
-) -> Vec<[u8; 32]> {
+) -> Vec<H::Digest> {
     let evaluations = group_slice_elements::<E, N>(evaluations);
-
-    let hash_fn = H::hash_fn();
-    let mut result = uninit_vector::<[u8; 32]>(evaluations.len());
+    let mut result = uninit_vector::<H::Digest>(evaluations.len());
 
     #[cfg(not(feature = "concurrent"))]
     for (result, evaluations) in result.iter_mut().zip(evaluations.iter()) {
-        hash_fn(E::elements_as_bytes(evaluations), result);
+        *result = H::hash_elements(evaluations);
     }
     #[cfg(feature = "concurrent")]
     result
         .par_iter_mut()
         .zip(evaluations.par_iter())
         .for_each(|(result, evaluations)| {
-            hash_fn(E::elements_as_bytes(evaluations), result);
+            *result = H::hash_elements(evaluations);
         });
 
     result
@@ -45,6 +45,7 @@ impl<E: FieldElement> ConstraintPoly<E> {
 
     /// Returns the length of the vector containing constraint polynomial coefficients;
     /// this is guaranteed to be a power of 2.
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         self.coefficients.len()
     }
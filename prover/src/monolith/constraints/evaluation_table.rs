@@ -137,15 +137,15 @@ impl<B: StarkField, E: FieldElement + From<B>> ConstraintEvaluationTable<B, E> {
     // --------------------------------------------------------------------------------------------
     /// Interpolates all constraint evaluations into polynomials, divides them by their respective
     /// divisors, and combines the results into a single polynomial
-    pub fn into_poly(self) -> Result<ConstraintPoly<E>, ProverError> {
+    pub fn into_poly(self, domain: &StarkDomain<B>) -> Result<ConstraintPoly<E>, ProverError> {
         let constraint_poly_degree = self.constraint_poly_degree();
         let domain_offset = self.domain_offset;
 
         // allocate memory for the combined polynomial
         let mut combined_poly = E::zeroed_vector(self.num_rows());
 
-        // build twiddles for interpolation; these can be used to interpolate all polynomials
-        let inv_twiddles = fft::get_inv_twiddles::<B>(self.num_rows());
+        // get twiddles for interpolation; these can be used to interpolate all polynomials
+        let inv_twiddles = domain.ce_inv_twiddles();
 
         #[cfg(feature = "concurrent")]
         {
@@ -155,7 +155,7 @@ impl<B: StarkField, E: FieldElement + From<B>> ConstraintEvaluationTable<B, E> {
                 .into_par_iter()
                 .zip(divisors.par_iter())
                 .map(|(column, divisor)| {
-                    apply_divisor(column, divisor, &inv_twiddles, domain_offset)
+                    apply_divisor(column, divisor, inv_twiddles, domain_offset)
                 })
                 .collect::<Vec<_>>();
 
@@ -169,7 +169,7 @@ impl<B: StarkField, E: FieldElement + From<B>> ConstraintEvaluationTable<B, E> {
         // iterate over all columns of the constraint evaluation table
         #[cfg(not(feature = "concurrent"))]
         for (column, divisor) in self.evaluations.into_iter().zip(self.divisors.iter()) {
-            let poly = apply_divisor(column, divisor, &inv_twiddles, domain_offset);
+            let poly = apply_divisor(column, divisor, inv_twiddles, domain_offset);
             #[cfg(debug_assertions)]
             validate_degree(&poly, constraint_poly_degree)?;
             add_in_place(&mut combined_poly, &poly);
@@ -21,23 +21,27 @@ const MIN_CONCURRENT_DOMAIN_SIZE: usize = 8192;
 // CONSTRAINT EVALUATOR
 // ================================================================================================
 
-pub struct ConstraintEvaluator<A: Air, E: FieldElement + From<A::BaseElement>> {
+pub struct ConstraintEvaluator<'a, A: Air, E: FieldElement + From<A::BaseElement>> {
     air: A,
     boundary_constraints: Vec<BoundaryConstraintGroup<A::BaseElement, E>>,
     transition_constraints: Vec<TransitionConstraintGroup<E>>,
-    periodic_values: PeriodicValueTable<A::BaseElement>,
+    periodic_values: &'a PeriodicValueTable<A::BaseElement>,
     divisors: Vec<ConstraintDivisor<A::BaseElement>>,
 
     #[cfg(debug_assertions)]
     transition_constraint_degrees: Vec<usize>,
 }
 
-impl<A: Air, E: FieldElement + From<A::BaseElement>> ConstraintEvaluator<A, E> {
+impl<'a, A: Air, E: FieldElement + From<A::BaseElement>> ConstraintEvaluator<'a, A, E> {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     /// Returns a new evaluator which can be used to evaluate transition and boundary constraints
-    /// over extended execution trace.
-    pub fn new<C: PublicCoin>(air: A, coin: &C) -> Self {
+    /// over extended execution trace; `periodic_values` must be built for the same AIR.
+    pub fn new<C: PublicCoin>(
+        air: A,
+        coin: &C,
+        periodic_values: &'a PeriodicValueTable<A::BaseElement>,
+    ) -> Self {
         // collect expected degrees for all transition constraints to compare them against actual
         // degrees; we do this in debug mode only because this comparison is expensive
         #[cfg(debug_assertions)]
@@ -53,9 +57,6 @@ impl<A: Air, E: FieldElement + From<A::BaseElement>> ConstraintEvaluator<A, E> {
         let transition_constraints =
             air.get_transition_constraints(coin.get_transition_coefficient_prng());
 
-        // build periodic value table
-        let periodic_values = PeriodicValueTable::new(&air);
-
         // set divisor for transition constraints; since divisors for all transition constraints
         // are the same: (x^steps - 1) / (x - x_at_last_step), all transition constraints will be
         // merged into a single value, and the divisor for that value will be first in the list
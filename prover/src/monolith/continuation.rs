@@ -0,0 +1,82 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{ExecutionTrace, Prover};
+use common::{
+    errors::ProverError, proof::StarkProof, Air, ProofOptions, SegmentBoundary, TraceInfo,
+};
+use math::field::StarkField;
+
+// CONTINUATIONS
+// ================================================================================================
+
+/// Generates STARK proofs for a computation which runs for `num_segments` segments of
+/// `segment_length` steps each, starting in `initial_state`.
+///
+/// Traces of the segments are filled in the same way as `ExecutionTrace::fill()` and
+/// `ExecutionTraceFragment::fill()` do: `update` closure receives the index of the last updated
+/// step of the entire computation and a mutable reference to the last updated state. The last
+/// state of every segment is repeated as the first state of the next segment, and thus, each
+/// segment advances the computation by `segment_length - 1` steps.
+///
+/// Only one segment trace is kept in memory at a time; each segment is proven against its
+/// `SegmentBoundary` as public inputs. The returned proofs, together with the boundaries, can
+/// be verified using `verifier::verify_segments()`. Periodic columns of the AIR, if any, restart
+/// at the beginning of every segment.
+#[allow(clippy::type_complexity)]
+pub fn prove_segments<B, AIR, U>(
+    initial_state: Vec<B>,
+    num_segments: usize,
+    segment_length: usize,
+    update: U,
+    options: ProofOptions,
+) -> Result<Vec<(StarkProof, SegmentBoundary<B>)>, ProverError>
+where
+    B: StarkField,
+    AIR: Air<BaseElement = B, PublicInputs = SegmentBoundary<B>>,
+    U: Fn(usize, &mut [B]),
+{
+    assert!(
+        num_segments > 0,
+        "number of segments must be greater than 0"
+    );
+
+    // boundary values are used only to instantiate the AIR for the prover; these do not affect
+    // the shape of the computation
+    let boundary = SegmentBoundary::new(initial_state.clone(), initial_state.clone());
+    let trace_info = TraceInfo {
+        length: segment_length,
+        meta: Vec::new(),
+    };
+    let trace_width = AIR::new(trace_info, boundary.clone(), options.clone()).trace_width();
+    assert!(
+        initial_state.len() == trace_width,
+        "initial state must consist of {} registers, but was {}",
+        trace_width,
+        initial_state.len()
+    );
+    let prover = Prover::<AIR>::new(segment_length, boundary, options)?;
+
+    let mut result = Vec::with_capacity(num_segments);
+    let mut state = initial_state;
+    for segment_idx in 0..num_segments {
+        let offset = segment_idx * (segment_length - 1);
+        let mut trace = ExecutionTrace::new(trace_width, segment_length);
+        trace.fill(
+            |first_state| first_state.copy_from_slice(&state),
+            |step, state| update(offset + step, state),
+        );
+
+        let mut final_state = vec![B::ZERO; trace_width];
+        trace.read_row_into(segment_length - 1, &mut final_state);
+        let boundary = SegmentBoundary::new(state, final_state.clone());
+
+        let proof = prover.prove(trace, boundary.clone())?;
+        result.push((proof, boundary));
+        state = final_state;
+    }
+
+    Ok(result)
+}
@@ -22,10 +22,17 @@ pub struct StarkDomain<B: StarkField> {
     /// vector is half the length of the trace domain size.
     trace_twiddles: Vec<B>,
 
+    /// Inverse twiddles which can be used to interpolate polynomials over the trace domain.
+    trace_inv_twiddles: Vec<B>,
+
     /// Twiddles which can be used to evaluate polynomials in the constraint evaluation domain.
     /// Length of this vector is half the length of constraint evaluation domain size.
     ce_twiddles: Vec<B>,
 
+    /// Inverse twiddles which can be used to interpolate polynomials over the constraint
+    /// evaluation domain.
+    ce_inv_twiddles: Vec<B>,
+
     // this is used a lot during constraint evaluation; cache it here to avoid recomputation
     ce_to_lde_blowup: usize,
 }
@@ -38,11 +45,15 @@ impl<B: StarkField> StarkDomain<B> {
     pub fn new(context: &ComputationContext) -> Self {
         let lde_domain = build_lde_domain(context.lde_domain_size(), context.domain_offset());
         let trace_twiddles = fft::get_twiddles(context.trace_length());
+        let trace_inv_twiddles = fft::get_inv_twiddles(context.trace_length());
         let ce_twiddles = fft::get_twiddles(context.ce_domain_size());
+        let ce_inv_twiddles = fft::get_inv_twiddles(context.ce_domain_size());
         StarkDomain {
             lde_domain,
             trace_twiddles,
+            trace_inv_twiddles,
             ce_twiddles,
+            ce_inv_twiddles,
             ce_to_lde_blowup: context.lde_domain_size() / context.ce_domain_size(),
         }
     }
@@ -60,6 +71,11 @@ impl<B: StarkField> StarkDomain<B> {
         &self.trace_twiddles
     }
 
+    /// Returns inverse twiddles which can be used to interpolate trace polynomials.
+    pub fn trace_inv_twiddles(&self) -> &[B] {
+        &self.trace_inv_twiddles
+    }
+
     /// Returns blowup factor from trace to constraint evaluation domain.
     #[allow(dead_code)]
     pub fn trace_to_ce_blowup(&self) -> usize {
@@ -84,6 +100,11 @@ impl<B: StarkField> StarkDomain<B> {
         &self.ce_twiddles
     }
 
+    /// Returns inverse twiddles which can be used to interpolate constraint polynomials.
+    pub fn ce_inv_twiddles(&self) -> &[B] {
+        &self.ce_inv_twiddles
+    }
+
     /// Returns blowup factor from constraint evaluation to LDE domain.
     pub fn ce_to_lde_blowup(&self) -> usize {
         self.ce_to_lde_blowup
@@ -4,46 +4,84 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    constraints::{ConstraintCommitment, ConstraintEvaluator},
+    constraints::{
+        ConstraintCommitment, ConstraintEvaluationTable, ConstraintEvaluator, ConstraintPoly,
+    },
     deep_fri::CompositionPoly,
-    trace::ExecutionTrace,
-    ProverChannel, StarkDomain,
+    trace::{ExecutionTrace, TracePolyTable, TraceTable},
+    BufferSizes, ProofSizes, Prover, ProverChannel, ProvingKey, ProvingReport, StarkDomain,
 };
-use common::{errors::ProverError, proof::StarkProof, Air, PublicCoin};
-use crypto::Hasher;
+use common::{
+    errors::ProverError, proof::StarkProof, Air, ComputationContext, EvaluationFrame, PublicCoin,
+};
+use crypto::{Digest, Hasher, MerkleTree};
+use fri::FriProver;
 use log::debug;
-use math::{fft::infer_degree, field::FieldElement, utils::log2};
-use std::time::Instant;
+use math::{
+    fft::infer_degree,
+    field::{FieldElement, StarkField},
+    utils::log2,
+};
+use std::{
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 // PROOF GENERATION PROCEDURE
 // ================================================================================================
 
-pub fn generate_proof<A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>(
+/// Generates a proof by running all stages of the proving pipeline one after another; a report
+/// summarizing proof generation is returned together with the proof.
+pub fn generate_proof<A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher + 'static>(
     air: A,
     trace: ExecutionTrace<A::BaseElement>,
-) -> Result<StarkProof, ProverError> {
-    // create a channel; this simulates interaction between the prover and the verifier;
-    // the channel will be used to commit to values and to draw randomness that should
-    // come from the verifier
-    let mut channel = ProverChannel::<H>::new(air.context());
-
-    let context = air.context().clone(); // TODO: find a better way?
-
-    // 1 ----- extend execution trace -------------------------------------------------------------
-
-    // build computation domain; this is used later for polynomial evaluations
+    prover: &Prover<A>,
+    proving_key: Option<&ProvingKey<A::BaseElement>>,
+) -> Result<(StarkProof, ProvingReport), ProverError> {
+    // if preprocessed columns were not extended during setup, we extend them here
     let now = Instant::now();
-    let domain = StarkDomain::new(air.context());
-    debug!(
-        "Built domain of 2^{} elements in {} ms",
-        log2(domain.lde_domain_size()),
-        now.elapsed().as_millis()
-    );
+    let generated_key;
+    let proving_key = match proving_key {
+        None if air.context().num_preprocessed_columns() > 0 => {
+            generated_key = ProvingKey::new::<A, H>(&air, &prover.domain);
+            Some(&generated_key)
+        }
+        key => key,
+    };
+
+    let (extended_trace, trace_polys) = extend_trace(trace, &prover.domain, proving_key);
+    let trace_extension = now.elapsed();
+    let preprocessed_tree = proving_key.and_then(|key| key.preprocessed_tree::<H>());
+
+    TraceCommitted::<A, E, H>::new(
+        air,
+        prover,
+        extended_trace,
+        trace_polys,
+        preprocessed_tree.into_iter().collect(),
+        trace_extension,
+    )
+    .evaluate_constraints()
+    .commit_constraints()?
+    .build_composition()
+    .run_fri()
+    .build_proof_with_report()
+}
 
+/// Extends the execution trace over the LDE domain, and appends extended preprocessed columns
+/// taken from the `proving_key` to the result; the key must be provided if the computation has
+/// preprocessed columns.
+pub(super) fn extend_trace<B: StarkField>(
+    trace: ExecutionTrace<B>,
+    domain: &StarkDomain<B>,
+    proving_key: Option<&ProvingKey<B>>,
+) -> (TraceTable<B>, TracePolyTable<B>) {
     // extend the execution trace; this interpolates each register of the trace into a polynomial,
     // and then evaluates the polynomial over the LDE domain; each of the trace polynomials has
     // degree = trace_length - 1
-    let (extended_trace, trace_polys) = trace.extend(&domain);
+    let now = Instant::now();
+    let (mut extended_trace, mut trace_polys) = trace.extend(domain);
     debug!(
         "Extended execution trace of {} registers from 2^{} to 2^{} steps ({}x blowup) in {} ms",
         extended_trace.width(),
@@ -53,156 +91,515 @@ pub fn generate_proof<A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>
         now.elapsed().as_millis()
     );
 
-    // 2 ----- commit to the extended execution trace ---------------------------------------------
-    let now = Instant::now();
-    let trace_tree = extended_trace.build_commitment::<H>();
-    channel.commit_trace(*trace_tree.root());
-    debug!(
-        "Committed to extended execution trace by building a Merkle tree of depth {} in {} ms",
-        trace_tree.depth(),
-        now.elapsed().as_millis()
-    );
-
-    // 3 ----- evaluate constraints ---------------------------------------------------------------
-    let now = Instant::now();
-
-    // build constraint evaluator; the channel is passed in for the evaluator to draw random
-    // values from; these values are used by the evaluator to compute a random linear
-    // combination of constraint evaluations
-    let evaluator = ConstraintEvaluator::new(air, &channel);
-
-    // apply constraint evaluator to the extended trace table to generate a
-    // constraint evaluation table
-    let constraint_evaluations = evaluator.evaluate(&extended_trace, &domain);
-    debug!(
-        "Evaluated constraints over domain of 2^{} elements in {} ms",
-        log2(constraint_evaluations.num_rows()),
-        now.elapsed().as_millis()
-    );
-
-    // 4 ----- commit to constraint evaluations ---------------------------------------------------
-
-    // first, build a single constraint polynomial from all constraint evaluations
-    let now = Instant::now();
-    let constraint_poly = constraint_evaluations.into_poly()?;
-    debug!(
-        "Converted constraint evaluations into a single polynomial of degree {} in {} ms",
-        constraint_poly.degree(),
-        now.elapsed().as_millis()
-    );
-
-    // then, evaluate constraint polynomial over the LDE domain
-    let now = Instant::now();
-    let combined_constraint_evaluations = constraint_poly.evaluate(&domain);
-    debug!(
-        "Evaluated constraint polynomial over LDE domain (2^{} elements) in {} ms",
-        log2(combined_constraint_evaluations.len()),
-        now.elapsed().as_millis()
-    );
-
-    // finally, commit to constraint polynomial evaluations
-    let now = Instant::now();
-    let constraint_commitment = ConstraintCommitment::<E, H>::new(combined_constraint_evaluations);
-    channel.commit_constraints(constraint_commitment.root());
-    debug!(
-        "Committed to constraint evaluations by building a Merkle tree of depth {} in {} ms",
-        constraint_commitment.tree_depth(),
-        now.elapsed().as_millis()
-    );
-
-    // 5 ----- build DEEP composition polynomial --------------------------------------------------
-    let now = Instant::now();
-
-    // draw an out-of-domain point z. Depending on the type of E, the point is drawn either
-    // from the base field or from an extension field defined by E.
-    //
-    // The purpose of sampling from the extension field here (instead of the base field) is to
-    // increase security. Soundness is limited by the size of the field that the random point
-    // is drawn from, and we can potentially save on performance by only drawing this point
-    // from an extension field, rather than increasing the size of the field overall.
-    let z = channel.draw_deep_point::<E>();
-
-    // draw random coefficients to use during polynomial composition
-    let coefficients = channel.draw_composition_coefficients();
+    // append extended preprocessed columns to the extended trace
+    if let Some(proving_key) = proving_key {
+        extended_trace.append_registers(proving_key.preprocessed_trace());
+        trace_polys.append_polys(proving_key.preprocessed_polys());
+        debug!(
+            "Appended {} preprocessed columns to the extended execution trace",
+            proving_key.verifying_key().num_columns
+        );
+    }
+
+    (extended_trace, trace_polys)
+}
 
-    // initialize composition polynomial
-    let mut composition_poly = CompositionPoly::new(&context, z, coefficients);
+// STAGE 1: TRACE COMMITTED
+// ================================================================================================
 
-    // combine all trace polynomials together and merge them into the composition polynomial;
-    // ood_frame are trace states at two out-of-domain points, and will go into the proof
-    let ood_frame = composition_poly.add_trace_polys(trace_polys);
+/// State of proof generation after the extended execution trace has been committed to.
+///
+/// This is the first stage of the proving pipeline; it is created by `Prover::commit_trace()`,
+/// `Prover::commit_extended_trace()`, or `Prover::commit_extended_trace_with_trees()`. Type
+/// parameters `E` and `H` specify the field from which the out-of-domain point is drawn and the
+/// hash function used for commitments; these must match the field extension and the hash
+/// function specified in the proof options, and this is checked when the stage is created.
+pub struct TraceCommitted<'a, A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher> {
+    air: A,
+    prover: &'a Prover<A>,
+    channel: ProverChannel<H>,
+    extended_trace: TraceTable<A::BaseElement>,
+    trace_polys: TracePolyTable<A::BaseElement>,
+    trace_trees: Vec<Arc<MerkleTree<H>>>,
+    report: ProvingReport,
+    _field: PhantomData<E>,
+}
 
-    // merge constraint polynomial into the composition polynomial
-    composition_poly.add_constraint_poly(constraint_poly);
+impl<'a, A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher> TraceCommitted<'a, A, E, H> {
+    /// Commits to the extended execution trace; the trace is split into segments, and a separate
+    /// Merkle tree is built for each segment; preprocessed columns, if any, form the last segment.
+    ///
+    /// `committed_trees` are trees which have already been built for the last segments of the
+    /// `extended_trace` (e.g. the tree of the preprocessed segment taken from a proving key);
+    /// trees are built only for the preceding segments. `trace_extension` is the time it took to
+    /// extend the trace; it is recorded in the report.
+    pub(super) fn new(
+        air: A,
+        prover: &'a Prover<A>,
+        extended_trace: TraceTable<A::BaseElement>,
+        trace_polys: TracePolyTable<A::BaseElement>,
+        committed_trees: Vec<Arc<MerkleTree<H>>>,
+        trace_extension: Duration,
+    ) -> Self {
+        // create a channel; this simulates interaction between the prover and the verifier;
+        // the channel will be used to commit to values and to draw randomness that should
+        // come from the verifier
+        let mut channel = ProverChannel::<H>::new(air.context());
+
+        let now = Instant::now();
+        let cap_height = air.context().options().merkle_cap_height();
+        let segment_widths = air.context().committed_segment_widths();
+        let num_built_segments = segment_widths.len() - committed_trees.len();
+        let mut trace_trees = extended_trace
+            .build_commitments::<H>(&segment_widths[..num_built_segments])
+            .into_iter()
+            .map(Arc::new)
+            .collect::<Vec<_>>();
+        trace_trees.extend(committed_trees);
+        let trace_caps = trace_trees
+            .iter()
+            .map(|tree| tree.cap(cap_height))
+            .collect();
+        channel.commit_trace(trace_caps);
+        debug!(
+            "Committed to extended execution trace by building {} Merkle tree(s) of depth {} in {} ms",
+            trace_trees.len(),
+            trace_trees[0].depth(),
+            now.elapsed().as_millis()
+        );
+
+        let mut report = ProvingReport::default();
+        report.timings.trace_extension = trace_extension;
+        report.timings.trace_commitment = now.elapsed();
+        report.buffer_sizes = BufferSizes {
+            extended_trace: extended_trace.width()
+                * extended_trace.len()
+                * A::BaseElement::ELEMENT_BYTES,
+            // a Merkle tree with n leaves contains n internal nodes
+            trace_trees: trace_trees
+                .iter()
+                .map(|tree| tree.leaves().len() * 2 * H::Digest::SIZE)
+                .sum(),
+            ..Default::default()
+        };
+
+        TraceCommitted {
+            air,
+            prover,
+            channel,
+            extended_trace,
+            trace_polys,
+            trace_trees,
+            report,
+            _field: PhantomData,
+        }
+    }
+
+    /// Returns the report of proof generation up to this stage.
+    pub fn report(&self) -> &ProvingReport {
+        &self.report
+    }
+
+    /// Returns evaluations of the execution trace (including preprocessed columns) over the LDE
+    /// domain.
+    pub fn extended_trace(&self) -> &TraceTable<A::BaseElement> {
+        &self.extended_trace
+    }
+
+    /// Returns polynomials interpolated from the execution trace.
+    pub fn trace_polys(&self) -> &TracePolyTable<A::BaseElement> {
+        &self.trace_polys
+    }
+
+    /// Returns Merkle trees committing to segments of the extended execution trace.
+    pub fn trace_trees(&self) -> &[Arc<MerkleTree<H>>] {
+        &self.trace_trees
+    }
+
+    /// Evaluates transition and boundary constraints over the constraint evaluation domain.
+    pub fn evaluate_constraints(self) -> ConstraintsEvaluated<'a, A, E, H> {
+        let now = Instant::now();
+        let context = self.air.context().clone();
+        let domain = &self.prover.domain;
+
+        // build constraint evaluator; the channel is passed in for the evaluator to draw random
+        // values from; these values are used by the evaluator to compute a random linear
+        // combination of constraint evaluations; periodic column values are cached by the prover
+        let evaluator =
+            ConstraintEvaluator::new(self.air, &self.channel, &self.prover.periodic_values);
+
+        // apply constraint evaluator to the extended trace table to generate a
+        // constraint evaluation table
+        let constraint_evaluations = evaluator.evaluate(&self.extended_trace, domain);
+        debug!(
+            "Evaluated constraints over domain of 2^{} elements in {} ms",
+            log2(constraint_evaluations.num_rows()),
+            now.elapsed().as_millis()
+        );
+
+        let mut report = self.report;
+        report.timings.constraint_evaluation = now.elapsed();
+        report.buffer_sizes.constraint_evaluations = constraint_evaluations.num_rows()
+            * constraint_evaluations.num_columns()
+            * E::ELEMENT_BYTES;
+
+        ConstraintsEvaluated {
+            context,
+            prover: self.prover,
+            channel: self.channel,
+            extended_trace: self.extended_trace,
+            trace_polys: self.trace_polys,
+            trace_trees: self.trace_trees,
+            constraint_evaluations,
+            report,
+        }
+    }
+}
 
-    debug!(
-        "Built DEEP composition polynomial of degree {} in {} ms",
-        composition_poly.degree(),
-        now.elapsed().as_millis()
-    );
+// STAGE 2: CONSTRAINTS EVALUATED
+// ================================================================================================
 
-    // 6 ----- evaluate DEEP composition polynomial over LDE domain -------------------------------
-    let now = Instant::now();
-    let composed_evaluations = composition_poly.evaluate(&domain);
-    // we check the following condition in debug mode only because infer_degree is an expensive
-    // operation
-    debug_assert_eq!(
-        context.deep_composition_degree(),
-        infer_degree(&composed_evaluations, domain.offset())
-    );
-    debug!(
-        "Evaluated DEEP composition polynomial over LDE domain (2^{} elements) in {} ms",
-        log2(context.lde_domain_size()),
-        now.elapsed().as_millis()
-    );
+/// State of proof generation after constraints have been evaluated over the constraint
+/// evaluation domain.
+pub struct ConstraintsEvaluated<'a, A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher> {
+    context: ComputationContext,
+    prover: &'a Prover<A>,
+    channel: ProverChannel<H>,
+    extended_trace: TraceTable<A::BaseElement>,
+    trace_polys: TracePolyTable<A::BaseElement>,
+    trace_trees: Vec<Arc<MerkleTree<H>>>,
+    constraint_evaluations: ConstraintEvaluationTable<A::BaseElement, E>,
+    report: ProvingReport,
+}
 
-    // 7 ----- compute FRI layers for the composition polynomial ----------------------------------
-    let now = Instant::now();
-    let mut fri_prover = fri::FriProver::new(context.options().to_fri_options());
-    fri_prover.build_layers(&mut channel, composed_evaluations, &domain.lde_values());
-    debug!(
-        "Computed {} FRI layers from composition polynomial evaluations in {} ms",
-        fri_prover.num_layers(),
-        now.elapsed().as_millis()
-    );
+impl<'a, A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>
+    ConstraintsEvaluated<'a, A, E, H>
+{
+    /// Returns the table of constraint evaluations; each column of the table contains merged
+    /// evaluations of all constraints sharing the same divisor.
+    pub fn constraint_evaluations(&self) -> &ConstraintEvaluationTable<A::BaseElement, E> {
+        &self.constraint_evaluations
+    }
+
+    /// Returns the report of proof generation up to this stage.
+    pub fn report(&self) -> &ProvingReport {
+        &self.report
+    }
+
+    /// Combines constraint evaluations into a single constraint polynomial, evaluates it over
+    /// the LDE domain, and commits to the result.
+    ///
+    /// Returns an error if components of the constraint polynomial are not all of the same degree.
+    pub fn commit_constraints(self) -> Result<ConstraintsCommitted<'a, A, E, H>, ProverError> {
+        let domain = &self.prover.domain;
+        let cap_height = self.context.options().merkle_cap_height();
+        let mut channel = self.channel;
+        let mut report = self.report;
+
+        // first, build a single constraint polynomial from all constraint evaluations
+        let now = Instant::now();
+        let constraint_poly = self.constraint_evaluations.into_poly(domain)?;
+        debug!(
+            "Converted constraint evaluations into a single polynomial of degree {} in {} ms",
+            constraint_poly.degree(),
+            now.elapsed().as_millis()
+        );
+        report.timings.constraint_interpolation = now.elapsed();
+
+        // then, evaluate constraint polynomial over the LDE domain
+        let now = Instant::now();
+        let combined_constraint_evaluations = constraint_poly.evaluate(domain);
+        debug!(
+            "Evaluated constraint polynomial over LDE domain (2^{} elements) in {} ms",
+            log2(combined_constraint_evaluations.len()),
+            now.elapsed().as_millis()
+        );
+        report.timings.constraint_commitment = now.elapsed();
+        report.buffer_sizes.constraint_commitment =
+            combined_constraint_evaluations.len() * E::ELEMENT_BYTES;
+
+        // finally, commit to constraint polynomial evaluations
+        let now = Instant::now();
+        let constraint_commitment =
+            ConstraintCommitment::<E, H>::new(combined_constraint_evaluations);
+        channel.commit_constraints(constraint_commitment.cap(cap_height));
+        debug!(
+            "Committed to constraint evaluations by building a Merkle tree of depth {} in {} ms",
+            constraint_commitment.tree_depth(),
+            now.elapsed().as_millis()
+        );
+        report.timings.constraint_commitment += now.elapsed();
+
+        Ok(ConstraintsCommitted {
+            context: self.context,
+            prover: self.prover,
+            channel,
+            extended_trace: self.extended_trace,
+            trace_polys: self.trace_polys,
+            trace_trees: self.trace_trees,
+            constraint_poly,
+            constraint_commitment,
+            report,
+        })
+    }
+}
 
-    // 8 ----- determine query positions ----------------------------------------------------------
-    let now = Instant::now();
+// STAGE 3: CONSTRAINTS COMMITTED
+// ================================================================================================
 
-    // apply proof-of-work to the query seed
-    channel.grind_query_seed();
+/// State of proof generation after evaluations of the constraint polynomial over the LDE domain
+/// have been committed to.
+pub struct ConstraintsCommitted<'a, A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher> {
+    context: ComputationContext,
+    prover: &'a Prover<A>,
+    channel: ProverChannel<H>,
+    extended_trace: TraceTable<A::BaseElement>,
+    trace_polys: TracePolyTable<A::BaseElement>,
+    trace_trees: Vec<Arc<MerkleTree<H>>>,
+    constraint_poly: ConstraintPoly<E>,
+    constraint_commitment: ConstraintCommitment<E, H>,
+    report: ProvingReport,
+}
 
-    // generate pseudo-random query positions
-    let query_positions = channel.draw_query_positions();
-    debug!(
-        "Determined {} query positions in {} ms",
-        query_positions.len(),
-        now.elapsed().as_millis()
-    );
+impl<'a, A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>
+    ConstraintsCommitted<'a, A, E, H>
+{
+    /// Returns the polynomial combining all constraint evaluations.
+    pub fn constraint_poly(&self) -> &ConstraintPoly<E> {
+        &self.constraint_poly
+    }
+
+    /// Returns the report of proof generation up to this stage.
+    pub fn report(&self) -> &ProvingReport {
+        &self.report
+    }
+
+    /// Draws an out-of-domain point z and merges trace and constraint polynomials into a DEEP
+    /// composition polynomial using coefficients drawn from the channel.
+    pub fn build_composition(self) -> CompositionBuilt<'a, A, E, H> {
+        let now = Instant::now();
+
+        // draw an out-of-domain point z. Depending on the type of E, the point is drawn either
+        // from the base field or from an extension field defined by E.
+        //
+        // The purpose of sampling from the extension field here (instead of the base field) is to
+        // increase security. Soundness is limited by the size of the field that the random point
+        // is drawn from, and we can potentially save on performance by only drawing this point
+        // from an extension field, rather than increasing the size of the field overall.
+        let z = self.channel.draw_deep_point::<E>();
+
+        // draw random coefficients to use during polynomial composition
+        let coefficients = self.channel.draw_composition_coefficients();
+
+        // initialize composition polynomial
+        let mut composition_poly = CompositionPoly::new(&self.context, z, coefficients);
+
+        // combine all trace polynomials together and merge them into the composition polynomial;
+        // ood_frame are trace states at two out-of-domain points, and will go into the proof
+        let ood_frame = composition_poly.add_trace_polys(self.trace_polys);
+
+        // merge constraint polynomial into the composition polynomial
+        composition_poly.add_constraint_poly(self.constraint_poly);
+
+        debug!(
+            "Built DEEP composition polynomial of degree {} in {} ms",
+            composition_poly.degree(),
+            now.elapsed().as_millis()
+        );
+
+        let mut report = self.report;
+        report.timings.deep_composition = now.elapsed();
+
+        CompositionBuilt {
+            context: self.context,
+            prover: self.prover,
+            channel: self.channel,
+            extended_trace: self.extended_trace,
+            trace_trees: self.trace_trees,
+            constraint_commitment: self.constraint_commitment,
+            composition_poly,
+            ood_frame,
+            report,
+        }
+    }
+}
 
-    // 9 ----- build proof object -----------------------------------------------------------------
-    let now = Instant::now();
+// STAGE 4: COMPOSITION BUILT
+// ================================================================================================
 
-    // generate FRI proof
-    let fri_proof = fri_prover.build_proof(&query_positions);
+/// State of proof generation after the DEEP composition polynomial has been built.
+pub struct CompositionBuilt<'a, A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher> {
+    context: ComputationContext,
+    prover: &'a Prover<A>,
+    channel: ProverChannel<H>,
+    extended_trace: TraceTable<A::BaseElement>,
+    trace_trees: Vec<Arc<MerkleTree<H>>>,
+    constraint_commitment: ConstraintCommitment<E, H>,
+    composition_poly: CompositionPoly<E>,
+    ood_frame: EvaluationFrame<E>,
+    report: ProvingReport,
+}
 
-    // query the execution trace at the selected position; for each query, we need the
-    // state of the trace at that position + Merkle authentication path
-    let trace_queries = extended_trace.query(trace_tree, &query_positions);
+impl<'a, A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher> CompositionBuilt<'a, A, E, H> {
+    /// Returns the DEEP composition polynomial.
+    pub fn composition_poly(&self) -> &CompositionPoly<E> {
+        &self.composition_poly
+    }
+
+    /// Returns states of the execution trace at the out-of-domain point z and at z * g, where
+    /// g is the generator of the trace domain.
+    pub fn ood_frame(&self) -> &EvaluationFrame<E> {
+        &self.ood_frame
+    }
+
+    /// Returns the report of proof generation up to this stage.
+    pub fn report(&self) -> &ProvingReport {
+        &self.report
+    }
+
+    /// Evaluates the DEEP composition polynomial over the LDE domain, builds FRI layers from the
+    /// evaluations, and determines query positions.
+    pub fn run_fri(self) -> FriCommitted<'a, A, E, H> {
+        let domain = &self.prover.domain;
+        let mut channel = self.channel;
+        let mut report = self.report;
+
+        // evaluate DEEP composition polynomial over LDE domain
+        let now = Instant::now();
+        let composed_evaluations = self.composition_poly.evaluate(domain);
+        // we check the following condition in debug mode only because infer_degree is an expensive
+        // operation
+        debug_assert_eq!(
+            self.context.deep_composition_degree(),
+            infer_degree(&composed_evaluations, domain.offset())
+        );
+        debug!(
+            "Evaluated DEEP composition polynomial over LDE domain (2^{} elements) in {} ms",
+            log2(self.context.lde_domain_size()),
+            now.elapsed().as_millis()
+        );
+        report.timings.deep_composition += now.elapsed();
+        report.buffer_sizes.composition_evaluations = composed_evaluations.len() * E::ELEMENT_BYTES;
+
+        // compute FRI layers for the composition polynomial
+        let now = Instant::now();
+        let mut fri_prover = FriProver::new(self.context.options().to_fri_options());
+        fri_prover.build_layers(&mut channel, composed_evaluations, &domain.lde_values());
+        debug!(
+            "Computed {} FRI layers from composition polynomial evaluations in {} ms",
+            fri_prover.num_layers(),
+            now.elapsed().as_millis()
+        );
+        report.timings.fri_layers = now.elapsed();
+        report.num_fri_layers = fri_prover.num_layers();
+
+        // apply proof-of-work to the query seed, and then generate pseudo-random query positions
+        let now = Instant::now();
+        channel.grind_query_seed();
+        report.timings.grinding = now.elapsed();
+        let now = Instant::now();
+        let query_positions = channel.draw_query_positions();
+        report.timings.query_drawing = now.elapsed();
+        debug!(
+            "Determined {} query positions in {} ms",
+            query_positions.len(),
+            (report.timings.grinding + report.timings.query_drawing).as_millis()
+        );
+
+        FriCommitted {
+            context: self.context,
+            channel,
+            extended_trace: self.extended_trace,
+            trace_trees: self.trace_trees,
+            constraint_commitment: self.constraint_commitment,
+            ood_frame: self.ood_frame,
+            fri_prover,
+            query_positions,
+            report,
+            _air: PhantomData,
+        }
+    }
+}
 
-    // query the constraint commitment at the selected positions; for each query, we need just
-    // a Merkle authentication path. this is because constraint evaluations for each step are
-    // merged into a single value and Merkle authentication paths contain these values already
-    let constraint_queries = constraint_commitment.query(&query_positions);
+// STAGE 5: FRI COMMITTED
+// ================================================================================================
 
-    // build the proof object
-    let proof = channel.build_proof::<A::BaseElement, E>(
-        trace_queries,
-        constraint_queries,
-        ood_frame,
-        fri_proof,
-    );
-    debug!("Built proof object in {} ms", now.elapsed().as_millis());
+/// State of proof generation after FRI layers have been committed to and query positions have
+/// been determined.
+pub struct FriCommitted<'a, A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher> {
+    context: ComputationContext,
+    channel: ProverChannel<H>,
+    extended_trace: TraceTable<A::BaseElement>,
+    trace_trees: Vec<Arc<MerkleTree<H>>>,
+    constraint_commitment: ConstraintCommitment<E, H>,
+    ood_frame: EvaluationFrame<E>,
+    fri_prover: FriProver<A::BaseElement, E, ProverChannel<H>, H>,
+    query_positions: Vec<usize>,
+    report: ProvingReport,
+    _air: PhantomData<&'a Prover<A>>,
+}
 
-    Ok(proof)
+impl<'a, A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher> FriCommitted<'a, A, E, H> {
+    /// Returns the number of FRI layers built from the DEEP composition polynomial.
+    pub fn num_fri_layers(&self) -> usize {
+        self.fri_prover.num_layers()
+    }
+
+    /// Returns positions in the LDE domain at which the commitments will be opened.
+    pub fn query_positions(&self) -> &[usize] {
+        &self.query_positions
+    }
+
+    /// Returns the report of proof generation up to this stage.
+    pub fn report(&self) -> &ProvingReport {
+        &self.report
+    }
+
+    /// Opens all commitments at the query positions and assembles the proof.
+    pub fn build_proof(self) -> Result<StarkProof, ProverError> {
+        self.build_proof_with_report().map(|(proof, _)| proof)
+    }
+
+    /// Opens all commitments at the query positions and assembles the proof; the complete report
+    /// of proof generation is returned together with the proof.
+    pub fn build_proof_with_report(mut self) -> Result<(StarkProof, ProvingReport), ProverError> {
+        let now = Instant::now();
+        let cap_height = self.context.options().merkle_cap_height();
+        let segment_widths = self.context.committed_segment_widths();
+
+        // generate FRI proof
+        let fri_proof = self.fri_prover.build_proof(&self.query_positions);
+
+        // query the execution trace at the selected position; for each query and each trace
+        // segment, we need the state of the segment at that position + Merkle authentication path
+        // to the segment cap
+        let trace_queries = self.extended_trace.query(
+            &self.trace_trees,
+            &segment_widths,
+            &self.query_positions,
+            cap_height,
+        );
+
+        // query the constraint commitment at the selected positions; for each query, we need just
+        // a Merkle authentication path. this is because constraint evaluations for each step are
+        // merged into a single value and Merkle authentication paths contain these values already
+        let constraint_queries = self
+            .constraint_commitment
+            .query(&self.query_positions, cap_height);
+
+        // build the proof object
+        let proof = self.channel.build_proof::<A::BaseElement, E>(
+            trace_queries,
+            constraint_queries,
+            self.ood_frame,
+            fri_proof,
+        );
+        debug!("Built proof object in {} ms", now.elapsed().as_millis());
+
+        let mut report = self.report;
+        report.timings.query_building = now.elapsed();
+        report.proof_sizes = ProofSizes::new(&proof);
+
+        Ok((proof, report))
+    }
 }
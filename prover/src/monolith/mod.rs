@@ -6,29 +6,59 @@
 use crate::channel::ProverChannel;
 use common::{
     errors::ProverError, proof::StarkProof, Air, FieldExtension, HashFunction, ProofOptions,
-    TraceInfo,
+    TraceInfo, VerifyingKey,
+};
+use crypto::{
+    hash::{
+        Blake3_160, Blake3_192, Blake3_256, Poseidon128_256, Poseidon62_248, Rp128_256, Rp62_248,
+        Sha3_256,
+    },
+    Hasher, MerkleTree,
+};
+use math::field::{FieldElement, QuadExtension};
+use std::{
+    any::TypeId,
+    sync::Arc,
+    time::{Duration, Instant},
 };
-use crypto::hash::{Blake3_256, Sha3_256};
-use math::field::QuadExtension;
 
 mod domain;
 use domain::StarkDomain;
 
 mod constraints;
+use constraints::PeriodicValueTable;
+pub use constraints::{ConstraintEvaluationTable, ConstraintPoly};
+
 mod deep_fri;
+pub use deep_fri::CompositionPoly;
 
 mod trace;
-pub use trace::{ExecutionTrace, ExecutionTraceFragment, TracePolyTable};
+pub use trace::{ExecutionTrace, ExecutionTraceFragment, TracePolyTable, TraceTable};
 
 mod generation;
-use generation::generate_proof;
+use generation::{extend_trace, generate_proof};
+pub use generation::{
+    CompositionBuilt, ConstraintsCommitted, ConstraintsEvaluated, FriCommitted, TraceCommitted,
+};
+
+mod setup;
+pub use setup::ProvingKey;
+
+mod report;
+pub use report::{BufferSizes, PhaseTimings, ProofSizes, ProvingReport};
+
+mod continuation;
+pub use continuation::prove_segments;
 
 // PROVER
 // ================================================================================================
 
 /// Generates a STARK proof attesting that the specified `trace` is a valid execution trace of the
 /// computation described by AIR generated using the specified public inputs.
-#[rustfmt::skip]
+///
+/// If the computation has preprocessed columns, these are extended and committed to as a part of
+/// proof generation; use `setup()` and `prove_with_key()` to do this only once. To generate many
+/// proofs for executions of the same length, use `Prover` instead.
 pub fn prove<AIR: Air>(
     trace: ExecutionTrace<AIR::BaseElement>,
     pub_inputs: AIR::PublicInputs,
@@ -42,31 +72,532 @@ pub fn prove<AIR: Air>(
         meta: Vec::new(),
     };
     let air = AIR::new(trace_info, pub_inputs, options);
+    Prover::from_air(&air)
+        .prove_air(air, trace, None)
+        .map(|(proof, _)| proof)
+}
+
+/// Generates a STARK proof in the same way as `prove()` does, and returns it together with a
+/// report containing time spent in each phase of proof generation, sizes of the largest buffers,
+/// and sizes of individual proof sections.
+pub fn prove_with_report<AIR: Air>(
+    trace: ExecutionTrace<AIR::BaseElement>,
+    pub_inputs: AIR::PublicInputs,
+    options: ProofOptions,
+) -> Result<(StarkProof, ProvingReport), ProverError> {
+    let trace_info = TraceInfo {
+        length: trace.len(),
+        meta: Vec::new(),
+    };
+    let air = AIR::new(trace_info, pub_inputs, options);
+    Prover::from_air(&air).prove_air(air, trace, None)
+}
+
+/// Generates a STARK proof in the same way as `prove()` does, but reuses preprocessed columns
+/// extended and committed to during setup.
+///
+/// Returns an error if the `proving_key` was generated for a different trace length, blowup
+/// factor, hash function, or Merkle tree cap height.
+pub fn prove_with_key<AIR: Air>(
+    trace: ExecutionTrace<AIR::BaseElement>,
+    pub_inputs: AIR::PublicInputs,
+    options: ProofOptions,
+    proving_key: &ProvingKey<AIR::BaseElement>,
+) -> Result<StarkProof, ProverError> {
+    let trace_info = TraceInfo {
+        length: trace.len(),
+        meta: Vec::new(),
+    };
+    let air = AIR::new(trace_info, pub_inputs, options);
+    if !proving_key
+        .verifying_key()
+        .is_compatible_with(air.context())
+    {
+        return Err(ProverError::IncompatibleProvingKey);
+    }
+    Prover::from_air(&air)
+        .prove_air(air, trace, Some(proving_key))
+        .map(|(proof, _)| proof)
+}
+
+// SETUP
+// ================================================================================================
+
+/// Extends and commits to preprocessed columns of the computation described by AIR instantiated
+/// for the specified trace length, public inputs, and proof options.
+///
+/// The returned proving key can be used to generate any number of proofs for computations with
+/// the same trace length and proof options via `prove_with_key()`; the verifying key contained
+/// in it is used by the verifier to check openings of the preprocessed columns.
+pub fn setup<AIR: Air>(
+    trace_length: usize,
+    pub_inputs: AIR::PublicInputs,
+    options: ProofOptions,
+) -> Result<ProvingKey<AIR::BaseElement>, ProverError> {
+    let trace_info = TraceInfo {
+        length: trace_length,
+        meta: Vec::new(),
+    };
+    let air = AIR::new(trace_info, pub_inputs, options);
+
+    let hash_fn = air.context().options().hash_fn();
+    if !hash_fn.supports_field::<AIR::BaseElement>() {
+        return Err(ProverError::UnsupportedHashFunction);
+    }
+
+    let domain = StarkDomain::new(air.context());
+    Ok(build_proving_key(&air, &domain))
+}
+
+// PROVER
+// ================================================================================================
+
+/// A STARK prover for executions of a specific length of the computation described by AIR.
+///
+/// The prover caches all components of proof generation which depend only on the shape of the
+/// computation rather than on a specific execution: the STARK domain together with FFT twiddles
+/// for the trace and constraint evaluation domains, the table of periodic column values, and,
+/// if the computation has preprocessed columns, their low-degree extension and commitment. The
+/// prover should be instantiated once, and then used to generate proofs for any number of
+/// executions of the computation.
+///
+/// Boundary constraint groups are not cached because their coefficients are drawn from the
+/// channel after the trace is committed to, and assertion values depend on public inputs; the
+/// same applies to the boundary constraint divisors, which are built together with the groups.
+/// The transition constraint divisor is derived from the trace length alone, and building it
+/// costs a single field exponentiation.
+pub struct Prover<AIR: Air> {
+    options: ProofOptions,
+    trace_length: usize,
+    domain: StarkDomain<AIR::BaseElement>,
+    periodic_column_values: Vec<Vec<AIR::BaseElement>>,
+    periodic_values: PeriodicValueTable<AIR::BaseElement>,
+    proving_key: Option<ProvingKey<AIR::BaseElement>>,
+}
+
+impl<AIR: Air> Prover<AIR> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
 
-    // make sure the specified trace is valid against the AIR. This checks validity of both,
-    // assertions and state transitions. we do this in debug mode only because this is a very
-    // expensive operation.
-    #[cfg(debug_assertions)]
-    trace.validate(&air);
-
-    // figure out which version of the generic proof generation procedure to run. this is a sort
-    // of static dispatch for selecting two generic parameter: extension field and hash function.
-    match air.context().options().field_extension() {
-        FieldExtension::None => match air.context().options().hash_fn() {
-            HashFunction::Blake3_256 => {
-                generate_proof::<AIR, AIR::BaseElement, Blake3_256>(air, trace)
-            }
-            HashFunction::Sha3_256 => {
-                generate_proof::<AIR, AIR::BaseElement, Sha3_256>(air, trace)
+    /// Returns a new prover for executions of the specified length of the computation described
+    /// by AIR instantiated with the specified proof options.
+    ///
+    /// `pub_inputs` are used only to instantiate the AIR from which periodic and preprocessed
+    /// columns are read; thus, preprocessed columns must not depend on public inputs. Periodic
+    /// columns may depend on public inputs, but proofs can then be generated only for public
+    /// inputs which yield the same periodic columns; otherwise, an error is returned.
+    pub fn new(
+        trace_length: usize,
+        pub_inputs: AIR::PublicInputs,
+        options: ProofOptions,
+    ) -> Result<Self, ProverError> {
+        let trace_info = TraceInfo {
+            length: trace_length,
+            meta: Vec::new(),
+        };
+        let air = AIR::new(trace_info, pub_inputs, options);
+
+        let hash_fn = air.context().options().hash_fn();
+        if !hash_fn.supports_field::<AIR::BaseElement>() {
+            return Err(ProverError::UnsupportedHashFunction);
+        }
+
+        let mut prover = Self::from_air(&air);
+        if air.context().num_preprocessed_columns() > 0 {
+            prover.proving_key = Some(build_proving_key(&air, &prover.domain));
+        }
+        Ok(prover)
+    }
+
+    /// Returns a prover for the specified AIR instance; preprocessed columns are not extended.
+    fn from_air(air: &AIR) -> Self {
+        Prover {
+            options: air.context().options().clone(),
+            trace_length: air.trace_length(),
+            domain: StarkDomain::new(air.context()),
+            periodic_column_values: air.get_periodic_column_values(),
+            periodic_values: PeriodicValueTable::new(air),
+            proving_key: None,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the verifying key for proofs generated by this prover, or None if the computation
+    /// does not have preprocessed columns.
+    pub fn verifying_key(&self) -> Option<&VerifyingKey> {
+        self.proving_key.as_ref().map(|key| key.verifying_key())
+    }
+
+    // STAGED PROOF GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Extends the specified `trace` over the LDE domain; extended preprocessed columns, if any,
+    /// are appended to the result.
+    ///
+    /// Returns an error if the length of the `trace` is different from the trace length this
+    /// prover was instantiated for.
+    #[allow(clippy::type_complexity)]
+    pub fn extend_trace(
+        &self,
+        trace: ExecutionTrace<AIR::BaseElement>,
+    ) -> Result<
+        (
+            TraceTable<AIR::BaseElement>,
+            TracePolyTable<AIR::BaseElement>,
+        ),
+        ProverError,
+    > {
+        self.check_trace_length(trace.len())?;
+        Ok(extend_trace(trace, &self.domain, self.proving_key.as_ref()))
+    }
+
+    /// Extends and commits to the specified `trace`; this is the first stage of proof generation.
+    ///
+    /// The returned state object can be used to run the remaining stages one by one: evaluate
+    /// constraints, commit to constraint evaluations, build the DEEP composition polynomial, run
+    /// FRI, and build the proof. `E` must be the base field of the computation or its quadratic
+    /// extension, and `H` must be the hash function, as specified in the proof options.
+    ///
+    /// Returns an error if `E` or `H` are inconsistent with the proof options, if the length of
+    /// the `trace` is different from the trace length this prover was instantiated for, or if
+    /// periodic columns of the AIR instantiated with `pub_inputs` are different from the ones
+    /// this prover was instantiated for.
+    pub fn commit_trace<E, H>(
+        &self,
+        trace: ExecutionTrace<AIR::BaseElement>,
+        pub_inputs: AIR::PublicInputs,
+    ) -> Result<TraceCommitted<'_, AIR, E, H>, ProverError>
+    where
+        E: FieldElement + From<AIR::BaseElement>,
+        H: Hasher + 'static,
+    {
+        self.check_proof_types::<E, H>()?;
+        let air = self.build_air(trace.len(), pub_inputs)?;
+        #[cfg(debug_assertions)]
+        trace.validate(&air);
+
+        let now = Instant::now();
+        let (extended_trace, trace_polys) = self.extend_trace(trace)?;
+        let trace_extension = now.elapsed();
+        let preprocessed_tree = self
+            .proving_key
+            .as_ref()
+            .and_then(|key| key.preprocessed_tree::<H>());
+        Ok(TraceCommitted::new(
+            air,
+            self,
+            extended_trace,
+            trace_polys,
+            preprocessed_tree.into_iter().collect(),
+            trace_extension,
+        ))
+    }
+
+    /// Commits to an execution trace which has already been extended over the LDE domain; this
+    /// can be used in place of `commit_trace()` when the trace is extended by other means.
+    ///
+    /// The `extended_trace` must include extended preprocessed columns, if any, and the trace is
+    /// not validated against the AIR.
+    ///
+    /// Returns an error if `E` or `H` are inconsistent with the proof options, if the width of
+    /// the `extended_trace` or the length of its columns is inconsistent with the computation,
+    /// or if periodic columns of the AIR instantiated with `pub_inputs` are different from the
+    /// ones this prover was instantiated for.
+    pub fn commit_extended_trace<E, H>(
+        &self,
+        extended_trace: TraceTable<AIR::BaseElement>,
+        trace_polys: TracePolyTable<AIR::BaseElement>,
+        pub_inputs: AIR::PublicInputs,
+    ) -> Result<TraceCommitted<'_, AIR, E, H>, ProverError>
+    where
+        E: FieldElement + From<AIR::BaseElement>,
+        H: Hasher + 'static,
+    {
+        self.check_proof_types::<E, H>()?;
+        let air = self.build_air(trace_polys.poly_size(), pub_inputs)?;
+        check_extended_trace(&air, &extended_trace, &trace_polys)?;
+
+        // preprocessed columns of the extended trace are provided by the caller, and thus, they
+        // are committed to anew rather than through the tree of the proving key
+        Ok(TraceCommitted::new(
+            air,
+            self,
+            extended_trace,
+            trace_polys,
+            Vec::new(),
+            Duration::default(),
+        ))
+    }
+
+    /// Commits to an execution trace which has already been extended over the LDE domain using
+    /// Merkle trees built by the caller; this can be used in place of `commit_extended_trace()`
+    /// when the trace commitment is also computed by other means.
+    ///
+    /// `trace_trees` must contain a tree for every committed trace segment (including the
+    /// segment of preprocessed columns, if any); leaves of each tree must be hashes of the
+    /// segment values in the rows of the `extended_trace`. This is checked by hashing the rows
+    /// of the `extended_trace`, but the internal nodes of the trees are not recomputed.
+    ///
+    /// Returns an error for the same reasons as `commit_extended_trace()`, and also if the
+    /// `trace_trees` do not commit to the `extended_trace`.
+    pub fn commit_extended_trace_with_trees<E, H>(
+        &self,
+        extended_trace: TraceTable<AIR::BaseElement>,
+        trace_polys: TracePolyTable<AIR::BaseElement>,
+        trace_trees: Vec<MerkleTree<H>>,
+        pub_inputs: AIR::PublicInputs,
+    ) -> Result<TraceCommitted<'_, AIR, E, H>, ProverError>
+    where
+        E: FieldElement + From<AIR::BaseElement>,
+        H: Hasher + 'static,
+    {
+        self.check_proof_types::<E, H>()?;
+        let air = self.build_air(trace_polys.poly_size(), pub_inputs)?;
+        check_extended_trace(&air, &extended_trace, &trace_polys)?;
+
+        let segment_widths = air.context().committed_segment_widths();
+        if !extended_trace.is_committed_by(&trace_trees, &segment_widths) {
+            return Err(ProverError::InconsistentTraceCommitment);
+        }
+
+        Ok(TraceCommitted::new(
+            air,
+            self,
+            extended_trace,
+            trace_polys,
+            trace_trees.into_iter().map(Arc::new).collect(),
+            Duration::default(),
+        ))
+    }
+
+    // PROOF GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Generates a STARK proof attesting that the specified `trace` is a valid execution trace
+    /// of the computation described by AIR generated using the specified public inputs.
+    ///
+    /// Returns an error if the length of the `trace` is different from the trace length this
+    /// prover was instantiated for, or if the AIR instantiated with `pub_inputs` has periodic
+    /// columns different from the ones this prover was instantiated for.
+    pub fn prove(
+        &self,
+        trace: ExecutionTrace<AIR::BaseElement>,
+        pub_inputs: AIR::PublicInputs,
+    ) -> Result<StarkProof, ProverError> {
+        self.prove_with_report(trace, pub_inputs)
+            .map(|(proof, _)| proof)
+    }
+
+    /// Generates a STARK proof in the same way as `prove()` does, and returns it together with a
+    /// report containing time spent in each phase of proof generation, sizes of the largest
+    /// buffers, and sizes of individual proof sections.
+    pub fn prove_with_report(
+        &self,
+        trace: ExecutionTrace<AIR::BaseElement>,
+        pub_inputs: AIR::PublicInputs,
+    ) -> Result<(StarkProof, ProvingReport), ProverError> {
+        let air = self.build_air(trace.len(), pub_inputs)?;
+        self.prove_air(air, trace, self.proving_key.as_ref())
+    }
+
+    /// Instantiates AIR for the specified public inputs and the options of this prover.
+    ///
+    /// Returns an error if `trace_length` is different from the trace length this prover was
+    /// instantiated for, or if periodic columns of the AIR are different from the ones cached
+    /// by this prover.
+    fn build_air(
+        &self,
+        trace_length: usize,
+        pub_inputs: AIR::PublicInputs,
+    ) -> Result<AIR, ProverError> {
+        self.check_trace_length(trace_length)?;
+        let trace_info = TraceInfo {
+            length: trace_length,
+            meta: Vec::new(),
+        };
+        let air = AIR::new(trace_info, pub_inputs, self.options.clone());
+        if air.get_periodic_column_values() != self.periodic_column_values {
+            return Err(ProverError::InconsistentPeriodicColumns);
+        }
+        Ok(air)
+    }
+
+    /// Returns an error if `trace_length` is different from the trace length this prover was
+    /// instantiated for.
+    fn check_trace_length(&self, trace_length: usize) -> Result<(), ProverError> {
+        if trace_length != self.trace_length {
+            return Err(ProverError::InconsistentTraceLength(
+                self.trace_length,
+                trace_length,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns an error if `E` is not the field specified by the field extension in the proof
+    /// options of this prover, or if `H` is not the hash function specified in these options.
+    fn check_proof_types<E, H>(&self) -> Result<(), ProverError>
+    where
+        E: FieldElement + From<AIR::BaseElement>,
+        H: Hasher + 'static,
+    {
+        let extension_degree = match self.options.field_extension() {
+            FieldExtension::None => 1,
+            FieldExtension::Quadratic => 2,
+        };
+        if E::ELEMENT_BYTES != AIR::BaseElement::ELEMENT_BYTES * extension_degree {
+            return Err(ProverError::InconsistentFieldExtension);
+        }
+        if !is_hash_fn::<H>(self.options.hash_fn()) {
+            return Err(ProverError::InconsistentHashFunction);
+        }
+        Ok(())
+    }
+
+    /// Generates a proof for the specified AIR instance; if the computation has preprocessed
+    /// columns, these are taken from the proving `key` when it is provided.
+    #[rustfmt::skip]
+    fn prove_air(
+        &self,
+        air: AIR,
+        trace: ExecutionTrace<AIR::BaseElement>,
+        key: Option<&ProvingKey<AIR::BaseElement>>,
+    ) -> Result<(StarkProof, ProvingReport), ProverError> {
+        // make sure the specified trace is valid against the AIR. This checks validity of both,
+        // assertions and state transitions. we do this in debug mode only because this is a very
+        // expensive operation.
+        #[cfg(debug_assertions)]
+        trace.validate(&air);
+
+        // algebraic hash functions are defined over specific fields; so, make sure the selected hash
+        // function can actually be used with the base field of the computation.
+        if !air.context().options().hash_fn().supports_field::<AIR::BaseElement>() {
+            return Err(ProverError::UnsupportedHashFunction);
+        }
+
+        // figure out which version of the generic proof generation procedure to run. this is a sort
+        // of static dispatch for selecting two generic parameter: extension field and hash function.
+        match air.context().options().field_extension() {
+            FieldExtension::None => match air.context().options().hash_fn() {
+                HashFunction::Blake3_256 => {
+                    generate_proof::<AIR, AIR::BaseElement, Blake3_256>(air, trace, self, key)
+                }
+                HashFunction::Blake3_192 => {
+                    generate_proof::<AIR, AIR::BaseElement, Blake3_192>(air, trace, self, key)
+                }
+                HashFunction::Blake3_160 => {
+                    generate_proof::<AIR, AIR::BaseElement, Blake3_160>(air, trace, self, key)
+                }
+                HashFunction::Sha3_256 => {
+                    generate_proof::<AIR, AIR::BaseElement, Sha3_256>(air, trace, self, key)
+                },
+                HashFunction::Rp62_248 => {
+                    generate_proof::<AIR, AIR::BaseElement, Rp62_248>(air, trace, self, key)
+                }
+                HashFunction::Rp128_256 => {
+                    generate_proof::<AIR, AIR::BaseElement, Rp128_256>(air, trace, self, key)
+                }
+                HashFunction::Poseidon62_248 => {
+                    generate_proof::<AIR, AIR::BaseElement, Poseidon62_248>(air, trace, self, key)
+                }
+                HashFunction::Poseidon128_256 => {
+                    generate_proof::<AIR, AIR::BaseElement, Poseidon128_256>(air, trace, self, key)
+                }
             },
-        },
-        FieldExtension::Quadratic => match air.context().options().hash_fn() {
-            HashFunction::Blake3_256 => {
-                generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Blake3_256>(air, trace)
-            }
-            HashFunction::Sha3_256 => {
-                generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Sha3_256>(air, trace)
-            }
-        },
+            FieldExtension::Quadratic => match air.context().options().hash_fn() {
+                HashFunction::Blake3_256 => {
+                    generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Blake3_256>(air, trace, self, key)
+                }
+                HashFunction::Blake3_192 => {
+                    generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Blake3_192>(air, trace, self, key)
+                }
+                HashFunction::Blake3_160 => {
+                    generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Blake3_160>(air, trace, self, key)
+                }
+                HashFunction::Sha3_256 => {
+                    generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Sha3_256>(air, trace, self, key)
+                }
+                HashFunction::Rp62_248 => {
+                    generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Rp62_248>(air, trace, self, key)
+                }
+                HashFunction::Rp128_256 => {
+                    generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Rp128_256>(air, trace, self, key)
+                }
+                HashFunction::Poseidon62_248 => {
+                    generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Poseidon62_248>(air, trace, self, key)
+                }
+                HashFunction::Poseidon128_256 => {
+                    generate_proof::<AIR, QuadExtension<AIR::BaseElement>, Poseidon128_256>(air, trace, self, key)
+                }
+            },
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Makes sure dimensions of the `extended_trace` and the number of `trace_polys` are consistent
+/// with the computation described by the `air`.
+fn check_extended_trace<AIR: Air>(
+    air: &AIR,
+    extended_trace: &TraceTable<AIR::BaseElement>,
+    trace_polys: &TracePolyTable<AIR::BaseElement>,
+) -> Result<(), ProverError> {
+    let context = air.context();
+    if extended_trace.width() != context.frame_width() {
+        return Err(ProverError::InconsistentTraceWidth(
+            context.frame_width(),
+            extended_trace.width(),
+        ));
+    }
+    if extended_trace.len() != context.lde_domain_size() {
+        return Err(ProverError::InconsistentExtendedTraceLength(
+            context.lde_domain_size(),
+            extended_trace.len(),
+        ));
+    }
+    if trace_polys.num_polys() != context.frame_width() {
+        return Err(ProverError::InconsistentTracePolyCount(
+            context.frame_width(),
+            trace_polys.num_polys(),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns true if `H` is the hash function specified by `hash_fn`.
+fn is_hash_fn<H: Hasher + 'static>(hash_fn: HashFunction) -> bool {
+    let hasher = TypeId::of::<H>();
+    match hash_fn {
+        HashFunction::Blake3_256 => hasher == TypeId::of::<Blake3_256>(),
+        HashFunction::Blake3_192 => hasher == TypeId::of::<Blake3_192>(),
+        HashFunction::Blake3_160 => hasher == TypeId::of::<Blake3_160>(),
+        HashFunction::Sha3_256 => hasher == TypeId::of::<Sha3_256>(),
+        HashFunction::Rp62_248 => hasher == TypeId::of::<Rp62_248>(),
+        HashFunction::Rp128_256 => hasher == TypeId::of::<Rp128_256>(),
+        HashFunction::Poseidon62_248 => hasher == TypeId::of::<Poseidon62_248>(),
+        HashFunction::Poseidon128_256 => hasher == TypeId::of::<Poseidon128_256>(),
+    }
+}
+
+/// Extends and commits to preprocessed columns of the specified AIR instance using the hash
+/// function specified in the proof options.
+#[rustfmt::skip]
+fn build_proving_key<AIR: Air>(
+    air: &AIR,
+    domain: &StarkDomain<AIR::BaseElement>,
+) -> ProvingKey<AIR::BaseElement> {
+    match air.context().options().hash_fn() {
+        HashFunction::Blake3_256 => ProvingKey::new::<AIR, Blake3_256>(air, domain),
+        HashFunction::Blake3_192 => ProvingKey::new::<AIR, Blake3_192>(air, domain),
+        HashFunction::Blake3_160 => ProvingKey::new::<AIR, Blake3_160>(air, domain),
+        HashFunction::Sha3_256 => ProvingKey::new::<AIR, Sha3_256>(air, domain),
+        HashFunction::Rp62_248 => ProvingKey::new::<AIR, Rp62_248>(air, domain),
+        HashFunction::Rp128_256 => ProvingKey::new::<AIR, Rp128_256>(air, domain),
+        HashFunction::Poseidon62_248 => ProvingKey::new::<AIR, Poseidon62_248>(air, domain),
+        HashFunction::Poseidon128_256 => ProvingKey::new::<AIR, Poseidon128_256>(air, domain),
     }
 }
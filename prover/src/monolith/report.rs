@@ -0,0 +1,157 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::proof::{SerializedQueries, StarkProof};
+use std::time::Duration;
+
+// PROVING REPORT
+// ================================================================================================
+
+/// Summary of a single run of proof generation.
+///
+/// The report contains time spent in each phase of proof generation, sizes of the largest
+/// buffers allocated by the prover, the number of FRI layers, and sizes of individual sections
+/// of the generated proof.
+#[derive(Debug, Clone, Default)]
+pub struct ProvingReport {
+    pub timings: PhaseTimings,
+    pub buffer_sizes: BufferSizes,
+    pub num_fri_layers: usize,
+    pub proof_sizes: ProofSizes,
+}
+
+/// Time spent in each phase of proof generation.
+#[derive(Debug, Clone, Default)]
+pub struct PhaseTimings {
+    /// Interpolation of the execution trace and its evaluation over the LDE domain; this is zero
+    /// when the trace was extended before being passed to the prover.
+    pub trace_extension: Duration,
+    /// Building Merkle trees for all segments of the extended execution trace.
+    pub trace_commitment: Duration,
+    /// Evaluation of transition and boundary constraints over the constraint evaluation domain.
+    pub constraint_evaluation: Duration,
+    /// Interpolation of constraint evaluations into a single constraint polynomial.
+    pub constraint_interpolation: Duration,
+    /// Evaluation of the constraint polynomial over the LDE domain and building a Merkle tree
+    /// from the evaluations.
+    pub constraint_commitment: Duration,
+    /// Building the DEEP composition polynomial and evaluating it over the LDE domain.
+    pub deep_composition: Duration,
+    /// Building and committing to FRI layers.
+    pub fri_layers: Duration,
+    /// Proof-of-work for the query seed.
+    pub grinding: Duration,
+    /// Drawing of pseudo-random query positions from the query seed.
+    pub query_drawing: Duration,
+    /// Opening all commitments at query positions and building the proof object.
+    pub query_building: Duration,
+}
+
+/// Sizes (in bytes) of the largest buffers allocated during proof generation.
+#[derive(Debug, Clone, Default)]
+pub struct BufferSizes {
+    /// Execution trace (including preprocessed columns) extended over the LDE domain.
+    pub extended_trace: usize,
+    /// Nodes of Merkle trees committing to segments of the extended execution trace.
+    pub trace_trees: usize,
+    /// Constraint evaluations over the constraint evaluation domain.
+    pub constraint_evaluations: usize,
+    /// Evaluations of the constraint polynomial over the LDE domain.
+    pub constraint_commitment: usize,
+    /// Evaluations of the DEEP composition polynomial over the LDE domain.
+    pub composition_evaluations: usize,
+}
+
+/// Sizes (in bytes) of serialized data contained in individual sections of a proof.
+#[derive(Debug, Clone, Default)]
+pub struct ProofSizes {
+    /// Caps of trace, constraint, and FRI layer commitments.
+    pub commitments: usize,
+    /// Queried trace states together with their Merkle authentication paths.
+    pub trace_queries: usize,
+    /// Merkle authentication paths for queried constraint evaluations.
+    pub constraint_queries: usize,
+    /// Trace states at the out-of-domain points.
+    pub ood_frame: usize,
+    /// Queried FRI layer values with their authentication paths, and the FRI remainder.
+    pub fri_proof: usize,
+}
+
+// IMPLEMENTATIONS
+// ================================================================================================
+
+impl PhaseTimings {
+    /// Returns the total time spent in all phases of proof generation.
+    pub fn total(&self) -> Duration {
+        self.trace_extension
+            + self.trace_commitment
+            + self.constraint_evaluation
+            + self.constraint_interpolation
+            + self.constraint_commitment
+            + self.deep_composition
+            + self.fri_layers
+            + self.grinding
+            + self.query_drawing
+            + self.query_building
+    }
+}
+
+impl BufferSizes {
+    /// Returns the size of the largest buffer.
+    pub fn peak(&self) -> usize {
+        *[
+            self.extended_trace,
+            self.trace_trees,
+            self.constraint_evaluations,
+            self.constraint_commitment,
+            self.composition_evaluations,
+        ]
+        .iter()
+        .max()
+        .unwrap()
+    }
+}
+
+impl ProofSizes {
+    /// Returns sizes of the sections of the specified `proof`.
+    pub fn new(proof: &StarkProof) -> Self {
+        let commitments = &proof.commitments;
+        let fri_proof = &proof.fri_proof;
+        ProofSizes {
+            commitments: bytes_size(&commitments.trace_caps)
+                + commitments.constraint_cap.len()
+                + bytes_size(&commitments.fri_caps),
+            trace_queries: proof.trace_queries.iter().map(queries_size).sum(),
+            constraint_queries: queries_size(&proof.constraint_queries),
+            ood_frame: proof.ood_frame.trace_at_z1.len() + proof.ood_frame.trace_at_z2.len(),
+            fri_proof: fri_proof
+                .layers
+                .iter()
+                .map(|layer| bytes_size(&layer.values) + bytes_size(&layer.paths))
+                .sum::<usize>()
+                + fri_proof.rem_values.len(),
+        }
+    }
+
+    /// Returns the total size of all proof sections.
+    pub fn total(&self) -> usize {
+        self.commitments
+            + self.trace_queries
+            + self.constraint_queries
+            + self.ood_frame
+            + self.fri_proof
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn queries_size(queries: &SerializedQueries) -> usize {
+    bytes_size(&queries.values) + bytes_size(&queries.paths)
+}
+
+fn bytes_size(data: &[Vec<u8>]) -> usize {
+    data.iter().map(|v| v.len()).sum()
+}
@@ -0,0 +1,99 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    trace::{ExecutionTrace, TracePolyTable, TraceTable},
+    StarkDomain,
+};
+use common::{Air, VerifyingKey};
+use crypto::{Hasher, MerkleTree};
+use math::field::StarkField;
+use std::{any::Any, sync::Arc};
+
+// PROVING KEY
+// ================================================================================================
+
+/// Low-degree extension of the preprocessed columns of a computation together with the Merkle
+/// tree and the verifying key committing to it.
+///
+/// The key is generated once during setup; proofs generated with the key reuse the extended
+/// preprocessed columns and their Merkle tree instead of interpolating, extending, and hashing
+/// them for every proof.
+pub struct ProvingKey<B: StarkField> {
+    preprocessed_trace: TraceTable<B>,
+    preprocessed_polys: TracePolyTable<B>,
+    // the key is not generic over the hash function, and thus, the tree is stored type-erased
+    preprocessed_tree: Arc<dyn Any + Send + Sync>,
+    verifying_key: VerifyingKey,
+}
+
+impl<B: StarkField> ProvingKey<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Extends preprocessed columns of the specified computation over the LDE domain, and
+    /// commits to the extended columns using hash function `H`.
+    pub(super) fn new<A: Air<BaseElement = B>, H: Hasher + 'static>(
+        air: &A,
+        domain: &StarkDomain<B>,
+    ) -> Self {
+        let context = air.context();
+        let columns = air.get_preprocessed_columns();
+        assert!(
+            !columns.is_empty(),
+            "computation does not have any preprocessed columns"
+        );
+        assert!(
+            columns.len() == context.num_preprocessed_columns(),
+            "inconsistent number of preprocessed columns: expected {}, but was {}",
+            context.num_preprocessed_columns(),
+            columns.len()
+        );
+        assert!(
+            columns[0].len() == context.trace_length(),
+            "preprocessed columns must contain {} values, but contained {}",
+            context.trace_length(),
+            columns[0].len()
+        );
+
+        let (preprocessed_trace, preprocessed_polys) = ExecutionTrace::init(columns).extend(domain);
+        let preprocessed_tree =
+            preprocessed_trace.build_commitment::<H>(0..preprocessed_trace.width());
+        let preprocessed_cap = preprocessed_tree.cap(context.options().merkle_cap_height());
+
+        ProvingKey {
+            preprocessed_trace,
+            preprocessed_polys,
+            preprocessed_tree: Arc::new(preprocessed_tree),
+            verifying_key: VerifyingKey::new::<H>(context, &preprocessed_cap),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the verifying key which commits to the preprocessed columns.
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.verifying_key
+    }
+
+    /// Returns evaluations of the preprocessed columns over the LDE domain.
+    pub(super) fn preprocessed_trace(&self) -> &TraceTable<B> {
+        &self.preprocessed_trace
+    }
+
+    /// Returns polynomials interpolated from the preprocessed columns.
+    pub(super) fn preprocessed_polys(&self) -> &TracePolyTable<B> {
+        &self.preprocessed_polys
+    }
+
+    /// Returns the Merkle tree committing to the extended preprocessed columns, or None if the
+    /// key was generated using a hash function other than `H`.
+    pub(super) fn preprocessed_tree<H: Hasher + 'static>(&self) -> Option<Arc<MerkleTree<H>>> {
+        self.preprocessed_tree
+            .clone()
+            .downcast::<MerkleTree<H>>()
+            .ok()
+    }
+}
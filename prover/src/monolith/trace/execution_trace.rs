@@ -118,7 +118,7 @@ impl<B: StarkField> ExecutionTrace<B> {
     /// Breaks the execution trace into mutable fragments each having the number of rows
     /// specified by `fragment_length` parameter. The returned fragments can be used to
     /// update data in the trace from multiple threads.
-    pub fn fragments(&mut self, fragment_length: usize) -> Vec<ExecutionTraceFragment<B>> {
+    pub fn fragments(&mut self, fragment_length: usize) -> Vec<ExecutionTraceFragment<'_, B>> {
         assert!(
             fragment_length >= MIN_FRAGMENT_LENGTH,
             "fragment length must be at least {}, but was {}",
@@ -216,9 +216,18 @@ impl<B: StarkField> ExecutionTrace<B> {
         let periodic_values_polys = air.get_periodic_column_polys();
         let mut periodic_values = vec![B::ZERO; periodic_values_polys.len()];
 
+        // preprocessed columns are placed into evaluation frames right after trace registers
+        let preprocessed_columns = air.get_preprocessed_columns();
+        assert!(
+            preprocessed_columns.len() == air.context().num_preprocessed_columns(),
+            "inconsistent number of preprocessed columns: expected {}, but was {}",
+            air.context().num_preprocessed_columns(),
+            preprocessed_columns.len()
+        );
+
         // initialize buffers to hold evaluation frames and results of constraint evaluations
         let mut x = B::ONE;
-        let mut ev_frame = EvaluationFrame::new(self.width());
+        let mut ev_frame = EvaluationFrame::new(air.context().frame_width());
         let mut evaluations = vec![B::ZERO; air.num_transition_constraints()];
 
         for step in 0..self.len() - 1 {
@@ -232,6 +241,10 @@ impl<B: StarkField> ExecutionTrace<B> {
             // build evaluation frame
             self.read_row_into(step, &mut ev_frame.current);
             self.read_row_into(step + 1, &mut ev_frame.next);
+            for (i, column) in preprocessed_columns.iter().enumerate() {
+                ev_frame.current[self.width() + i] = column[step];
+                ev_frame.next[self.width() + i] = column[step + 1];
+            }
 
             // evaluate transition constraints
             air.evaluate_transition(&ev_frame, &periodic_values, &mut evaluations);
@@ -262,9 +275,9 @@ impl<B: StarkField> ExecutionTrace<B> {
             domain.trace_length(),
             "inconsistent trace length"
         );
-        // build and cache trace twiddles for FFT interpolation; we do it here so that we
-        // don't have to rebuild these twiddles for every register.
-        let inv_twiddles = fft::get_inv_twiddles::<B>(domain.trace_length());
+        // get twiddles for FFT interpolation; these are cached in the domain so that we don't
+        // have to rebuild them for every register.
+        let inv_twiddles = domain.trace_inv_twiddles();
 
         // extend all registers (either in multiple threads or in a single thread); the extension
         // procedure first interpolates register traces into polynomials (in-place), then evaluates
@@ -273,14 +286,14 @@ impl<B: StarkField> ExecutionTrace<B> {
         let extended_trace = self
             .0
             .par_iter_mut()
-            .map(|register_trace| extend_register(register_trace, &domain, &inv_twiddles))
+            .map(|register_trace| extend_register(register_trace, &domain, inv_twiddles))
             .collect();
 
         #[cfg(not(feature = "concurrent"))]
         let extended_trace = self
             .0
             .iter_mut()
-            .map(|register_trace| extend_register(register_trace, &domain, &inv_twiddles))
+            .map(|register_trace| extend_register(register_trace, &domain, inv_twiddles))
             .collect();
 
         (
@@ -55,6 +55,16 @@ impl<B: StarkField> TracePolyTable<B> {
         &self.0[idx]
     }
 
+    /// Appends copies of all polynomials of the `other` table to the polynomials of this table.
+    pub fn append_polys(&mut self, other: &TracePolyTable<B>) {
+        assert_eq!(
+            self.poly_size(),
+            other.poly_size(),
+            "all trace polynomials must have the same size"
+        );
+        self.0.extend(other.0.iter().cloned());
+    }
+
     /// Converts this table into a vector of polynomials.
     pub fn into_vec(self) -> Vec<Vec<B>> {
         self.0
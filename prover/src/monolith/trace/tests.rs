@@ -87,10 +87,9 @@ fn commit_trace_table() {
     let (extended_trace, _) = trace.extend(&domain);
 
     // commit to the trace
-    let trace_tree = extended_trace.build_commitment::<Blake3_256>();
+    let trace_tree = extended_trace.build_commitment::<Blake3_256>(0..extended_trace.width());
 
     // build Merkle tree from trace rows
-    let hash_fn = Blake3_256::hash_fn();
     let mut hashed_states = Vec::new();
     let mut trace_state = vec![BaseElement::ZERO; extended_trace.width()];
     #[allow(clippy::needless_range_loop)]
@@ -98,12 +97,52 @@ fn commit_trace_table() {
         for j in 0..extended_trace.width() {
             trace_state[j] = extended_trace.get(j, i);
         }
-        let mut buf = [0; 32];
-        hash_fn(BaseElement::elements_as_bytes(&trace_state), &mut buf);
-        hashed_states.push(buf);
+        hashed_states.push(Blake3_256::hash_elements(&trace_state));
     }
-    let expected_tree = MerkleTree::new(hashed_states, hash_fn);
+    let expected_tree = MerkleTree::<Blake3_256>::new(hashed_states);
 
     // compare the result
     assert_eq!(expected_tree.root(), trace_tree.root())
 }
+
+#[test]
+fn commit_trace_table_segments() {
+    // build and extend trace table
+    let trace_length = 8;
+    let context = build_context(trace_length, 2, 4);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&context);
+    let (extended_trace, _) = trace.extend(&domain);
+
+    // commit to each register of the trace separately
+    let trace_trees = extended_trace.build_commitments::<Blake3_256>(&[1, 1]);
+    assert_eq!(2, trace_trees.len());
+
+    // build a Merkle tree from the values of each register
+    for (register, trace_tree) in trace_trees.iter().enumerate() {
+        let hashed_states = (0..extended_trace.len())
+            .map(|i| Blake3_256::hash_elements(&[extended_trace.get(register, i)]))
+            .collect();
+        let expected_tree = MerkleTree::<Blake3_256>::new(hashed_states);
+        assert_eq!(expected_tree.root(), trace_tree.root());
+    }
+}
+
+#[test]
+fn append_trace_table_registers() {
+    let trace_length = 8;
+    let context = build_context(trace_length, 2, 4);
+    let domain = StarkDomain::new(&context);
+    let (mut extended_trace, _) = build_fib_trace(trace_length * 2).extend(&domain);
+    let (other_trace, _) = build_fib_trace(trace_length * 2).extend(&domain);
+
+    extended_trace.append_registers(&other_trace);
+    assert_eq!(4, extended_trace.width());
+
+    // appended registers must share values with the other table rather than copy them
+    for register in 0..other_trace.width() {
+        let appended = extended_trace.get_register(register + 2);
+        assert_eq!(other_trace.get_register(register), appended);
+        assert!(std::ptr::eq(other_trace.get_register(register), appended));
+    }
+}
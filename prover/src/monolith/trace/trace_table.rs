@@ -6,6 +6,7 @@
 use common::{proof::Queries, EvaluationFrame};
 use crypto::{Hasher, MerkleTree};
 use math::field::StarkField;
+use std::{ops::Range, sync::Arc};
 use utils::uninit_vector;
 
 #[cfg(feature = "concurrent")]
@@ -13,8 +14,12 @@ use rayon::prelude::*;
 
 // TRACE TABLE
 // ================================================================================================
+/// Evaluations of trace registers over the LDE domain.
+///
+/// Registers are reference-counted so that registers appended from another table (e.g. extended
+/// preprocessed columns of a proving key) are shared with that table rather than copied.
 pub struct TraceTable<B: StarkField> {
-    data: Vec<Vec<B>>,
+    data: Vec<Arc<Vec<B>>>,
     blowup: usize,
 }
 
@@ -23,7 +28,10 @@ impl<B: StarkField> TraceTable<B> {
     // --------------------------------------------------------------------------------------------
     /// Creates a new trace table from a list of provided register traces.
     pub(super) fn new(data: Vec<Vec<B>>, blowup: usize) -> Self {
-        TraceTable { data, blowup }
+        TraceTable {
+            data: data.into_iter().map(Arc::new).collect(),
+            blowup,
+        }
     }
 
     // PUBLIC ACCESSORS
@@ -53,14 +61,12 @@ impl<B: StarkField> TraceTable<B> {
     /// Returns the entire register trace for the register at the specified index.
     #[cfg(test)]
     pub fn get_register(&self, idx: usize) -> &[B] {
-        &self.data[idx]
+        &self.data[idx][..]
     }
 
     /// Copies values of all registers at the specified `step` into the `destination` slice.
     pub fn read_row_into(&self, step: usize, row: &mut [B]) {
-        for (register, value) in self.data.iter().zip(row.iter_mut()) {
-            *value = register[step];
-        }
+        read_row_into(&self.data, step, row);
     }
 
     /// Reads current and next rows from the execution trace table into the specified frame.
@@ -72,13 +78,58 @@ impl<B: StarkField> TraceTable<B> {
         self.read_row_into(next_lde_step, &mut frame.next);
     }
 
+    // DATA MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Appends all registers of the `other` table to the registers of this table; values of the
+    /// appended registers are shared with the `other` table rather than copied.
+    pub fn append_registers(&mut self, other: &TraceTable<B>) {
+        assert_eq!(self.len(), other.len(), "inconsistent trace table length");
+        assert_eq!(self.blowup, other.blowup, "inconsistent trace table blowup");
+        self.data.extend(other.data.iter().map(Arc::clone));
+    }
+
     // TRACE COMMITMENT
     // --------------------------------------------------------------------------------------------
-    /// Builds a Merkle tree out of trace table rows (hash of each row becomes a leaf in the tree).
-    pub fn build_commitment<H: Hasher>(&self) -> MerkleTree {
-        let hash_fn = H::hash_fn();
+    /// Builds a Merkle tree for every trace segment; registers are split into consecutive
+    /// segments of the specified widths, and a hash of the segment values in each row becomes
+    /// a leaf in the tree of this segment.
+    pub fn build_commitments<H: Hasher>(&self, segment_widths: &[usize]) -> Vec<MerkleTree<H>> {
+        get_segment_ranges(segment_widths)
+            .map(|registers| self.build_commitment(registers))
+            .collect()
+    }
+
+    /// Returns true if the `commitments` contain a Merkle tree for every trace segment, and
+    /// leaves of each tree are hashes of the segment values in the rows of this table; segments
+    /// are defined by `segment_widths` in the same way as for `build_commitments()`.
+    pub fn is_committed_by<H: Hasher>(
+        &self,
+        commitments: &[MerkleTree<H>],
+        segment_widths: &[usize],
+    ) -> bool {
+        commitments.len() == segment_widths.len()
+            && commitments
+                .iter()
+                .zip(get_segment_ranges(segment_widths))
+                .all(|(commitment, registers)| {
+                    commitment.leaves() == &self.hash_rows::<H>(registers)[..]
+                })
+    }
+
+    /// Builds a Merkle tree out of trace table rows restricted to the specified registers (hash
+    /// of each restricted row becomes a leaf in the tree).
+    pub fn build_commitment<H: Hasher>(&self, registers: Range<usize>) -> MerkleTree<H> {
+        MerkleTree::new(self.hash_rows::<H>(registers))
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+    /// Returns hashes of trace table rows restricted to the specified registers.
+    fn hash_rows<H: Hasher>(&self, registers: Range<usize>) -> Vec<H::Digest> {
         // allocate vector to store row hashes
-        let mut hashed_states = uninit_vector::<[u8; 32]>(self.len());
+        let mut hashed_states = uninit_vector::<H::Digest>(self.len());
+        let data = &self.data[registers];
 
         // iterate though table rows, hashing each row; the hashing is done by first copying
         // the state into trace_state buffer to avoid unneeded allocations, and then by applying
@@ -91,51 +142,85 @@ impl<B: StarkField> TraceTable<B> {
                 .enumerate()
                 .for_each(|(batch_idx, hashed_states_batch)| {
                     let offset = batch_idx * batch_size;
-                    let mut trace_state = vec![B::ZERO; self.width()];
+                    let mut trace_state = vec![B::ZERO; data.len()];
                     for (i, row_hash) in hashed_states_batch.iter_mut().enumerate() {
-                        self.read_row_into(i + offset, &mut trace_state);
-                        hash_fn(B::elements_as_bytes(&trace_state), row_hash);
+                        read_row_into(data, i + offset, &mut trace_state);
+                        *row_hash = H::hash_elements(&trace_state);
                     }
                 });
         }
 
         #[cfg(not(feature = "concurrent"))]
         {
-            let mut trace_state = vec![B::ZERO; self.width()];
+            let mut trace_state = vec![B::ZERO; data.len()];
             for (i, row_hash) in hashed_states.iter_mut().enumerate() {
-                self.read_row_into(i, &mut trace_state);
-                hash_fn(B::elements_as_bytes(&trace_state), row_hash);
+                read_row_into(data, i, &mut trace_state);
+                *row_hash = H::hash_elements(&trace_state);
             }
         }
 
-        // build Merkle tree out of hashed rows
-        MerkleTree::new(hashed_states, hash_fn)
+        hashed_states
     }
 
     // QUERY TRACE
     // --------------------------------------------------------------------------------------------
     /// Returns trace table rows at the specified positions along with Merkle authentication paths
-    /// from the `commitment` root to these rows.
-    pub fn query(&self, commitment: MerkleTree, positions: &[usize]) -> Queries {
+    /// from the `commitments` caps of the specified height to these rows. The rows are split into
+    /// segments of the specified widths, and a set of queries is returned for every segment.
+    pub fn query<H: Hasher>(
+        &self,
+        commitments: &[Arc<MerkleTree<H>>],
+        segment_widths: &[usize],
+        positions: &[usize],
+        cap_height: usize,
+    ) -> Vec<Queries<H::Digest>> {
         assert_eq!(
-            self.len(),
-            commitment.leaves().len(),
-            "inconsistent trace table commitment"
+            segment_widths.len(),
+            commitments.len(),
+            "inconsistent number of trace table commitments"
         );
 
-        // allocate memory for queried trace states
-        let mut trace_states = Vec::with_capacity(positions.len());
+        commitments
+            .iter()
+            .zip(get_segment_ranges(segment_widths))
+            .map(|(commitment, registers)| {
+                assert_eq!(
+                    self.len(),
+                    commitment.leaves().len(),
+                    "inconsistent trace table commitment"
+                );
+
+                // copy values of the segment registers at the specified positions into rows
+                let data = &self.data[registers];
+                let trace_states = positions
+                    .iter()
+                    .map(|&i| data.iter().map(|r| r[i]).collect())
+                    .collect();
+
+                // build Merkle authentication paths to the leaves specified by positions
+                let trace_proof = commitment.prove_batch_to_cap(positions, cap_height);
+
+                Queries::new(trace_proof, trace_states)
+            })
+            .collect()
+    }
+}
 
-        // copy values from the trace table at the specified positions into rows
-        // and append the rows to trace_states
-        for &i in positions.iter() {
-            let row = self.data.iter().map(|r| r[i]).collect();
-            trace_states.push(row);
-        }
+// HELPER FUNCTIONS
+// ================================================================================================
 
-        // build Merkle authentication paths to the leaves specified by positions
-        let trace_proof = commitment.prove_batch(&positions);
+/// Returns register ranges of consecutive trace segments of the specified widths.
+fn get_segment_ranges(segment_widths: &[usize]) -> impl Iterator<Item = Range<usize>> + '_ {
+    segment_widths.iter().scan(0, |start, &width| {
+        let range = *start..(*start + width);
+        *start += width;
+        Some(range)
+    })
+}
 
-        Queries::new(trace_proof, trace_states)
+/// Copies values of the provided registers at the specified `step` into the `row` slice.
+fn read_row_into<B: StarkField>(registers: &[Arc<Vec<B>>], step: usize, row: &mut [B]) {
+    for (register, value) in registers.iter().zip(row.iter_mut()) {
+        *value = register[step];
     }
 }
@@ -0,0 +1,121 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BatchSize, BenchmarkGroup, BenchmarkId,
+    Criterion,
+};
+use prover::{
+    math::field::{f128::BaseElement, FieldElement},
+    Air, Assertion, ComputationContext, EvaluationFrame, ExecutionTrace, FieldExtension,
+    HashFunction, ProofOptions, TraceInfo, TransitionConstraintDegree,
+};
+use std::time::Duration;
+
+const TRACE_LENGTH: usize = 1_024;
+const NUM_QUERIES: usize = 84;
+
+fn verifier(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verifier");
+    group.measurement_time(Duration::from_secs(10));
+
+    // cost of DEEP composition grows with the width of the trace and the number of queries, and
+    // is higher in the extension field, where the trace is also checked against its conjugate
+    for &extension in [FieldExtension::None, FieldExtension::Quadratic].iter() {
+        let options = ProofOptions::new(NUM_QUERIES, 8, 0, HashFunction::Blake3_256, extension);
+        bench_verify::<8>(&mut group, &options);
+        bench_verify::<64>(&mut group, &options);
+    }
+    group.finish();
+}
+
+fn bench_verify<const W: usize>(group: &mut BenchmarkGroup<WallTime>, options: &ProofOptions) {
+    let (trace, result) = build_trace::<W>(TRACE_LENGTH);
+    let proof = prover::prove::<WideAir<W>>(trace, result, options.clone()).unwrap();
+
+    let name = match options.field_extension() {
+        FieldExtension::None => "verify_base",
+        FieldExtension::Quadratic => "verify_quadratic",
+    };
+    group.bench_function(BenchmarkId::new(name, W), |bench| {
+        bench.iter_batched(
+            || proof.clone(),
+            |proof| verifier::verify::<WideAir<W>>(proof, result).unwrap(),
+            BatchSize::SmallInput,
+        );
+    });
+}
+
+criterion_group!(verifier_group, verifier);
+criterion_main!(verifier_group);
+
+// WIDE AIR
+// ================================================================================================
+
+/// A computation over a trace of arbitrary width in which every register is updated by adding
+/// the value of the next register to it: s_{j, i+1} = s_{j, i} + s_{j+1 mod W, i}.
+struct WideAir<const W: usize> {
+    context: ComputationContext,
+    result: BaseElement,
+}
+
+impl<const W: usize> Air for WideAir<W> {
+    type BaseElement = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1); W];
+        WideAir {
+            context: ComputationContext::new(W, trace_info.length, degrees, options),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &ComputationContext {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = &frame.current;
+        let next = &frame.next;
+        for (i, result) in result.iter_mut().enumerate() {
+            *result = next[i] - (current[i] + current[(i + 1) % W]);
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseElement>> {
+        let last_step = self.trace_length() - 1;
+        let mut assertions = (0..W)
+            .map(|i| Assertion::single(i, 0, BaseElement::from(i as u64 + 1)))
+            .collect::<Vec<_>>();
+        assertions.push(Assertion::single(0, last_step, self.result));
+        assertions
+    }
+}
+
+fn build_trace<const W: usize>(length: usize) -> (ExecutionTrace<BaseElement>, BaseElement) {
+    let mut trace = ExecutionTrace::new(W, length);
+    trace.fill(
+        |state| {
+            for (i, value) in state.iter_mut().enumerate() {
+                *value = BaseElement::from(i as u64 + 1);
+            }
+        },
+        |_, state| {
+            let first = state[0];
+            for i in 0..W - 1 {
+                state[i] += state[i + 1];
+            }
+            state[W - 1] += first;
+        },
+    );
+    let result = trace.get(0, length - 1);
+    (trace, result)
+}
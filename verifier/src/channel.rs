@@ -6,17 +6,19 @@
 use common::{
     errors::VerifierError,
     proof::{Commitments, OodEvaluationFrame, Queries, StarkProof},
-    utils, Air, ComputationContext, EvaluationFrame, ProofOptions, PublicCoin,
+    utils, Air, ComputationContext, EvaluationFrame, ProofOptions, PublicCoin, VerifyingKey,
 };
-use crypto::{BatchMerkleProof, DefaultRandomElementGenerator, Hasher, MerkleTree};
+use crypto::{BatchMerkleProof, DefaultRandomElementGenerator, Digest, Hasher, MerkleTree};
 use fri::{PublicCoin as FriPublicCoin, VerifierChannel as FriVerifierChannel};
 use math::{
     field::{FieldElement, StarkField},
     utils::read_elements_into_vec,
 };
-use std::convert::TryInto;
 use std::marker::PhantomData;
 
+#[cfg(feature = "concurrent")]
+use rayon::prelude::*;
+
 // TYPES AND INTERFACES
 // ================================================================================================
 
@@ -24,18 +26,22 @@ type Bytes = Vec<u8>;
 
 pub struct VerifierChannel<B: StarkField, E: FieldElement + From<B>, H: Hasher> {
     context: ComputationContext,
-    commitments: Commitments,
-    trace_queries: Queries,
-    constraint_queries: Queries,
+    trace_roots: Vec<H::Digest>,
+    trace_root: H::Digest,
+    trace_caps: Vec<Vec<H::Digest>>,
+    constraint_root: H::Digest,
+    constraint_cap: Vec<H::Digest>,
+    fri_roots: Vec<H::Digest>,
+    fri_caps: Vec<Vec<H::Digest>>,
+    trace_queries: Vec<Queries<H::Digest>>,
+    constraint_queries: Queries<H::Digest>,
     ood_frame: OodEvaluationFrame,
-    fri_layer_proofs: Vec<BatchMerkleProof>,
-    fri_layer_queries: Vec<Vec<Bytes>>,
+    fri_layer_proofs: Vec<BatchMerkleProof<H>>,
+    fri_layer_queries: Vec<Vec<[E; 4]>>,
     fri_remainder: Bytes,
     fri_partitioned: bool,
-    query_seed: [u8; 32],
+    query_seed: H::Digest,
     _base_element: PhantomData<B>,
-    _extension_element: PhantomData<E>,
-    _hasher: PhantomData<H>,
 }
 
 // VERIFIER CHANNEL IMPLEMENTATION
@@ -47,46 +53,139 @@ where
     E: FieldElement + From<B>,
     H: Hasher,
 {
-    /// Creates and returns a new verifier channel initialized from the specified `proof`.
-    pub fn new<A: Air<BaseElement = B>>(air: &A, proof: StarkProof) -> Result<Self, VerifierError> {
+    /// Creates and returns a new verifier channel initialized from the specified `proof`; when
+    /// the computation has preprocessed columns, their commitment is taken from `verifying_key`.
+    pub fn new<A: Air<BaseElement = B>>(
+        air: &A,
+        proof: StarkProof,
+        verifying_key: Option<&VerifyingKey>,
+    ) -> Result<Self, VerifierError> {
         // TODO: validate field modulus
         // TODO: verify ce blowup factor
 
+        // --- parse commitments ------------------------------------------------------------------
+        let Commitments {
+            mut trace_caps,
+            constraint_cap,
+            fri_caps,
+        } = Commitments::deserialize(&proof.commitments)
+            .map_err(|_| VerifierError::CommitmentDeserializationFailed)?;
+        if trace_caps.len() != air.context().num_trace_segments() {
+            return Err(VerifierError::CommitmentDeserializationFailed);
+        }
+
+        // make sure all caps have the number of nodes expected for the trees committed to by the
+        // prover; otherwise, the verifier could be tricked into using caps of arbitrary height
+        let context = air.context();
+        let cap_height = context.options().merkle_cap_height();
+        let lde_domain_size = context.lde_domain_size();
+        let trace_cap_len = MerkleTree::<H>::cap_len(lde_domain_size, cap_height);
+        let constraint_cap_len = MerkleTree::<H>::cap_len(
+            lde_domain_size / utils::evaluations_per_leaf::<E, H>(),
+            cap_height,
+        );
+        if trace_caps.iter().any(|cap| cap.len() != trace_cap_len)
+            || constraint_cap.len() != constraint_cap_len
+        {
+            return Err(VerifierError::CommitmentDeserializationFailed);
+        }
+        // each leaf of a FRI layer tree contains as many evaluations as the folding factor
+        let folding_factor = context.options().to_fri_options::<B>().folding_factor();
+        let mut fri_num_leaves = lde_domain_size;
+        for cap in fri_caps.iter() {
+            fri_num_leaves /= folding_factor;
+            if cap.len() != MerkleTree::<H>::cap_len(fri_num_leaves, cap_height) {
+                return Err(VerifierError::CommitmentDeserializationFailed);
+            }
+        }
+
+        // commitment to preprocessed columns is not included in the proof; instead, it is
+        // appended to the trace segment caps from the verifying key
+        if air.context().num_preprocessed_columns() > 0 {
+            let verifying_key = verifying_key.ok_or(VerifierError::MissingVerifyingKey)?;
+            if !verifying_key.is_compatible_with(air.context()) {
+                return Err(VerifierError::IncompatibleVerifyingKey);
+            }
+            let preprocessed_cap = verifying_key
+                .parse_cap::<H>()
+                .map_err(|_| VerifierError::IncompatibleVerifyingKey)?;
+            if preprocessed_cap.len() != trace_cap_len {
+                return Err(VerifierError::IncompatibleVerifyingKey);
+            }
+            trace_caps.push(preprocessed_cap);
+        }
+
+        // roots of the committed trees are used as seeds for drawing randomness, and thus, need
+        // to be computed from the caps; roots of trace segment trees are combined together
+        let trace_roots = trace_caps
+            .iter()
+            .map(|cap| get_root_from_cap::<H>(cap))
+            .collect::<Result<Vec<_>, _>>()?;
+        let trace_root = utils::merge_trace_roots::<H>(&trace_roots);
+        let constraint_root = get_root_from_cap::<H>(&constraint_cap)?;
+        let fri_roots = fri_caps
+            .iter()
+            .map(|cap| get_root_from_cap::<H>(cap))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // --- parse queries ----------------------------------------------------------------------
+        let trace_queries = proof
+            .trace_queries
+            .iter()
+            .map(Queries::deserialize)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| VerifierError::TraceQueryDeserializationFailed)?;
+        let constraint_queries = Queries::deserialize(&proof.constraint_queries)
+            .map_err(|_| VerifierError::ConstraintQueryDeserializationFailed)?;
+
         // --- parse FRI proofs -------------------------------------------------------------------
         let fri_partitioned = proof.fri_proof.partitioned;
         let (fri_layer_proofs, fri_layer_queries, fri_remainder) =
-            Self::parse_fri_proof(proof.fri_proof);
+            Self::parse_fri_proof(proof.fri_proof, lde_domain_size, folding_factor)
+                .map_err(VerifierError::FriVerificationFailed)?;
 
         // --- build query seed -------------------------------------------------------------------
-        let query_seed = build_query_seed::<H>(
-            &proof.commitments.fri_roots,
-            proof.pow_nonce,
-            &air.context().options(),
-        )?;
+        let query_seed =
+            build_query_seed::<H>(&fri_roots, proof.pow_nonce, air.context().options())?;
 
         Ok(VerifierChannel {
             context: air.context().clone(),
-            commitments: proof.commitments,
+            trace_roots,
+            trace_root,
+            trace_caps,
+            constraint_root,
+            constraint_cap,
+            fri_roots,
+            fri_caps,
             ood_frame: proof.ood_frame,
-            trace_queries: proof.trace_queries,
-            constraint_queries: proof.constraint_queries,
+            trace_queries,
+            constraint_queries,
             fri_layer_proofs,
             fri_layer_queries,
             fri_remainder,
             fri_partitioned,
             query_seed,
             _base_element: PhantomData,
-            _extension_element: PhantomData,
-            _hasher: PhantomData,
         })
     }
 
+    /// Returns roots of the trace segment commitments; when the computation has preprocessed
+    /// columns, the root of their commitment is the last one.
+    pub fn trace_roots(&self) -> &[H::Digest] {
+        &self.trace_roots
+    }
+
+    /// Returns the root of the constraint evaluation commitment.
+    pub fn constraint_root(&self) -> H::Digest {
+        self.constraint_root
+    }
+
     /// Returns trace polynomial evaluations at OOD points z and z * g, where g is the generator
     /// of the LDE domain.
     pub fn read_ood_frame(&self) -> Result<EvaluationFrame<E>, VerifierError> {
         let current = match read_elements_into_vec(&self.ood_frame.trace_at_z1) {
             Ok(elements) => {
-                if elements.len() != self.context.trace_width() {
+                if elements.len() != self.context.frame_width() {
                     return Err(VerifierError::OodFrameDeserializationFailed);
                 }
                 elements
@@ -95,7 +194,7 @@ where
         };
         let next = match read_elements_into_vec(&self.ood_frame.trace_at_z2) {
             Ok(elements) => {
-                if elements.len() != self.context.trace_width() {
+                if elements.len() != self.context.frame_width() {
                     return Err(VerifierError::OodFrameDeserializationFailed);
                 }
                 elements
@@ -107,40 +206,44 @@ where
     }
 
     /// Returns trace states at the specified positions. This also checks if the
-    /// trace states are valid against the trace commitment sent by the prover.
+    /// trace states are valid against the commitments to all trace segments sent by the prover.
+    /// When the computation has preprocessed columns, the returned states also include values
+    /// of these columns, checked against the commitment from the verifying key. When `concurrent`
+    /// feature is enabled, the segments are checked in multiple threads.
     pub fn read_trace_states(&self, positions: &[usize]) -> Result<Vec<Vec<B>>, VerifierError> {
-        // TODO: avoid cloning
-        let (trace_proof, trace_values) = self
-            .trace_queries
-            .clone()
-            .into_batch::<H>(self.context.lde_domain_size());
-
-        // make sure the states included in the proof correspond to the trace commitment
-        if !MerkleTree::verify_batch(
-            &self.commitments.trace_root,
-            positions,
-            &trace_proof,
-            H::hash_fn(),
-        ) {
-            return Err(VerifierError::TraceQueryDoesNotMatchCommitment);
+        let segment_widths = self.context.committed_segment_widths();
+        if self.trace_queries.len() != segment_widths.len() {
+            return Err(VerifierError::TraceQueryDeserializationFailed);
         }
 
-        // convert query bytes into field elements of appropriate type
-        let mut states = Vec::new();
-        for state_bytes in trace_values.iter() {
-            let trace_state = match read_elements_into_vec(state_bytes) {
-                Ok(elements) => {
-                    if elements.len() != self.context.trace_width() {
-                        return Err(VerifierError::TraceQueryDeserializationFailed);
-                    }
-                    elements
-                }
-                Err(_) => return Err(VerifierError::TraceQueryDeserializationFailed),
-            };
-            states.push(trace_state);
+        #[cfg(not(feature = "concurrent"))]
+        let segments = self.trace_queries.iter().zip(self.trace_caps.iter());
+
+        #[cfg(feature = "concurrent")]
+        let segments = self
+            .trace_queries
+            .par_iter()
+            .zip(self.trace_caps.par_iter());
+
+        // results are collected before errors are checked so that the error for the first
+        // invalid segment is returned regardless of the order in which segments were checked
+        let segments = segments
+            .zip(segment_widths)
+            .map(|((queries, trace_cap), segment_width)| {
+                self.read_segment_states(queries, trace_cap, segment_width, positions)
+            })
+            .collect::<Vec<_>>();
+
+        // append states of each segment to the states of the preceding segments
+        let mut trace_states =
+            vec![Vec::with_capacity(self.context.frame_width()); positions.len()];
+        for segment_states in segments {
+            for (state, segment_state) in trace_states.iter_mut().zip(segment_states?) {
+                state.extend_from_slice(&segment_state);
+            }
         }
 
-        Ok(states)
+        Ok(trace_states)
     }
 
     /// Returns constraint evaluations at the specified positions. This also checks if the
@@ -152,15 +255,14 @@ where
         let evaluations_per_leaf = utils::evaluations_per_leaf::<E, H>();
         let num_leaves = self.context.lde_domain_size() / evaluations_per_leaf;
         // TODO: avoid cloning
-        let (constraint_proof, constraint_values) =
-            self.constraint_queries.clone().into_batch::<H>(num_leaves);
+        let (constraint_proof, constraint_values) = self
+            .constraint_queries
+            .clone()
+            .into_batch::<H, E>(num_leaves)
+            .map_err(|_| VerifierError::ConstraintQueryDeserializationFailed)?;
         let c_positions = utils::map_trace_to_constraint_positions(positions, evaluations_per_leaf);
-        if !MerkleTree::verify_batch(
-            &self.commitments.constraint_root,
-            &c_positions,
-            &constraint_proof,
-            H::hash_fn(),
-        ) {
+        if !MerkleTree::verify_batch_with_cap(&self.constraint_cap, &c_positions, &constraint_proof)
+        {
             return Err(VerifierError::ConstraintQueryDoesNotMatchCommitment);
         }
 
@@ -173,18 +275,49 @@ where
                 .iter()
                 .position(|&v| v == position / evaluations_per_leaf)
                 .unwrap();
-            let element_start = (position % evaluations_per_leaf) * E::ELEMENT_BYTES;
-            let element_bytes =
-                &constraint_values[leaf_idx][element_start..(element_start + E::ELEMENT_BYTES)];
-            evaluations.push(E::try_from(element_bytes).map_err(|_| {
-                // TODO: display the error from try_from; this requires adding
-                // Debug trait bound to the Error associated type of TryFrom
-                VerifierError::ConstraintQueryDeserializationFailed
-            })?);
+            let evaluation = constraint_values[leaf_idx]
+                .get(position % evaluations_per_leaf)
+                .ok_or(VerifierError::ConstraintQueryDeserializationFailed)?;
+            evaluations.push(*evaluation);
         }
 
         Ok(evaluations)
     }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns states of a single trace segment at the specified positions, and checks them
+    /// against the commitment to the segment.
+    fn read_segment_states(
+        &self,
+        queries: &Queries<H::Digest>,
+        trace_cap: &[H::Digest],
+        segment_width: usize,
+        positions: &[usize],
+    ) -> Result<Vec<Vec<B>>, VerifierError> {
+        // TODO: avoid cloning
+        let (segment_proof, segment_states) = queries
+            .clone()
+            .into_batch::<H, B>(self.context.lde_domain_size())
+            .map_err(|_| VerifierError::TraceQueryDeserializationFailed)?;
+
+        // make sure the states included in the proof correspond to the segment commitment
+        if !MerkleTree::verify_batch_with_cap(trace_cap, positions, &segment_proof) {
+            return Err(VerifierError::TraceQueryDoesNotMatchCommitment);
+        }
+
+        // make sure all segment states have the expected width
+        if segment_states.len() != positions.len()
+            || segment_states
+                .iter()
+                .any(|state| state.len() != segment_width)
+        {
+            return Err(VerifierError::TraceQueryDeserializationFailed);
+        }
+
+        Ok(segment_states)
+    }
 }
 
 impl<B, E, H> FriVerifierChannel<E> for VerifierChannel<B, E, H>
@@ -193,13 +326,15 @@ where
     E: FieldElement + From<B>,
     H: Hasher,
 {
-    type Hasher = H;
+    fn fri_layer_caps(&self) -> &[Vec<H::Digest>] {
+        &self.fri_caps
+    }
 
-    fn fri_layer_proofs(&self) -> &[BatchMerkleProof] {
+    fn fri_layer_proofs(&self) -> &[BatchMerkleProof<H>] {
         &self.fri_layer_proofs
     }
 
-    fn fri_layer_queries(&self) -> &[Vec<Bytes>] {
+    fn fri_layer_queries(&self) -> &[Vec<[E; 4]>] {
         &self.fri_layer_queries
     }
 
@@ -220,21 +355,19 @@ where
     E: FieldElement + From<B>,
     H: Hasher,
 {
-    type Hasher = H;
-
     fn context(&self) -> &ComputationContext {
         &self.context
     }
 
-    fn constraint_seed(&self) -> [u8; 32] {
-        self.commitments.trace_root
+    fn constraint_seed(&self) -> H::Digest {
+        self.trace_root
     }
 
-    fn composition_seed(&self) -> [u8; 32] {
-        self.commitments.constraint_root
+    fn composition_seed(&self) -> H::Digest {
+        self.constraint_root
     }
 
-    fn query_seed(&self) -> [u8; 32] {
+    fn query_seed(&self) -> H::Digest {
         self.query_seed
     }
 }
@@ -245,43 +378,35 @@ where
     E: FieldElement + From<B>,
     H: Hasher,
 {
+    type Hasher = H;
     type RandomElementGenerator = DefaultRandomElementGenerator<H>;
 
-    fn fri_layer_commitments(&self) -> &[[u8; 32]] {
-        &self.commitments.fri_roots
+    fn fri_layer_commitments(&self) -> &[H::Digest] {
+        &self.fri_roots
     }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
+fn get_root_from_cap<H: Hasher>(cap: &[H::Digest]) -> Result<H::Digest, VerifierError> {
+    MerkleTree::<H>::get_root_from_cap(cap).ok_or(VerifierError::CommitmentDeserializationFailed)
+}
+
 fn build_query_seed<H: Hasher>(
-    fri_roots: &[[u8; 32]],
+    fri_roots: &[H::Digest],
     nonce: u64,
     options: &ProofOptions,
-) -> Result<[u8; 32], VerifierError> {
-    let hash_fn = H::hash_fn();
-
+) -> Result<H::Digest, VerifierError> {
     // combine roots of all FIR layers into a single array of bytes
     let mut root_bytes: Vec<u8> = Vec::with_capacity(fri_roots.len() * 32);
     for root in fri_roots.iter() {
-        root.iter().for_each(|&v| root_bytes.push(v));
+        root_bytes.extend_from_slice(&root.as_bytes());
     }
 
-    // hash the array of bytes into a single 32-byte value
-    let mut query_seed = [0u8; 32];
-    hash_fn(&root_bytes, &mut query_seed);
+    // hash the array of bytes into a single digest
+    let query_seed = H::hash(&root_bytes);
 
     // verify proof of work
-    let mut input_bytes = [0; 64];
-    input_bytes[0..32].copy_from_slice(&query_seed);
-    input_bytes[56..].copy_from_slice(&nonce.to_le_bytes());
-
-    hash_fn(&input_bytes, &mut query_seed);
-
-    let seed_head = u64::from_le_bytes(query_seed[..8].try_into().unwrap());
-    if seed_head.trailing_zeros() < options.grinding_factor() {
-        return Err(VerifierError::QuerySeedProofOfWorkVerificationFailed);
-    }
-
-    Ok(query_seed)
+    fri::utils::verify_pow_nonce::<H>(query_seed, nonce, options.grinding_factor())
+        .ok_or(VerifierError::QuerySeedProofOfWorkVerificationFailed)
 }
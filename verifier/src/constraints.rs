@@ -3,18 +3,18 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use common::{Air, CompositionCoefficients, ConstraintDivisor, EvaluationFrame, PublicCoin};
-use math::{
-    field::{FieldElement, StarkField},
-    polynom,
-};
+use super::VerificationSetup;
+use common::{Air, CompositionCoefficients, EvaluationFrame, PublicCoin};
+use math::{field::FieldElement, polynom};
 
 // CONSTRAINT EVALUATION
 // ================================================================================================
 
-/// Evaluates constraints for the specified evaluation frame.
+/// Evaluates constraints for the specified evaluation frame; periodic column polynomials and the
+/// transition divisor are taken from the `setup`.
 pub fn evaluate_constraints<A: Air, C: PublicCoin, E: FieldElement + From<A::BaseElement>>(
     air: &A,
+    setup: &VerificationSetup<A::BaseElement>,
     coin: &C,
     ood_frame: &EvaluationFrame<E>,
     x: E,
@@ -25,8 +25,8 @@ pub fn evaluate_constraints<A: Air, C: PublicCoin, E: FieldElement + From<A::Bas
     let mut t_evaluations = E::zeroed_vector(air.num_transition_constraints());
 
     // compute values of periodic columns at x
-    let periodic_values = air
-        .get_periodic_column_polys()
+    let periodic_values = setup
+        .periodic_column_polys()
         .iter()
         .map(|poly| {
             let num_cycles = air.trace_length() / poly.len();
@@ -45,12 +45,9 @@ pub fn evaluate_constraints<A: Air, C: PublicCoin, E: FieldElement + From<A::Bas
         acc + group.merge_evaluations(&t_evaluations, x)
     });
 
-    // build the divisor for transition constraints; divisors for all transition constraints are
-    // the same and have the form: (x^steps - 1) / (x - x_at_last_step)
-    let t_divisor = ConstraintDivisor::<A::BaseElement>::from_transition(air.context());
-
-    // divide out the evaluation of divisor at x
-    let z = t_divisor.evaluate_at(x);
+    // divisors for all transition constraints are the same and have the form:
+    // (x^steps - 1) / (x - x_at_last_step); divide out the evaluation of divisor at x
+    let z = setup.transition_divisor().evaluate_at(x);
     let mut result = t_evaluation / z;
 
     // ----- evaluate boundary constraints --------------------------------------------------------
@@ -85,19 +82,20 @@ pub fn evaluate_constraints<A: Air, C: PublicCoin, E: FieldElement + From<A::Bas
 // CONSTRAINT COMPOSITION
 // ================================================================================================
 
-/// TODO: add comments
-pub fn compose_constraints<B: StarkField, E: FieldElement + From<B>>(
+/// Divides out the DEEP point from constraint evaluations at the queried positions and merges
+/// the results using a pseudo-random coefficient; `inv_x_z` contains values 1 / (x - z) for all
+/// queried x coordinates.
+pub fn compose_constraints<E: FieldElement>(
     evaluations: Vec<E>,
-    x_coordinates: &[B],
-    z: E,
+    inv_x_z: &[E],
     evaluation_at_z: E,
     cc: &CompositionCoefficients<E>,
 ) -> Vec<E> {
     // divide out deep point from the evaluations
     let mut result = Vec::with_capacity(evaluations.len());
-    for (evaluation, &x) in evaluations.into_iter().zip(x_coordinates) {
+    for (evaluation, &inv_x_z) in evaluations.into_iter().zip(inv_x_z) {
         // compute C(x) = (P(x) - P(z)) / (x - z)
-        let composition = (evaluation - evaluation_at_z) / (E::from(x) - z);
+        let composition = (evaluation - evaluation_at_z) * inv_x_z;
         // multiply by pseudo-random coefficient for linear combination
         result.push(composition * cc.constraints);
     }
@@ -4,14 +4,20 @@
 // LICENSE file in the root directory of this source tree.
 
 pub use common::{
-    errors::VerifierError, proof::StarkProof, Air, FieldExtension, HashFunction, TraceInfo,
+    errors::VerifierError, proof::StarkProof, Air, FieldExtension, HashFunction, LinkConstraints,
+    NoLinks, ProductAir, ProductInputs, SegmentBoundary, TraceInfo, VerifyingKey,
 };
 
 pub use crypto;
-use crypto::hash::{Blake3_256, Sha3_256};
+use crypto::hash::{
+    Blake3_160, Blake3_192, Blake3_256, Poseidon128_256, Poseidon62_248, Rp128_256, Rp62_248,
+    Sha3_256,
+};
+
+pub use fri;
 
 pub use math;
-use math::field::QuadExtension;
+use math::field::{QuadExtension, StarkField};
 
 mod channel;
 use channel::VerifierChannel;
@@ -22,22 +28,178 @@ use verification::perform_verification;
 mod constraints;
 use constraints::{compose_constraints, evaluate_constraints};
 
+mod setup;
+use setup::VerificationSetup;
+
+mod transcript;
+pub use transcript::{read_transcript, ProofTranscript};
+
+#[cfg(feature = "concurrent")]
+use rayon::prelude::*;
+
 // VERIFIER
 // ================================================================================================
 
 /// Verifies STARK `proof` attesting that the computation specified by `AIR` was executed correctly
 /// against the provided `pub_inputs`.
-#[rustfmt::skip]
+///
+/// Proofs for computations with preprocessed columns cannot be verified without a verifying key;
+/// use `verify_with_key()` for such computations.
 pub fn verify<AIR: Air>(
     proof: StarkProof,
     pub_inputs: AIR::PublicInputs,
 ) -> Result<(), VerifierError> {
-    // ----- create AIR instance for the computation specified in the proof -----------------------
+    verify_proof::<AIR>(proof, pub_inputs, None)
+}
+
+/// Verifies STARK `proof` in the same way as `verify()` does, but checks openings of preprocessed
+/// columns against the commitment contained in the `verifying_key`.
+pub fn verify_with_key<AIR: Air>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    verifying_key: &VerifyingKey,
+) -> Result<(), VerifierError> {
+    verify_proof::<AIR>(proof, pub_inputs, Some(verifying_key))
+}
+
+/// Verifies many STARK proofs for the same computation, each against its own public inputs, and
+/// returns a result for each of the proofs in the order in which the proofs were provided.
+///
+/// AIR instances are still created for each proof, but values which depend only on the trace
+/// length and proof options (e.g. periodic column polynomials) are computed once and shared by
+/// all proofs with the same trace length and options. When `concurrent` feature is enabled, the
+/// proofs are verified in multiple threads.
+///
+/// Proofs for computations with preprocessed columns must be verified with
+/// `verify_many_with_key()`.
+pub fn verify_many<AIR: Air>(
+    proofs: Vec<(StarkProof, AIR::PublicInputs)>,
+) -> Vec<Result<(), VerifierError>> {
+    verify_many_proofs::<AIR>(proofs, None)
+}
+
+/// Verifies many STARK proofs in the same way as `verify_many()` does, but checks openings of
+/// preprocessed columns against the commitment contained in the `verifying_key`.
+pub fn verify_many_with_key<AIR: Air>(
+    proofs: Vec<(StarkProof, AIR::PublicInputs)>,
+    verifying_key: &VerifyingKey,
+) -> Vec<Result<(), VerifierError>> {
+    verify_many_proofs::<AIR>(proofs, Some(verifying_key))
+}
+
+// CONTINUATIONS
+// ================================================================================================
+
+/// Verifies that a computation split into segments (e.g. with `prover::prove_segments()`)
+/// starts in `initial_state`, ends in `final_state`, and that all of its segments were executed
+/// correctly.
+///
+/// Segments must be provided in the order of execution, each with its STARK proof and its
+/// boundary values; the initial state of every segment must be equal to the final state of the
+/// preceding segment. Segment proofs are verified using `verify_many()`, and thus, can be
+/// verified in multiple threads.
+pub fn verify_segments<B, AIR>(
+    segments: Vec<(StarkProof, SegmentBoundary<B>)>,
+    initial_state: &[B],
+    final_state: &[B],
+) -> Result<(), VerifierError>
+where
+    B: StarkField,
+    AIR: Air<BaseElement = B, PublicInputs = SegmentBoundary<B>>,
+{
+    // make sure the segments are linked together and connect the initial and the final states
+    // of the computation
+    let first = &segments.first().ok_or(VerifierError::NoSegments)?.1;
+    if first.initial_state() != initial_state {
+        return Err(VerifierError::InitialStateMismatch);
+    }
+    for (i, pair) in segments.windows(2).enumerate() {
+        if !pair[0].1.links_to(&pair[1].1) {
+            return Err(VerifierError::SegmentsNotLinked(i + 1));
+        }
+    }
+    let last = &segments[segments.len() - 1].1;
+    if last.final_state() != final_state {
+        return Err(VerifierError::FinalStateMismatch);
+    }
+
+    // verify proofs of all segments against their boundary values
+    for (i, result) in verify_many::<AIR>(segments).into_iter().enumerate() {
+        result.map_err(|err| VerifierError::SegmentVerificationFailed(i, Box::new(err)))?;
+    }
+
+    Ok(())
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn verify_proof<AIR: Air>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    verifying_key: Option<&VerifyingKey>,
+) -> Result<(), VerifierError> {
+    let air = build_air::<AIR>(&proof, pub_inputs);
+    let setup = VerificationSetup::new(&air);
+    verify_air(air, proof, verifying_key, &setup)
+}
+
+fn verify_many_proofs<AIR: Air>(
+    proofs: Vec<(StarkProof, AIR::PublicInputs)>,
+    verifying_key: Option<&VerifyingKey>,
+) -> Vec<Result<(), VerifierError>> {
+    // create AIR instances for all proofs, and build a setup for each distinct combination of
+    // trace length, proof options, and periodic column values
+    let mut setups: Vec<VerificationSetup<AIR::BaseElement>> = Vec::new();
+    let mut batch = Vec::with_capacity(proofs.len());
+    for (proof, pub_inputs) in proofs {
+        let air = build_air::<AIR>(&proof, pub_inputs);
+        let setup_idx = match setups
+            .iter()
+            .position(|setup| setup.is_compatible_with(&air))
+        {
+            Some(setup_idx) => setup_idx,
+            None => {
+                setups.push(VerificationSetup::new(&air));
+                setups.len() - 1
+            }
+        };
+        batch.push((air, proof, setup_idx));
+    }
+
+    // proofs are independent of each other, and thus, can be verified in parallel
+    #[cfg(not(feature = "concurrent"))]
+    let batch = batch.into_iter();
+
+    #[cfg(feature = "concurrent")]
+    let batch = batch.into_par_iter();
+
+    batch
+        .map(|(air, proof, setup_idx)| verify_air(air, proof, verifying_key, &setups[setup_idx]))
+        .collect()
+}
+
+/// Creates an AIR instance for the computation specified in the `proof`.
+fn build_air<AIR: Air>(proof: &StarkProof, pub_inputs: AIR::PublicInputs) -> AIR {
     let trace_info = TraceInfo {
         length: proof.trace_length(),
         meta: vec![],
     };
-    let air = AIR::new(trace_info, pub_inputs, proof.options().clone());
+    AIR::new(trace_info, pub_inputs, proof.options().clone())
+}
+
+#[rustfmt::skip]
+fn verify_air<AIR: Air>(
+    air: AIR,
+    proof: StarkProof,
+    verifying_key: Option<&VerifyingKey>,
+    setup: &VerificationSetup<AIR::BaseElement>,
+) -> Result<(), VerifierError> {
+    // algebraic hash functions are defined over specific fields; a proof which specifies a hash
+    // function incompatible with the base field of the computation is rejected right away.
+    if !air.context().options().hash_fn().supports_field::<AIR::BaseElement>() {
+        return Err(VerifierError::UnsupportedHashFunction);
+    }
 
     // ----- instantiate verifier channel and run the verification --------------------------------
     // figure out which version of the generic proof verification procedure to run. this is a sort
@@ -45,22 +207,70 @@ pub fn verify<AIR: Air>(
     match air.context().options().field_extension() {
         FieldExtension::None => match air.context().options().hash_fn() {
             HashFunction::Blake3_256 => {
-                let channel = VerifierChannel::new(&air, proof)?;
-                perform_verification::<AIR, AIR::BaseElement, Blake3_256>(air, channel)
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, AIR::BaseElement, Blake3_256>(air, channel, setup)
+            }
+            HashFunction::Blake3_192 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, AIR::BaseElement, Blake3_192>(air, channel, setup)
+            }
+            HashFunction::Blake3_160 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, AIR::BaseElement, Blake3_160>(air, channel, setup)
             }
             HashFunction::Sha3_256 => {
-                let channel = VerifierChannel::new(&air, proof)?;
-                perform_verification::<AIR, AIR::BaseElement, Sha3_256>(air, channel)
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, AIR::BaseElement, Sha3_256>(air, channel, setup)
+            }
+            HashFunction::Rp62_248 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, AIR::BaseElement, Rp62_248>(air, channel, setup)
+            }
+            HashFunction::Rp128_256 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, AIR::BaseElement, Rp128_256>(air, channel, setup)
+            }
+            HashFunction::Poseidon62_248 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, AIR::BaseElement, Poseidon62_248>(air, channel, setup)
+            }
+            HashFunction::Poseidon128_256 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, AIR::BaseElement, Poseidon128_256>(air, channel, setup)
             }
         },
         FieldExtension::Quadratic => match air.context().options().hash_fn() {
             HashFunction::Blake3_256 => {
-                let channel = VerifierChannel::new(&air, proof)?;
-                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Blake3_256>(air, channel)
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Blake3_256>(air, channel, setup)
+            }
+            HashFunction::Blake3_192 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Blake3_192>(air, channel, setup)
+            }
+            HashFunction::Blake3_160 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Blake3_160>(air, channel, setup)
             }
             HashFunction::Sha3_256 => {
-                let channel = VerifierChannel::new(&air, proof)?;
-                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Sha3_256>(air, channel)
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Sha3_256>(air, channel, setup)
+            }
+            HashFunction::Rp62_248 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Rp62_248>(air, channel, setup)
+            }
+            HashFunction::Rp128_256 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Rp128_256>(air, channel, setup)
+            }
+            HashFunction::Poseidon62_248 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Poseidon62_248>(air, channel, setup)
+            }
+            HashFunction::Poseidon128_256 => {
+                let channel = VerifierChannel::new(&air, proof, verifying_key)?;
+                perform_verification::<AIR, QuadExtension<AIR::BaseElement>, Poseidon128_256>(air, channel, setup)
             }
         },
     }
@@ -0,0 +1,69 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::{Air, ConstraintDivisor, ProofOptions};
+use math::field::StarkField;
+
+// VERIFICATION SETUP
+// ================================================================================================
+
+/// Values which depend only on the AIR, the trace length, proof options, and periodic columns,
+/// and thus, can be shared by all proofs for the same computation.
+pub struct VerificationSetup<B: StarkField> {
+    trace_length: usize,
+    options: ProofOptions,
+    periodic_column_values: Vec<Vec<B>>,
+    periodic_column_polys: Vec<Vec<B>>,
+    transition_divisor: ConstraintDivisor<B>,
+    lde_domain_generator: B,
+    domain_offset: B,
+}
+
+impl<B: StarkField> VerificationSetup<B> {
+    /// Builds the setup for the specified AIR; this interpolates periodic columns of the AIR.
+    pub fn new<A: Air<BaseElement = B>>(air: &A) -> Self {
+        let context = air.context();
+        VerificationSetup {
+            trace_length: context.trace_length(),
+            options: context.options().clone(),
+            periodic_column_values: air.get_periodic_column_values(),
+            periodic_column_polys: air.get_periodic_column_polys(),
+            transition_divisor: ConstraintDivisor::from_transition(context),
+            lde_domain_generator: context.get_lde_domain_generator(),
+            domain_offset: context.domain_offset(),
+        }
+    }
+
+    /// Returns true if this setup can be used to verify proofs for the specified AIR; this is
+    /// the case when the AIR has the same trace length, proof options, and periodic columns as
+    /// the AIR for which the setup was built. Periodic columns are compared because they may
+    /// depend on public inputs.
+    pub fn is_compatible_with<A: Air<BaseElement = B>>(&self, air: &A) -> bool {
+        let context = air.context();
+        self.trace_length == context.trace_length()
+            && self.options == *context.options()
+            && self.periodic_column_values == air.get_periodic_column_values()
+    }
+
+    /// Returns polynomials describing periodic columns of the AIR.
+    pub fn periodic_column_polys(&self) -> &[Vec<B>] {
+        &self.periodic_column_polys
+    }
+
+    /// Returns the divisor of transition constraints.
+    pub fn transition_divisor(&self) -> &ConstraintDivisor<B> {
+        &self.transition_divisor
+    }
+
+    /// Returns the generator of the LDE domain.
+    pub fn lde_domain_generator(&self) -> B {
+        self.lde_domain_generator
+    }
+
+    /// Returns the offset by which the LDE domain is shifted.
+    pub fn domain_offset(&self) -> B {
+        self.domain_offset
+    }
+}
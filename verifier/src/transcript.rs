@@ -0,0 +1,114 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{build_air, evaluate_constraints, VerificationSetup, VerifierChannel};
+use common::{
+    errors::VerifierError, proof::StarkProof, Air, CompositionCoefficients, EvaluationFrame,
+    PublicCoin,
+};
+use crypto::Hasher;
+use fri::{PublicCoin as FriPublicCoin, VerifierChannel as FriVerifierChannel};
+use math::field::StarkField;
+
+// PROOF TRANSCRIPT
+// ================================================================================================
+
+/// Values read from a STARK proof and pseudo-random values derived from them by the verifier
+/// before any of the queried values are checked.
+///
+/// Together with openings of the queried trace states, constraint evaluations, and FRI layers,
+/// these values are sufficient to complete verification of the proof. Thus, a transcript can be
+/// used to verify the queried part of a proof elsewhere, e.g. inside of another STARK.
+pub struct ProofTranscript<B: StarkField, H: Hasher> {
+    /// Root of the execution trace commitment.
+    pub trace_root: H::Digest,
+    /// Root of the constraint evaluation commitment.
+    pub constraint_root: H::Digest,
+    /// Roots of FRI layer commitments; the last root is the commitment to the FRI remainder.
+    pub fri_roots: Vec<H::Digest>,
+    /// Trace polynomial evaluations at the out-of-domain points z and z * g.
+    pub ood_frame: EvaluationFrame<B>,
+    /// Out-of-domain point z used for DEEP composition.
+    pub z: B,
+    /// Evaluation of the constraint composition polynomial at z computed from the OOD frame.
+    pub constraint_evaluation_at_z: B,
+    /// Coefficients used to compute DEEP composition of trace and constraint polynomials.
+    pub composition_coefficients: CompositionCoefficients<B>,
+    /// Values used to fold each of the FRI layers.
+    pub fri_alphas: Vec<B>,
+    /// Values of the last FRI layer.
+    pub fri_remainder: Vec<B>,
+    /// Positions in the LDE domain at which the proof was queried.
+    pub query_positions: Vec<usize>,
+}
+
+/// Reads a transcript of STARK `proof` for the computation specified by `AIR` against the
+/// provided `pub_inputs`.
+///
+/// This performs all checks of the verification procedure which do not involve queried values:
+/// commitments and the out-of-domain frame are parsed, proof-of-work for the query seed is
+/// verified, and the FRI remainder is checked against its commitment and its expected degree.
+/// Queried values are not read at all, and thus, they can be removed from the proof beforehand.
+///
+/// `H` must be the hash function specified in the options of the proof. Transcripts can be read
+/// only for proofs generated without field extension, and for computations with a single trace
+/// segment and without preprocessed columns.
+pub fn read_transcript<AIR: Air, H: Hasher>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+) -> Result<ProofTranscript<AIR::BaseElement, H>, VerifierError> {
+    let air = build_air::<AIR>(&proof, pub_inputs);
+    let options = air.context().options();
+    if !options.hash_fn().supports_field::<AIR::BaseElement>() {
+        return Err(VerifierError::UnsupportedHashFunction);
+    }
+    if !options.field_extension().is_none() {
+        return Err(VerifierError::UnsupportedFieldExtension);
+    }
+    let context = air.context();
+    if context.num_trace_segments() != 1 || context.num_preprocessed_columns() > 0 {
+        return Err(VerifierError::UnsupportedTraceLayout);
+    }
+
+    let setup = VerificationSetup::new(&air);
+    let channel = VerifierChannel::<AIR::BaseElement, AIR::BaseElement, H>::new(&air, proof, None)?;
+
+    // draw the out-of-domain point and evaluate constraints at it
+    let z = channel.draw_deep_point();
+    let ood_frame = channel.read_ood_frame()?;
+    let constraint_evaluation_at_z = evaluate_constraints(&air, &setup, &channel, &ood_frame, z);
+
+    // draw coefficients for DEEP composition and FRI layer folding
+    let composition_coefficients = channel.draw_composition_coefficients();
+    let fri_context = fri::VerifierContext::new(
+        air.context().lde_domain_size(),
+        air.context().composition_degree(),
+        channel.num_fri_partitions(),
+        options.to_fri_options::<AIR::BaseElement>(),
+    );
+    let fri_alphas = (0..fri_context.num_fri_layers())
+        .map(|depth| channel.draw_fri_alpha(depth))
+        .collect();
+
+    // make sure the remainder matches its commitment and has the expected degree
+    let fri_remainder = channel
+        .read_remainder()
+        .map_err(VerifierError::FriVerificationFailed)?;
+    fri::verify_remainder(&fri_context, fri_remainder.clone())
+        .map_err(VerifierError::FriVerificationFailed)?;
+
+    Ok(ProofTranscript {
+        trace_root: channel.trace_roots()[0],
+        constraint_root: channel.constraint_root(),
+        fri_roots: channel.fri_layer_commitments().to_vec(),
+        ood_frame,
+        z,
+        constraint_evaluation_at_z,
+        composition_coefficients,
+        fri_alphas,
+        fri_remainder,
+        query_positions: channel.draw_query_positions(),
+    })
+}
@@ -3,12 +3,18 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{compose_constraints, evaluate_constraints, VerifierChannel};
+use super::{compose_constraints, evaluate_constraints, VerificationSetup, VerifierChannel};
 use common::CompositionCoefficients;
 use common::{errors::VerifierError, Air, EvaluationFrame, PublicCoin};
 use crypto::Hasher;
 use fri::VerifierChannel as FriVerifierChannel;
-use math::field::{FieldElement, StarkField};
+use math::{
+    field::{FieldElement, StarkField},
+    utils::batch_inversion,
+};
+
+#[cfg(feature = "concurrent")]
+use rayon::prelude::*;
 
 // VERIFICATION PROCEDURE
 // ================================================================================================
@@ -16,6 +22,7 @@ use math::field::{FieldElement, StarkField};
 pub fn perform_verification<A: Air, E: FieldElement + From<A::BaseElement>, H: Hasher>(
     air: A,
     channel: VerifierChannel<A::BaseElement, E, H>,
+    setup: &VerificationSetup<A::BaseElement>,
 ) -> Result<(), VerifierError> {
     // 1 ----- Compute constraint evaluations at OOD point z ----------------------------------
 
@@ -24,7 +31,7 @@ pub fn perform_verification<A: Air, E: FieldElement + From<A::BaseElement>, H: H
 
     // evaluate constraints at z
     let ood_frame = channel.read_ood_frame()?;
-    let constraint_evaluation_at_z = evaluate_constraints(&air, &channel, &ood_frame, z);
+    let constraint_evaluation_at_z = evaluate_constraints(&air, setup, &channel, &ood_frame, z);
 
     // 2 ----- Read queried trace states and constraint evaluations ---------------------------
 
@@ -32,8 +39,8 @@ pub fn perform_verification<A: Air, E: FieldElement + From<A::BaseElement>, H: H
     let query_positions = channel.draw_query_positions();
 
     // compute LDE domain coordinates for all query positions
-    let g_lde = air.context().get_lde_domain_generator::<A::BaseElement>();
-    let domain_offset = air.context().domain_offset::<A::BaseElement>();
+    let g_lde = setup.lde_domain_generator();
+    let domain_offset = setup.domain_offset();
     let x_coordinates: Vec<A::BaseElement> = query_positions
         .iter()
         .map(|&p| g_lde.exp((p as u64).into()) * domain_offset)
@@ -41,8 +48,20 @@ pub fn perform_verification<A: Air, E: FieldElement + From<A::BaseElement>, H: H
 
     // read trace states and constraint evaluations at the queried positions; this also
     // checks that Merkle authentication paths for the states and evaluations are valid
-    let trace_states = channel.read_trace_states(&query_positions)?;
-    let constraint_evaluations = channel.read_constraint_evaluations(&query_positions)?;
+    #[cfg(not(feature = "concurrent"))]
+    let (trace_states, constraint_evaluations) = (
+        channel.read_trace_states(&query_positions),
+        channel.read_constraint_evaluations(&query_positions),
+    );
+
+    #[cfg(feature = "concurrent")]
+    let (trace_states, constraint_evaluations) = rayon::join(
+        || channel.read_trace_states(&query_positions),
+        || channel.read_constraint_evaluations(&query_positions),
+    );
+
+    let trace_states = trace_states?;
+    let constraint_evaluations = constraint_evaluations?;
 
     // 3 ----- Compute composition polynomial evaluations -------------------------------------
 
@@ -51,21 +70,23 @@ pub fn perform_verification<A: Air, E: FieldElement + From<A::BaseElement>, H: H
     // polynomial
     let coefficients = channel.draw_composition_coefficients();
 
+    // invert denominators of the composition at all queried positions in a single batch
+    let denominators = DeepDenominators::new(&air, &x_coordinates, z);
+
     // compute composition of trace registers
     let t_composition = compose_registers(
         &air,
         &trace_states,
         &x_coordinates,
         &ood_frame,
-        z,
         &coefficients,
+        &denominators,
     );
 
     // compute composition of constraints
     let c_composition = compose_constraints(
         constraint_evaluations,
-        &x_coordinates,
-        z,
+        &denominators.z,
         constraint_evaluation_at_z,
         &coefficients,
     );
@@ -93,17 +114,16 @@ pub fn perform_verification<A: Air, E: FieldElement + From<A::BaseElement>, H: H
 // TRACE COMPOSITION
 // ================================================================================================
 
-/// TODO: add comments
+/// Computes a pseudo-random linear combination of DEEP quotients of all trace registers at the
+/// queried positions, and raises the degree of the result to match the composition degree.
 fn compose_registers<B: StarkField, E: FieldElement + From<B>, A: Air<BaseElement = B>>(
     air: &A,
     trace_states: &[Vec<B>],
     x_coordinates: &[B],
     ood_frame: &EvaluationFrame<E>,
-    z: E,
     cc: &CompositionCoefficients<E>,
+    denominators: &DeepDenominators<E>,
 ) -> Vec<E> {
-    let next_z = z * E::from(air.trace_domain_generator());
-
     let trace_at_z1 = &ood_frame.current;
     let trace_at_z2 = &ood_frame.next;
 
@@ -111,58 +131,118 @@ fn compose_registers<B: StarkField, E: FieldElement + From<B>, A: Air<BaseElemen
     let composition_degree = air.context().deep_composition_degree();
     let incremental_degree = (composition_degree - (air.trace_length() - 2)) as u32;
 
-    // when field extension is enabled, these will be set to conjugates of trace values at
-    // z as well as conjugate of z itself
-    let conjugate_values = get_conjugate_values(air, trace_at_z1, z);
+    // when field extension is enabled, these will be set to conjugates of trace values at z
+    let trace_at_z1_conjugates = get_conjugate_values(air, trace_at_z1);
 
-    let mut result = Vec::with_capacity(trace_states.len());
-    for (registers, &x) in trace_states.iter().zip(x_coordinates) {
-        let x = E::from(x);
-        let mut composition = E::ZERO;
-        for (i, &value) in registers.iter().enumerate() {
+    let compose = |(i, (registers, &x)): (usize, (&Vec<B>, &B))| {
+        // all quotients for the same x share denominators; so, we first combine numerators
+        // of the quotients, and then multiply the sums by inverses of the denominators
+        let mut t1_sum = E::ZERO;
+        let mut t2_sum = E::ZERO;
+        let mut t3_sum = E::ZERO;
+        for (j, &value) in registers.iter().enumerate() {
             let value = E::from(value);
-            // compute T1(x) = (T(x) - T(z)) / (x - z)
-            let t1 = (value - trace_at_z1[i]) / (x - z);
-            // multiply it by a pseudo-random coefficient, and combine with result
-            composition += t1 * cc.trace[i].0;
+            // compute numerator of T1(x) = (T(x) - T(z)) / (x - z), multiply it by a
+            // pseudo-random coefficient, and combine with the sum
+            t1_sum += (value - trace_at_z1[j]) * cc.trace[j].0;
 
-            // compute T2(x) = (T(x) - T(z * g)) / (x - z * g)
-            let t2 = (value - trace_at_z2[i]) / (x - next_z);
-            // multiply it by a pseudo-random coefficient, and combine with result
-            composition += t2 * cc.trace[i].1;
+            // compute numerator of T2(x) = (T(x) - T(z * g)) / (x - z * g)
+            t2_sum += (value - trace_at_z2[j]) * cc.trace[j].1;
 
-            // compute T3(x) = (T(x) - T(z_conjugate)) / (x - z_conjugate)
+            // compute numerator of T3(x) = (T(x) - T(z_conjugate)) / (x - z_conjugate)
             // when extension field is enabled, this constraint is needed in order to verify
             // that the trace is defined over the base field, rather than the extension field
-            if let Some((z_conjugate, ref trace_at_z1_conjugates)) = conjugate_values {
-                let t3 = (value - trace_at_z1_conjugates[i]) / (x - z_conjugate);
-                composition += t3 * cc.trace[i].2;
+            if let Some(ref trace_at_z1_conjugates) = trace_at_z1_conjugates {
+                t3_sum += (value - trace_at_z1_conjugates[j]) * cc.trace[j].2;
             }
         }
 
+        let mut composition = t1_sum * denominators.z[i] + t2_sum * denominators.next_z[i];
+        if trace_at_z1_conjugates.is_some() {
+            composition += t3_sum * denominators.z_conjugate[i];
+        }
+
         // raise the degree to match composition degree
-        let xp = x.exp(incremental_degree.into());
-        composition *= cc.trace_degree.0 + xp * cc.trace_degree.1;
+        let xp = E::from(x).exp(incremental_degree.into());
+        composition * (cc.trace_degree.0 + xp * cc.trace_degree.1)
+    };
 
-        result.push(composition);
-    }
+    // compositions at different queries are independent, and thus, can be computed in parallel
+    #[cfg(not(feature = "concurrent"))]
+    let result = trace_states
+        .iter()
+        .zip(x_coordinates)
+        .enumerate()
+        .map(compose)
+        .collect();
+
+    #[cfg(feature = "concurrent")]
+    let result = trace_states
+        .par_iter()
+        .zip(x_coordinates)
+        .enumerate()
+        .map(compose)
+        .collect();
 
     result
 }
 
-/// When field extension is used, returns conjugate values of the `trace_state` and `z`;
-/// otherwise, returns None.
+/// When field extension is used, returns conjugate values of the `trace_state`; otherwise,
+/// returns None.
 fn get_conjugate_values<A: Air, E: FieldElement + From<A::BaseElement>>(
     air: &A,
     trace_state: &[E],
-    z: E,
-) -> Option<(E, Vec<E>)> {
+) -> Option<Vec<E>> {
     if air.context().options().field_extension().is_none() {
         None
     } else {
-        Some((
-            z.conjugate(),
-            trace_state.iter().map(|v| v.conjugate()).collect(),
-        ))
+        Some(trace_state.iter().map(|v| v.conjugate()).collect())
+    }
+}
+
+// DEEP COMPOSITION DENOMINATORS
+// ================================================================================================
+
+/// Inverses of denominators of DEEP composition at all queried x coordinates. Denominators do
+/// not depend on trace registers, and thus, the same inverses are shared by all registers as
+/// well as by the composition of constraints.
+struct DeepDenominators<E: FieldElement> {
+    /// Values 1 / (x - z).
+    z: Vec<E>,
+    /// Values 1 / (x - z * g), where g is the generator of the trace domain.
+    next_z: Vec<E>,
+    /// Values 1 / (x - z_conjugate); empty when field extension is not used.
+    z_conjugate: Vec<E>,
+}
+
+impl<E: FieldElement> DeepDenominators<E> {
+    /// Computes inverses of all denominators for the specified x coordinates using a single
+    /// batch inversion.
+    fn new<A: Air>(air: &A, x_coordinates: &[A::BaseElement], z: E) -> Self
+    where
+        E: From<A::BaseElement>,
+    {
+        let next_z = z * E::from(air.trace_domain_generator());
+        let mut points = vec![z, next_z];
+        if !air.context().options().field_extension().is_none() {
+            points.push(z.conjugate());
+        }
+
+        // denominators for each point are placed next to each other, so that after the batch
+        // inversion, inverses for each point occupy a contiguous chunk of the result
+        let n = x_coordinates.len();
+        let mut denominators = Vec::with_capacity(n * points.len());
+        for &point in points.iter() {
+            denominators.extend(x_coordinates.iter().map(|&x| E::from(x) - point));
+        }
+        let mut inverses = batch_inversion(&denominators);
+
+        let z_conjugate = inverses.split_off(n * 2);
+        let next_z = inverses.split_off(n);
+        DeepDenominators {
+            z: inverses,
+            next_z,
+            z_conjugate,
+        }
     }
 }